@@ -2,12 +2,18 @@
 
 use crate::error::{ChainError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Default coinset.org endpoint (used by the anchoring subsystem).
 pub const DEFAULT_COINSET_URL: &str = "https://api.coinset.org";
 /// Default cached-unlock TTL in seconds (1 hour).
 pub const DEFAULT_UNLOCK_TTL: u64 = 3600;
+/// The wallet profile whose seed and session live directly in `~/.dig`.
+pub const DEFAULT_PROFILE: &str = "default";
+/// Environment variable naming the wallet profile to use (set by the global
+/// `--wallet-profile` flag); wins over `wallet.active_profile`.
+pub const PROFILE_ENV: &str = "DIGSTORE_WALLET_PROFILE";
 
 /// Resolves the global `~/.dig` directory.
 ///
@@ -22,21 +28,216 @@ pub fn dig_home() -> Result<PathBuf> {
     Ok(home.join(".dig"))
 }
 
+/// The active wallet profile's encrypted seed (see [`wallet_dir`]).
 pub fn seed_path(home: &Path) -> PathBuf {
-    home.join("seed.enc")
+    wallet_dir(home).join("seed.enc")
 }
+/// The active wallet profile's cached-unlock session (see [`wallet_dir`]).
 pub fn session_path(home: &Path) -> PathBuf {
-    home.join("session")
+    wallet_dir(home).join("session")
+}
+
+/// Directory holding the active profile's seed and session: `home` itself for
+/// [`DEFAULT_PROFILE`], else `home/profiles/<name>`. An unreadable config
+/// falls back to the default profile.
+pub fn wallet_dir(home: &Path) -> PathBuf {
+    let profile = active_profile(home).unwrap_or_else(|_| DEFAULT_PROFILE.to_string());
+    profile_dir(home, &profile)
+}
+
+/// Directory of wallet profile `name` (see [`wallet_dir`]).
+pub fn profile_dir(home: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        home.to_path_buf()
+    } else {
+        home.join("profiles").join(name)
+    }
+}
+
+/// The wallet profile in use: [`PROFILE_ENV`], else `wallet.active_profile`,
+/// else [`DEFAULT_PROFILE`].
+pub fn active_profile(home: &Path) -> Result<String> {
+    if let Some(name) = std::env::var_os(PROFILE_ENV) {
+        let name = name
+            .into_string()
+            .map_err(|_| ChainError::Config(format!("{PROFILE_ENV} is not valid UTF-8")))?;
+        validate_profile_name(&name)?;
+        return Ok(name);
+    }
+    Ok(GlobalConfig::load(home)?
+        .wallet
+        .active_profile
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string()))
+}
+
+/// Every wallet profile: [`DEFAULT_PROFILE`] plus each `home/profiles/<name>`
+/// directory, sorted.
+pub fn list_profiles(home: &Path) -> Result<Vec<String>> {
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    let dir = home.join("profiles");
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    if validate_profile_name(name).is_ok() && name != DEFAULT_PROFILE {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    names[1..].sort();
+    Ok(names)
+}
+
+/// Profile names: non-empty, only `[A-Za-z0-9._-]`, not starting with `.`.
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let ok = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if ok {
+        Ok(())
+    } else {
+        Err(ChainError::Config(format!(
+            "invalid wallet profile '{name}': use letters, digits, '.', '_', '-'"
+        )))
+    }
 }
 pub fn config_path(home: &Path) -> PathBuf {
     home.join("config.toml")
 }
+/// The default user-global ignore file, used when `core.excludesFile` is unset.
+pub fn global_ignore_path(home: &Path) -> PathBuf {
+    home.join("ignore")
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GlobalConfig {
     pub coinset_url: String,
     pub unlock_ttl: u64,
     pub fee: u64,
+    #[serde(default, skip_serializing_if = "CoreConfig::is_empty")]
+    pub core: CoreConfig,
+    #[serde(default, skip_serializing_if = "OutputConfig::is_empty")]
+    pub output: OutputConfig,
+    /// Machine-wide store names (`[names.<name>]`), usable as `--store <name>`
+    /// from any directory.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub names: BTreeMap<String, NamedStore>,
+    #[serde(default, skip_serializing_if = "WalletConfig::is_empty")]
+    pub wallet: WalletConfig,
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+}
+
+/// The `[hooks]` table: shell commands `digstore commit` runs around a commit.
+/// A project's `dig.toml` `[hooks]` overrides these per hook.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// `hooks.preCommit`: runs before anything is anchored; a nonzero exit
+    /// aborts the commit.
+    #[serde(default, rename = "preCommit", skip_serializing_if = "Option::is_none")]
+    pub pre_commit: Option<String>,
+    /// `hooks.postCommit`: runs once the commit is finalized; its exit status
+    /// is reported but does not undo anything.
+    #[serde(
+        default,
+        rename = "postCommit",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub post_commit: Option<String>,
+}
+
+impl HooksConfig {
+    fn is_empty(&self) -> bool {
+        self.pre_commit.is_none() && self.post_commit.is_none()
+    }
+}
+
+/// The `[wallet]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletConfig {
+    /// `wallet.active_profile`: the profile `wallet use` selected. Unset means
+    /// [`DEFAULT_PROFILE`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+impl WalletConfig {
+    fn is_empty(&self) -> bool {
+        self.active_profile.is_none()
+    }
+}
+
+/// A registered store name: the store's id and the directory it lives in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedStore {
+    /// Store id (64 hex).
+    pub id: String,
+    /// Absolute path of the store directory (the one holding `config.toml`).
+    pub path: String,
+}
+
+/// The `[core]` table: settings that apply to every store on this machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoreConfig {
+    /// `core.excludesFile`: a gitignore-syntax file applied to every store,
+    /// beneath each store's own `.digignore`/`.gitignore` files. `~/` expands
+    /// to the home directory. Unset means [`global_ignore_path`].
+    #[serde(
+        default,
+        rename = "excludesFile",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub excludes_file: Option<String>,
+    /// `core.checkForUpdates`: `false` stops the background check for a newer
+    /// release that runs after commands. Unset means enabled; `digstore
+    /// update` always checks.
+    #[serde(
+        default,
+        rename = "checkForUpdates",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub check_for_updates: Option<bool>,
+}
+
+impl CoreConfig {
+    fn is_empty(&self) -> bool {
+        self.excludes_file.is_none() && self.check_for_updates.is_none()
+    }
+
+    /// Whether the background update check may run (`core.checkForUpdates`).
+    pub fn update_checks_enabled(&self) -> bool {
+        self.check_for_updates != Some(false)
+    }
+}
+
+/// The `[output]` table: how commands render their results.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// `output.format`: the format used when neither `--json` nor `--human`
+    /// is given.
+    #[serde(default)]
+    pub format: OutputFormat,
+}
+
+impl OutputConfig {
+    fn is_empty(&self) -> bool {
+        self.format == OutputFormat::Auto
+    }
+}
+
+/// Values of `output.format`. `auto` (the default) keeps human output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Auto,
+    Json,
+    Human,
 }
 
 impl Default for GlobalConfig {
@@ -45,13 +246,28 @@ impl Default for GlobalConfig {
             coinset_url: DEFAULT_COINSET_URL.to_string(),
             unlock_ttl: DEFAULT_UNLOCK_TTL,
             fee: 0,
+            core: CoreConfig::default(),
+            output: OutputConfig::default(),
+            names: BTreeMap::new(),
+            wallet: WalletConfig::default(),
+            hooks: HooksConfig::default(),
         }
     }
 }
 
 impl GlobalConfig {
-    /// Loads config from `<home>/config.toml`, or returns defaults if absent.
+    /// The effective config: `<home>/config.toml` (or defaults if absent) with
+    /// each set [`ConfigKey::env_var`] applied over it, so env > file > default.
     pub fn load(home: &Path) -> Result<Self> {
+        let mut cfg = Self::load_file(home)?;
+        cfg.apply_env(|var| std::env::var(var).ok())?;
+        Ok(cfg)
+    }
+
+    /// Loads config from `<home>/config.toml` alone, or returns defaults if
+    /// absent. Anything that edits and [`save`](Self::save)s the file starts
+    /// here, so an environment override is never written back.
+    pub fn load_file(home: &Path) -> Result<Self> {
         let path = config_path(home);
         if !path.exists() {
             return Ok(GlobalConfig::default());
@@ -60,6 +276,49 @@ impl GlobalConfig {
         toml::from_str(&text).map_err(|e| ChainError::Config(e.to_string()))
     }
 
+    /// Apply every key whose environment variable `lookup` returns, validated
+    /// as [`ConfigKey::set`] would. An empty variable counts as unset.
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for key in ConfigKey::ALL {
+            let Some(value) = lookup(key.env_var()).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            key.set(self, &value).map_err(|e| match e {
+                ChainError::Config(m) => {
+                    ChainError::Config(format!("{m} (from {})", key.env_var()))
+                }
+                e => e,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Where each key's effective value comes from, in [`ConfigKey::ALL`]
+    /// order: its environment variable, `config.toml`, or the default.
+    pub fn origins(home: &Path) -> Result<Vec<(ConfigKey, ConfigOrigin)>> {
+        let path = config_path(home);
+        let file: toml::Table = if path.exists() {
+            let text = std::fs::read_to_string(&path)?;
+            text.parse()
+                .map_err(|e: toml::de::Error| ChainError::Config(e.to_string()))?
+        } else {
+            toml::Table::new()
+        };
+        Ok(ConfigKey::ALL
+            .into_iter()
+            .map(|key| {
+                let origin = if std::env::var(key.env_var()).is_ok_and(|v| !v.is_empty()) {
+                    ConfigOrigin::Env
+                } else if key.is_in(&file) {
+                    ConfigOrigin::File
+                } else {
+                    ConfigOrigin::Default
+                };
+                (key, origin)
+            })
+            .collect())
+    }
+
     /// Writes config to `<home>/config.toml`, creating the dir if needed.
     pub fn save(&self, home: &Path) -> Result<()> {
         std::fs::create_dir_all(home)?;
@@ -67,6 +326,259 @@ impl GlobalConfig {
         std::fs::write(config_path(home), text)?;
         Ok(())
     }
+
+    /// Every problem in this config, one message per bad value: each key's
+    /// value re-checked as [`ConfigKey::set`] would, and each registered store
+    /// name's id (64 hex) and path (absolute). Empty when the config is sound.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems: Vec<String> = ConfigKey::ALL
+            .into_iter()
+            .filter_map(|key| key.validate(&key.get(self)?).err())
+            .map(|e| match e {
+                ChainError::Config(m) => m,
+                e => e.to_string(),
+            })
+            .collect();
+        for (name, store) in &self.names {
+            if store.id.len() != 64 || !store.id.bytes().all(|b| b.is_ascii_hexdigit()) {
+                problems.push(format!(
+                    "invalid names.{name}.id '{}': expected a 64-character hex store id",
+                    store.id
+                ));
+            }
+            if !Path::new(&store.path).is_absolute() {
+                problems.push(format!(
+                    "invalid names.{name}.path '{}': expected an absolute path",
+                    store.path
+                ));
+            }
+        }
+        problems
+    }
+
+    /// The user-global ignore file to apply, if it exists: `core.excludesFile`
+    /// when set, else `<home>/ignore`.
+    pub fn excludes_file(&self, home: &Path) -> Option<PathBuf> {
+        let path = match self.core.excludes_file.as_deref() {
+            Some(p) => match p.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()?.join(rest),
+                None => PathBuf::from(p),
+            },
+            None => global_ignore_path(home),
+        };
+        path.is_file().then_some(path)
+    }
+}
+
+/// A global setting `digstore config` reads and writes, by its dotted name as
+/// it appears in `config.toml`. Each key checks a value before it is stored,
+/// so a bad one is rejected when it is set rather than when it is first used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKey {
+    CoinsetUrl,
+    UnlockTtl,
+    Fee,
+    CoreExcludesFile,
+    CoreCheckForUpdates,
+    OutputFormat,
+    WalletActiveProfile,
+    HooksPreCommit,
+    HooksPostCommit,
+}
+
+/// Where a setting's effective value was read from (`config --show-origin`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// The key's [`ConfigKey::env_var`].
+    Env,
+    /// `config.toml`.
+    File,
+    /// Built in; set nowhere.
+    Default,
+}
+
+impl ConfigKey {
+    /// Every key, in the order `config.toml` lists them.
+    pub const ALL: [ConfigKey; 9] = [
+        ConfigKey::CoinsetUrl,
+        ConfigKey::UnlockTtl,
+        ConfigKey::Fee,
+        ConfigKey::CoreExcludesFile,
+        ConfigKey::CoreCheckForUpdates,
+        ConfigKey::OutputFormat,
+        ConfigKey::WalletActiveProfile,
+        ConfigKey::HooksPreCommit,
+        ConfigKey::HooksPostCommit,
+    ];
+
+    /// The key's dotted name (`table.field`, or just `field` at the top level).
+    pub fn name(self) -> &'static str {
+        match self {
+            ConfigKey::CoinsetUrl => "coinset_url",
+            ConfigKey::UnlockTtl => "unlock_ttl",
+            ConfigKey::Fee => "fee",
+            ConfigKey::CoreExcludesFile => "core.excludesFile",
+            ConfigKey::CoreCheckForUpdates => "core.checkForUpdates",
+            ConfigKey::OutputFormat => "output.format",
+            ConfigKey::WalletActiveProfile => "wallet.active_profile",
+            ConfigKey::HooksPreCommit => "hooks.preCommit",
+            ConfigKey::HooksPostCommit => "hooks.postCommit",
+        }
+    }
+
+    /// The environment variable that overrides this key: `DIGSTORE_` plus the
+    /// dotted name in upper snake case (`hooks.preCommit` ->
+    /// `DIGSTORE_HOOKS_PRE_COMMIT`).
+    pub fn env_var(self) -> &'static str {
+        match self {
+            ConfigKey::CoinsetUrl => "DIGSTORE_COINSET_URL",
+            ConfigKey::UnlockTtl => "DIGSTORE_UNLOCK_TTL",
+            ConfigKey::Fee => "DIGSTORE_FEE",
+            ConfigKey::CoreExcludesFile => "DIGSTORE_CORE_EXCLUDES_FILE",
+            ConfigKey::CoreCheckForUpdates => "DIGSTORE_CORE_CHECK_FOR_UPDATES",
+            ConfigKey::OutputFormat => "DIGSTORE_OUTPUT_FORMAT",
+            ConfigKey::WalletActiveProfile => "DIGSTORE_WALLET_ACTIVE_PROFILE",
+            ConfigKey::HooksPreCommit => "DIGSTORE_HOOKS_PRE_COMMIT",
+            ConfigKey::HooksPostCommit => "DIGSTORE_HOOKS_POST_COMMIT",
+        }
+    }
+
+    /// Whether `file` (a parsed `config.toml`) sets this key.
+    fn is_in(self, file: &toml::Table) -> bool {
+        match self.name().split_once('.') {
+            Some((table, field)) => file
+                .get(table)
+                .and_then(|t| t.as_table())
+                .is_some_and(|t| t.contains_key(field)),
+            None => file.contains_key(self.name()),
+        }
+    }
+
+    /// The key named `name`; an unknown name lists the known ones.
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.name() == name)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|k| k.name()).collect();
+                ChainError::Config(format!(
+                    "unknown config key '{name}'; known keys: {}",
+                    known.join(", ")
+                ))
+            })
+    }
+
+    /// Check `value` for this key without storing it.
+    pub fn validate(self, value: &str) -> Result<()> {
+        let invalid =
+            |why: String| ChainError::Config(format!("invalid {} '{value}': {why}", self.name()));
+        match self {
+            ConfigKey::CoinsetUrl => {
+                let host = value
+                    .strip_prefix("https://")
+                    .or_else(|| value.strip_prefix("http://"))
+                    .ok_or_else(|| invalid("expected an http:// or https:// URL".into()))?;
+                if host.is_empty() || host.starts_with('/') || value.contains(char::is_whitespace) {
+                    return Err(invalid("the URL has no host".into()));
+                }
+            }
+            ConfigKey::UnlockTtl | ConfigKey::Fee => {
+                value.parse::<u64>().map_err(|_| {
+                    invalid(match self {
+                        ConfigKey::UnlockTtl => "expected a whole number of seconds".into(),
+                        _ => "expected a whole number of mojos".into(),
+                    })
+                })?;
+            }
+            ConfigKey::CoreCheckForUpdates => {
+                value
+                    .parse::<bool>()
+                    .map_err(|_| invalid("expected true or false".into()))?;
+            }
+            ConfigKey::OutputFormat => {
+                if !matches!(value, "auto" | "json" | "human") {
+                    return Err(invalid("expected auto, json or human".into()));
+                }
+            }
+            ConfigKey::WalletActiveProfile => {
+                validate_profile_name(value)
+                    .map_err(|_| invalid("use letters, digits, '.', '_', '-'".into()))?;
+            }
+            ConfigKey::CoreExcludesFile
+            | ConfigKey::HooksPreCommit
+            | ConfigKey::HooksPostCommit => {
+                if value.trim().is_empty() {
+                    return Err(invalid("must not be empty; unset it instead".into()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The key's value in `cfg`, as `config.toml` spells it; `None` when an
+    /// optional key is unset.
+    pub fn get(self, cfg: &GlobalConfig) -> Option<String> {
+        match self {
+            ConfigKey::CoinsetUrl => Some(cfg.coinset_url.clone()),
+            ConfigKey::UnlockTtl => Some(cfg.unlock_ttl.to_string()),
+            ConfigKey::Fee => Some(cfg.fee.to_string()),
+            ConfigKey::CoreExcludesFile => cfg.core.excludes_file.clone(),
+            ConfigKey::CoreCheckForUpdates => cfg.core.check_for_updates.map(|b| b.to_string()),
+            ConfigKey::OutputFormat => Some(
+                match cfg.output.format {
+                    OutputFormat::Auto => "auto",
+                    OutputFormat::Json => "json",
+                    OutputFormat::Human => "human",
+                }
+                .to_string(),
+            ),
+            ConfigKey::WalletActiveProfile => cfg.wallet.active_profile.clone(),
+            ConfigKey::HooksPreCommit => cfg.hooks.pre_commit.clone(),
+            ConfigKey::HooksPostCommit => cfg.hooks.post_commit.clone(),
+        }
+    }
+
+    /// Validate `value` and store it in `cfg`.
+    pub fn set(self, cfg: &mut GlobalConfig, value: &str) -> Result<()> {
+        self.validate(value)?;
+        let text = || Some(value.to_string());
+        match self {
+            ConfigKey::CoinsetUrl => cfg.coinset_url = value.to_string(),
+            ConfigKey::UnlockTtl => cfg.unlock_ttl = value.parse().expect("validated"),
+            ConfigKey::Fee => cfg.fee = value.parse().expect("validated"),
+            ConfigKey::CoreExcludesFile => cfg.core.excludes_file = text(),
+            ConfigKey::CoreCheckForUpdates => {
+                cfg.core.check_for_updates = Some(value.parse().expect("validated"))
+            }
+            ConfigKey::OutputFormat => {
+                cfg.output.format = match value {
+                    "json" => OutputFormat::Json,
+                    "human" => OutputFormat::Human,
+                    _ => OutputFormat::Auto,
+                }
+            }
+            ConfigKey::WalletActiveProfile => cfg.wallet.active_profile = text(),
+            ConfigKey::HooksPreCommit => cfg.hooks.pre_commit = text(),
+            ConfigKey::HooksPostCommit => cfg.hooks.post_commit = text(),
+        }
+        Ok(())
+    }
+
+    /// Reset the key in `cfg` to its default (unset, for an optional key).
+    pub fn unset(self, cfg: &mut GlobalConfig) {
+        let default = GlobalConfig::default();
+        match self {
+            ConfigKey::CoinsetUrl => cfg.coinset_url = default.coinset_url,
+            ConfigKey::UnlockTtl => cfg.unlock_ttl = default.unlock_ttl,
+            ConfigKey::Fee => cfg.fee = default.fee,
+            ConfigKey::CoreExcludesFile => cfg.core.excludes_file = None,
+            ConfigKey::CoreCheckForUpdates => cfg.core.check_for_updates = None,
+            ConfigKey::OutputFormat => cfg.output.format = OutputFormat::Auto,
+            ConfigKey::WalletActiveProfile => cfg.wallet.active_profile = None,
+            ConfigKey::HooksPreCommit => cfg.hooks.pre_commit = None,
+            ConfigKey::HooksPostCommit => cfg.hooks.post_commit = None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -95,12 +607,228 @@ mod tests {
             coinset_url: "https://example.org".into(),
             unlock_ttl: 60,
             fee: 5,
+            core: CoreConfig {
+                excludes_file: Some("/etc/digignore".into()),
+                check_for_updates: Some(false),
+            },
+            output: OutputConfig {
+                format: OutputFormat::Json,
+            },
+            names: BTreeMap::from([(
+                "site".to_string(),
+                NamedStore {
+                    id: "ab".repeat(32),
+                    path: "/srv/site/.dig/stores/site".into(),
+                },
+            )]),
+            wallet: WalletConfig {
+                active_profile: Some("work".into()),
+            },
+            hooks: HooksConfig {
+                pre_commit: Some("npm run lint".into()),
+                post_commit: None,
+            },
         };
         c.save(dir.path()).unwrap();
         let loaded = GlobalConfig::load(dir.path()).unwrap();
         assert_eq!(loaded, c);
     }
 
+    #[test]
+    fn config_without_core_table_still_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            config_path(dir.path()),
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            GlobalConfig::load(dir.path()).unwrap(),
+            GlobalConfig::default()
+        );
+    }
+
+    #[test]
+    fn update_checks_default_on_and_read_check_for_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(GlobalConfig::default().core.update_checks_enabled());
+        std::fs::write(
+            config_path(dir.path()),
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n\n[core]\ncheckForUpdates = false\n",
+        )
+        .unwrap();
+        let cfg = GlobalConfig::load(dir.path()).unwrap();
+        assert!(!cfg.core.update_checks_enabled());
+    }
+
+    #[test]
+    fn output_format_reads_from_the_output_table() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            config_path(dir.path()),
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n\n[output]\nformat = \"json\"\n",
+        )
+        .unwrap();
+        let c = GlobalConfig::load(dir.path()).unwrap();
+        assert_eq!(c.output.format, OutputFormat::Json);
+        assert_eq!(GlobalConfig::default().output.format, OutputFormat::Auto);
+    }
+
+    #[test]
+    fn excludes_file_prefers_the_configured_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = GlobalConfig::default();
+        assert_eq!(c.excludes_file(dir.path()), None);
+        std::fs::write(global_ignore_path(dir.path()), "*.swp\n").unwrap();
+        assert_eq!(
+            c.excludes_file(dir.path()),
+            Some(global_ignore_path(dir.path()))
+        );
+        let custom = dir.path().join("custom-ignore");
+        std::fs::write(&custom, "*.bak\n").unwrap();
+        c.core.excludes_file = Some(custom.display().to_string());
+        assert_eq!(c.excludes_file(dir.path()), Some(custom));
+    }
+
+    #[test]
+    fn config_keys_round_trip_through_set_and_get() {
+        let mut c = GlobalConfig::default();
+        for (key, value) in [
+            ("coinset_url", "https://coinset.example"),
+            ("unlock_ttl", "60"),
+            ("fee", "1000"),
+            ("core.excludesFile", "~/.digignore"),
+            ("core.checkForUpdates", "false"),
+            ("output.format", "json"),
+            ("wallet.active_profile", "work"),
+            ("hooks.preCommit", "npm test"),
+            ("hooks.postCommit", "./notify.sh"),
+        ] {
+            let key = ConfigKey::parse(key).unwrap();
+            key.set(&mut c, value).unwrap();
+            assert_eq!(key.get(&c).as_deref(), Some(value), "{}", key.name());
+        }
+        assert_eq!(c.output.format, OutputFormat::Json);
+        assert!(c.problems().is_empty());
+
+        for key in ConfigKey::ALL {
+            key.unset(&mut c);
+        }
+        assert_eq!(c, GlobalConfig::default());
+    }
+
+    #[test]
+    fn config_keys_reject_bad_values_at_set_time() {
+        let mut c = GlobalConfig::default();
+        for (key, value) in [
+            ("coinset_url", "api.coinset.org"),
+            ("coinset_url", "https://"),
+            ("unlock_ttl", "an hour"),
+            ("fee", "-1"),
+            ("core.checkForUpdates", "nope"),
+            ("output.format", "yaml"),
+            ("wallet.active_profile", "../x"),
+            ("hooks.preCommit", "  "),
+        ] {
+            let err = ConfigKey::parse(key)
+                .unwrap()
+                .set(&mut c, value)
+                .unwrap_err();
+            assert!(err.to_string().contains(key), "{err}");
+        }
+        assert_eq!(c, GlobalConfig::default(), "nothing was stored");
+        let err = ConfigKey::parse("user.name").unwrap_err().to_string();
+        assert!(err.contains("known keys: coinset_url"), "{err}");
+    }
+
+    #[test]
+    fn env_overrides_the_file_which_overrides_the_default() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            config_path(dir.path()),
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 60\nfee = 5\n",
+        )
+        .unwrap();
+        let mut c = GlobalConfig::load_file(dir.path()).unwrap();
+        c.apply_env(|var| match var {
+            "DIGSTORE_FEE" => Some("7".into()),
+            "DIGSTORE_HOOKS_PRE_COMMIT" => Some("make lint".into()),
+            "DIGSTORE_OUTPUT_FORMAT" => Some(String::new()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(c.fee, 7, "env wins over the file");
+        assert_eq!(c.unlock_ttl, 60, "the file wins over the default");
+        assert_eq!(c.hooks.pre_commit.as_deref(), Some("make lint"));
+        assert_eq!(c.output.format, OutputFormat::Auto, "empty counts as unset");
+
+        let err = c
+            .apply_env(|var| (var == "DIGSTORE_UNLOCK_TTL").then(|| "soon".into()))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("invalid unlock_ttl") && err.contains("DIGSTORE_UNLOCK_TTL"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn env_var_names_follow_the_dotted_key() {
+        for key in ConfigKey::ALL {
+            let mut expected = String::from("DIGSTORE_");
+            for c in key.name().chars() {
+                match c {
+                    '.' => expected.push('_'),
+                    c if c.is_ascii_uppercase() => {
+                        expected.push('_');
+                        expected.push(c);
+                    }
+                    c => expected.push(c.to_ascii_uppercase()),
+                }
+            }
+            assert_eq!(key.env_var(), expected);
+        }
+    }
+
+    #[test]
+    fn origins_tell_file_values_from_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            config_path(dir.path()),
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n\n[hooks]\npreCommit = \"true\"\n",
+        )
+        .unwrap();
+        let origins: BTreeMap<&str, ConfigOrigin> = GlobalConfig::origins(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(k, o)| (k.name(), o))
+            .collect();
+        assert_eq!(origins["fee"], ConfigOrigin::File);
+        assert_eq!(origins["hooks.preCommit"], ConfigOrigin::File);
+        assert_eq!(origins["hooks.postCommit"], ConfigOrigin::Default);
+        assert_eq!(origins["output.format"], ConfigOrigin::Default);
+    }
+
+    #[test]
+    fn problems_lists_each_bad_value_in_a_loaded_config() {
+        let mut c = GlobalConfig {
+            coinset_url: "ftp://x".into(),
+            ..GlobalConfig::default()
+        };
+        c.names.insert(
+            "site".into(),
+            NamedStore {
+                id: "zz".into(),
+                path: "relative/dir".into(),
+            },
+        );
+        let problems = c.problems();
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert!(problems[0].starts_with("invalid coinset_url"));
+        assert!(problems[1].starts_with("invalid names.site.id"));
+        assert!(problems[2].starts_with("invalid names.site.path"));
+    }
+
     #[test]
     fn dig_home_honors_env_override() {
         // Restore the env var on drop so a panic cannot leak it into other
@@ -124,4 +852,27 @@ mod tests {
         assert_eq!(session_path(h), PathBuf::from("/x/.dig/session"));
         assert_eq!(config_path(h), PathBuf::from("/x/.dig/config.toml"));
     }
+
+    #[test]
+    fn profiles_live_under_profiles_and_follow_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path();
+        assert_eq!(profile_dir(home, DEFAULT_PROFILE), home);
+        assert_eq!(profile_dir(home, "work"), home.join("profiles/work"));
+        assert_eq!(list_profiles(home).unwrap(), vec![DEFAULT_PROFILE]);
+
+        std::fs::create_dir_all(home.join("profiles/work")).unwrap();
+        std::fs::create_dir_all(home.join("profiles/alt")).unwrap();
+        assert_eq!(list_profiles(home).unwrap(), ["default", "alt", "work"]);
+
+        let mut c = GlobalConfig::default();
+        c.wallet.active_profile = Some("work".into());
+        c.save(home).unwrap();
+        assert_eq!(active_profile(home).unwrap(), "work");
+        assert_eq!(seed_path(home), home.join("profiles/work/seed.enc"));
+
+        assert!(validate_profile_name("a b").is_err());
+        assert!(validate_profile_name("../x").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
 }
@@ -1,11 +1,12 @@
-use digstore_core::Bytes32;
+use digstore_core::{Bytes32, HashAlgorithm};
 use sha2::{Digest, Sha256};
 
 /// A single content-defined chunk: its raw bytes, the byte offset of its first
-/// byte within the original input, and its SHA-256 content address (paper §8.1).
+/// byte within the original input, and its SHA-256 content address (paper §8.1),
+/// or the store's [`HashAlgorithm`] via [`Chunk::with_algorithm`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Chunk {
-    /// SHA-256 of `data` — the chunk's content address.
+    /// Hash of `data` — the chunk's content address.
     pub hash: Bytes32,
     /// The raw chunk bytes.
     pub data: Vec<u8>,
@@ -16,7 +17,12 @@ pub struct Chunk {
 impl Chunk {
     /// Build a chunk from its offset and raw bytes, computing the SHA-256 address.
     pub fn new(offset: usize, data: Vec<u8>) -> Self {
-        let hash = hash_data(&data);
+        Self::with_algorithm(offset, data, HashAlgorithm::Sha256)
+    }
+
+    /// [`Chunk::new`], addressing the chunk with `algorithm`.
+    pub fn with_algorithm(offset: usize, data: Vec<u8>, algorithm: HashAlgorithm) -> Self {
+        let hash = hash_data_with(algorithm, &data);
         Chunk { hash, data, offset }
     }
 
@@ -41,6 +47,14 @@ pub fn hash_data(data: &[u8]) -> Bytes32 {
     Bytes32(out)
 }
 
+/// Content address of a byte slice under `algorithm`.
+pub fn hash_data_with(algorithm: HashAlgorithm, data: &[u8]) -> Bytes32 {
+    match algorithm {
+        HashAlgorithm::Sha256 => hash_data(data),
+        other => other.digest(data),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,16 +1,23 @@
 use crate::boundary::find_boundary;
 use crate::chunk::Chunk;
-use digstore_core::ChunkerConfig;
+use digstore_core::{ChunkerConfig, HashAlgorithm};
 
-/// A reusable content-defined chunker bound to a `ChunkerConfig`.
+/// A reusable content-defined chunker bound to a `ChunkerConfig` and the
+/// hash its chunks are addressed with (SHA-256 unless set).
 pub struct Chunker {
     config: ChunkerConfig,
+    algorithm: HashAlgorithm,
 }
 
 impl Chunker {
     /// Create a chunker with the given configuration.
     pub fn new(config: ChunkerConfig) -> Self {
-        Chunker { config }
+        Self::with_algorithm(config, HashAlgorithm::Sha256)
+    }
+
+    /// Create a chunker whose chunks are addressed with `algorithm`.
+    pub fn with_algorithm(config: ChunkerConfig, algorithm: HashAlgorithm) -> Self {
+        Chunker { config, algorithm }
     }
 
     /// The configuration this chunker uses.
@@ -18,9 +25,14 @@ impl Chunker {
         &self.config
     }
 
+    /// The hash chunks are addressed with.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
     /// Chunk a full byte slice, returning content-addressed chunks in order.
     pub fn chunk_slice(&self, data: &[u8]) -> Vec<Chunk> {
-        chunk_slice(data, &self.config)
+        chunk_slice_with(data, &self.config, self.algorithm)
     }
 }
 
@@ -31,12 +43,22 @@ impl Chunker {
 /// and every chunk except possibly the last satisfies
 /// `min_size <= len <= max_size` (paper §8.1).
 pub fn chunk_slice(data: &[u8], cfg: &ChunkerConfig) -> Vec<Chunk> {
+    chunk_slice_with(data, cfg, HashAlgorithm::Sha256)
+}
+
+/// [`chunk_slice`] with chunks addressed by `algorithm`. Boundaries depend
+/// only on the gear hash, so they are identical for every algorithm.
+pub fn chunk_slice_with(data: &[u8], cfg: &ChunkerConfig, algorithm: HashAlgorithm) -> Vec<Chunk> {
     let mut chunks = Vec::new();
     let mut start = 0usize;
     while start < data.len() {
         let end = find_boundary(data, start, cfg);
         debug_assert!(end > start, "boundary must advance");
-        chunks.push(Chunk::new(start, data[start..end].to_vec()));
+        chunks.push(Chunk::with_algorithm(
+            start,
+            data[start..end].to_vec(),
+            algorithm,
+        ));
         start = end;
     }
     chunks
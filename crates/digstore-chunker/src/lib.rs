@@ -20,7 +20,7 @@ mod chunker;
 mod config;
 mod gear;
 
-pub use chunk::{hash_data, Chunk};
-pub use chunker::{chunk_slice, chunk_stream, Chunker};
+pub use chunk::{hash_data, hash_data_with, Chunk};
+pub use chunker::{chunk_slice, chunk_slice_with, chunk_stream, Chunker};
 pub use config::{default_config, mask_for_target};
 pub use gear::GEAR_TABLE;
@@ -0,0 +1,49 @@
+use digstore_chunker::{chunk_slice, chunk_slice_with, default_config, hash_data_with, Chunker};
+use digstore_core::HashAlgorithm;
+
+fn noise(len: usize) -> Vec<u8> {
+    let mut x: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+#[test]
+fn boundaries_are_shared_and_only_addresses_change() {
+    let data = noise(1_000_000);
+    let cfg = default_config();
+    let sha = chunk_slice(&data, &cfg);
+    let b3 = Chunker::with_algorithm(cfg, HashAlgorithm::Blake3).chunk_slice(&data);
+    assert!(sha.len() > 1);
+    assert_eq!(sha.len(), b3.len());
+    for (s, b) in sha.iter().zip(&b3) {
+        assert_eq!((s.offset, &s.data), (b.offset, &b.data));
+        assert_ne!(s.hash, b.hash);
+        assert_eq!(b.hash, hash_data_with(HashAlgorithm::Blake3, &b.data));
+    }
+    assert_eq!(chunk_slice_with(&data, &cfg, HashAlgorithm::Sha256), sha);
+}
+
+#[test]
+#[ignore = "benchmark: chunk hashing throughput per algorithm; run with --ignored --nocapture"]
+fn bench_chunk_hashing_throughput() {
+    let data = noise(64 * 1024 * 1024);
+    let chunks = chunk_slice(&data, &default_config());
+    for alg in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+        let t = std::time::Instant::now();
+        for c in &chunks {
+            std::hint::black_box(hash_data_with(alg, &c.data));
+        }
+        let secs = t.elapsed().as_secs_f64();
+        eprintln!(
+            "{alg:>7}: {:>8.1} MiB/s over {} chunks",
+            data.len() as f64 / (1024.0 * 1024.0) / secs,
+            chunks.len()
+        );
+    }
+}
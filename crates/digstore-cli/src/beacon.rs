@@ -2,12 +2,14 @@
 //! stderr when a newer DigStore release exists.
 //!
 //! Contract (all enforced here):
-//!   * NEVER blocks or slows a command meaningfully — short 2s network timeout,
-//!     and only when the 24h throttle window has elapsed.
+//!   * NEVER blocks or slows a command meaningfully — short 800ms network
+//!     timeout, and only when the 24h throttle window has elapsed. Failed
+//!     checks (offline, captive portal) double that window, up to a week.
 //!   * NEVER fails a command — every error path is silent; this function returns
 //!     `()` and is called for its side effect only.
 //!   * Stays out of the way — disabled for non-TTY runs, `--json`/`--quiet`
-//!     output, and when `DIGSTORE_NO_UPDATE_CHECK=1` is set.
+//!     output, when `DIGSTORE_NO_UPDATE_CHECK=1` is set, and when
+//!     `core.checkForUpdates = false` is in `~/.dig/config.toml`.
 //!
 //! It reuses the release-resolution / version-compare / throttle logic in
 //! [`crate::commands::update`] so the beacon and the explicit command agree on
@@ -17,7 +19,6 @@ use std::io::{IsTerminal, Write};
 
 use crate::commands::update::{
     current_version, fetch_latest_release_quiet, is_newer, load_cache, now_unix, save_cache,
-    should_check, CheckCache,
 };
 
 /// Environment variable that disables the beacon entirely.
@@ -47,13 +48,17 @@ pub fn maybe_notify(json: bool, quiet: bool) {
     if !beacon_enabled(json, quiet, is_tty, disable.as_deref()) {
         return;
     }
+    if !config_enabled() {
+        return;
+    }
 
     let cache = load_cache();
     let now = now_unix();
 
-    // Within the 24h window: reuse the cached result instead of hitting the
-    // network, so repeated interactive commands stay instant.
-    if !should_check(now, cache.last_check_unix) {
+    // Within the 24h window (longer after failed checks): reuse the cached
+    // result instead of hitting the network, so repeated interactive commands
+    // stay instant.
+    if !cache.due(now) {
         if is_newer(current_version(), &cache.latest_tag) {
             print_notice(&cache.latest_tag);
         }
@@ -61,25 +66,32 @@ pub fn maybe_notify(json: bool, quiet: bool) {
     }
 
     // Throttle window elapsed: do a short, best-effort network check on a
-    // throwaway runtime. Any failure is swallowed (we still bump the timestamp
-    // so a persistent outage doesn't retry on every command).
+    // throwaway runtime. Any failure is swallowed; the timestamp still moves
+    // and the failure count backs off the next attempt, so a persistent
+    // outage doesn't retry on every command, and the last known tag is kept.
     let release = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .ok()
         .and_then(|rt| rt.block_on(fetch_latest_release_quiet()));
 
-    let latest_tag = release.map(|r| r.tag_name).unwrap_or_default();
-    save_cache(&CheckCache {
-        last_check_unix: now,
-        latest_tag: latest_tag.clone(),
-    });
+    let cache = cache.after_check(now, release.map(|r| r.tag_name));
+    save_cache(&cache);
+    let latest_tag = cache.latest_tag;
 
     if !latest_tag.is_empty() && is_newer(current_version(), &latest_tag) {
         print_notice(&latest_tag);
     }
 }
 
+/// `core.checkForUpdates` from the global config; an unreadable config
+/// leaves the check on.
+fn config_enabled() -> bool {
+    digstore_chain::config::dig_home()
+        .and_then(|home| digstore_chain::config::GlobalConfig::load(&home))
+        .map_or(true, |cfg| cfg.core.update_checks_enabled())
+}
+
 /// Print the one-line stderr notice. Best-effort; ignores write errors.
 fn print_notice(latest_tag: &str) {
     let tag = if latest_tag.starts_with('v') {
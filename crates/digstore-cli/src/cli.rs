@@ -3,6 +3,9 @@
 use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand};
+use digstore_chain::config::OutputFormat;
+
+use crate::ops::range::{ByteRange, LineRange};
 
 #[derive(Debug, Parser)]
 #[command(name = "digstore", version, about, long_about = None)]
@@ -11,8 +14,12 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub dig_dir: Option<PathBuf>,
     /// Emit machine-readable JSON instead of human-formatted output.
-    #[arg(long, global = true)]
+    #[arg(long, global = true, conflicts_with = "human")]
     pub json: bool,
+    /// Emit human-formatted output even when `output.format = "json"` is set
+    /// in `~/.dig/config.toml`.
+    #[arg(long, global = true)]
+    pub human: bool,
     /// Enable verbose (debug-level) logging.
     #[arg(short, long, global = true)]
     pub verbose: bool,
@@ -22,7 +29,11 @@ pub struct Cli {
     /// Suppress progress and hints.
     #[arg(short, long, global = true)]
     pub quiet: bool,
-    /// Operate on a specific store by name (overrides the active store).
+    /// Never draw progress bars or spinners (other output is unchanged).
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+    /// Operate on a specific store by name (overrides the active store), or by
+    /// the path of its directory when the value contains a `/`.
     #[arg(long = "store", alias = "project", global = true)]
     pub store_name: Option<String>,
     /// Operating directory for add/urn/status (overrides the store's content root).
@@ -36,10 +47,51 @@ pub struct Cli {
     /// confirmation in non-interactive mode.
     #[arg(short = 'y', long, global = true)]
     pub yes: bool,
+    /// Use this wallet profile's seed for this command (overrides the profile
+    /// chosen with `digstore wallet use`).
+    #[arg(long, global = true, value_name = "PROFILE")]
+    pub wallet_profile: Option<String>,
+    /// Break the store's writer lock before running. Only for a lock left by
+    /// a process that is hung; two live writers can corrupt the store.
+    #[arg(long, global = true)]
+    pub force_unlock: bool,
     #[command(subcommand)]
     pub command: Command,
 }
 
+impl Cli {
+    /// Resolve `--json` against `--human` and the configured `output.format`:
+    /// an explicit flag wins, then the config; `auto` means human output.
+    pub fn resolve_output_format(&mut self, configured: OutputFormat) {
+        if !self.json && !self.human {
+            self.json = configured == OutputFormat::Json;
+        }
+    }
+}
+
+impl Command {
+    /// Whether this command writes the store (staging, generations, branches
+    /// or its policy), and so must hold the store's writer lock.
+    pub fn writes_store(&self) -> bool {
+        match self {
+            Command::Add(_)
+            | Command::Commit(_)
+            | Command::Unstage(_)
+            | Command::Import(_)
+            | Command::Sign(_)
+            | Command::Squash(_)
+            | Command::Prune(_)
+            | Command::Pull(_)
+            | Command::Compile(_)
+            | Command::Deploy(_) => true,
+            Command::Access(a) => a.action.is_some(),
+            Command::Branch(a) => a.name.is_some(),
+            Command::Checkout(a) => a.out.is_none() && a.to_tar.is_none(),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Start a new store from a template — free, no wallet, no spend.
@@ -64,7 +116,41 @@ pub enum Command {
     Log(LogArgs),
     /// Show what changed between two published versions.
     Diff(DiffArgs),
-    /// Save a published capsule's files into a local folder.
+    /// Export a deployment's canonical JSON manifest (every file, leaf and chunk).
+    Manifest(ManifestArgs),
+    /// Write the versions committed since a root to a delta bundle (incremental backup).
+    Export(ExportArgs),
+    /// Apply a delta bundle from `digstore export` onto this store.
+    Import(ImportArgs),
+    /// Show one published file's size, retrieval key and chunk layout.
+    Stat(StatArgs),
+    /// Show how much space the store's versions take, before and after dedup.
+    Size(SizeArgs),
+    /// Search the text files of a published version for a regular expression.
+    Grep(GrepArgs),
+    /// Find the files, in every version, that a chunk or file hash belongs to.
+    Whereis(WhereisArgs),
+    /// List the versions where one file's content changed.
+    FileHistory(FileHistoryArgs),
+    /// Show which version introduced each line of a text file.
+    Blame(BlameArgs),
+    /// Collapse a run of local versions into the last one and free their chunks.
+    Squash(SquashArgs),
+    /// Remove old local versions (by date, id, or keeping the newest N) and free their chunks.
+    Prune(PruneArgs),
+    /// Preview re-encrypting the current version under a new secret salt.
+    RotateSalt(RotateSaltArgs),
+    /// Sign a published version's root with your wallet key.
+    Sign(SignArgs),
+    /// Prove every published file of a version against its root.
+    Verify(VerifyArgs),
+    /// Check a version's root signatures and show who signed it.
+    VerifySignature(VerifySignatureArgs),
+    /// Show or change which wallet keys may write to the store.
+    Access(AccessArgs),
+    /// List branches, or create one at the current (or a given) version.
+    Branch(BranchArgs),
+    /// Save a published capsule's files into a local folder, or switch branch.
     Checkout(CheckoutArgs),
     /// Read a published file by its share link (URN) or retrieval key.
     Cat(CatArgs),
@@ -93,14 +179,24 @@ pub enum Command {
     Staged(StagedArgs),
     /// Print the URN(s) for staged or committed resources.
     Urn(UrnArgs),
+    /// Derive the storage address and encryption key for one or many URNs.
+    Keygen(KeygenArgs),
+    /// Decrypt a resource's sealed bytes (e.g. from `cat <retrieval-key>`) by its URN.
+    Decrypt(DecryptArgs),
     /// List the retrieval key (and URN) for every committed resource.
     Keys(KeysArgs),
+    /// List the files in a deployment (paths, sizes, or a tree).
+    Ls(LsArgs),
     /// Update DigStore to the latest release.
     Update(UpdateArgs),
     /// Manage the encrypted wallet seed in ~/.dig.
     Seed(SeedArgs),
     /// Lock the seed (clear the cached-unlock session).
     Lock(LockArgs),
+    /// Manage named wallet profiles, each with its own seed.
+    Wallet(WalletArgs),
+    /// Read, set, or check the global settings in ~/.dig/config.toml.
+    Config(ConfigArgs),
     /// Resume or inspect the store's on-chain anchor.
     Anchor(AnchorArgs),
     /// Show wallet XCH + DIG balance.
@@ -451,8 +547,10 @@ pub struct DevArgs {
 #[command(
     after_help = "Runs pre-publish checks so a costly on-chain publish doesn't fail halfway: is \
 your seed present + unlocked, do you have enough $DIG + XCH for a publish, are you logged in to \
-DIGHUb, is the default remote reachable, and does your content directory exist. Prints each as \
-pass/fail and exits non-zero if any hard check fails.\n\nEXAMPLES:\n  digstore doctor\n  digstore \
+DIGHUb, is the default remote reachable, and does your content directory exist. Also checks the \
+environment: the .dig workspace is writable, store configs parse, there is free disk space, and \
+this binary is the latest release. Prints each as pass/warn/fail with a fix, and exits non-zero if \
+any hard check fails (warnings never fail).\n\nEXAMPLES:\n  digstore doctor\n  digstore \
 doctor --json"
 )]
 pub struct DoctorArgs {}
@@ -485,7 +583,7 @@ pub struct InitArgs {
 
 #[derive(Debug, Args)]
 #[command(
-    after_help = "EXAMPLES:\n  digstore add file.txt\n  digstore add -A\n  digstore add . src/*.rs\n  digstore add logo.png --key assets/logo.png"
+    after_help = "EXAMPLES:\n  digstore add file.txt\n  digstore add -A\n  digstore add . src/*.rs\n  digstore add logo.png --key assets/logo.png\n  git ls-files | digstore add --from-stdin\n  find . -name '*.html' -print0 | digstore add --from-stdin -0\n  ./build-report | digstore add --stdin-as reports/latest.html\n  digstore add --tar site.tar.gz\n  curl -sL $URL | digstore add --tar -"
 )]
 pub struct AddArgs {
     /// Files, directories, or glob patterns to stage (relative to the store root).
@@ -502,6 +600,25 @@ pub struct AddArgs {
     /// Stage the /.well-known/dig/manifest.json discovery manifest.
     #[arg(long)]
     pub discovery: bool,
+    /// Also read paths to stage from stdin, one per line.
+    #[arg(long)]
+    pub from_stdin: bool,
+    /// With --from-stdin, paths are NUL-separated instead (as from `find -print0`),
+    /// so names containing newlines survive.
+    #[arg(short = '0', long = "null", requires = "from_stdin")]
+    pub null: bool,
+    /// Stage stdin itself as the file at this path (e.g. a command's output), with no
+    /// file on disk.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["all", "from_stdin", "key", "discovery", "paths"])]
+    pub stdin_as: Option<String>,
+    /// Stage the regular files in a tar or .tar.gz archive (`-` reads stdin)
+    /// under their in-archive paths, without extracting it. Symlinks and
+    /// hardlinks are skipped with a warning.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["all", "from_stdin", "key", "discovery", "paths", "stdin_as", "dry_run"])]
+    pub tar: Option<PathBuf>,
+    /// Stage even when the store's disk looks too full for the new bytes.
+    #[arg(long)]
+    pub force: bool,
 }
 
 #[derive(Debug, Args)]
@@ -516,15 +633,64 @@ hub Teams \"Deployer\" flow pre-authorizes it). This command advances the root,
 --writer-key. Env: DIGSTORE_WRITER_KEY.\n  --deploy-key  (a DIFFERENT key) the §21 HUB HEAD-PUSH \
 key — lets DIGHUb ACCEPT the capsule; used by `digstore deploy`, NOT here. From `digstore \
 deploy-key export`. Env: DIGSTORE_DEPLOY_KEY.\n\nEXAMPLES:\n  digstore commit -m \"first \
-version\"\n  digstore commit --dry-run\n  digstore commit -m deploy --writer-key $DIGSTORE_WRITER_KEY"
+version\"\n  digstore commit --dry-run\n  digstore commit --allow-empty -m \"release 1.2 checkpoint\"\n  digstore commit --changes-only -m \"hotfix layer\"\n  digstore commit --no-verify -m \"skip the lint hook\"\n  digstore commit --deterministic --date 2024-05-01T00:00:00Z -m \"v1.0\"\n  digstore commit -m deploy --writer-key $DIGSTORE_WRITER_KEY"
 )]
 pub struct CommitArgs {
     #[arg(short, long)]
     pub message: Option<String>,
+    /// Who to record as the author in `digstore log` (default: your dighub
+    /// `@handle` when logged in). Local only; never published.
+    #[arg(long)]
+    pub author: Option<String>,
+    /// Record this as the commit's date instead of now (e.g. when importing
+    /// history): RFC 3339 (`2024-05-01T12:00:00+02:00`), Unix seconds, or
+    /// git's `@<seconds> ±HHMM`. The offset is kept. Unparseable input is an
+    /// error.
+    #[arg(long, value_name = "DATE", value_parser = crate::ops::date::parse_commit_date)]
+    pub date: Option<crate::ops::date::CommitDate>,
+    /// Make the commit reproducible: record `--date` as the generation's
+    /// timestamp instead of the clock, so the same files committed at the same
+    /// point in a store's history write the same root and a byte-identical
+    /// generation manifest, whatever the message, author or add order.
+    #[arg(long, requires = "date")]
+    pub deterministic: bool,
     /// Preview the resulting version (root) + exact DIG/XCH cost WITHOUT spending,
     /// anchoring, or finalizing anything. Nothing is published.
     #[arg(long)]
     pub dry_run: bool,
+    /// Commit even when nothing changed: with nothing staged, record a
+    /// checkpoint of the current version (same root, new entry in `log` with
+    /// its own message). The chain already holds that root, so a checkpoint is
+    /// local-only: never anchored, nothing spent.
+    #[arg(long)]
+    pub allow_empty: bool,
+    /// Record only the staged files as a layer on top of the current version
+    /// instead of a version of its own: reads at the new root of any file it
+    /// does not hold fall through to the version it was committed on (and on
+    /// up that chain), and `ls`, `grep` and `export` show that whole chain.
+    /// Useful for delta bundles. The layer is LOCAL-ONLY: its module holds
+    /// only the staged files and a remote cannot fall through to the parent,
+    /// so it is never anchored (nothing is spent) and `push` and `serve`
+    /// refuse it.
+    #[arg(
+        long,
+        visible_alias = "no-cumulative",
+        conflicts_with_all = ["allow_empty", "push"]
+    )]
+    pub changes_only: bool,
+    /// Skip the pre-commit and post-commit hooks (`[hooks]` in `dig.toml` or
+    /// the global config).
+    #[arg(long)]
+    pub no_verify: bool,
+    /// Commit even when the store's disk looks too full for the new chunks
+    /// and module.
+    #[arg(long)]
+    pub force: bool,
+    /// Show the sealing progress bar (bytes, MB/s, ETA) even when not attached to a
+    /// terminal. It is drawn on stderr; `--quiet`, `--json` and `--no-progress` still
+    /// hide it.
+    #[arg(long)]
+    pub progress: bool,
     /// Seconds to wait for on-chain confirmation (default 300; 0 = a single
     /// check, do not block). On a timeout the local generation is NOT finalized
     /// and a resumable pending anchor is left; re-run `digstore commit` to finish.
@@ -610,14 +776,54 @@ pub struct CompileArgs {
 }
 
 #[derive(Debug, Args)]
-#[command(after_help = "EXAMPLES:\n  digstore status")]
-pub struct StatusArgs {}
+#[command(
+    after_help = "EXAMPLES:\n  digstore status\n  digstore status --ignored\n  digstore status --porcelain\n  digstore status --porcelain=v2\n\nPORCELAIN (stable across releases; one record per line, sorted by path):\n  v1  <XY> <path>\n  v2  # porcelain v2\n      # root <root hex, or ->\n      <XY> <size> <sha256> <path>\n\n  A.  staged, new            M.  staged, replacing a committed file\n  AM  staged, changed since add    AD  staged, deleted since add\n  .M  modified, not staged   ??  untracked   !!  ignored (--ignored)"
+)]
+pub struct StatusArgs {
+    /// Also list the paths `add -A` skips, with the ignore pattern and file
+    /// responsible for each.
+    #[arg(long)]
+    pub ignored: bool,
+    /// Print a stable, line-per-file format for scripts (v1 when no version
+    /// is given; v2 adds each file's size and SHA-256).
+    #[arg(
+        long,
+        value_enum,
+        value_name = "VERSION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "v1"
+    )]
+    pub porcelain: Option<crate::commands::status::Porcelain>,
+}
 
 #[derive(Debug, Args)]
-#[command(after_help = "EXAMPLES:\n  digstore log\n  digstore log --limit 10")]
+#[command(
+    after_help = "EXAMPLES:\n  digstore log\n  digstore log --limit 10\n  digstore log --author alice --since 2024-05-01\n  digstore log --grep '^fix' --until 2024-06-01T00:00:00Z\n  digstore log --graph\n\nFilters combine (all must match) and apply before --limit. Dates are YYYY-MM-DD\n(midnight UTC), an RFC 3339 time, or Unix seconds."
+)]
 pub struct LogArgs {
     #[arg(short, long)]
     pub limit: Option<usize>,
+    /// Only deployments whose author contains this text.
+    #[arg(long)]
+    pub author: Option<String>,
+    /// Only deployments whose commit message matches this regex.
+    #[arg(long, value_name = "REGEX", value_parser = parse_regex)]
+    pub grep: Option<regex::Regex>,
+    /// Only deployments made at or after this date.
+    #[arg(long, value_name = "DATE", value_parser = crate::ops::date::parse_date)]
+    pub since: Option<u64>,
+    /// Only deployments made at or before this date.
+    #[arg(long, value_name = "DATE", value_parser = crate::ops::date::parse_date)]
+    pub until: Option<u64>,
+    /// Draw the deployments as a graph, one node per deployment joined to its
+    /// parent, with gaps where deployments are not shown.
+    #[arg(long)]
+    pub graph: bool,
+}
+
+fn parse_regex(s: &str) -> Result<regex::Regex, String> {
+    regex::Regex::new(s).map_err(|e| e.to_string())
 }
 
 #[derive(Debug, Args)]
@@ -628,32 +834,197 @@ pub struct DiffArgs {
 }
 
 #[derive(Debug, Args)]
-#[command(after_help = "EXAMPLES:\n  digstore checkout <root> --out ./out")]
+#[command(
+    after_help = "EXAMPLES:\n  digstore squash <rootA> <rootB> --dry-run\n  digstore squash <rootA> <rootB>\n\nEvery version from <from> up to (not including) <to> is removed from the local\nhistory; <to> keeps its root and content. On-chain anchors are not touched."
+)]
+pub struct SquashArgs {
+    /// The oldest version to drop.
+    pub from: String,
+    /// The version the range collapses into (kept).
+    pub to: String,
+    /// Report what would be dropped and freed without changing anything.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore rotate-salt --dry-run\n\nA rotation re-encrypts every file the current version serves under a new secret\nsalt and writes the result as a new version, which has to be published like a\ncommit. Only the preview is available from the CLI; older versions keep the\nsalt they were sealed under."
+)]
+pub struct RotateSaltArgs {
+    /// Report how many files and chunks a rotation would re-encrypt, without changing anything.
+    #[arg(long, required = true)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore prune --keep-last 10 --dry-run\n  digstore prune --before 2024-01-01\n  digstore prune --before-id 40 --keep-last 5\n\nWith several cutoffs a version is removed only when it matches all of them.\nThe current version is always kept. On-chain anchors are not touched."
+)]
+pub struct PruneArgs {
+    /// Remove versions committed before this date (YYYY-MM-DD, RFC 3339, or Unix seconds).
+    #[arg(long, value_name = "DATE", value_parser = crate::ops::date::parse_date, conflicts_with = "before_id")]
+    pub before: Option<u64>,
+    /// Remove versions whose deployment id (see `digstore log`) is below this one.
+    #[arg(long, value_name = "ID")]
+    pub before_id: Option<u64>,
+    /// Keep the newest N versions.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub keep_last: Option<u64>,
+    /// Report what would be removed and freed without changing anything.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore access\n  digstore access allow\n  digstore access allow <public-key>\n  digstore access revoke <public-key>\n  digstore access clear\n\nWith no policy any wallet may write. Once one is set, `add` and `commit` refuse\nwallets it does not list, and only a listed wallet can change it. `allow`\nwithout a key adds your own wallet's key."
+)]
+pub struct AccessArgs {
+    /// `allow`, `revoke` or `clear`; omitted to show the current policy.
+    #[command(subcommand)]
+    pub action: Option<AccessAction>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AccessAction {
+    /// Let a wallet key write to the store, starting a policy if there is none.
+    Allow {
+        /// BLS public key (96 hex); defaults to your wallet's key.
+        public_key: Option<String>,
+    },
+    /// Stop a wallet key from writing to the store.
+    Revoke {
+        /// BLS public key (96 hex).
+        public_key: String,
+    },
+    /// Drop the policy so any wallet may write again.
+    Clear,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore sign\n  digstore sign <root>\n\nThe signature covers the root and this store's id and is kept beside the\nversion locally; signing again with the same wallet replaces it."
+)]
+pub struct SignArgs {
+    /// Version root to sign (hex); defaults to the current root.
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore verify --all\n  digstore verify --all --at <root>\n  digstore verify --all --json\n\nEach file's content is re-hashed from its stored chunks and its merkle proof\nchecked against the version root. Exits 5 when any file fails."
+)]
+pub struct VerifyArgs {
+    /// Verify every file of the version.
+    #[arg(long, required = true)]
+    pub all: bool,
+    /// Deployment root to verify (hex); defaults to the current root.
+    #[arg(long, visible_alias = "at")]
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore verify-signature <root>\n  digstore verify-signature <root> --pubkey <hex>\n  digstore verify-signature <root> --json\n\nExits 5 when no recorded signature (or none by --pubkey) verifies."
+)]
+pub struct VerifySignatureArgs {
+    /// Version root to check (hex).
+    pub root: String,
+    /// Only accept a signature by this BLS public key (48-byte hex).
+    #[arg(long)]
+    pub pubkey: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore branch\n  digstore branch experiment\n  digstore branch hotfix --at <root>\n  digstore checkout experiment\n\nA commit advances only the current branch; `digstore log` still lists every\nversion of every branch."
+)]
+pub struct BranchArgs {
+    /// Create a branch with this name (lists branches when omitted).
+    pub name: Option<String>,
+    /// Start the new branch at this version root (hex) instead of the current one.
+    #[arg(long, value_name = "ROOT", requires = "name")]
+    pub at: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore checkout <root> --out ./out\n  digstore checkout experiment --out ./out\n  digstore checkout <root> --to-tar release.tar\n  digstore checkout <root> --to-tar - | tar -tv\n  digstore checkout experiment\n\nWithout --out or --to-tar, <root> names a branch to switch to; later commits advance it."
+)]
 pub struct CheckoutArgs {
+    /// Version root (hex) or branch name.
     pub root: String,
+    /// Write the version's files here.
     #[arg(long, short)]
-    pub out: PathBuf,
+    pub out: Option<PathBuf>,
+    /// Write the version as a tar archive to this file (`-` for stdout) instead
+    /// of a directory. Files are streamed chunk by chunk, sorted by path.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["out", "salt"])]
+    pub to_tar: Option<PathBuf>,
     #[arg(long)]
     pub salt: Option<String>,
 }
 
 #[derive(Debug, Args)]
 #[command(
-    after_help = "EXAMPLES:\n  digstore cat urn:dig:chia:<storeID>:<root>/readme\n  digstore cat urn:dig:chia:<storeID>/logo.png --out logo.png\n  digstore cat <64-hex-retrieval-key> --out blob.enc"
+    after_help = "EXAMPLES:\n  digstore cat urn:dig:chia:<storeID>:<root>/readme\n  digstore cat urn:dig:chia:<storeID>/logo.png --out logo.png\n  digstore cat urn:dig:chia:<storeID>/logo.png > logo.png\n  digstore cat urn:dig:chia:<storeID>/video.mp4 --bytes 0-1023\n  digstore cat urn:dig:chia:<storeID>/log.txt --lines 100:120 --number\n  digstore cat 'urn:dig:chia:<storeID>/docs/**/*.md' --out-dir docs\n  digstore cat urn:dig:chia:<storeID>:<root> --batch paths.txt --out-dir restore\n  digstore cat <64-hex-retrieval-key> --out blob.enc\n  digstore cat docs/readme.md --at HEAD~2\n  digstore cat <64-hex-named-file> --at main\n  digstore cat docs/readme.md --all-versions --out-dir versions\n\nOutput is written byte for byte. Binary content is not printed to a terminal\nunless you pass --out -; with --json it is base64-encoded.\n\nBYTE RANGES (--bytes):\n  START-END   bytes START through END, inclusive, counting from 0\n  START-      from START to the end\n  -N          the last N bytes\n  A%-B%       from A to B percent of the file's length (0-100)\n  last:N      the last N bytes, same as -N"
 )]
 pub struct CatArgs {
     /// A `urn:dig:…` (streamed out DECRYPTED) or a 64-char hex retrieval key
     /// (streamed out as RAW ENCRYPTED bytes, resolved within the active store).
+    /// With --at, a resource path in the active store instead.
     pub urn: String,
-    /// Write output to this file instead of stdout.
+    /// Read the argument as a resource path at this version: a root, a unique
+    /// root prefix, a branch, HEAD or HEAD~N. A path that looks like a hash or
+    /// a URN is still taken as a path.
+    #[arg(long, value_name = "REF", conflicts_with = "batch")]
+    pub at: Option<String>,
+    /// Write output to this file instead of stdout (`-` forces stdout, even
+    /// for binary content on a terminal).
     #[arg(long, short)]
     pub out: Option<PathBuf>,
+    /// Write each matched resource under this directory at its resource key
+    /// (for a glob URN such as `urn:dig:chia:<storeID>/src/**/*.rs`).
+    #[arg(long, value_name = "DIR", conflicts_with = "out")]
+    pub out_dir: Option<PathBuf>,
+    /// Restore the resource keys listed in this file (one per line; blank lines
+    /// and `#` comments skipped) from the URN's root into --out-dir.
+    #[arg(long, value_name = "LISTFILE", requires = "out_dir")]
+    pub batch: Option<PathBuf>,
     /// Decryption salt (32-byte hex) for a private store.
     #[arg(long)]
     pub salt: Option<String>,
     /// Verify the resource's merkle proof against the trusted root before output.
     #[arg(long)]
     pub verify_proof: bool,
+    /// Output only this byte range: START-END (inclusive), START-, -N (last N),
+    /// A%-B% (a share of the length) or last:N.
+    #[arg(
+        long,
+        value_name = "RANGE",
+        conflicts_with = "lines",
+        allow_hyphen_values = true
+    )]
+    pub bytes: Option<ByteRange>,
+    /// Output only these lines: START:END (1-based, inclusive), START:, or :END.
+    #[arg(long, value_name = "START:END")]
+    pub lines: Option<LineRange>,
+    /// Number the output lines (numbering follows --lines when given).
+    #[arg(long, short = 'n')]
+    pub number: bool,
+    /// Reject a URN whose store ID or root hash is not exactly 64 lowercase hex chars.
+    #[arg(long)]
+    pub strict: bool,
+    /// Write every distinct version of the resource path into --out-dir as
+    /// `<path>.<shorthash>`, with a `versions.json` mapping each to its
+    /// hash, root and timestamp.
+    #[arg(
+        long,
+        requires = "out_dir",
+        conflicts_with_all = ["at", "batch", "bytes", "lines", "number"]
+    )]
+    pub all_versions: bool,
 }
 
 #[derive(Debug, Args)]
@@ -704,6 +1075,9 @@ pub struct PullArgs {
     /// the resource key's last path segment in the current directory).
     #[arg(long, short)]
     pub out: Option<PathBuf>,
+    /// Reject a URN whose store ID or root hash is not exactly 64 lowercase hex chars.
+    #[arg(long)]
+    pub strict: bool,
 }
 
 #[derive(Debug, Args)]
@@ -727,7 +1101,7 @@ pub struct RevokeArgs {
 
 #[derive(Debug, Args)]
 #[command(
-    after_help = "Runs a dig:// remote NODE for the active store: serves clone/pull/push\nover the §21 protocol (the same one rpc.dig.net speaks), so anyone can host\nan origin. Every request must be authenticated by a signed message from the\ncaller's identity key (§21.9).\n\nEXAMPLES:\n  digstore serve --bind 0.0.0.0:8443\n  digstore serve --store site --bind 127.0.0.1:9000"
+    after_help = "Runs a dig:// remote NODE for the active store: serves clone/pull/push\nover the §21 protocol (the same one rpc.dig.net speaks), so anyone can host\nan origin. Every request must be authenticated by a signed message from the\ncaller's identity key (§21.9). Chunks are served with HTTP range support at\n/stores/<id>/chunks/<hash>; GET /health is an unauthenticated liveness probe.\n\nEXAMPLES:\n  digstore serve --bind 0.0.0.0:8443\n  digstore serve --store site --bind 127.0.0.1:9000\n  digstore serve --anonymous --read-only --token-file ./serve.token"
 )]
 pub struct ServeArgs {
     /// Address to bind the node to (host:port).
@@ -737,11 +1111,49 @@ pub struct ServeArgs {
     /// Off by default — the node requires a signed request from every caller.
     #[arg(long)]
     pub anonymous: bool,
+    /// Refuse pushes and tombstones: serve only what is already committed.
+    #[arg(long)]
+    pub read_only: bool,
+    /// Also require `Authorization: Bearer <token>` on every store request, with
+    /// the token read from this file (default: `$DIGSTORE_SERVE_TOKEN`, if set).
+    #[arg(long, value_name = "PATH")]
+    pub token_file: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
-#[command(after_help = "EXAMPLES:\n  digstore stores")]
-pub struct StoresArgs {}
+#[command(
+    after_help = "EXAMPLES:\n  digstore stores\n  digstore stores add-name blog <store-id>\n  digstore stores list-names\n  digstore stores info --config --paths\n  digstore stores info blog --json\n  digstore --store blog log\n\nA registered name works with --store from any directory; names of stores in\nthe current workspace take precedence. `stores info --json` always emits the\nfull object, config and paths included."
+)]
+pub struct StoresArgs {
+    /// Manage machine-wide store names; omitted to list this workspace's stores.
+    #[command(subcommand)]
+    pub action: Option<StoresAction>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StoresAction {
+    /// Register a workspace store under a name usable from any directory.
+    AddName {
+        name: String,
+        /// Store id (64 hex) of a store in this workspace.
+        store_id: String,
+    },
+    /// List the registered store names.
+    ListNames,
+    /// Forget a registered store name (the store itself is untouched).
+    RemoveName { name: String },
+    /// Show one store's id, versions, size, chunking, encryption and paths.
+    Info {
+        /// Workspace store name; defaults to the active store.
+        name: Option<String>,
+        /// Also show the store's configuration (chunking, hashing, encryption).
+        #[arg(long)]
+        config: bool,
+        /// Also show every resolved on-disk path.
+        #[arg(long)]
+        paths: bool,
+    },
+}
 
 #[derive(Debug, Args)]
 #[command(after_help = "EXAMPLES:\n  digstore use site")]
@@ -760,8 +1172,29 @@ pub struct DirArgs {
 pub struct UnstageArgs {}
 
 #[derive(Debug, Args)]
-#[command(after_help = "EXAMPLES:\n  digstore staged")]
-pub struct StagedArgs {}
+#[command(
+    after_help = "EXAMPLES:\n  digstore staged\n  digstore staged --estimate\n  digstore staged diff\n  digstore staged diff --stat\n  digstore staged diff --json"
+)]
+pub struct StagedArgs {
+    /// `diff` to compare staging with the current version; omitted to list
+    /// what is staged.
+    #[command(subcommand)]
+    pub action: Option<StagedAction>,
+    /// Also estimate what committing would add to disk: new chunks and their
+    /// sealed bytes, after deduplication against every stored version.
+    #[arg(long)]
+    pub estimate: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StagedAction {
+    /// Show which files the next commit adds, modifies and deletes.
+    Diff {
+        /// Print only the totals.
+        #[arg(long)]
+        stat: bool,
+    },
+}
 
 #[derive(Debug, Args)]
 #[command(
@@ -775,6 +1208,39 @@ pub struct UrnArgs {
     pub root: Option<String>,
 }
 
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore cat <retrieval-key> --out blob.enc\n  digstore decrypt blob.enc --urn urn:dig:chia:<storeID>/logo.png --out logo.png\n\nWHICH KEY OPENS WHAT:\n  The storage address (retrieval key) is SHA-256 of the rootless URN. It only\n  locates the sealed bytes and cannot decrypt them.\n  The content key is derived from the same URN (plus the secret salt of a\n  private store) and opens every chunk. `digstore keygen <urn>` prints both.\n\nChunk boundaries come from this store's manifest; without it the blob must be\na single chunk."
+)]
+pub struct DecryptArgs {
+    /// File holding the sealed bytes, as written by `cat <retrieval-key> --out`.
+    pub input: PathBuf,
+    /// The resource's URN (`urn:dig:chia:<storeID>[:<root>]/<path>`).
+    #[arg(long)]
+    pub urn: String,
+    /// Decryption salt (32-byte hex) for a private store.
+    #[arg(long)]
+    pub salt: Option<String>,
+    /// Write the plaintext to this file instead of stdout.
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore keygen urn:dig:chia:<store>/index.html\n  digstore urn -A --json | jq -r '.[].urn' | digstore keygen --from-stdin --json\n  digstore keygen --urns-file urns.txt --json\n\nOne URN per line in --urns-file or on stdin; blank lines and # comments are\nskipped. Output follows input order: storage address (the retrieval key of\nthe rootless URN) and the AES-256 content key, as hex. URNs of this store use\nits secret salt when it is private."
+)]
+pub struct KeygenArgs {
+    /// URNs to derive keys for.
+    pub urns: Vec<String>,
+    /// Also read URNs, one per line, from stdin.
+    #[arg(long)]
+    pub from_stdin: bool,
+    /// Also read URNs, one per line, from this file.
+    #[arg(long, value_name = "FILE")]
+    pub urns_file: Option<PathBuf>,
+}
+
 #[derive(Debug, Args)]
 #[command(
     after_help = "EXAMPLES:\n  digstore keys\n  digstore keys --root <hex>\n  digstore keys --json"
@@ -785,6 +1251,143 @@ pub struct KeysArgs {
     pub root: Option<String>,
 }
 
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore ls\n  digstore ls --long\n  digstore ls --tree --root <hex>\n  digstore ls --json"
+)]
+pub struct LsArgs {
+    /// Deployment root to list (hex); defaults to the current root.
+    #[arg(long, visible_alias = "at")]
+    pub root: Option<String>,
+    /// Show size (bytes), chunk count and short retrieval key per file.
+    #[arg(long, short = 'l', conflicts_with = "tree")]
+    pub long: bool,
+    /// Show the files as a directory tree.
+    #[arg(long)]
+    pub tree: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore manifest > snapshot.json\n  digstore manifest --at <root> --out snapshot.json\n\nThe output is canonical: files sorted by path, fixed field order, so two exports\nof the same root are byte-identical and can be hashed or signed as a whole."
+)]
+pub struct ManifestArgs {
+    /// Deployment root to export (hex); defaults to the current root.
+    #[arg(long, visible_alias = "at")]
+    pub root: Option<String>,
+    /// Write the manifest to this file instead of stdout.
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore export --since <root> --output delta.dig\n  digstore import delta.dig\n\nThe bundle holds every version committed after <root> and only the chunks no\nversion up to <root> stores. Import it on a copy of the store whose latest\nversion is <root>."
+)]
+pub struct ExportArgs {
+    /// Base root (hex): export the versions committed after it.
+    #[arg(long, value_name = "ROOT")]
+    pub since: String,
+    /// Write the bundle to this file.
+    #[arg(long, short)]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore import delta.dig\n\nFails unless this store's latest version is the bundle's base root. Every\nimported version is rebuilt from its chunks and checked against its root."
+)]
+pub struct ImportArgs {
+    /// Delta bundle written by `digstore export --since`.
+    pub bundle: PathBuf,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore stat index.html\n  digstore stat assets/app.js --at <root>\n  digstore stat logo.png --json\n  digstore stat logo.png --metadata"
+)]
+pub struct StatArgs {
+    /// Resource key (path) to describe.
+    pub path: String,
+    /// Deployment root to read (hex); defaults to the current root.
+    #[arg(long, visible_alias = "at")]
+    pub root: Option<String>,
+    /// Also sniff the content type from the first chunk: MIME type, whether
+    /// it is binary, and a text encoding guess.
+    #[arg(long)]
+    pub metadata: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore size\n  digstore size --layers\n  digstore size --efficiency --json\n  digstore size --detailed --at <root>\n\nStored sizes are sealed chunk bytes; chunks shared between versions are\ncounted once in the on-disk total."
+)]
+pub struct SizeArgs {
+    /// Also show one row per version (logical and stored bytes, chunk counts).
+    #[arg(long)]
+    pub layers: bool,
+    /// Also show the bytes deduplication saved, split into repeats within a
+    /// version and chunks shared across versions.
+    #[arg(long)]
+    pub efficiency: bool,
+    /// Also show the distribution of chunk sizes (min, max, mean, median and
+    /// a power-of-two histogram), each distinct chunk counted once.
+    #[arg(long)]
+    pub detailed: bool,
+    /// Limit --detailed to one version (root hex); defaults to every version.
+    #[arg(long, requires = "detailed")]
+    pub at: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore grep TODO\n  digstore grep -n 'fn main' --at <root>\n  digstore grep -l 'api[_-]key'\n\nFiles with a NUL byte near the start are treated as binary and skipped\nunless --text is given."
+)]
+pub struct GrepArgs {
+    /// Regular expression to search for.
+    #[arg(value_parser = parse_regex)]
+    pub pattern: regex::Regex,
+    /// Deployment root to search (hex); defaults to the current root.
+    #[arg(long, visible_alias = "at")]
+    pub root: Option<String>,
+    /// Print only the paths of files with a match.
+    #[arg(short = 'l', long)]
+    pub files_with_matches: bool,
+    /// Prefix each matching line with its line number.
+    #[arg(short = 'n', long)]
+    pub line_number: bool,
+    /// Search binary files as if they were text.
+    #[arg(short = 'a', long)]
+    pub text: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore whereis <chunk-hash>\n  digstore whereis <file-hash> --json\n\nThe hash may be a chunk's content hash, a file's merkle leaf, or a file's\nretrieval key; each match says which it was."
+)]
+pub struct WhereisArgs {
+    /// The hash to look up (64 hex characters).
+    pub hash: String,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore file-history index.html\n  digstore file-history assets/app.js --json\n\nOne line per version where the file was added or its content changed, oldest\nfirst; the hash is the file's merkle leaf in that version."
+)]
+pub struct FileHistoryArgs {
+    /// The file's path (resource key) in the store.
+    pub path: String,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore blame index.html\n  digstore blame src/app.js --json\n\nEach line of the file's newest version, prefixed with the short root, author\nand date of the version that introduced it. Binary files are refused."
+)]
+pub struct BlameArgs {
+    /// The file's path (resource key) in the store.
+    pub path: String,
+}
+
 #[derive(Debug, Args)]
 pub struct SeedArgs {
     #[command(subcommand)]
@@ -807,6 +1410,18 @@ pub enum SeedAction {
     },
     /// Show whether a seed exists and is currently unlocked.
     Status,
+    /// Print the seed's mnemonic so it can be backed up.
+    #[command(
+        after_help = "EXAMPLES:\n  digstore seed export --show-mnemonic\n  digstore seed export --show-mnemonic --force-print --json\n\nAsks for confirmation first (--yes skips it). Without a terminal it refuses\nunless --force-print is given, so a script cannot leak the mnemonic into a\nlog by accident. Restore it with `digstore seed import`."
+    )]
+    Export {
+        /// Required: acknowledge that the mnemonic is printed in plain text.
+        #[arg(long)]
+        show_mnemonic: bool,
+        /// Print even when not attached to a terminal (scripts, pipes, CI).
+        #[arg(long)]
+        force_print: bool,
+    },
 }
 
 /// clap value parser for `--dig-amount`: a human DIG decimal string (max 3 dp) →
@@ -827,6 +1442,67 @@ fn parse_word_count(s: &str) -> Result<usize, String> {
     }
 }
 
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore wallet create work\n  digstore wallet create ci --mnemonic \"<24 words>\"\n  digstore wallet use work\n  digstore wallet list --json\n  digstore --wallet-profile ci balance\n  digstore wallet delete ci\n\nThe `default` profile is the seed in ~/.dig; other profiles live in\n~/.dig/profiles/<name>/. `seed` and `lock` act on the active profile."
+)]
+pub struct WalletArgs {
+    #[command(subcommand)]
+    pub action: WalletAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WalletAction {
+    /// List profiles, marking the active one.
+    List,
+    /// Create a profile with a new (or imported) seed, unlocked.
+    Create {
+        /// Profile name (letters, digits, '.', '_', '-').
+        name: String,
+        /// Import this mnemonic instead of generating one.
+        #[arg(long)]
+        mnemonic: Option<String>,
+        /// Word count for a generated mnemonic (12/15/18/21/24).
+        #[arg(long, default_value_t = 24, value_parser = parse_word_count)]
+        words: usize,
+    },
+    /// Make a profile the active one.
+    Use {
+        /// Profile name.
+        name: String,
+    },
+    /// Delete a profile and its seed (not the active or default profile).
+    Delete {
+        /// Profile name.
+        name: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(
+    after_help = "EXAMPLES:\n  digstore config output.format\n  digstore config output.format json\n  digstore config unlock_ttl 600\n  digstore config --unset hooks.preCommit\n  digstore config --validate\n  digstore config --list --show-origin\n  DIGSTORE_FEE=100 digstore commit\n\nKeys: coinset_url, unlock_ttl, fee, core.excludesFile, core.checkForUpdates,\noutput.format, wallet.active_profile, hooks.preCommit, hooks.postCommit.\nA value is checked before it is written; a bad one leaves config.toml unchanged.\nDIGSTORE_<KEY> (the key in upper snake case, e.g. DIGSTORE_HOOKS_PRE_COMMIT)\noverrides a key for one process: environment > config.toml > default."
+)]
+pub struct ConfigArgs {
+    /// Setting to read or write (e.g. `output.format`).
+    #[arg(required_unless_present_any = ["validate", "list"])]
+    pub key: Option<String>,
+    /// New value; omitted to print the current one.
+    #[arg(conflicts_with = "unset")]
+    pub value: Option<String>,
+    /// Reset the setting to its default.
+    #[arg(long)]
+    pub unset: bool,
+    /// Check every setting in config.toml and report each bad value.
+    #[arg(long, conflicts_with_all = ["key", "unset"])]
+    pub validate: bool,
+    /// Print every setting that has a value, environment overrides applied.
+    #[arg(long, conflicts_with_all = ["key", "unset", "validate"])]
+    pub list: bool,
+    /// With --list, say where each value came from (env, file, or default).
+    #[arg(long, requires = "list")]
+    pub show_origin: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct LockArgs {}
 
@@ -1119,6 +1795,22 @@ mod tests {
         assert!(matches!(cli.command, Command::Doctor(_)));
     }
 
+    #[test]
+    fn parses_commit_progress_and_global_no_progress() {
+        let cli = Cli::try_parse_from(["digstore", "commit", "--progress"]).unwrap();
+        assert!(!cli.no_progress);
+        match cli.command {
+            Command::Commit(c) => assert!(c.progress),
+            _ => panic!("expected commit"),
+        }
+        let cli = Cli::try_parse_from(["digstore", "commit", "--no-progress"]).unwrap();
+        assert!(cli.no_progress);
+        match cli.command {
+            Command::Commit(c) => assert!(!c.progress),
+            _ => panic!("expected commit"),
+        }
+    }
+
     #[test]
     fn parses_commit_dry_run() {
         let cli = Cli::try_parse_from(["digstore", "commit", "--dry-run"]).unwrap();
@@ -1208,6 +1900,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_cat_path_at_ref() {
+        let cli = Cli::try_parse_from(["digstore", "cat", "docs/a.md", "--at", "HEAD~1"]).unwrap();
+        match cli.command {
+            Command::Cat(c) => {
+                assert_eq!(c.urn, "docs/a.md");
+                assert_eq!(c.at.as_deref(), Some("HEAD~1"));
+            }
+            _ => panic!("expected cat"),
+        }
+    }
+
     #[test]
     fn parses_remote_add_subcommand() {
         let cli =
@@ -1513,6 +2217,491 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_cat_ranges() {
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "cat",
+            "urn:dig:chia:abcd/r",
+            "--lines",
+            "2:5",
+            "-n",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Cat(c) => {
+                assert_eq!(
+                    c.lines,
+                    Some(LineRange {
+                        start: Some(2),
+                        end: Some(5)
+                    })
+                );
+                assert!(c.number);
+            }
+            _ => panic!("expected cat"),
+        }
+        let cli = Cli::try_parse_from(["digstore", "cat", "urn:dig:chia:abcd/r", "--bytes", "-16"])
+            .unwrap();
+        match cli.command {
+            Command::Cat(c) => assert_eq!(c.bytes, Some(ByteRange::Last(16))),
+            _ => panic!("expected cat"),
+        }
+        assert!(
+            Cli::try_parse_from(["digstore", "cat", "x", "--bytes", "0-1", "--lines", "1:2"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["digstore", "cat", "x", "--bytes", "9-3"]).is_err());
+    }
+
+    #[test]
+    fn parses_cat_out_dir() {
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "cat",
+            "urn:dig:chia:abcd/src/**/*.rs",
+            "--out-dir",
+            "dst",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Cat(c) => assert_eq!(c.out_dir, Some(PathBuf::from("dst"))),
+            _ => panic!("expected cat"),
+        }
+        assert!(
+            Cli::try_parse_from(["digstore", "cat", "x", "--out-dir", "d", "--out", "f"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parses_cat_batch_only_with_out_dir() {
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "cat",
+            "urn:dig:chia:abcd",
+            "--batch",
+            "list.txt",
+            "--out-dir",
+            "dst",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Cat(c) => assert_eq!(c.batch, Some(PathBuf::from("list.txt"))),
+            _ => panic!("expected cat"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "cat", "x", "--batch", "list.txt"]).is_err());
+    }
+
+    #[test]
+    fn parses_cat_all_versions_only_with_out_dir() {
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "cat",
+            "docs/a.md",
+            "--all-versions",
+            "--out-dir",
+            "dst",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Cat(c) => assert!(c.all_versions),
+            _ => panic!("expected cat"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "cat", "x", "--all-versions"]).is_err());
+        assert!(Cli::try_parse_from([
+            "digstore",
+            "cat",
+            "x",
+            "--all-versions",
+            "--out-dir",
+            "d",
+            "--at",
+            "HEAD"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn commit_date_is_parsed_strictly() {
+        let cli =
+            Cli::try_parse_from(["digstore", "commit", "--date", "@1700000000 +0130"]).unwrap();
+        match cli.command {
+            Command::Commit(c) => {
+                assert_eq!(c.date.unwrap().to_string(), "2023-11-14T23:43:20+01:30")
+            }
+            _ => panic!("expected commit"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "commit", "--date", "last tuesday"]).is_err());
+    }
+
+    #[test]
+    fn add_tar_takes_a_file_or_stdin_and_stands_alone() {
+        let cli = Cli::try_parse_from(["digstore", "add", "--tar", "-"]).unwrap();
+        assert!(
+            matches!(cli.command, Command::Add(a) if a.tar.as_deref() == Some(std::path::Path::new("-")))
+        );
+        assert!(Cli::try_parse_from(["digstore", "add", "--tar", "a.tar", "x.txt"]).is_err());
+        assert!(Cli::try_parse_from(["digstore", "add", "--tar", "a.tar", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn commit_deterministic_requires_a_date() {
+        assert!(Cli::try_parse_from(["digstore", "commit", "--deterministic"]).is_err());
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "commit",
+            "--deterministic",
+            "--date",
+            "@0 +0000",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Commit(CommitArgs {
+                deterministic: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_commit_changes_only_and_its_alias() {
+        for flag in ["--changes-only", "--no-cumulative"] {
+            let cli = Cli::try_parse_from(["digstore", "commit", flag]).unwrap();
+            assert!(matches!(
+                cli.command,
+                Command::Commit(CommitArgs {
+                    changes_only: true,
+                    ..
+                })
+            ));
+        }
+        assert!(
+            Cli::try_parse_from(["digstore", "commit", "--changes-only", "--allow-empty"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["digstore", "commit", "--changes-only", "--push"]).is_err());
+    }
+
+    #[test]
+    fn add_and_commit_take_force_for_the_space_check() {
+        let cli = Cli::try_parse_from(["digstore", "commit", "--force"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Commit(CommitArgs { force: true, .. })
+        ));
+        let cli = Cli::try_parse_from(["digstore", "add", "-A", "--force"]).unwrap();
+        assert!(matches!(cli.command, Command::Add(a) if a.force && a.all));
+    }
+
+    #[test]
+    fn parses_squash_dry_run() {
+        let cli = Cli::try_parse_from(["digstore", "squash", "aa", "bb", "--dry-run"]).unwrap();
+        match cli.command {
+            Command::Squash(a) => {
+                assert_eq!((a.from.as_str(), a.to.as_str()), ("aa", "bb"));
+                assert!(a.dry_run);
+            }
+            _ => panic!("expected squash"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "squash", "aa"]).is_err());
+    }
+
+    #[test]
+    fn rotate_salt_parses_only_as_a_dry_run() {
+        let cli = Cli::try_parse_from(["digstore", "rotate-salt", "--dry-run"]).unwrap();
+        assert!(matches!(cli.command, Command::RotateSalt(a) if a.dry_run));
+        assert!(Cli::try_parse_from(["digstore", "rotate-salt"]).is_err());
+    }
+
+    #[test]
+    fn parses_add_from_stdin_null() {
+        let cli = Cli::try_parse_from(["digstore", "add", "--from-stdin", "-0"]).unwrap();
+        match cli.command {
+            Command::Add(a) => assert!(a.from_stdin && a.null),
+            _ => panic!("expected add"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "add", "-0", "x"]).is_err());
+        let cli = Cli::try_parse_from(["digstore", "add", "--stdin-as", "out/a.log"]).unwrap();
+        assert!(
+            matches!(cli.command, Command::Add(a) if a.stdin_as.as_deref() == Some("out/a.log"))
+        );
+        assert!(Cli::try_parse_from(["digstore", "add", "--stdin-as", "a", "b.txt"]).is_err());
+    }
+
+    #[test]
+    fn parses_grep_flags() {
+        let cli = Cli::try_parse_from(["digstore", "grep", "-ln", "fo+", "--at", "aa"]).unwrap();
+        match cli.command {
+            Command::Grep(a) => {
+                assert_eq!(a.pattern.as_str(), "fo+");
+                assert_eq!(a.root.as_deref(), Some("aa"));
+                assert!(a.files_with_matches && a.line_number && !a.text);
+            }
+            _ => panic!("expected grep"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "grep", "("]).is_err());
+    }
+
+    #[test]
+    fn output_flags_override_the_configured_format() {
+        let resolved = |args: &[&str], configured: OutputFormat| {
+            let mut cli = Cli::try_parse_from(args).unwrap();
+            cli.resolve_output_format(configured);
+            cli.json
+        };
+        assert!(!resolved(&["digstore", "status"], OutputFormat::Auto));
+        assert!(resolved(&["digstore", "status"], OutputFormat::Json));
+        assert!(!resolved(
+            &["digstore", "status", "--human"],
+            OutputFormat::Json
+        ));
+        assert!(resolved(
+            &["digstore", "status", "--json"],
+            OutputFormat::Human
+        ));
+        assert!(Cli::try_parse_from(["digstore", "status", "--json", "--human"]).is_err());
+    }
+
+    #[test]
+    fn parses_store_name_registry() {
+        let cli = Cli::try_parse_from(["digstore", "stores", "add-name", "blog", "ab"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Stores(StoresArgs {
+                action: Some(StoresAction::AddName { ref name, ref store_id })
+            }) if name == "blog" && store_id == "ab"
+        ));
+        let cli = Cli::try_parse_from(["digstore", "stores", "list-names"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Stores(StoresArgs {
+                action: Some(StoresAction::ListNames)
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_whereis() {
+        let cli = Cli::try_parse_from(["digstore", "whereis", "ab"]).unwrap();
+        assert!(matches!(cli.command, Command::Whereis(a) if a.hash == "ab"));
+        let cli =
+            Cli::try_parse_from(["digstore", "export", "--since", "ab", "-o", "d.dig"]).unwrap();
+        assert!(matches!(cli.command, Command::Export(a) if a.since == "ab"));
+        assert!(Cli::try_parse_from(["digstore", "export", "-o", "d.dig"]).is_err());
+        let cli = Cli::try_parse_from(["digstore", "import", "d.dig"]).unwrap();
+        assert!(matches!(cli.command, Command::Import(_)));
+        let cli = Cli::try_parse_from(["digstore", "file-history", "a/b.txt"]).unwrap();
+        assert!(matches!(cli.command, Command::FileHistory(a) if a.path == "a/b.txt"));
+        let cli = Cli::try_parse_from(["digstore", "blame", "a/b.txt"]).unwrap();
+        assert!(matches!(cli.command, Command::Blame(a) if a.path == "a/b.txt"));
+        assert!(Cli::try_parse_from(["digstore", "whereis"]).is_err());
+    }
+
+    #[test]
+    fn parses_branch_and_checkout_of_a_branch() {
+        let cli = Cli::try_parse_from(["digstore", "branch"]).unwrap();
+        assert!(matches!(cli.command, Command::Branch(a) if a.name.is_none()));
+        let cli = Cli::try_parse_from(["digstore", "branch", "exp", "--at", "ab"]).unwrap();
+        assert!(matches!(cli.command, Command::Branch(a)
+            if a.name.as_deref() == Some("exp") && a.at.as_deref() == Some("ab")));
+        assert!(Cli::try_parse_from(["digstore", "branch", "--at", "ab"]).is_err());
+        let cli = Cli::try_parse_from(["digstore", "checkout", "exp"]).unwrap();
+        assert!(matches!(cli.command, Command::Checkout(a) if a.out.is_none()));
+    }
+
+    #[test]
+    fn parses_config_get_set_unset_and_validate() {
+        let cli = Cli::try_parse_from(["digstore", "config", "fee", "100"]).unwrap();
+        assert!(matches!(cli.command, Command::Config(ConfigArgs {
+            key: Some(k), value: Some(v), unset: false, validate: false, list: false, ..
+        }) if k == "fee" && v == "100"));
+        let cli = Cli::try_parse_from(["digstore", "config", "--validate"]).unwrap();
+        assert!(matches!(cli.command, Command::Config(a) if a.validate && a.key.is_none()));
+        let cli = Cli::try_parse_from(["digstore", "config", "--unset", "fee"]).unwrap();
+        assert!(matches!(cli.command, Command::Config(a) if a.unset && a.value.is_none()));
+        assert!(Cli::try_parse_from(["digstore", "config"]).is_err());
+        assert!(Cli::try_parse_from(["digstore", "config", "fee", "1", "--unset"]).is_err());
+        assert!(Cli::try_parse_from(["digstore", "config", "fee", "--validate"]).is_err());
+        let cli = Cli::try_parse_from(["digstore", "config", "--list", "--show-origin"]).unwrap();
+        assert!(matches!(cli.command, Command::Config(a) if a.list && a.show_origin));
+        assert!(Cli::try_parse_from(["digstore", "config", "--show-origin"]).is_err());
+        assert!(Cli::try_parse_from(["digstore", "config", "fee", "--list"]).is_err());
+    }
+
+    #[test]
+    fn parses_wallet_profiles_and_the_global_override() {
+        let cli =
+            Cli::try_parse_from(["digstore", "wallet", "create", "work", "--words", "12"]).unwrap();
+        assert!(matches!(cli.command, Command::Wallet(WalletArgs {
+            action: WalletAction::Create { name, mnemonic: None, words: 12 },
+        }) if name == "work"));
+        let cli =
+            Cli::try_parse_from(["digstore", "seed", "status", "--wallet-profile", "ci"]).unwrap();
+        assert_eq!(cli.wallet_profile.as_deref(), Some("ci"));
+        assert!(
+            Cli::try_parse_from(["digstore", "wallet", "create", "x", "--words", "13"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parses_stores_info() {
+        let cli = Cli::try_parse_from(["digstore", "stores", "info", "--paths", "--json"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Command::Stores(StoresArgs {
+                action: Some(StoresAction::Info {
+                    name: None,
+                    config: false,
+                    paths: true
+                })
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_access_actions() {
+        let cli = Cli::try_parse_from(["digstore", "access"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Access(AccessArgs { action: None })
+        ));
+        let cli = Cli::try_parse_from(["digstore", "access", "allow"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Access(AccessArgs {
+                action: Some(AccessAction::Allow { public_key: None })
+            })
+        ));
+        assert!(Cli::try_parse_from(["digstore", "access", "revoke"]).is_err());
+    }
+
+    #[test]
+    fn parses_size_layers() {
+        let cli = Cli::try_parse_from(["digstore", "size", "--layers", "--json"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(cli.command, Command::Size(a) if a.layers));
+    }
+
+    #[test]
+    fn size_at_requires_detailed() {
+        assert!(Cli::try_parse_from(["digstore", "size", "--at", "ab"]).is_err());
+        let cli = Cli::try_parse_from(["digstore", "size", "--detailed", "--at", "ab"]).unwrap();
+        assert!(
+            matches!(cli.command, Command::Size(a) if a.detailed && a.at.as_deref() == Some("ab"))
+        );
+    }
+
+    #[test]
+    fn parses_staged_diff() {
+        let cli = Cli::try_parse_from(["digstore", "staged"]).unwrap();
+        assert!(matches!(cli.command, Command::Staged(a) if a.action.is_none()));
+        let cli = Cli::try_parse_from(["digstore", "staged", "diff", "--stat"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Staged(StagedArgs {
+                action: Some(StagedAction::Diff { stat: true }),
+                estimate: false,
+            })
+        ));
+        let cli = Cli::try_parse_from(["digstore", "staged", "--estimate"]).unwrap();
+        assert!(matches!(cli.command, Command::Staged(a) if a.estimate && a.action.is_none()));
+    }
+
+    #[test]
+    fn parses_verify_all() {
+        let cli = Cli::try_parse_from(["digstore", "verify", "--all", "--at", "aa"]).unwrap();
+        match cli.command {
+            Command::Verify(a) => {
+                assert!(a.all);
+                assert_eq!(a.root.as_deref(), Some("aa"));
+            }
+            _ => panic!("expected verify"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "verify"]).is_err());
+    }
+
+    #[test]
+    fn parses_serve_node_gates() {
+        let cli =
+            Cli::try_parse_from(["digstore", "serve", "--read-only", "--token-file", "t.txt"])
+                .unwrap();
+        match cli.command {
+            Command::Serve(a) => {
+                assert!(a.read_only && !a.anonymous);
+                assert_eq!(a.token_file.as_deref(), Some(std::path::Path::new("t.txt")));
+            }
+            _ => panic!("expected serve"),
+        }
+    }
+
+    #[test]
+    fn parses_prune_cutoffs() {
+        let cli = Cli::try_parse_from([
+            "digstore",
+            "prune",
+            "--before",
+            "2024-01-01",
+            "--keep-last",
+            "3",
+            "--dry-run",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Prune(a) => {
+                assert_eq!(a.before, Some(1_704_067_200));
+                assert_eq!(a.keep_last, Some(3));
+                assert!(a.dry_run);
+            }
+            _ => panic!("expected prune"),
+        }
+        let cli = Cli::try_parse_from(["digstore", "prune", "--before-id", "7"]).unwrap();
+        assert!(matches!(cli.command, Command::Prune(a) if a.before_id == Some(7)));
+        assert!(Cli::try_parse_from(["digstore", "prune", "--keep-last", "0"]).is_err());
+        assert!(Cli::try_parse_from([
+            "digstore",
+            "prune",
+            "--before",
+            "2024-01-01",
+            "--before-id",
+            "1"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_sign_and_verify_signature() {
+        let cli = Cli::try_parse_from(["digstore", "sign"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Sign(SignArgs { root: None })
+        ));
+        let cli =
+            Cli::try_parse_from(["digstore", "verify-signature", "aa", "--pubkey", "bb"]).unwrap();
+        match cli.command {
+            Command::VerifySignature(a) => {
+                assert_eq!(a.root, "aa");
+                assert_eq!(a.pubkey.as_deref(), Some("bb"));
+            }
+            _ => panic!("expected verify-signature"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "verify-signature"]).is_err());
+    }
+
+    #[test]
+    fn parses_ls() {
+        let cli = Cli::try_parse_from(["digstore", "ls", "--at", "ab", "-l"]).unwrap();
+        match cli.command {
+            Command::Ls(a) => {
+                assert_eq!(a.root.as_deref(), Some("ab"));
+                assert!(a.long);
+                assert!(!a.tree);
+            }
+            _ => panic!("expected ls"),
+        }
+        assert!(Cli::try_parse_from(["digstore", "ls", "--long", "--tree"]).is_err());
+    }
+
     #[test]
     fn private_salt_flag_on_cat() {
         let cli = Cli::try_parse_from([
@@ -1785,4 +2974,23 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn parses_status_porcelain_versions() {
+        use crate::commands::status::Porcelain;
+        let porcelain = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Command::Status(s) => s.porcelain,
+            _ => panic!("expected status"),
+        };
+        assert_eq!(porcelain(&["digstore", "status"]), None);
+        assert_eq!(
+            porcelain(&["digstore", "status", "--porcelain"]),
+            Some(Porcelain::V1)
+        );
+        assert_eq!(
+            porcelain(&["digstore", "status", "--porcelain=v2"]),
+            Some(Porcelain::V2)
+        );
+        assert!(Cli::try_parse_from(["digstore", "status", "--porcelain=v3"]).is_err());
+    }
 }
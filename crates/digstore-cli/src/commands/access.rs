@@ -0,0 +1,114 @@
+use digstore_chain::keys::WalletKeys;
+use digstore_core::Bytes48;
+use digstore_store::AccessPolicy;
+
+use crate::cli::{AccessAction, AccessArgs};
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::{store_ops, wallet};
+use crate::ui::Ui;
+
+/// Show the store's write policy, or allow / revoke a wallet key or clear the
+/// policy. Changes are made as the active wallet, which the current policy
+/// must list.
+pub fn run(ctx: &CliContext, ui: &Ui, args: AccessArgs) -> Result<(), CliError> {
+    let Some(action) = args.action else {
+        return show(ctx, ui);
+    };
+    let (sk, own) = wallet_key(ui)?;
+    let existing = store_ops::access_policy(ctx)?;
+    let mut policy = existing.clone().unwrap_or_default();
+    let (changed, message) = match action {
+        AccessAction::Allow { public_key } => {
+            let key = public_key.as_deref().map(parse_key).transpose()?;
+            let key = key.unwrap_or(own);
+            // Starting a policy for someone else must not lock this wallet out.
+            if policy.writers.is_empty() {
+                policy.allow(own);
+            }
+            let added = policy.allow(key);
+            let verb = if added { "Allowed" } else { "Already allowed:" };
+            (added, format!("{verb} {}", key.to_hex()))
+        }
+        AccessAction::Revoke { public_key } => {
+            let key = parse_key(&public_key)?;
+            if key == own {
+                return Err(CliError::InvalidArgument(
+                    "cannot revoke your own wallet key; run `digstore access clear` to drop the policy"
+                        .into(),
+                ));
+            }
+            let removed = policy.revoke(&key);
+            let verb = if removed { "Revoked" } else { "Not a writer:" };
+            (removed, format!("{verb} {}", key.to_hex()))
+        }
+        AccessAction::Clear => {
+            store_ops::set_access_policy(ctx, &sk, None)?;
+            ui.success("Cleared the access policy; any wallet may write");
+            return emit(ui, None);
+        }
+    };
+    if changed {
+        store_ops::set_access_policy(ctx, &sk, Some(&policy))?;
+    }
+    ui.success(message);
+    emit(ui, (existing.is_some() || changed).then_some(&policy))
+}
+
+/// Make sure the active wallet may write to this store before a write verb
+/// touches it. A store without a policy never needs the wallet unlocked.
+pub(crate) fn require_writer(ctx: &CliContext, ui: &Ui) -> Result<(), CliError> {
+    if store_ops::access_policy(ctx)?.is_none() {
+        return Ok(());
+    }
+    let (_, own) = wallet_key(ui)?;
+    store_ops::check_writer(ctx, &own)
+}
+
+fn show(ctx: &CliContext, ui: &Ui) -> Result<(), CliError> {
+    let policy = store_ops::access_policy(ctx)?;
+    emit(ui, policy.as_ref())?;
+    match policy {
+        None => ui.line("No access policy: any wallet may write."),
+        Some(policy) => {
+            ui.line(format!("Writers ({}):", policy.writers.len()));
+            for key in &policy.writers {
+                ui.line(format!("  {}", key.to_hex()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn emit(ui: &Ui, policy: Option<&AccessPolicy>) -> Result<(), CliError> {
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "restricted": policy.is_some(),
+            "writers": policy
+                .map(|p| p.writers.iter().map(|k| k.to_hex()).collect::<Vec<_>>())
+                .unwrap_or_default(),
+        }));
+    }
+    Ok(())
+}
+
+/// The active wallet's synthetic key, as the BLS key stores are opened with.
+fn wallet_key(ui: &Ui) -> Result<(digstore_crypto::bls::SecretKey, Bytes48), CliError> {
+    let (keys, _cfg) = wallet::unlock_wallet_keys(ui)?;
+    writer_key(&keys)
+}
+
+/// `keys`' synthetic key and its public key, which access policies list.
+pub(crate) fn writer_key(
+    keys: &WalletKeys,
+) -> Result<(digstore_crypto::bls::SecretKey, Bytes48), CliError> {
+    let sk = digstore_crypto::bls::SecretKey::from_bytes(&keys.synthetic_sk.to_bytes())
+        .map_err(|e| CliError::Other(anyhow::anyhow!("wallet key: {e}")))?;
+    let pk = sk.public_key().to_bytes();
+    Ok((sk, pk))
+}
+
+fn parse_key(hex: &str) -> Result<Bytes48, CliError> {
+    Bytes48::from_hex(hex)
+        .map_err(|_| CliError::InvalidArgument(format!("bad public key hex: {hex}")))
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use crate::cli::AddArgs;
 use crate::context::CliContext;
 use crate::error::CliError;
@@ -5,25 +7,63 @@ use crate::ops::store_ops;
 use crate::ui::theme::Marker;
 use crate::ui::Ui;
 
-pub fn run(ctx: &CliContext, ui: &Ui, args: AddArgs) -> Result<(), CliError> {
+pub fn run(ctx: &CliContext, ui: &Ui, mut args: AddArgs) -> Result<(), CliError> {
     if args.discovery {
         return run_discovery(ctx, ui);
     }
-    if args.paths.is_empty() && !args.all {
-        return Err(CliError::InvalidArgument(
-            "nothing to add: pass paths, or -A to stage everything".into(),
-        ));
+    if args.from_stdin {
+        let mut input = Vec::new();
+        std::io::Read::read_to_end(&mut std::io::stdin(), &mut input)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("read stdin: {e}")))?;
+        args.paths.extend(split_path_list(&input, args.null));
+    }
+    if !args.dry_run {
+        super::access::require_writer(ctx, ui)?;
     }
-    let outcome = store_ops::add_files(ctx, &args.paths, args.all, args.dry_run, args.key)?;
+    let mut skipped = None;
+    let outcome = if let Some(tar) = &args.tar {
+        let (outcome, links) = if tar.as_os_str() == "-" {
+            store_ops::add_tar(ctx, &mut std::io::stdin().lock())?
+        } else {
+            let mut file = std::fs::File::open(tar).map_err(|e| {
+                CliError::InvalidArgument(format!("cannot read {}: {e}", tar.display()))
+            })?;
+            store_ops::add_tar(ctx, &mut file)?
+        };
+        for s in &links {
+            eprintln!(
+                "warning: skipped {} {} (only regular files are staged)",
+                s.kind, s.path
+            );
+        }
+        skipped = Some(links);
+        outcome
+    } else if let Some(key) = args.stdin_as {
+        store_ops::add_reader(ctx, &key, &mut std::io::stdin().lock(), args.dry_run)?
+    } else {
+        if args.paths.is_empty() && !args.all {
+            return Err(CliError::InvalidArgument(
+                "nothing to add: pass paths, or -A to stage everything".into(),
+            ));
+        }
+        add_with_progress(ctx, ui, &args)?
+    };
 
     if ui.json() {
-        ui.emit_json(&serde_json::json!({
+        let mut obj = serde_json::json!({
             "staged": outcome.staged.iter().map(|(k, _)| k).collect::<Vec<_>>(),
             "unchanged": outcome.unchanged,
             "dry_run": outcome.dry_run,
             "staged_bytes": outcome.staged_bytes,
             "limit_bytes": outcome.limit_bytes,
-        }));
+        });
+        if let Some(skipped) = skipped {
+            obj["skipped"] = skipped
+                .iter()
+                .map(|s| serde_json::json!({ "path": s.path, "kind": s.kind }))
+                .collect();
+        }
+        ui.emit_json(&obj);
         return Ok(());
     }
     let verb = if outcome.dry_run {
@@ -45,6 +85,55 @@ pub fn run(ctx: &CliContext, ui: &Ui, args: AddArgs) -> Result<(), CliError> {
     Ok(())
 }
 
+/// Stage `args.paths` (or `-A`) with a file counter during the walk and a bar
+/// while the found files are read.
+fn add_with_progress(
+    ctx: &CliContext,
+    ui: &Ui,
+    args: &AddArgs,
+) -> Result<store_ops::AddOutcome, CliError> {
+    let scanning = ui.file_progress("Scanning", None);
+    let reading = std::sync::OnceLock::new();
+    let outcome = store_ops::add_files_with_progress(
+        ctx,
+        &args.paths,
+        args.all,
+        args.dry_run,
+        args.key.clone(),
+        args.force,
+        &|step| match step {
+            store_ops::AddProgress::Found => scanning.inc(1),
+            store_ops::AddProgress::Read { done, total } => {
+                let pb = reading.get_or_init(|| {
+                    scanning.finish_and_clear();
+                    ui.file_progress("Reading", Some(total))
+                });
+                pb.set_position(done);
+            }
+        },
+    );
+    scanning.finish_and_clear();
+    if let Some(pb) = reading.get() {
+        pb.finish_and_clear();
+    }
+    outcome
+}
+
+/// Split a `--from-stdin` path list on newlines (a trailing `\r` is dropped),
+/// or on NUL with `-0`. Empty entries are skipped.
+fn split_path_list(input: &[u8], null: bool) -> Vec<PathBuf> {
+    let sep = if null { b'\0' } else { b'\n' };
+    input
+        .split(|&b| b == sep)
+        .map(|entry| match entry {
+            [rest @ .., b'\r'] if !null => rest,
+            _ => entry,
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| PathBuf::from(String::from_utf8_lossy(entry).into_owned()))
+        .collect()
+}
+
 fn run_discovery(ctx: &CliContext, ui: &Ui) -> Result<(), CliError> {
     let manifest = store_ops::stage_discovery_manifest(ctx)?;
     if ui.json() {
@@ -61,3 +150,20 @@ fn run_discovery(ctx: &CliContext, ui: &Ui) -> Result<(), CliError> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_lists_split_on_newline_or_nul() {
+        assert_eq!(
+            split_path_list(b"a.txt\r\nsub/b.md\n\n", false),
+            vec![PathBuf::from("a.txt"), PathBuf::from("sub/b.md")]
+        );
+        assert_eq!(
+            split_path_list(b"odd\nname.txt\0./c.txt\0", true),
+            vec![PathBuf::from("odd\nname.txt"), PathBuf::from("./c.txt")]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use crate::cli::BlameArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::commit_note::CommitNote;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Print each line of a text file's newest version with the version that
+/// introduced it: its short root, author and date (the commit's `--date`
+/// when recorded, else its timestamp). Fails with `NotFound` when no version
+/// holds the file and `INVALID_ARGUMENT` when it is binary.
+pub fn run(ctx: &CliContext, ui: &Ui, args: BlameArgs) -> Result<(), CliError> {
+    let lines = store_ops::blame(ctx, &args.path)?;
+    if lines.is_empty() && store_ops::file_versions(ctx, &args.path)?.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "{} is not in any version",
+            args.path
+        )));
+    }
+    let mut notes: HashMap<u64, CommitNote> = HashMap::new();
+    for l in &lines {
+        if !notes.contains_key(&l.generation_id) {
+            let note = CommitNote::load(&ctx.dig_dir, l.generation_id)?.unwrap_or_default();
+            notes.insert(l.generation_id, note);
+        }
+    }
+
+    if ui.json() {
+        ui.emit_json(
+            &lines
+                .iter()
+                .map(|l| {
+                    let note = &notes[&l.generation_id];
+                    serde_json::json!({
+                        "line": l.line,
+                        "text": l.text,
+                        "root": l.root.to_hex(),
+                        "id": l.generation_id,
+                        "timestamp": l.timestamp,
+                        "author": note.author,
+                        "date": note.date,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+        return Ok(());
+    }
+    let author_width = notes
+        .values()
+        .map(|n| n.author.as_deref().map_or(1, str::len))
+        .max()
+        .unwrap_or(1);
+    let line_width = lines.len().to_string().len();
+    for l in &lines {
+        let note = &notes[&l.generation_id];
+        let when = note.date.clone().unwrap_or_else(|| l.timestamp.to_string());
+        ui.line(format!(
+            "{} ({:<author_width$} {when} {:>line_width$}) {}",
+            &l.root.to_hex()[..8],
+            note.author.as_deref().unwrap_or("-"),
+            l.line,
+            l.text
+        ));
+    }
+    Ok(())
+}
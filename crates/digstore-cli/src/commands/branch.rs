@@ -0,0 +1,54 @@
+use digstore_core::Bytes32;
+
+use crate::cli::BranchArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+
+pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: BranchArgs) -> Result<(), CliError> {
+    let Some(name) = args.name else {
+        return list(ctx, ui);
+    };
+    let at = match &args.at {
+        Some(hex) => Some(
+            Bytes32::from_hex(hex)
+                .map_err(|_| CliError::InvalidArgument("--at must be 32-byte hex".into()))?,
+        ),
+        None => None,
+    };
+    let root = store_ops::create_branch(ctx, &name, at)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({ "branch": name, "root": root.to_hex() }));
+    } else {
+        ui.success(format!("created branch {name} at {}", root.to_hex()));
+        ui.hint(format!("switch to it with `digstore checkout {name}`"));
+    }
+    Ok(())
+}
+
+fn list(ctx: &CliContext, ui: &crate::ui::Ui) -> Result<(), CliError> {
+    let (branches, current) = store_ops::branches(ctx)?;
+    if ui.json() {
+        let rows: Vec<serde_json::Value> = branches
+            .iter()
+            .map(|(name, root)| {
+                serde_json::json!({
+                    "name": name,
+                    "root": root.to_hex(),
+                    "current": *name == current,
+                })
+            })
+            .collect();
+        ui.emit_json(&serde_json::json!({ "current": current, "branches": rows }));
+        return Ok(());
+    }
+    if branches.is_empty() {
+        ui.line(format!("on branch {current} (no versions yet)"));
+        return Ok(());
+    }
+    for (name, root) in &branches {
+        let marker = if *name == current { '*' } else { ' ' };
+        ui.line(format!("{marker} {name}  {}", root.to_hex()));
+    }
+    Ok(())
+}
@@ -1,38 +1,262 @@
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::Path;
 
-use digstore_core::{Bytes32, Urn};
+use base64::Engine;
+
+use digstore_core::{Bytes32, Urn, CHAIN};
 
 use crate::cli::CatArgs;
+use crate::commands::checkout::safe_resource_path;
 use crate::context::CliContext;
 use crate::error::CliError;
-use crate::ops::{client_crypto, serve, store_ops};
+use crate::ops::{client_crypto, range, serve, store_ops};
 
-pub fn run(ctx: &CliContext, _ui: &crate::ui::Ui, args: CatArgs) -> Result<(), CliError> {
+pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CatArgs) -> Result<(), CliError> {
     let target = args.urn.trim();
 
     // Two retrieval modes:
     //   * URN (`urn:dig:…`)        → fetch + DECRYPT, so the streamed-out bytes
-    //                                 are the final plaintext.
+    //                                 are the final plaintext. A resource part
+    //                                 with glob metacharacters selects every
+    //                                 matching resource (see `cat_many`).
     //   * 64-char hex retrieval key → fetch the RAW ENCRYPTED bytes within the
     //                                 active store; no decryption is performed.
+    // With `--at` the argument is neither: it is a resource path in the active
+    // store, even when it looks like a hash or a URN. So is it with
+    // `--all-versions`, which writes out every version of that path.
+    if args.all_versions {
+        return cat_all_versions(ctx, ui, &args);
+    }
+    if let Some(spec) = &args.at {
+        let urn = Urn {
+            chain: CHAIN.to_string(),
+            store_id: ctx.find_store_id()?,
+            root_hash: Some(store_ops::resolve_version(ctx, spec)?),
+            resource_key: Some(target.trim_start_matches('/').to_string()),
+        };
+        if store_ops::is_glob(target) || args.out_dir.is_some() {
+            return cat_many(ctx, ui, &args, urn);
+        }
+        let bytes = cat_by_urn(ctx, &args, urn)?;
+        return write_out(ui, args.out.as_deref(), &select(&args, bytes)?);
+    }
     let bytes = if target.starts_with("urn:") {
-        cat_by_urn(ctx, &args, target)?
+        let parse = if args.strict {
+            Urn::parse_strict
+        } else {
+            Urn::parse
+        };
+        let urn = parse(target).map_err(|e| CliError::InvalidArgument(format!("bad urn: {e}")))?;
+        let globbed = urn.resource_key.as_deref().is_some_and(store_ops::is_glob);
+        if args.batch.is_some() && urn.resource_key.is_some() {
+            return Err(CliError::InvalidArgument(
+                "--batch takes the resource keys from its list file; pass a URN \
+                 without a resource part"
+                    .into(),
+            ));
+        }
+        if globbed || args.out_dir.is_some() {
+            return cat_many(ctx, ui, &args, urn);
+        }
+        cat_by_urn(ctx, &args, urn)?
     } else if let Ok(rk) = Bytes32::from_hex(target) {
+        if args.out_dir.is_some() {
+            return Err(CliError::InvalidArgument(
+                "--out-dir needs a URN, not a retrieval key".into(),
+            ));
+        }
         cat_by_retrieval_key(ctx, rk)?
     } else {
         return Err(CliError::InvalidArgument(
-            "expected a 'urn:dig:…' URN or a 64-character hex retrieval key".into(),
+            "expected a 'urn:dig:…' URN or a 64-character hex retrieval key \
+             (for a resource path, pass --at <ref>)"
+                .into(),
         ));
     };
 
-    write_out(args.out.as_deref(), &bytes)
+    write_out(ui, args.out.as_deref(), &select(&args, bytes)?)
 }
 
-/// URN path: resolve, serve, decrypt, return plaintext.
-fn cat_by_urn(ctx: &CliContext, args: &CatArgs, target: &str) -> Result<Vec<u8>, CliError> {
-    let urn = Urn::parse(target).map_err(|e| CliError::InvalidArgument(format!("bad urn: {e}")))?;
+/// Glob / `--batch` / `--out-dir` path: decrypt every resource the URN's
+/// resource part matches (or the `--batch` list names) and write each under
+/// `--out-dir` at its resource key. Without `--out-dir`, `--json` lists the
+/// matches instead.
+fn cat_many(
+    ctx: &CliContext,
+    ui: &crate::ui::Ui,
+    args: &CatArgs,
+    urn: Urn,
+) -> Result<(), CliError> {
+    if args.bytes.is_some() || args.lines.is_some() || args.number {
+        return Err(CliError::InvalidArgument(
+            "--bytes/--lines/--number apply to a single resource, not a pattern".into(),
+        ));
+    }
+    let root = match urn.root_hash {
+        Some(r) => r,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let pattern = urn.resource_key.clone().unwrap_or_default();
+    let keys = if let Some(list) = &args.batch {
+        read_batch_list(list)?
+    } else if store_ops::is_glob(&pattern) {
+        store_ops::match_resources(ctx, &root, &pattern)?
+    } else {
+        vec![store_ops::resolve_resource_key(ctx, &root, &urn)]
+    };
+
+    let Some(dir) = args.out_dir.as_deref() else {
+        if ui.json() {
+            ui.emit_json(&serde_json::json!({
+                "root": root.to_hex(),
+                "pattern": pattern,
+                "matched": keys,
+            }));
+            return Ok(());
+        }
+        return Err(CliError::InvalidArgument(format!(
+            "'{pattern}' matches {} resources; pass --out-dir <dir> to write them \
+             (or --json to list them)",
+            keys.len()
+        )));
+    };
+
+    let started = std::time::Instant::now();
+    let mut total_bytes = 0u64;
+    for key in &keys {
+        let one = Urn {
+            root_hash: Some(root),
+            resource_key: Some(key.clone()),
+            ..urn.clone()
+        };
+        let bytes = cat_by_urn(ctx, args, one)?;
+        let dest = safe_resource_path(dir, key)?;
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| CliError::Other(e.into()))?;
+        }
+        std::fs::write(&dest, &bytes)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", dest.display())))?;
+        total_bytes += bytes.len() as u64;
+    }
+    let elapsed = started.elapsed();
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "pattern": pattern,
+            "matched": keys,
+            "out_dir": dir.display().to_string(),
+            "bytes": total_bytes,
+            "elapsed_ms": elapsed.as_millis() as u64,
+        }));
+    } else {
+        let secs = elapsed.as_secs_f64().max(1e-3);
+        ui.success(format!(
+            "wrote {} files ({total_bytes} bytes) from {} into {} in {secs:.2}s ({:.1} MB/s)",
+            keys.len(),
+            root.to_hex(),
+            dir.display(),
+            total_bytes as f64 / 1_000_000.0 / secs
+        ));
+    }
+    Ok(())
+}
+
+/// `--all-versions` path: write each distinct version of the resource path
+/// into `--out-dir` as `<path>.<first 8 hex of its hash>`, plus a
+/// `versions.json` manifest listing the files oldest first.
+fn cat_all_versions(ctx: &CliContext, ui: &crate::ui::Ui, args: &CatArgs) -> Result<(), CliError> {
+    let path = args.urn.trim().trim_start_matches('/');
+    let dir = args
+        .out_dir
+        .as_deref()
+        .ok_or_else(|| CliError::InvalidArgument("--all-versions needs --out-dir".into()))?;
+    let versions = store_ops::read_file_versions(ctx, path)?;
+    if versions.is_empty() {
+        return Err(CliError::NotFound(format!("{path} is not in any version")));
+    }
+    let mut written = Vec::with_capacity(versions.len());
+    for (v, content) in &versions {
+        let hash = v.hash.to_hex();
+        let file = format!("{path}.{}", &hash[..8]);
+        let dest = safe_resource_path(dir, &file)?;
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| CliError::Other(e.into()))?;
+        }
+        std::fs::write(&dest, content)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", dest.display())))?;
+        written.push(serde_json::json!({
+            "file": file,
+            "hash": hash,
+            "root": v.root.to_hex(),
+            "id": v.generation_id,
+            "timestamp": v.timestamp,
+            "bytes": content.len(),
+        }));
+    }
+    let manifest = dir.join("versions.json");
+    let text = serde_json::to_string_pretty(&written).map_err(|e| CliError::Other(e.into()))?;
+    std::fs::write(&manifest, text + "\n")
+        .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", manifest.display())))?;
+
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "path": path,
+            "out_dir": dir.display().to_string(),
+            "versions": written,
+        }));
+    } else {
+        ui.success(format!(
+            "wrote {} versions of {path} into {} (see {})",
+            written.len(),
+            dir.display(),
+            manifest.display()
+        ));
+    }
+    Ok(())
+}
 
+/// Read a `--batch` list: one resource key per line, surrounding whitespace
+/// trimmed, blank lines and `#` comments skipped.
+fn read_batch_list(path: &Path) -> Result<Vec<String>, CliError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| CliError::InvalidArgument(format!("read {}: {e}", path.display())))?;
+    let keys: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    if keys.is_empty() {
+        return Err(CliError::InvalidArgument(format!(
+            "{} lists no resource keys",
+            path.display()
+        )));
+    }
+    Ok(keys)
+}
+
+/// Apply `--bytes` / `--lines` / `--number` to the full resource bytes.
+fn select(args: &CatArgs, bytes: Vec<u8>) -> Result<Vec<u8>, CliError> {
+    let (span, first_line) = if let Some(r) = &args.bytes {
+        (r.resolve(bytes.len() as u64)?, 1)
+    } else if let Some(r) = &args.lines {
+        (r.resolve(&bytes), r.first_line())
+    } else if args.number {
+        (0..bytes.len(), 1)
+    } else {
+        return Ok(bytes);
+    };
+    let selected = &bytes[span];
+    Ok(if args.number {
+        range::number_lines(selected, first_line)
+    } else {
+        selected.to_vec()
+    })
+}
+
+/// URN path: resolve, serve, decrypt, return plaintext.
+fn cat_by_urn(ctx: &CliContext, args: &CatArgs, urn: Urn) -> Result<Vec<u8>, CliError> {
     // Trusted root: prefer the URN's root, else the current local root.
     let trusted_root: Bytes32 = match urn.root_hash {
         Some(r) => r,
@@ -40,8 +264,6 @@ fn cat_by_urn(ctx: &CliContext, args: &CatArgs, target: &str) -> Result<Vec<u8>,
             .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
     };
 
-    let module_path = store_ops::module_path_for(ctx, &urn.store_id, Some(trusted_root))?;
-
     // §8.5 social conventions: a URN with no resource key resolves to the store's
     // landing resource `index.html` (its default view) when that key exists in the
     // generation manifest; otherwise it falls back to the store-level empty key.
@@ -57,6 +279,16 @@ fn cat_by_urn(ctx: &CliContext, args: &CatArgs, target: &str) -> Result<Vec<u8>,
         urn
     };
 
+    // A changes-only generation serves the files it does not hold from the
+    // nearest generation up its parent chain that does, verified against that
+    // generation's root.
+    let trusted_root = store_ops::generation_holding(
+        ctx,
+        &trusted_root,
+        urn.resource_key.as_deref().unwrap_or_default(),
+    )?;
+    let module_path = store_ops::module_path_for(ctx, &urn.store_id, Some(trusted_root))?;
+
     let resp = serve::serve_content(ctx, &module_path, &urn, trusted_root)?;
 
     if args.verify_proof {
@@ -109,13 +341,60 @@ fn cat_by_retrieval_key(ctx: &CliContext, retrieval_key: Bytes32) -> Result<Vec<
     Ok(resp.ciphertext)
 }
 
-/// Stream bytes to `out` (a file) or stdout.
-fn write_out(out: Option<&Path>, bytes: &[u8]) -> Result<(), CliError> {
-    match out {
-        Some(path) => std::fs::write(path, bytes)
-            .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", path.display()))),
-        None => std::io::stdout()
-            .write_all(bytes)
-            .map_err(|e| CliError::Other(e.into())),
+/// Stream bytes to `out` (a file; `-` is stdout) or stdout, byte for byte.
+///
+/// Without an explicit `--out -`, binary content is not dumped onto a
+/// terminal. In `--json` mode stdout carries one object instead, with binary
+/// content base64-encoded.
+fn write_out(ui: &crate::ui::Ui, out: Option<&Path>, bytes: &[u8]) -> Result<(), CliError> {
+    let file = out.filter(|p| *p != Path::new("-"));
+    if let Some(path) = file {
+        std::fs::write(path, bytes)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", path.display())))?;
+        if ui.json() {
+            ui.emit_json(&serde_json::json!({
+                "out": path.display().to_string(),
+                "bytes": bytes.len(),
+            }));
+        }
+        return Ok(());
+    }
+    if ui.json() {
+        ui.emit_json(&json_content(bytes));
+        return Ok(());
     }
+    if out.is_none() && is_binary(bytes) && std::io::stdout().is_terminal() {
+        return Err(CliError::InvalidArgument(
+            "binary output can mess up your terminal; pass --out <file>, or --out - to \
+             print it anyway"
+                .into(),
+        ));
+    }
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(bytes)
+        .and_then(|()| stdout.flush())
+        .map_err(|e| CliError::Other(e.into()))
+}
+
+/// `--json` payload for a single resource: UTF-8 text as-is, anything else
+/// base64 (standard alphabet, padded).
+fn json_content(bytes: &[u8]) -> serde_json::Value {
+    let (encoding, content) = match std::str::from_utf8(bytes) {
+        Ok(text) if !is_binary(bytes) => ("utf-8", text.to_string()),
+        _ => (
+            "base64",
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        ),
+    };
+    serde_json::json!({
+        "bytes": bytes.len(),
+        "encoding": encoding,
+        "content": content,
+    })
+}
+
+/// Binary for output purposes: a NUL byte, or not valid UTF-8.
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
 }
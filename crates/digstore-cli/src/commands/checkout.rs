@@ -15,7 +15,7 @@ use crate::ops::{client_crypto, serve, store_ops};
 /// Only plain relative path components are accepted; `..`, absolute roots,
 /// Windows drive prefixes, and any component containing `:` (drive / NTFS ADS)
 /// are rejected.
-fn safe_resource_path(base: &Path, key: &str) -> Result<PathBuf, CliError> {
+pub(crate) fn safe_resource_path(base: &Path, key: &str) -> Result<PathBuf, CliError> {
     let reject = || CliError::InvalidArgument(format!("unsafe resource key path: {key:?}"));
     if key.is_empty() {
         return Err(reject());
@@ -44,8 +44,30 @@ fn safe_resource_path(base: &Path, key: &str) -> Result<PathBuf, CliError> {
 }
 
 pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CheckoutArgs) -> Result<(), CliError> {
-    let root = Bytes32::from_hex(&args.root)
-        .map_err(|_| CliError::InvalidArgument("root must be 32-byte hex".into()))?;
+    if args.out.is_none() && args.to_tar.is_none() {
+        return switch(ctx, ui, &args.root);
+    }
+    let root = match Bytes32::from_hex(&args.root) {
+        Ok(root) => root,
+        Err(_) => store_ops::branches(ctx)?
+            .0
+            .get(&args.root)
+            .copied()
+            .ok_or_else(|| {
+                CliError::InvalidArgument(format!(
+                    "{} is neither a 32-byte hex root nor a branch",
+                    args.root
+                ))
+            })?,
+    };
+    let Some(out) = &args.out else {
+        return to_tar(
+            ctx,
+            ui,
+            root,
+            args.to_tar.as_deref().unwrap_or(Path::new("-")),
+        );
+    };
     let store_id = ctx.find_store_id()?;
     let module_path = store_ops::module_path_for(ctx, &store_id, Some(root))?;
 
@@ -58,7 +80,7 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CheckoutArgs) -> Result<(
         None => None,
     };
 
-    fs::create_dir_all(&args.out).map_err(|e| CliError::Other(e.into()))?;
+    fs::create_dir_all(out).map_err(|e| CliError::Other(e.into()))?;
     let keys = store_ops::list_generation_resources(ctx, &root)?;
     let mut count = 0usize;
     for key in keys {
@@ -72,7 +94,7 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CheckoutArgs) -> Result<(
         let chunk_lens = store_ops::resource_chunk_lens(ctx, &root, &key).unwrap_or_default();
         let plaintext =
             client_crypto::decrypt_and_verify(&resp, &urn, salt.as_ref(), &root, &chunk_lens)?;
-        let dest = safe_resource_path(&args.out, &key)?;
+        let dest = safe_resource_path(out, &key)?;
         if let Some(parent) = dest.parent() {
             fs::create_dir_all(parent).map_err(|e| CliError::Other(e.into()))?;
         }
@@ -86,8 +108,58 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CheckoutArgs) -> Result<(
             "checked out {} files from {} into {}",
             count,
             root.to_hex(),
-            args.out.display()
+            out.display()
+        ));
+    }
+    Ok(())
+}
+
+/// `checkout <root> --to-tar <file|->`: stream the version out as a tar.
+fn to_tar(
+    ctx: &CliContext,
+    ui: &crate::ui::Ui,
+    root: Bytes32,
+    dest: &Path,
+) -> Result<(), CliError> {
+    let pb = ui.byte_progress(0, "exporting", false);
+    let count = if dest.as_os_str() == "-" {
+        let stdout = std::io::stdout();
+        let mut lock = stdout.lock();
+        store_ops::export_tar(ctx, root, &mut lock, Some(&pb))?
+    } else {
+        let mut file =
+            std::io::BufWriter::new(fs::File::create(dest).map_err(|e| CliError::Other(e.into()))?);
+        store_ops::export_tar(ctx, root, &mut file, Some(&pb))?
+    };
+    pb.finish_and_clear();
+    // With the archive on stdout, the summary must not interleave with it.
+    if dest.as_os_str() == "-" {
+        return Ok(());
+    }
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "files": count,
+            "tar": dest.display().to_string(),
+        }));
+    } else {
+        ui.success(format!(
+            "wrote {} files from {} to {}",
+            count,
+            root.to_hex(),
+            dest.display()
         ));
     }
     Ok(())
 }
+
+/// `checkout <branch>` without `--out`: make the branch current.
+fn switch(ctx: &CliContext, ui: &crate::ui::Ui, branch: &str) -> Result<(), CliError> {
+    let tip = store_ops::switch_branch(ctx, branch)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({ "branch": branch, "root": tip.to_hex() }));
+    } else {
+        ui.success(format!("switched to branch {branch} at {}", tip.to_hex()));
+    }
+    Ok(())
+}
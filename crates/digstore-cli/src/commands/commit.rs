@@ -4,6 +4,8 @@ use crate::cli::CommitArgs;
 use crate::context::CliContext;
 use crate::error::CliError;
 use crate::ops::anchor_state::{AnchorState, AnchorStatus};
+use crate::ops::commit_note::{self, CommitNote};
+use crate::ops::hooks::{self, Hook};
 use crate::ops::{anchor_backend, anchor_ux, store_ops};
 use crate::runtime::block_on;
 use digstore_chain::anchor::ConfirmState;
@@ -17,10 +19,35 @@ use digstore_chain::dig::{self, format_dig, format_xch};
 /// only after the update confirms is the generation persisted. A confirmation
 /// timeout (or any confirm error) leaves staging + history untouched and a
 /// resumable Pending `anchor.toml`, so a re-run reuses the in-flight update.
+/// The exceptions are local-only and never anchored: a `--changes-only` layer,
+/// and an `--allow-empty` checkpoint of the head.
 pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CommitArgs) -> Result<(), CliError> {
     // 1. Compute the next root from staging. Persists NOTHING. Fails fast if
     //    nothing is staged — before any wallet/anchor work.
-    let prepared = store_ops::stage_to_root(ctx)?;
+    let pb = ui.byte_progress(0, "Sealing", args.progress);
+    let mut on_progress = |done: u64, total: u64| {
+        pb.set_length(total);
+        pb.set_position(done);
+    };
+    let prepared = if args.allow_empty {
+        store_ops::stage_to_root_allow_empty(ctx, &mut on_progress)
+    } else {
+        store_ops::stage_to_root_with_progress(ctx, false, &mut on_progress).map(Some)
+    };
+    pb.finish_and_clear();
+    let Some(mut prepared) = prepared? else {
+        return checkpoint(ctx, ui, &args);
+    };
+    // --changes-only: the new generation layers the staged files on the head.
+    if args.changes_only {
+        if let Some(head) = store_ops::current_root(ctx)? {
+            prepared = prepared.with_parent(head);
+        }
+    }
+    // --deterministic: the generation records the given date, not the clock.
+    if let Some(date) = args.date.filter(|_| args.deterministic) {
+        prepared = prepared.with_timestamp(date.secs);
+    }
     let new_root_hex = prepared.root.to_hex();
 
     // Resolve the per-capsule DIG amount: flag > env (DIGSTORE_DIG_AMOUNT) > dig.toml
@@ -33,12 +60,40 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CommitArgs) -> Result<(),
     // STOP — no seed unlock, no wallet scan, no on-chain update, no finalize.
     // Nothing is spent and nothing is published; this is a safe cost preview.
     if args.dry_run {
-        return dry_run(ctx, ui, &prepared.root, dig_amount);
+        return dry_run(ctx, ui, args.changes_only, &prepared.root, dig_amount);
+    }
+
+    // Room for the new chunks and the module must exist before anything is
+    // anchored: a commit that dies on a full disk after the on-chain update
+    // would leave the chain ahead of local history.
+    if !args.force {
+        let estimate = store_ops::estimate_commit_size(ctx)?;
+        store_ops::check_free_space(ctx, store_ops::commit_space_needed(&estimate))?;
+    }
+
+    // The pre-commit hook sees the staged keys and the root it is about to
+    // anchor; a failure stops here, before any wallet/anchor work.
+    let hook_keys: Vec<String> = store_ops::list_staged(ctx)?
+        .0
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    if !args.no_verify {
+        hooks::run(ui, &ctx.op_dir, Hook::PreCommit, &hook_keys, &prepared.root)?;
+    }
+
+    // A `--changes-only` layer is local-only (see `store_ops::ensure_publishable`):
+    // anchoring it would move the singleton to a version no remote can serve, so
+    // it is finalized without touching the wallet or the chain.
+    if args.changes_only {
+        return commit_locally(ctx, ui, &args, prepared, &hook_keys);
     }
 
     // 2. Anchor gate: unlock seed (NoSeed → exit 9), build the (mock or real)
     //    backend, warn if mocked, surface the fee.
     let (keys, mnemonic, anchor, mocked, fee) = anchor_backend::prepare_anchor(ui)?;
+    let (_, writer) = super::access::writer_key(&keys)?;
+    store_ops::check_writer(ctx, &writer)?;
 
     // 3. Load the store's anchor state. Every store is anchored at init, so a
     //    missing anchor.toml is an error state, not a fresh-store case.
@@ -218,6 +273,16 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CommitArgs) -> Result<(),
                 Some(cs),
                 crate::ops::serve::empty_manifest(),
             )?;
+            save_note(ctx, &args, outcome.generation)?;
+            if !args.no_verify {
+                hooks::run(
+                    ui,
+                    &ctx.op_dir,
+                    Hook::PostCommit,
+                    &hook_keys,
+                    &outcome.roothash,
+                )?;
+            }
             let coin_hex = hex::encode(coin_id.as_ref());
 
             // The capsule identity of this deployment: `storeId:rootHash`
@@ -283,26 +348,130 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: CommitArgs) -> Result<(),
     }
 }
 
+/// Finalize a commit that is never anchored (`--changes-only`): the generation,
+/// module and history entry are written with no on-chain pointer, and the
+/// singleton keeps the last anchored root.
+fn commit_locally(
+    ctx: &CliContext,
+    ui: &crate::ui::Ui,
+    args: &CommitArgs,
+    prepared: store_ops::PreparedCommit,
+    hook_keys: &[String],
+) -> Result<(), CliError> {
+    let outcome =
+        store_ops::finalize_commit(ctx, prepared, None, crate::ops::serve::empty_manifest())?;
+    save_note(ctx, args, outcome.generation)?;
+    if !args.no_verify {
+        hooks::run(
+            ui,
+            &ctx.op_dir,
+            Hook::PostCommit,
+            hook_keys,
+            &outcome.roothash,
+        )?;
+    }
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": outcome.roothash.to_hex(),
+            "module": outcome.output_path.display().to_string(),
+            "size": outcome.output_size,
+            "anchor_status": "local",
+        }));
+    } else {
+        ui.success(format!(
+            "Committed {} locally — not anchored, nothing spent.",
+            outcome.roothash.to_hex()
+        ));
+        if ctx.verbose {
+            ui.line(format!(
+                "  module: {} ({} bytes)",
+                outcome.output_path.display(),
+                outcome.output_size
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `commit --allow-empty` with nothing new: a checkpoint of the head. It only
+/// adds a history entry (with a note of its own) for the head's root; the
+/// chain already holds that root, so it is never anchored and costs nothing.
+fn checkpoint(ctx: &CliContext, ui: &crate::ui::Ui, args: &CommitArgs) -> Result<(), CliError> {
+    let head = store_ops::current_root(ctx)?
+        .ok_or_else(|| CliError::NotFound("no committed root".into()))?;
+    if args.dry_run {
+        return dry_run(ctx, ui, true, &head, 0);
+    }
+    let hook_keys: Vec<String> = store_ops::list_staged(ctx)?
+        .0
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    if !args.no_verify {
+        hooks::run(ui, &ctx.op_dir, Hook::PreCommit, &hook_keys, &head)?;
+    }
+    let timestamp = args.date.filter(|_| args.deterministic).map(|d| d.secs);
+    let entry = store_ops::record_checkpoint(ctx, timestamp)?;
+    save_note(ctx, args, entry.id)?;
+    if !args.no_verify {
+        hooks::run(ui, &ctx.op_dir, Hook::PostCommit, &hook_keys, &head)?;
+    }
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": head.to_hex(),
+            "id": entry.id,
+            "checkpoint": true,
+            "anchor_status": "local",
+        }));
+    } else {
+        ui.success(format!(
+            "Recorded a checkpoint of {} — local only, nothing spent.",
+            head.to_hex()
+        ));
+    }
+    Ok(())
+}
+
+/// Record the commit's `-m`, `--author` (default: the dighub handle) and
+/// `--date` as the note of history entry `id`.
+fn save_note(ctx: &CliContext, args: &CommitArgs, id: u64) -> Result<(), CliError> {
+    CommitNote {
+        message: args.message.clone(),
+        author: args.author.clone().or_else(commit_note::default_author),
+        date: args.date.map(|d| d.to_string()),
+    }
+    .save(&ctx.dig_dir, id)
+}
+
 /// `commit --dry-run`: report the resulting version (root) and the EXACT cost of
 /// publishing it (the per-capsule DIG amount + the configured XCH fee) WITHOUT spending, anchoring,
 /// or finalizing anything. The root is computed from staging exactly as a real
 /// commit would (so the previewed root is the one a real commit produces); the
-/// fee is read from the global config without unlocking the seed.
+/// fee is read from the global config without unlocking the seed. The disk
+/// growth comes from [`digstore_store::Store::estimate_commit_size`].
 fn dry_run(
     ctx: &CliContext,
     ui: &crate::ui::Ui,
+    local: bool,
     root: &digstore_core::Bytes32,
     dig_amount: u64,
 ) -> Result<(), CliError> {
     let store_id = ctx.find_store_id()?;
     let capsule = format!("{}:{}", store_id.to_hex(), root.to_hex());
+    let estimate = store_ops::estimate_commit_size(ctx)?;
 
     // The XCH fee is a global-config value; load it directly (no wallet/seed). On
     // any load failure, fall back to the default fee so the preview still works.
-    let fee = digstore_chain::config::dig_home()
-        .and_then(|home| digstore_chain::config::GlobalConfig::load(&home))
-        .map(|g| g.fee)
-        .unwrap_or_else(|_| digstore_chain::config::GlobalConfig::default().fee);
+    // A `local` (never anchored) commit costs nothing.
+    let (dig_amount, fee) = if local {
+        (0, 0)
+    } else {
+        let fee = digstore_chain::config::dig_home()
+            .and_then(|home| digstore_chain::config::GlobalConfig::load(&home))
+            .map(|g| g.fee)
+            .unwrap_or_else(|_| digstore_chain::config::GlobalConfig::default().fee);
+        (dig_amount, fee)
+    };
 
     if ui.json() {
         ui.emit_json(&serde_json::json!({
@@ -315,6 +484,7 @@ fn dry_run(
             "fee_xch_mojos": fee,
             "fee_xch_display": format_xch(fee),
             "spent": false,
+            "estimate": super::staged::estimate_json(&estimate),
         }));
     } else {
         ui.success(format!("dry run — would publish version {}", root.to_hex()));
@@ -324,6 +494,10 @@ fn dry_run(
             format_dig(dig_amount),
             format_xch(fee)
         ));
+        ui.line(format!(
+            "  disk: {} bytes in {} new chunks ({} bytes deduplicated)",
+            estimate.new_bytes, estimate.new_chunks, estimate.reused_bytes
+        ));
         ui.hint("digstore commit -m \"<message>\"   # to actually publish");
     }
     Ok(())
@@ -350,8 +524,10 @@ fn do_push(
     ui: &crate::ui::Ui,
     args: &CommitArgs,
 ) -> Option<Result<crate::commands::push::PushOutcome, CliError>> {
-    // Explicit opt-out, or a non-interactive run with no `--push`: do not push.
-    if args.no_push || (!args.push && !ui.can_prompt()) {
+    // Explicit opt-out, a local-only `--changes-only` layer (see
+    // `store_ops::ensure_publishable`), or a non-interactive run with no
+    // `--push`: do not push.
+    if args.no_push || args.changes_only || (!args.push && !ui.can_prompt()) {
         return None;
     }
     // `--push` pushes unconditionally; otherwise ask (we are interactive here).
@@ -372,6 +548,8 @@ fn do_push(
 /// result into its single object instead of printing.)
 fn maybe_offer_push(ctx: &CliContext, ui: &crate::ui::Ui, args: &CommitArgs) {
     match do_push(ctx, ui, args) {
+        // A `--changes-only` layer is local-only: there is nothing to push.
+        None if args.changes_only => {}
         // No push attempted (opted out / declined / non-interactive default).
         None => ui.hint("digstore push origin"),
         Some(Ok(out)) => {
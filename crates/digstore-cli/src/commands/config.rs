@@ -0,0 +1,138 @@
+//! `digstore config` — read, set, and check the global settings in
+//! `~/.dig/config.toml` (see `digstore_chain::config::ConfigKey`). Every value
+//! is validated before it is written, so a typo is rejected at set time instead
+//! of breaking the first command that reads it. A key's `DIGSTORE_<KEY>`
+//! environment variable overrides the file for one process; reads show the
+//! effective value, while writes only ever touch the file.
+
+use crate::cli::ConfigArgs;
+use crate::error::CliError;
+use crate::ui::Ui;
+use digstore_chain::config::{self, ConfigKey, ConfigOrigin, GlobalConfig};
+use digstore_chain::ChainError;
+
+/// A bad key or value is the caller's mistake, not an internal failure.
+fn invalid(e: ChainError) -> CliError {
+    match e {
+        ChainError::Config(m) => CliError::InvalidArgument(m),
+        e => CliError::from(e),
+    }
+}
+
+pub fn run(ui: &Ui, args: ConfigArgs) -> Result<(), CliError> {
+    let home = config::dig_home().map_err(CliError::from)?;
+    if args.validate {
+        return validate(ui, &home);
+    }
+    if args.list {
+        return list(ui, &home, args.show_origin);
+    }
+    let name = args
+        .key
+        .expect("clap requires a key without --validate or --list");
+    let key = ConfigKey::parse(&name).map_err(invalid)?;
+
+    if args.unset || args.value.is_some() {
+        let mut cfg = GlobalConfig::load_file(&home).map_err(invalid)?;
+        match &args.value {
+            Some(value) => key.set(&mut cfg, value).map_err(invalid)?,
+            None => key.unset(&mut cfg),
+        }
+        cfg.save(&home).map_err(CliError::from)?;
+        if ui.json() {
+            ui.emit_json(&serde_json::json!({ "key": key.name(), "value": key.get(&cfg) }));
+        } else if args.unset {
+            ui.success(format!("Unset {}", key.name()));
+        } else {
+            ui.success(format!(
+                "Set {} = {}",
+                key.name(),
+                args.value.unwrap_or_default()
+            ));
+        }
+        if std::env::var(key.env_var()).is_ok_and(|v| !v.is_empty()) {
+            ui.hint(format!("{} is set and overrides this value", key.env_var()));
+        }
+        return Ok(());
+    }
+
+    let cfg = GlobalConfig::load(&home).map_err(invalid)?;
+    let value = key
+        .get(&cfg)
+        .ok_or_else(|| CliError::NotFound(format!("{} is not set", key.name())))?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({ "key": key.name(), "value": value }));
+    } else {
+        ui.line(value);
+    }
+    Ok(())
+}
+
+/// `--list [--show-origin]`: every key with a value, in `ConfigKey::ALL` order.
+fn list(ui: &Ui, home: &std::path::Path, show_origin: bool) -> Result<(), CliError> {
+    let cfg = GlobalConfig::load(home).map_err(invalid)?;
+    let path = config::config_path(home);
+    let mut rows = Vec::new();
+    for (key, origin) in GlobalConfig::origins(home).map_err(invalid)? {
+        let Some(value) = key.get(&cfg) else {
+            continue;
+        };
+        let origin = match origin {
+            ConfigOrigin::Env => format!("env:{}", key.env_var()),
+            ConfigOrigin::File => format!("file:{}", path.display()),
+            ConfigOrigin::Default => "default".to_string(),
+        };
+        rows.push((key, value, origin));
+    }
+    if ui.json() {
+        ui.emit_json(&serde_json::json!(rows
+            .iter()
+            .map(|(key, value, origin)| {
+                let mut row = serde_json::json!({ "key": key.name(), "value": value });
+                if show_origin {
+                    row["origin"] = serde_json::json!(origin);
+                }
+                row
+            })
+            .collect::<Vec<_>>()));
+        return Ok(());
+    }
+    for (key, value, origin) in &rows {
+        if show_origin {
+            ui.line(format!("{origin}\t{}={value}", key.name()));
+        } else {
+            ui.line(format!("{}={value}", key.name()));
+        }
+    }
+    Ok(())
+}
+
+fn validate(ui: &Ui, home: &std::path::Path) -> Result<(), CliError> {
+    let path = config::config_path(home);
+    let cfg = GlobalConfig::load_file(home).map_err(invalid)?;
+    let mut problems = cfg.problems();
+    // A bad override would fail every command that loads the config.
+    if let Err(e) = cfg.clone().apply_env(|var| std::env::var(var).ok()) {
+        problems.push(match e {
+            ChainError::Config(m) => m,
+            e => e.to_string(),
+        });
+    }
+    if !problems.is_empty() {
+        return Err(CliError::InvalidArgument(format!(
+            "{} has {} invalid setting(s):\n  {}",
+            path.display(),
+            problems.len(),
+            problems.join("\n  ")
+        )));
+    }
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "valid": true,
+            "path": path.display().to_string(),
+        }));
+    } else {
+        ui.success(format!("{} is valid", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use digstore_core::{Bytes32, Urn};
+
+use crate::cli::DecryptArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::{client_crypto, store_ops};
+
+/// Decrypt sealed resource bytes that were fetched on their own (for instance
+/// `cat <retrieval-key> --out blob.enc`) with the key the URN derives. The
+/// resource's chunk lengths come from this store's manifest at the URN's root
+/// (or the current root); without one the blob must be a single chunk.
+pub fn run(ctx: &CliContext, _ui: &crate::ui::Ui, args: DecryptArgs) -> Result<(), CliError> {
+    let urn = Urn::parse(args.urn.trim())
+        .map_err(|e| CliError::InvalidArgument(format!("bad urn: {e}")))?;
+    let resource_key = urn.resource_key.clone().ok_or_else(|| {
+        CliError::InvalidArgument("the URN must name a resource (…/<path>)".into())
+    })?;
+    let blob = std::fs::read(&args.input)
+        .map_err(|e| CliError::InvalidArgument(format!("read {}: {e}", args.input.display())))?;
+    let salt: Option<[u8; 32]> = match &args.salt {
+        Some(hex) => Some(
+            Bytes32::from_hex(hex)
+                .map_err(|_| CliError::InvalidArgument("salt must be 32-byte hex".into()))?
+                .0,
+        ),
+        None => None,
+    };
+
+    let root = match urn.root_hash {
+        Some(root) => Some(root),
+        None => store_ops::current_root(ctx).ok().flatten(),
+    };
+    let chunk_lens = root
+        .and_then(|root| store_ops::resource_chunk_lens(ctx, &root, &resource_key).ok())
+        .unwrap_or_default();
+
+    let plaintext = client_crypto::decrypt_chunks(&blob, &urn, salt.as_ref(), &chunk_lens)?;
+    write_out(args.out.as_deref(), &plaintext)
+}
+
+fn write_out(out: Option<&Path>, bytes: &[u8]) -> Result<(), CliError> {
+    use std::io::Write;
+    match out {
+        Some(path) => std::fs::write(path, bytes)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("write {}: {e}", path.display()))),
+        None => std::io::stdout()
+            .write_all(bytes)
+            .map_err(|e| CliError::Other(e.into())),
+    }
+}
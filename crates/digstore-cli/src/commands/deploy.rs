@@ -400,11 +400,19 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: DeployArgs) -> Result<(),
         ui,
         CommitArgs {
             message: cfg.message.clone(),
+            author: None,
+            date: None,
+            deterministic: false,
             wait_timeout: cfg.wait_timeout,
             resubmit: false,
             push: true,
             no_push: false,
             dry_run: false,
+            allow_empty: false,
+            changes_only: false,
+            no_verify: false,
+            force: false,
+            progress: false,
             // #17: pass the WRITER DELEGATE key (deploy token) down so CI advances the
             // root with a revocable writer key, not the owner seed. `--writer-key` /
             // DIGSTORE_WRITER_KEY (distinct from the §21 publisher --deploy-key above).
@@ -1,4 +1,4 @@
-//! `digstore doctor` — pre-publish preflight.
+//! `digstore doctor` — environment check and pre-publish preflight.
 //!
 //! Publishing is a costly, irreversible on-chain action (the per-capsule $DIG price,
 //! plus an XCH fee, per version). `doctor` runs the checks that a publish depends on
@@ -12,7 +12,14 @@
 //!     is unlocked, so `doctor` never prompts for a passphrase),
 //!   - dighub login (so `push` to the default remote is authorized),
 //!   - the default remote is reachable,
-//!   - the content/output directory exists.
+//!   - the content/output directory exists,
+//!   - the `.dig` workspace is writable and its store configs parse,
+//!   - there is free disk space for new chunks,
+//!   - the running binary is the latest release (from the cached update check,
+//!     so this never touches the network).
+//!
+//! Environment checks only warn when something is merely absent (no `.dig`
+//! yet, unknown latest release); a warning never fails `doctor`.
 
 use digstore_chain::dig::{self, format_dig, format_xch};
 use digstore_chain::{config as chain_config, seed as chain_seed, unlock};
@@ -23,36 +30,50 @@ use crate::dig_toml::DigToml;
 use crate::error::CliError;
 use crate::runtime::block_on;
 use crate::ui::Ui;
+use crate::workspace::Workspace;
+use std::path::Path;
+
+/// Below this much free space on the `.dig` volume, doctor fails; below
+/// [`LOW_DISK_BYTES`] it warns.
+const MIN_DISK_BYTES: u64 = 100 * 1024 * 1024;
+const LOW_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    /// Worth fixing, but does not fail doctor.
+    Warn,
+    Fail,
+    /// Could not be checked (a soft note).
+    Skip,
+}
 
 /// One preflight check result.
 struct Check {
     name: &'static str,
-    /// `Some(true)` pass, `Some(false)` fail, `None` skipped/unknown (a soft note).
-    status: Option<bool>,
+    status: Status,
     detail: String,
 }
 
 impl Check {
-    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
         Check {
             name,
-            status: Some(true),
+            status,
             detail: detail.into(),
         }
     }
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Pass, detail)
+    }
+    fn warn(name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Warn, detail)
+    }
     fn fail(name: &'static str, detail: impl Into<String>) -> Self {
-        Check {
-            name,
-            status: Some(false),
-            detail: detail.into(),
-        }
+        Self::new(name, Status::Fail, detail)
     }
     fn skip(name: &'static str, detail: impl Into<String>) -> Self {
-        Check {
-            name,
-            status: None,
-            detail: detail.into(),
-        }
+        Self::new(name, Status::Skip, detail)
     }
 }
 
@@ -185,9 +206,123 @@ pub fn run(ctx: &CliContext, ui: &Ui, _args: DoctorArgs) -> Result<(), CliError>
         ));
     }
 
+    // 7. The `.dig` workspace is writable and every store config parses.
+    checks.push(workspace_check(&ctx.workspace_dir));
+    checks.push(config_check(&ctx.workspace_dir));
+
+    // 8. Free space on the volume that will hold new chunks.
+    checks.push(disk_check(&ctx.workspace_dir));
+
+    // 9. The running binary vs the latest release seen by the update check.
+    let cache = crate::commands::update::load_cache();
+    checks.push(version_check(
+        crate::commands::update::current_version(),
+        &cache.latest_tag,
+    ));
+
     emit(ui, &checks)
 }
 
+/// `.dig` exists and accepts a write (probed with a throwaway file). A missing
+/// workspace is only a warning: `init` creates it.
+fn workspace_check(dir: &Path) -> Check {
+    if !dir.is_dir() {
+        return Check::warn(
+            ".dig workspace",
+            format!(
+                "{} does not exist yet — `digstore init` creates it",
+                dir.display()
+            ),
+        );
+    }
+    let probe = dir.join(format!(".doctor-probe-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::pass(".dig workspace", format!("{} writable", dir.display()))
+        }
+        Err(e) => Check::fail(
+            ".dig workspace",
+            format!(
+                "{} is not writable ({e}) — fix its permissions or pass --dig-dir",
+                dir.display()
+            ),
+        ),
+    }
+}
+
+/// `workspace.toml` and each store's `config.toml` parse.
+fn config_check(dir: &Path) -> Check {
+    if !dir.join("workspace.toml").exists() {
+        return Check::skip("config", "no workspace.toml");
+    }
+    let ws = match Workspace::load(dir) {
+        Ok(ws) => ws,
+        Err(e) => return Check::fail("config", format!("{e} — fix or remove workspace.toml")),
+    };
+    for name in ws.stores.keys() {
+        let path = dir.join("stores").join(name).join("config.toml");
+        if let Err(e) = digstore_store::load_config(&path) {
+            return Check::fail(
+                "config",
+                format!("store '{name}': {} — {e}", path.display()),
+            );
+        }
+    }
+    let active = ws.active.as_deref().unwrap_or("default");
+    Check::pass(
+        "config",
+        format!("{} store(s), active '{active}'", ws.stores.len()),
+    )
+}
+
+/// Free space on the volume holding `dir` (or its nearest existing ancestor).
+fn disk_check(dir: &Path) -> Check {
+    let Some(existing) = dir.ancestors().find(|d| d.exists()) else {
+        return Check::skip("disk space", format!("{} not found", dir.display()));
+    };
+    match fs4::available_space(existing) {
+        Ok(free) => disk_status(free),
+        Err(e) => Check::skip("disk space", format!("could not read free space: {e}")),
+    }
+}
+
+fn disk_status(free: u64) -> Check {
+    let detail = format!("{} MiB free", free / (1024 * 1024));
+    if free < MIN_DISK_BYTES {
+        Check::fail(
+            "disk space",
+            format!("{detail} — free some space before committing"),
+        )
+    } else if free < LOW_DISK_BYTES {
+        Check::warn(
+            "disk space",
+            format!("{detail} — large commits may not fit"),
+        )
+    } else {
+        Check::pass("disk space", detail)
+    }
+}
+
+/// Compare the running version with the latest release tag cached by the
+/// update check (empty when it never ran).
+fn version_check(current: &str, latest_tag: &str) -> Check {
+    if latest_tag.is_empty() {
+        return Check::skip(
+            "version",
+            format!("v{current} (latest release unknown — `digstore update` checks)"),
+        );
+    }
+    if crate::commands::update::is_newer(current, latest_tag) {
+        Check::warn(
+            "version",
+            format!("v{current}, but {latest_tag} is out — run `digstore update`"),
+        )
+    } else {
+        Check::pass("version", format!("v{current} (latest)"))
+    }
+}
+
 /// Scan the wallet once (unlocked seed) and return `(dig, xch, fee)`. Uses the
 /// shared anchor gate so the mock backend is honored in tests/CI.
 fn scan_balances(ui: &Ui) -> Result<(u64, u64, u64), CliError> {
@@ -238,7 +373,7 @@ fn base_url(url: &str) -> String {
 /// Render the checks (human table or JSON) and return a non-zero error if any
 /// HARD check failed (skips/soft notes do not fail doctor).
 fn emit(ui: &Ui, checks: &[Check]) -> Result<(), CliError> {
-    let any_fail = checks.iter().any(|c| c.status == Some(false));
+    let any_fail = checks.iter().any(|c| c.status == Status::Fail);
 
     if ui.json() {
         let arr: Vec<_> = checks
@@ -247,9 +382,10 @@ fn emit(ui: &Ui, checks: &[Check]) -> Result<(), CliError> {
                 serde_json::json!({
                     "check": c.name,
                     "status": match c.status {
-                        Some(true) => "pass",
-                        Some(false) => "fail",
-                        None => "skip",
+                        Status::Pass => "pass",
+                        Status::Warn => "warn",
+                        Status::Fail => "fail",
+                        Status::Skip => "skip",
                     },
                     "detail": c.detail,
                 })
@@ -260,12 +396,13 @@ fn emit(ui: &Ui, checks: &[Check]) -> Result<(), CliError> {
             "checks": arr,
         }));
     } else {
-        ui.line("Pre-publish checks:");
+        ui.line("Environment and pre-publish checks:");
         for c in checks {
             let mark = match c.status {
-                Some(true) => "✓",
-                Some(false) => "✗",
-                None => "•",
+                Status::Pass => "✓",
+                Status::Warn => "!",
+                Status::Fail => "✗",
+                Status::Skip => "•",
             };
             ui.line(format!("  {mark} {:<16} {}", c.name, c.detail));
         }
@@ -318,7 +455,28 @@ mod tests {
     }
 
     #[test]
-    fn emit_ok_when_all_pass_or_skip() {
+    fn disk_and_version_checks_grade_their_inputs() {
+        assert_eq!(disk_status(50 * 1024 * 1024).status, Status::Fail);
+        assert_eq!(disk_status(500 * 1024 * 1024).status, Status::Warn);
+        assert_eq!(disk_status(LOW_DISK_BYTES).status, Status::Pass);
+        assert_eq!(version_check("0.4.0", "v0.5.0").status, Status::Warn);
+        assert_eq!(version_check("0.4.0", "v0.4.0").status, Status::Pass);
+        assert_eq!(version_check("0.4.0", "").status, Status::Skip);
+    }
+
+    #[test]
+    fn workspace_check_warns_when_missing_and_passes_when_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().join(".dig");
+        assert_eq!(workspace_check(&ws).status, Status::Warn);
+        std::fs::create_dir(&ws).unwrap();
+        assert_eq!(workspace_check(&ws).status, Status::Pass);
+        assert_eq!(std::fs::read_dir(&ws).unwrap().count(), 0);
+        assert_eq!(config_check(&ws).status, Status::Skip);
+    }
+
+    #[test]
+    fn emit_ok_when_all_pass_warn_or_skip() {
         let ui = Ui::resolve(
             crate::ui::ColorChoice::Never,
             true,
@@ -327,7 +485,11 @@ mod tests {
             false,
             false,
         );
-        let checks = vec![Check::pass("a", "ok"), Check::skip("b", "n/a")];
+        let checks = vec![
+            Check::pass("a", "ok"),
+            Check::warn("b", "meh"),
+            Check::skip("c", "n/a"),
+        ];
         assert!(emit(&ui, &checks).is_ok());
     }
 }
@@ -0,0 +1,34 @@
+use digstore_core::Bytes32;
+
+use crate::cli::ExportArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Write the versions committed after `--since` to a delta bundle.
+pub fn run(ctx: &CliContext, ui: &Ui, args: ExportArgs) -> Result<(), CliError> {
+    let base = Bytes32::from_hex(&args.since)
+        .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {}", args.since)))?;
+    let bundle = store_ops::export_since(ctx, base, &args.output)?;
+    let bytes: u64 = bundle.chunks.iter().map(|(_, c)| c.len() as u64).sum();
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "base": base.to_hex(),
+            "roots": bundle.generations.iter().map(|m| m.root.to_hex()).collect::<Vec<_>>(),
+            "chunks": bundle.chunks.len(),
+            "chunk_bytes": bytes,
+            "output": args.output.display().to_string(),
+        }));
+    } else {
+        ui.success(format!(
+            "exported {} version(s) and {} chunk(s) ({} bytes) since {} to {}",
+            bundle.generations.len(),
+            bundle.chunks.len(),
+            bytes,
+            base.to_hex(),
+            args.output.display()
+        ));
+    }
+    Ok(())
+}
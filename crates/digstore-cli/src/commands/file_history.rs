@@ -0,0 +1,43 @@
+use crate::cli::FileHistoryArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// List the versions where one file was added or its content changed,
+/// oldest first. Fails with `NotFound` when no version holds the file.
+pub fn run(ctx: &CliContext, ui: &Ui, args: FileHistoryArgs) -> Result<(), CliError> {
+    let versions = store_ops::file_versions(ctx, &args.path)?;
+    if versions.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "{} is not in any version",
+            args.path
+        )));
+    }
+    if ui.json() {
+        ui.emit_json(
+            &versions
+                .iter()
+                .map(|v| {
+                    serde_json::json!({
+                        "id": v.generation_id,
+                        "root": v.root.to_hex(),
+                        "hash": v.hash.to_hex(),
+                        "timestamp": v.timestamp,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+    } else {
+        for v in &versions {
+            ui.line(format!(
+                "deployment {}  root {}  hash {}  ts {}",
+                v.generation_id,
+                v.root.to_hex(),
+                v.hash.to_hex(),
+                v.timestamp
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use digstore_core::Bytes32;
+
+use crate::cli::GrepArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Search a deployment's text files for a regular expression. Matches are
+/// printed as they are found (`path:line`, `path:n:line`, or just `path` with
+/// `-l`); `--json` collects them into one array.
+pub fn run(ctx: &CliContext, ui: &Ui, args: GrepArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let mut json_matches = Vec::new();
+    store_ops::grep(ctx, &args.pattern, root, args.text, |m| {
+        if ui.json() {
+            json_matches.push(if args.files_with_matches {
+                serde_json::json!({ "path": m.resource_key })
+            } else {
+                serde_json::json!({
+                    "path": m.resource_key,
+                    "line_number": m.line_number,
+                    "line": m.line,
+                })
+            });
+        } else if args.files_with_matches {
+            ui.line(&m.resource_key);
+        } else if args.line_number {
+            ui.line(&format!("{}:{}:{}", m.resource_key, m.line_number, m.line));
+        } else {
+            ui.line(&format!("{}:{}", m.resource_key, m.line));
+        }
+        !args.files_with_matches
+    })?;
+    if ui.json() {
+        ui.emit_json(&json_matches);
+    }
+    Ok(())
+}
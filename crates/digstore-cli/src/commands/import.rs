@@ -0,0 +1,25 @@
+use crate::cli::ImportArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Apply a delta bundle from `digstore export --since` onto this store.
+pub fn run(ctx: &CliContext, ui: &Ui, args: ImportArgs) -> Result<(), CliError> {
+    let roots = store_ops::import_delta(ctx, &args.bundle)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "roots": roots.iter().map(|r| r.to_hex()).collect::<Vec<_>>(),
+        }));
+    } else {
+        ui.success(format!(
+            "imported {} version(s) from {}",
+            roots.len(),
+            args.bundle.display()
+        ));
+        for root in &roots {
+            ui.line(root.to_hex());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::io::Read;
+
+use digstore_core::{Bytes32, SecretSalt, Urn, Visibility, CHAIN};
+
+use crate::cli::KeygenArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ui::Ui;
+
+/// One URN's derived material.
+struct UrnKeys {
+    urn: String,
+    storage_address: String,
+    encryption_key: String,
+}
+
+/// Derive the storage address (retrieval key) and content encryption key for
+/// each URN given as an argument, read from `--urns-file`, or piped with
+/// `--from-stdin`, in input order. Both come from the rootless canonical URN,
+/// as `commit` derives them; this store's secret salt is mixed into the key for
+/// its own URNs when it is private.
+pub fn run(ctx: &CliContext, ui: &Ui, args: KeygenArgs) -> Result<(), CliError> {
+    let mut inputs = args.urns;
+    if let Some(path) = &args.urns_file {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| CliError::InvalidArgument(format!("read {}: {e}", path.display())))?;
+        inputs.extend(urn_lines(&text));
+    }
+    if args.from_stdin {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("read stdin: {e}")))?;
+        inputs.extend(urn_lines(&text));
+    }
+    if inputs.is_empty() {
+        return Err(CliError::InvalidArgument(
+            "no URNs: pass them as arguments, --urns-file <file> or --from-stdin".into(),
+        ));
+    }
+
+    // Only a private store of our own has a salt to mix in.
+    let own_salt = match ctx.load_config() {
+        Ok(cfg) => match cfg.visibility {
+            Visibility::Private(salt) => Some((cfg.store_id, salt)),
+            Visibility::Public => None,
+        },
+        Err(_) => None,
+    };
+    let keys = inputs
+        .iter()
+        .map(|text| derive(text, own_salt.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if ui.json() {
+        ui.emit_json(
+            &keys
+                .iter()
+                .map(|k| {
+                    serde_json::json!({
+                        "urn": k.urn,
+                        "storage_address": k.storage_address,
+                        "encryption_key": k.encryption_key,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+        return Ok(());
+    }
+    for k in &keys {
+        ui.line(format!(
+            "{}\t{}\t{}",
+            k.urn, k.storage_address, k.encryption_key
+        ));
+    }
+    Ok(())
+}
+
+/// Non-empty lines that are not `#` comments, trimmed.
+fn urn_lines(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+}
+
+fn derive(text: &str, own_salt: Option<&(Bytes32, SecretSalt)>) -> Result<UrnKeys, CliError> {
+    let urn = Urn::parse(text).map_err(|e| CliError::InvalidArgument(format!("{text}: {e}")))?;
+    let Some(resource_key) = urn.resource_key.clone() else {
+        return Err(CliError::InvalidArgument(format!(
+            "{text}: names no resource (expected urn:dig:chia:<store>[:<root>]/<path>)"
+        )));
+    };
+    let rootless = Urn {
+        chain: CHAIN.to_string(),
+        store_id: urn.store_id,
+        root_hash: None,
+        resource_key: Some(resource_key),
+    };
+    let salt = own_salt
+        .filter(|(store_id, _)| *store_id == urn.store_id)
+        .map(|(_, salt)| salt);
+    let key = digstore_crypto::derive_decryption_key(&rootless.canonical(), salt);
+    Ok(UrnKeys {
+        urn: text.to_string(),
+        storage_address: rootless.retrieval_key().to_hex(),
+        encryption_key: hex::encode(key),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urn_lines_skip_blanks_and_comments() {
+        let lines: Vec<_> = urn_lines("# tree\nurn:a\n\n  urn:b  \r\n").collect();
+        assert_eq!(lines, ["urn:a", "urn:b"]);
+    }
+}
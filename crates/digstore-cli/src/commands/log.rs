@@ -5,11 +5,22 @@ use crate::ops::store_ops;
 use crate::output;
 
 pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: LogArgs) -> Result<(), CliError> {
-    let entries = store_ops::log(ctx, args.limit)?;
+    let filter = store_ops::LogFilter {
+        limit: args.limit,
+        author: args.author,
+        grep: args.grep,
+        since: args.since,
+        until: args.until,
+    };
+    let entries = store_ops::log(ctx, &filter)?;
     if ui.json() {
         ui.emit_json(&entries);
     } else {
-        let text = output::render_log(&entries, false);
+        let text = if args.graph {
+            output::render_log_graph(&entries)
+        } else {
+            output::render_log(&entries, false)
+        };
         let trimmed = text.trim_end_matches('\n');
         if !trimmed.is_empty() {
             ui.line(trimmed);
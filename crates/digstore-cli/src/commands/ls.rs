@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use digstore_core::Bytes32;
+
+use crate::cli::LsArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops::{self, FileEntry};
+use crate::ui::Ui;
+
+/// List the resources committed in a deployment: one path per line, a
+/// `--long` listing with size / chunk count / short retrieval key, or a
+/// `--tree` view grouped by `/`-separated directories.
+pub fn run(ctx: &CliContext, ui: &Ui, args: LsArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+
+    let files = store_ops::list_files(ctx, &root)?;
+
+    if ui.json() {
+        ui.emit_json(&files);
+        return Ok(());
+    }
+
+    if files.is_empty() {
+        ui.line("(no resources in this generation)");
+        return Ok(());
+    }
+    if args.tree {
+        for line in render_tree(&files) {
+            ui.line(line);
+        }
+    } else if args.long {
+        for f in &files {
+            ui.line(format!(
+                "{:>12}  {:>4}  {}  {}",
+                f.size,
+                f.chunks,
+                &f.retrieval_key[..12],
+                f.path
+            ));
+        }
+    } else {
+        for f in &files {
+            ui.line(&f.path);
+        }
+    }
+    Ok(())
+}
+
+/// A directory level of the `--tree` view: sub-directories and file names.
+#[derive(Default)]
+struct Dir {
+    dirs: BTreeMap<String, Dir>,
+    files: Vec<String>,
+}
+
+fn render_tree(files: &[FileEntry]) -> Vec<String> {
+    let mut top = Dir::default();
+    for f in files {
+        let mut parts: Vec<&str> = f.path.split('/').collect();
+        let name = parts.pop().unwrap_or_default();
+        let mut dir = &mut top;
+        for p in parts {
+            dir = dir.dirs.entry(p.to_string()).or_default();
+        }
+        dir.files.push(name.to_string());
+    }
+    let mut out = Vec::new();
+    walk(&top, "", &mut out);
+    out
+}
+
+fn walk(dir: &Dir, prefix: &str, out: &mut Vec<String>) {
+    let entries: Vec<(&str, Option<&Dir>)> = dir
+        .dirs
+        .iter()
+        .map(|(name, sub)| (name.as_str(), Some(sub)))
+        .chain(dir.files.iter().map(|name| (name.as_str(), None)))
+        .collect();
+    let last = entries.len().saturating_sub(1);
+    for (i, (name, sub)) in entries.into_iter().enumerate() {
+        let (branch, indent) = if i == last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        match sub {
+            Some(sub) => {
+                out.push(format!("{prefix}{branch}{name}/"));
+                walk(sub, &format!("{prefix}{indent}"), out);
+            }
+            None => out.push(format!("{prefix}{branch}{name}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> FileEntry {
+        FileEntry {
+            path: path.into(),
+            size: 0,
+            chunks: 1,
+            retrieval_key: "00".repeat(32),
+        }
+    }
+
+    #[test]
+    fn tree_groups_by_directory() {
+        let files = [
+            entry("assets/css/site.css"),
+            entry("assets/logo.png"),
+            entry("index.html"),
+        ];
+        assert_eq!(
+            render_tree(&files),
+            vec![
+                "├── assets/",
+                "│   ├── css/",
+                "│   │   └── site.css",
+                "│   └── logo.png",
+                "└── index.html",
+            ]
+        );
+    }
+}
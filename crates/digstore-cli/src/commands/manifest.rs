@@ -0,0 +1,43 @@
+use digstore_core::Bytes32;
+
+use crate::cli::ManifestArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Write a deployment's canonical snapshot manifest to stdout or `--out`.
+/// Exporting re-derives every leaf from the stored chunks and checks them
+/// against the root, so a tampered generation fails here.
+pub fn run(ctx: &CliContext, ui: &Ui, args: ManifestArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let text = store_ops::export_manifest(ctx, root)?;
+
+    let Some(path) = &args.out else {
+        // Already JSON; print the canonical bytes in either output mode.
+        ui.line(text.trim_end_matches('\n'));
+        return Ok(());
+    };
+    std::fs::write(path, text.as_bytes()).map_err(|e| CliError::Other(e.into()))?;
+    let sha256 = digstore_crypto::sha256(text.as_bytes()).to_hex();
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "out": path.display().to_string(),
+            "sha256": sha256,
+        }));
+    } else {
+        ui.success(format!(
+            "manifest for {} written to {}",
+            root.to_hex(),
+            path.display()
+        ));
+        ui.line(format!("sha256 {sha256}"));
+    }
+    Ok(())
+}
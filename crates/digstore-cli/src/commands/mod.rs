@@ -4,9 +4,12 @@ use crate::cli::{Cli, Command};
 use crate::context::CliContext;
 use crate::error::CliError;
 
+pub mod access;
 pub mod add;
 pub mod anchor;
 pub mod balance;
+pub mod blame;
+pub mod branch;
 pub mod cat;
 pub mod checkout;
 pub mod clone;
@@ -14,6 +17,8 @@ pub mod collection;
 pub mod commit;
 pub mod compile;
 pub mod completion;
+pub mod config;
+pub mod decrypt;
 pub mod deploy;
 pub mod deploy_key;
 pub mod dev;
@@ -21,36 +26,65 @@ pub mod did;
 pub mod diff;
 pub mod dir;
 pub mod doctor;
+pub mod export;
+pub mod file_history;
+pub mod grep;
+pub mod import;
 pub mod init;
+pub mod keygen;
 pub mod keys;
 pub mod link;
 pub mod lock;
 pub mod log;
 pub mod login;
 pub mod logout;
+pub mod ls;
+pub mod manifest;
 pub mod new;
 pub mod nft;
 pub mod offer;
+pub mod prune;
 pub mod pull;
 pub mod push;
 pub mod remote;
 pub mod revoke;
+pub mod rotate_salt;
 pub mod seed;
 pub mod serve;
 pub mod setup;
+pub mod sign;
+pub mod size;
+pub mod squash;
 pub mod staged;
+pub mod stat;
 pub mod status;
 pub mod stores;
 pub mod unstage;
 pub mod update;
 pub mod urn;
 pub mod use_store;
+pub mod verify;
+pub mod verify_signature;
+pub mod wallet;
+pub mod whereis;
 pub mod whoami;
 
 pub fn dispatch(cli: Cli) -> Result<(), CliError> {
-    let ui =
-        crate::ui::Ui::from_flags(cli.color, cli.json, cli.quiet, cli.non_interactive, cli.yes);
+    let ui = crate::ui::Ui::from_flags(
+        cli.color,
+        cli.json,
+        cli.quiet,
+        cli.non_interactive,
+        cli.yes,
+        cli.no_progress,
+    );
     let cwd = std::env::current_dir().map_err(|e| CliError::Other(e.into()))?;
+    // Every seed/session path resolves the profile through this variable, so
+    // the override reaches wallet code that never sees the parsed flags.
+    if let Some(profile) = &cli.wallet_profile {
+        wallet::bad_name(profile)?;
+        std::env::set_var(digstore_chain::config::PROFILE_ENV, profile);
+    }
 
     // `init` and `clone` CREATE a store, so they anchor to CWD/.dig (no walk-up,
     // like `git init`/`git clone`); `compile` is a self-contained headless build
@@ -177,6 +211,8 @@ pub fn dispatch(cli: Cli) -> Result<(), CliError> {
         }
         Command::Seed(a) => return seed::run(&ui, a),
         Command::Lock(_) => return lock::run(&ui),
+        Command::Wallet(a) => return wallet::run(&ui, a),
+        Command::Config(a) => return config::run(&ui, a),
         // `setup`/`auth` guides seed + fund check + optional login; like `seed`/
         // `login` it is workspace-independent (it touches the identity dir, not a
         // store). `completion` just prints a static script.
@@ -214,18 +250,49 @@ pub fn dispatch(cli: Cli) -> Result<(), CliError> {
     }
 
     // Store-scoped commands: resolve the workspace, the store name, and op_dir.
+    // A `--store` value with a path separator names a store directory directly
+    // and skips the workspace; otherwise it is a store name in the workspace,
+    // then one registered machine-wide (`stores add-name`).
     let ws = crate::workspace::Workspace::load_or_migrate(&workspace_dir)?;
-    let name = ws.resolve_store_name(cli.store_name.as_deref())?;
-    let content_root = ws.content_root(&name);
-    let ctx = CliContext::for_store_with_op(
-        workspace_dir,
-        &name,
-        content_root,
-        cli.cwd.clone(),
-        cwd,
-        cli.json,
-        cli.verbose,
-    );
+    let outside = cli.store_name.as_deref().and_then(|value| {
+        CliContext::for_store_path(value, cli.cwd.clone(), cwd.clone(), cli.json, cli.verbose)
+            .or_else(|| {
+                if ws.stores.contains_key(value) {
+                    return None;
+                }
+                CliContext::for_named_store(
+                    value,
+                    cli.cwd.clone(),
+                    cwd.clone(),
+                    cli.json,
+                    cli.verbose,
+                )
+            })
+    });
+    let ctx = match outside {
+        Some(ctx) => ctx?,
+        None => {
+            let name = ws.resolve_store_name(cli.store_name.as_deref())?;
+            let content_root = ws.content_root(&name);
+            CliContext::for_store_with_op(
+                workspace_dir,
+                &name,
+                content_root,
+                cli.cwd.clone(),
+                cwd,
+                cli.json,
+                cli.verbose,
+            )
+        }
+    };
+
+    // Held until the command returns, so a second process writing the same
+    // store fails fast instead of interleaving with this one.
+    let _lock = if cli.command.writes_store() {
+        crate::ops::store_ops::lock_store(&ctx, cli.force_unlock)?
+    } else {
+        None
+    };
 
     match cli.command {
         Command::Add(a) => add::run(&ctx, &ui, a),
@@ -233,13 +300,33 @@ pub fn dispatch(cli: Cli) -> Result<(), CliError> {
         Command::Status(a) => status::run(&ctx, &ui, a),
         Command::Log(a) => log::run(&ctx, &ui, a),
         Command::Diff(a) => diff::run(&ctx, &ui, a),
+        Command::Squash(a) => squash::run(&ctx, &ui, a),
+        Command::Prune(a) => prune::run(&ctx, &ui, a),
+        Command::RotateSalt(a) => rotate_salt::run(&ctx, &ui, a),
+        Command::Sign(a) => sign::run(&ctx, &ui, a),
+        Command::Verify(a) => verify::run(&ctx, &ui, a),
+        Command::VerifySignature(a) => verify_signature::run(&ctx, &ui, a),
+        Command::Access(a) => access::run(&ctx, &ui, a),
+        Command::Stat(a) => stat::run(&ctx, &ui, a),
+        Command::Size(a) => size::run(&ctx, &ui, a),
+        Command::Grep(a) => grep::run(&ctx, &ui, a),
+        Command::Whereis(a) => whereis::run(&ctx, &ui, a),
+        Command::FileHistory(a) => file_history::run(&ctx, &ui, a),
+        Command::Blame(a) => blame::run(&ctx, &ui, a),
+        Command::Manifest(a) => manifest::run(&ctx, &ui, a),
+        Command::Export(a) => export::run(&ctx, &ui, a),
+        Command::Import(a) => import::run(&ctx, &ui, a),
+        Command::Branch(a) => branch::run(&ctx, &ui, a),
         Command::Checkout(a) => checkout::run(&ctx, &ui, a),
         Command::Cat(a) => cat::run(&ctx, &ui, a),
         Command::Keys(a) => keys::run(&ctx, &ui, a),
+        Command::Ls(a) => ls::run(&ctx, &ui, a),
         Command::Dir(a) => dir::run(&ctx, &ui, a),
         Command::Unstage(a) => unstage::run(&ctx, &ui, a),
         Command::Staged(a) => staged::run(&ctx, &ui, a),
         Command::Urn(a) => urn::run(&ctx, &ui, a),
+        Command::Keygen(a) => keygen::run(&ctx, &ui, a),
+        Command::Decrypt(a) => decrypt::run(&ctx, &ui, a),
         Command::Remote(a) => remote::run(&ctx, &ui, a),
         Command::Push(a) => push::run(&ctx, &ui, a),
         Command::Pull(a) => pull::run(&ctx, &ui, a),
@@ -262,6 +349,8 @@ pub fn dispatch(cli: Cli) -> Result<(), CliError> {
         | Command::Update(_)
         | Command::Seed(_)
         | Command::Lock(_)
+        | Command::Wallet(_)
+        | Command::Config(_)
         | Command::Balance(_)
         | Command::Login(_)
         | Command::Whoami(_)
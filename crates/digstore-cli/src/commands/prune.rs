@@ -0,0 +1,45 @@
+use digstore_store::PruneBefore;
+
+use crate::cli::PruneArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+
+pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: PruneArgs) -> Result<(), CliError> {
+    let before = match (args.before, args.before_id) {
+        (Some(secs), _) => Some(PruneBefore::Timestamp(secs)),
+        (None, Some(id)) => Some(PruneBefore::Generation(id)),
+        (None, None) => None,
+    };
+    if before.is_none() && args.keep_last.is_none() {
+        return Err(CliError::InvalidArgument(
+            "nothing to prune by; pass --before, --before-id or --keep-last".into(),
+        ));
+    }
+    let keep_last = args.keep_last.map(|n| n as usize);
+    let report = store_ops::prune(ctx, before, keep_last, args.dry_run)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "dropped": report.dropped.iter().map(|r| r.to_hex()).collect::<Vec<_>>(),
+            "chunks_freed": report.chunks_freed,
+            "bytes_freed": report.bytes_freed,
+            "dry_run": args.dry_run,
+        }));
+        return Ok(());
+    }
+    let verb = if args.dry_run {
+        "would remove"
+    } else {
+        "removed"
+    };
+    ui.line(&format!(
+        "{verb} {} version(s), freeing {} chunk(s) ({} bytes)",
+        report.dropped.len(),
+        report.chunks_freed,
+        report.bytes_freed,
+    ));
+    if args.dry_run && !report.dropped.is_empty() {
+        ui.hint("re-run without --dry-run to apply");
+    }
+    Ok(())
+}
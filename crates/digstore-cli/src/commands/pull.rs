@@ -69,6 +69,10 @@ fn out_file_name(resource_key: &str) -> String {
 }
 
 pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: PullArgs) -> Result<(), CliError> {
+    if args.strict && args.remote.starts_with("urn:") {
+        Urn::parse_strict(&args.remote)
+            .map_err(|e| CliError::InvalidArgument(format!("bad urn: {e}")))?;
+    }
     // Product gate: require a dighub account only for a DIGHUB remote (*.dig.net). A URN pull or a
     // remote name resolving to a self-hosted / loopback node needs no dighub account.
     let gate_base = if args.remote.starts_with("urn:") {
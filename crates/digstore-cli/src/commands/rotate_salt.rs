@@ -0,0 +1,22 @@
+use crate::cli::RotateSaltArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+
+pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: RotateSaltArgs) -> Result<(), CliError> {
+    let report = store_ops::rotate_salt_preview(ctx)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "resources": report.resources,
+            "chunks": report.chunks,
+            "key_epoch": report.key_epoch,
+            "dry_run": args.dry_run,
+        }));
+        return Ok(());
+    }
+    ui.line(&format!(
+        "would re-encrypt {} file(s) in {} chunk(s) under key epoch {}",
+        report.resources, report.chunks, report.key_epoch
+    ));
+    Ok(())
+}
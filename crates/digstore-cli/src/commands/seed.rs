@@ -1,6 +1,6 @@
 use crate::cli::{SeedAction, SeedArgs};
 use crate::error::CliError;
-use crate::ops::wallet::resolve_passphrase;
+use crate::ops::wallet::{resolve_passphrase, unlock_wallet_phrase};
 use crate::ui::Ui;
 use digstore_chain::{config, seed, unlock};
 use zeroize::Zeroizing;
@@ -66,5 +66,33 @@ pub fn run(ui: &Ui, args: SeedArgs) -> Result<(), CliError> {
             }
             Ok(())
         }
+        SeedAction::Export {
+            show_mnemonic,
+            force_print,
+        } => {
+            if !show_mnemonic {
+                return Err(CliError::InvalidArgument(
+                    "seed export prints your mnemonic in plain text; pass --show-mnemonic".into(),
+                ));
+            }
+            if ui.can_prompt() {
+                ui.confirm_or_fail("Print your mnemonic to the terminal?")?;
+            } else if !force_print {
+                return Err(CliError::InvalidArgument(
+                    "refusing to print the mnemonic without a terminal; pass --force-print".into(),
+                ));
+            }
+            let (_keys, phrase, _cfg) = unlock_wallet_phrase(ui)?;
+            if ui.json() {
+                ui.emit_json(&serde_json::json!({ "mnemonic": &*phrase }));
+            } else {
+                ui.line("");
+                ui.line("Your mnemonic — store it offline; anyone holding it controls the wallet:");
+                ui.line("");
+                ui.line(format!("    {}", &*phrase));
+                ui.line("");
+            }
+            Ok(())
+        }
     }
 }
@@ -29,6 +29,7 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: ServeArgs) -> Result<(),
     let root = store_ops::current_root(ctx)?.ok_or_else(|| {
         CliError::NotFound("no committed root to serve; run `digstore commit` first".into())
     })?;
+    store_ops::ensure_publishable(ctx, &root)?;
     let module_path = store_ops::module_path_for(ctx, &store_id, Some(root))?;
     let module = std::fs::read(&module_path).map_err(|e| CliError::Other(e.into()))?;
     let identity = digstore_compiler::verify_module_root(&module, &store_id)
@@ -44,6 +45,13 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: ServeArgs) -> Result<(),
     if args.anonymous {
         server = server.allow_anonymous();
     }
+    if args.read_only {
+        server = server.read_only();
+    }
+    let token = serve_token(&args)?;
+    if let Some(t) = &token {
+        server = server.with_bearer_token(t.clone());
+    }
 
     let auth = if args.anonymous {
         "anonymous (public read mirror)"
@@ -57,15 +65,20 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: ServeArgs) -> Result<(),
             "store_id": store_id.to_hex(),
             "root": root.to_hex(),
             "auth": if args.anonymous { "anonymous" } else { "required" },
+            "bearer": token.is_some(),
+            "read_only": args.read_only,
         }));
     } else {
         ui.line(format!(
-            "serving store {} at http://{} (clone via dig://{}/{})\nauth: {}\nPress Ctrl-C to stop.",
+            "serving store {} at http://{} (clone via dig://{}/{})\nauth: {}{}{}\nhealth: http://{}/health\nPress Ctrl-C to stop.",
             store_id.to_hex(),
             args.bind,
             args.bind,
             store_id.to_hex(),
             auth,
+            if token.is_some() { " + bearer token" } else { "" },
+            if args.read_only { ", read-only" } else { "" },
+            args.bind,
         ));
     }
 
@@ -77,3 +90,22 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: ServeArgs) -> Result<(),
         .map_err(|e| CliError::Other(anyhow::anyhow!("server error: {e}")))?;
     Ok(())
 }
+
+/// The node's read token: `--token-file` (trimmed), else `$DIGSTORE_SERVE_TOKEN`.
+/// An empty token is refused rather than silently leaving the node open.
+fn serve_token(args: &ServeArgs) -> Result<Option<String>, CliError> {
+    let raw = match &args.token_file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            CliError::InvalidArgument(format!("--token-file {}: {e}", path.display()))
+        })?,
+        None => match std::env::var("DIGSTORE_SERVE_TOKEN") {
+            Ok(t) => t,
+            Err(_) => return Ok(None),
+        },
+    };
+    let token = raw.trim();
+    if token.is_empty() {
+        return Err(CliError::InvalidArgument("the serve token is empty".into()));
+    }
+    Ok(Some(token.to_string()))
+}
@@ -0,0 +1,35 @@
+use digstore_core::Bytes32;
+
+use crate::cli::SignArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::{store_ops, wallet};
+use crate::ui::Ui;
+
+/// Sign a version root with the wallet's synthetic key. The BLS signature
+/// covers `(root, store_id)` under its own role tag, so it can't be replayed
+/// as a push or request signature, and is stored beside the generation.
+pub fn run(ctx: &CliContext, ui: &Ui, args: SignArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let (keys, _cfg) = wallet::unlock_wallet_keys(ui)?;
+    let sk = digstore_crypto::bls::SecretKey::from_bytes(&keys.synthetic_sk.to_bytes())
+        .map_err(|e| CliError::Other(anyhow::anyhow!("wallet key: {e}")))?;
+    let signed = store_ops::sign_root(ctx, root, &sk)?;
+
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "public_key": signed.public_key.to_hex(),
+            "signature": signed.signature.to_hex(),
+        }));
+        return Ok(());
+    }
+    ui.success(format!("Signed {}", root.to_hex()));
+    ui.line(format!("  signer  {}", signed.public_key.to_hex()));
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use digstore_core::Bytes32;
+
+use crate::cli::SizeArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Report the store's storage use from its generation manifests: the
+/// deduplicated bytes on disk against what each version would take alone, and
+/// with `--layers` one row per version, with `--efficiency` how much
+/// deduplication saved within versions and across them, with `--detailed` the
+/// spread of chunk sizes. No chunk data is read.
+pub fn run(ctx: &CliContext, ui: &Ui, args: SizeArgs) -> Result<(), CliError> {
+    let report = store_ops::size_report(ctx)?;
+    let distribution = if args.detailed {
+        let root = match &args.at {
+            Some(hex) => Some(
+                Bytes32::from_hex(hex)
+                    .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+            ),
+            None => None,
+        };
+        Some(store_ops::chunk_size_distribution(ctx, root)?)
+    } else {
+        None
+    };
+    let stored_bytes = report.stored_bytes();
+    let (intra, inter) = (report.intra_dedup_bytes(), report.inter_dedup_bytes());
+    // Share of the undeduplicated bytes that never reached the disk.
+    let saved_ratio = |saved: u64| match stored_bytes {
+        0 => 0.0,
+        n => saved as f64 / n as f64,
+    };
+    let logical_bytes = report.generations.last().map_or(0, |g| g.logical_bytes);
+
+    if ui.json() {
+        let mut out = serde_json::json!({
+            "versions": report.generations.len(),
+            "logical_bytes": logical_bytes,
+            "stored_bytes": stored_bytes,
+            "physical_bytes": report.physical_bytes,
+            "unique_chunks": report.unique_chunks,
+        });
+        if args.layers {
+            out["layers"] = report
+                .generations
+                .iter()
+                .map(|g| {
+                    serde_json::json!({
+                        "generation": g.generation_id,
+                        "root": g.root.to_hex(),
+                        "logical_bytes": g.logical_bytes,
+                        "stored_bytes": g.stored_bytes,
+                        "chunk_count": g.chunks,
+                        "unique_chunk_count": g.unique_chunks,
+                        "intra_dedup_bytes": g.intra_dedup_bytes,
+                        "inter_dedup_bytes": g.inter_dedup_bytes,
+                    })
+                })
+                .collect();
+        }
+        if args.efficiency {
+            out["efficiency"] = serde_json::json!({
+                "saved_bytes": intra + inter,
+                "saved_ratio": saved_ratio(intra + inter),
+                "intra_layer_saved_bytes": intra,
+                "inter_layer_saved_bytes": inter,
+            });
+        }
+        if let Some(d) = &distribution {
+            out["chunk_sizes"] = serde_json::json!({
+                "count": d.count,
+                "min": d.min,
+                "max": d.max,
+                "mean": d.mean,
+                "median": d.median,
+                "histogram": d.buckets.iter().map(|(bound, count)| serde_json::json!({
+                    "max_size": bound,
+                    "count": count,
+                })).collect::<Vec<_>>(),
+            });
+        }
+        ui.emit_json(&out);
+        return Ok(());
+    }
+
+    ui.line(format!("versions       {}", report.generations.len()));
+    ui.line(format!("current size   {logical_bytes} bytes"));
+    ui.line(format!(
+        "on disk        {} bytes in {} chunks",
+        report.physical_bytes, report.unique_chunks
+    ));
+    ui.line(format!("without dedup  {stored_bytes} bytes"));
+    if args.efficiency {
+        ui.line(format!(
+            "saved          {} bytes ({:.1}%)",
+            intra + inter,
+            saved_ratio(intra + inter) * 100.0
+        ));
+        ui.line(format!(
+            "  within       {intra} bytes ({:.1}%)  repeats inside one version",
+            saved_ratio(intra) * 100.0
+        ));
+        ui.line(format!(
+            "  across       {inter} bytes ({:.1}%)  chunks shared with an earlier version",
+            saved_ratio(inter) * 100.0
+        ));
+    }
+    if args.layers {
+        ui.line(format!(
+            "{:>5}  {:<12}  {:>12}  {:>12}  {:>7}  {:>7}",
+            "#", "root", "logical", "stored", "chunks", "unique"
+        ));
+        for g in &report.generations {
+            ui.line(format!(
+                "{:>5}  {:<12}  {:>12}  {:>12}  {:>7}  {:>7}",
+                g.generation_id,
+                &g.root.to_hex()[..12],
+                g.logical_bytes,
+                g.stored_bytes,
+                g.chunks,
+                g.unique_chunks
+            ));
+        }
+    }
+    if let Some(d) = &distribution {
+        ui.line(format!(
+            "chunk sizes    {} chunks, min {} / median {} / mean {:.0} / max {} bytes",
+            d.count, d.min, d.median, d.mean, d.max
+        ));
+        let widest = d.buckets.iter().map(|(_, n)| *n).max().unwrap_or(0);
+        for (bound, count) in &d.buckets {
+            let bar = "#".repeat((count * 40).div_ceil(widest.max(1)));
+            ui.line(format!("  <= {bound:>10}  {count:>5}  {bar}"));
+        }
+    }
+    Ok(())
+}
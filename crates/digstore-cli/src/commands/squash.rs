@@ -0,0 +1,40 @@
+use digstore_core::Bytes32;
+
+use crate::cli::SquashArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+
+pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: SquashArgs) -> Result<(), CliError> {
+    let from = Bytes32::from_hex(&args.from)
+        .map_err(|_| CliError::InvalidArgument("from must be 32-byte hex".into()))?;
+    let to = Bytes32::from_hex(&args.to)
+        .map_err(|_| CliError::InvalidArgument("to must be 32-byte hex".into()))?;
+    let report = store_ops::squash(ctx, from, to, args.dry_run)?;
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "kept": report.kept.to_hex(),
+            "dropped": report.dropped.iter().map(|r| r.to_hex()).collect::<Vec<_>>(),
+            "chunks_freed": report.chunks_freed,
+            "bytes_freed": report.bytes_freed,
+            "dry_run": args.dry_run,
+        }));
+        return Ok(());
+    }
+    let verb = if args.dry_run {
+        "would drop"
+    } else {
+        "dropped"
+    };
+    ui.line(&format!(
+        "{verb} {} version(s), freeing {} chunk(s) ({} bytes); kept {}",
+        report.dropped.len(),
+        report.chunks_freed,
+        report.bytes_freed,
+        report.kept.to_hex()
+    ));
+    if args.dry_run {
+        ui.hint("re-run without --dry-run to apply");
+    }
+    Ok(())
+}
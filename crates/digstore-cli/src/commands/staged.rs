@@ -1,16 +1,33 @@
+use digstore_store::{CommitEstimate, DiffSummary, FileChange};
+
+use crate::cli::{StagedAction, StagedArgs};
 use crate::context::CliContext;
 use crate::error::CliError;
 use crate::ui::theme::Marker;
 use crate::ui::Ui;
 
-pub fn run(ctx: &CliContext, ui: &Ui, _args: crate::cli::StagedArgs) -> Result<(), CliError> {
+pub fn run(ctx: &CliContext, ui: &Ui, args: StagedArgs) -> Result<(), CliError> {
+    if let Some(StagedAction::Diff { stat }) = args.action {
+        let summary = crate::ops::store_ops::staged_diff_summary(ctx)?;
+        render_diff(ui, &summary, stat);
+        return Ok(());
+    }
     let (entries, total, limit) = crate::ops::store_ops::list_staged(ctx)?;
+    let estimate = if args.estimate {
+        Some(crate::ops::store_ops::estimate_commit_size(ctx)?)
+    } else {
+        None
+    };
     if ui.json() {
-        ui.emit_json(&serde_json::json!({
+        let mut out = serde_json::json!({
             "staged": entries.iter().map(|(k, s)| serde_json::json!({ "key": k, "size": s })).collect::<Vec<_>>(),
             "total_bytes": total,
             "limit_bytes": limit,
-        }));
+        });
+        if let Some(e) = &estimate {
+            out["estimate"] = estimate_json(e);
+        }
+        ui.emit_json(&out);
         return Ok(());
     }
     if entries.is_empty() {
@@ -25,5 +42,88 @@ pub fn run(ctx: &CliContext, ui: &Ui, _args: crate::cli::StagedArgs) -> Result<(
         );
     }
     ui.capacity(total, limit);
+    if let Some(e) = &estimate {
+        render_estimate(ui, e);
+    }
     Ok(())
 }
+
+/// A [`CommitEstimate`] as the `estimate` object of `staged --estimate` and
+/// `commit --dry-run`.
+pub fn estimate_json(e: &CommitEstimate) -> serde_json::Value {
+    serde_json::json!({
+        "files": e.files,
+        "logical_bytes": e.logical_bytes,
+        "chunk_count": e.chunks,
+        "new_chunk_count": e.new_chunks,
+        "new_bytes": e.new_bytes,
+        "reused_bytes": e.reused_bytes,
+    })
+}
+
+/// The human form of [`estimate_json`].
+pub fn render_estimate(ui: &Ui, e: &CommitEstimate) {
+    ui.line(format!(
+        "estimate       {} bytes in {} new chunks; {} of {} chunk references ({} bytes) deduplicated",
+        e.new_bytes,
+        e.new_chunks,
+        e.chunks - e.new_chunks,
+        e.chunks,
+        e.reused_bytes
+    ));
+}
+
+/// `staged diff`: each changed file (unless `--stat`), then the totals.
+/// `--json` always carries both, so scripts can gate on `changed`.
+fn render_diff(ui: &Ui, summary: &DiffSummary, stat: bool) {
+    let change_name = |c: FileChange| match c {
+        FileChange::Added => "added",
+        FileChange::Modified => "modified",
+        FileChange::Deleted => "deleted",
+    };
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "changed": !summary.is_empty(),
+            "added": summary.added,
+            "modified": summary.modified,
+            "deleted": summary.deleted,
+            "bytes_added": summary.bytes_added,
+            "bytes_removed": summary.bytes_removed,
+            "files": summary.files.iter().map(|f| serde_json::json!({
+                "path": f.resource_key,
+                "change": change_name(f.change),
+                "old_size": f.old_size,
+                "new_size": f.new_size,
+            })).collect::<Vec<_>>(),
+        }));
+        return;
+    }
+    if !stat {
+        for f in &summary.files {
+            let marker = match f.change {
+                FileChange::Added => Marker::Staged,
+                FileChange::Modified => Marker::Modified,
+                FileChange::Deleted => Marker::Removed,
+            };
+            let sizes = match (f.old_size, f.new_size) {
+                (Some(old), Some(new)) => format!("{old} -> {new} bytes"),
+                (None, Some(n)) | (Some(n), None) => format!("{n} bytes"),
+                (None, None) => String::new(),
+            };
+            ui.item(marker, format!("{}  ({sizes})", f.resource_key));
+        }
+    }
+    if summary.is_empty() {
+        ui.line("no changes against the current version");
+    } else {
+        ui.line(format!(
+            "{} files changed: {} added, {} modified, {} deleted (+{} / -{} bytes)",
+            summary.files.len(),
+            summary.added,
+            summary.modified,
+            summary.deleted,
+            summary.bytes_added,
+            summary.bytes_removed
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+use digstore_core::Bytes32;
+
+use crate::cli::StatArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Describe one committed resource from its generation manifest: size,
+/// retrieval key, every chunk (hash and stored size) and a chunk-size
+/// histogram. No chunk data is read unless `--metadata` asks for the content
+/// type, which decrypts the first chunk only.
+pub fn run(ctx: &CliContext, ui: &Ui, args: StatArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let stat = store_ops::stat_file(ctx, root, &args.path)?;
+    let histogram = stat.size_histogram();
+    let content_type = if args.metadata {
+        Some(store_ops::content_type(ctx, root, &args.path)?)
+    } else {
+        None
+    };
+
+    if ui.json() {
+        let mut out = serde_json::json!({
+            "path": stat.resource_key,
+            "root": stat.root.to_hex(),
+            "retrieval_key": stat.static_key.to_hex(),
+            "size": stat.size,
+            "chunk_count": stat.chunks.len(),
+            "chunks": stat.chunks.iter().map(|c| serde_json::json!({
+                "hash": c.hash.to_hex(),
+                "size": c.size,
+            })).collect::<Vec<_>>(),
+            "histogram": histogram.iter().map(|(bound, count)| serde_json::json!({
+                "max_size": bound,
+                "count": count,
+            })).collect::<Vec<_>>(),
+        });
+        if let Some(ct) = &content_type {
+            out["mime_type"] = ct.mime_type.into();
+            out["is_binary"] = ct.is_binary.into();
+            out["encoding_guess"] = ct.encoding_guess.into();
+        }
+        ui.emit_json(&out);
+        return Ok(());
+    }
+
+    ui.line(format!("path           {}", stat.resource_key));
+    ui.line(format!("root           {}", stat.root.to_hex()));
+    ui.line(format!("retrieval key  {}", stat.static_key.to_hex()));
+    ui.line(format!("size           {} bytes", stat.size));
+    if let Some(ct) = &content_type {
+        ui.line(format!("mime type      {}", ct.mime_type));
+        ui.line(format!(
+            "encoding       {}",
+            ct.encoding_guess.unwrap_or("binary")
+        ));
+    }
+    ui.line(format!("chunks         {}", stat.chunks.len()));
+    let widest = histogram.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    for (bound, count) in &histogram {
+        let bar = "#".repeat((count * 40).div_ceil(widest.max(1)));
+        ui.line(format!("  <= {bound:>10}  {count:>5}  {bar}"));
+    }
+    Ok(())
+}
@@ -1,12 +1,182 @@
+//! `digstore status`, including the `--porcelain` formats for scripts.
+//!
+//! Both porcelain formats are stable: a release may add header lines or
+//! status codes, but never changes the meaning or order of existing fields.
+//! One record per line, sorted by path. The path is always the last field, so
+//! it may contain spaces; a newline or backslash in it is written as `\n` or
+//! `\\`.
+//!
+//! `--porcelain` (v1):
+//!
+//! ```text
+//! <XY> <path>
+//! ```
+//!
+//! `--porcelain=v2` opens with `#` header lines and adds each file's size and
+//! content hash:
+//!
+//! ```text
+//! # porcelain v2
+//! # root <root hex, or - before the first commit>
+//! <XY> <size> <sha256> <path>
+//! ```
+//!
+//! `size` (decimal bytes) and `sha256` (64 lowercase hex) describe the staged
+//! copy when X is set, otherwise the working copy; both are `-` for an
+//! ignored path. Readers should skip `#` lines they do not recognise.
+//!
+//! `X` is the staging state, `Y` the working-tree state relative to it:
+//!
+//! ```text
+//! A.  staged, new to the current root
+//! M.  staged, replacing a committed resource
+//! AM  staged, working file changed since `add` (also MM)
+//! AD  staged, working file deleted since `add` (also MD)
+//! .M  committed resource modified, not staged
+//! ??  untracked
+//! !!  ignored (only with --ignored; a directory ends in `/`)
+//! ```
+//!
+//! `D.` and `.D` are reserved for deleted resources.
+
 use crate::cli::StatusArgs;
 use crate::context::CliContext;
 use crate::error::CliError;
-use crate::ops::store_ops;
-use crate::output;
+use crate::ops::{store_ops, walk};
+use crate::output::{self, FileDigest, StatusView};
 use crate::ui::Ui;
 
-pub fn run(ctx: &CliContext, ui: &Ui, _args: StatusArgs) -> Result<(), CliError> {
-    let view = store_ops::compute_status(ctx)?;
-    output::render_status(&view, ui);
+/// `--porcelain` format version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Porcelain {
+    V1,
+    V2,
+}
+
+pub fn run(ctx: &CliContext, ui: &Ui, args: StatusArgs) -> Result<(), CliError> {
+    let mut view = store_ops::compute_status(ctx)?;
+    if args.ignored {
+        view.ignored = Some(walk::list_ignored(&ctx.op_dir, &ctx.workspace_dir));
+    }
+    match args.porcelain {
+        Some(version) => print!("{}", porcelain(&view, version)),
+        None => output::render_status(&view, ui),
+    }
     Ok(())
 }
+
+/// Render `view` in porcelain `version` (see the module docs).
+pub(crate) fn porcelain(view: &StatusView, version: Porcelain) -> String {
+    let mut records: Vec<(String, String, Option<FileDigest>)> = Vec::new();
+    for key in &view.staged {
+        let x = if view.detail.committed.contains(key) {
+            'M'
+        } else {
+            'A'
+        };
+        let y = if view.stale.contains(key) {
+            'M'
+        } else if view.missing.contains(key) {
+            'D'
+        } else {
+            '.'
+        };
+        records.push((
+            format!("{x}{y}"),
+            key.clone(),
+            view.detail.staged.get(key).copied(),
+        ));
+    }
+    for (code, keys) in [(".M", &view.modified), ("??", &view.untracked)] {
+        for key in keys {
+            records.push((
+                code.into(),
+                key.clone(),
+                view.detail.working.get(key).copied(),
+            ));
+        }
+    }
+    for ignored in view.ignored.iter().flatten() {
+        records.push(("!!".into(), ignored.path.clone(), None));
+    }
+    records.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut out = String::new();
+    if version == Porcelain::V2 {
+        out.push_str("# porcelain v2\n");
+        out.push_str(&format!("# root {}\n", view.root.as_deref().unwrap_or("-")));
+    }
+    for (code, path, digest) in records {
+        let path = escape_path(&path);
+        match version {
+            Porcelain::V1 => out.push_str(&format!("{code} {path}\n")),
+            Porcelain::V2 => {
+                let (size, sha) = match digest {
+                    Some(d) => (d.size.to_string(), d.sha256.to_hex()),
+                    None => ("-".into(), "-".into()),
+                };
+                out.push_str(&format!("{code} {size} {sha} {path}\n"));
+            }
+        }
+    }
+    out
+}
+
+fn escape_path(path: &str) -> String {
+    path.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::StatusDetail;
+
+    fn view() -> StatusView {
+        let mut detail = StatusDetail::default();
+        for (key, bytes) in [("a.txt", &b"new"[..]), ("b.txt", b"edit")] {
+            detail.staged.insert(key.into(), FileDigest::of(bytes));
+        }
+        detail.committed.insert("b.txt".into());
+        for (key, bytes) in [("c.txt", &b"changed"[..]), ("d\nx.txt", b"odd")] {
+            detail.working.insert(key.into(), FileDigest::of(bytes));
+        }
+        StatusView {
+            root: Some("ab".repeat(32)),
+            staged: vec!["a.txt".into(), "b.txt".into()],
+            modified: vec!["c.txt".into()],
+            untracked: vec!["d\nx.txt".into()],
+            stale: vec!["b.txt".into()],
+            missing: vec![],
+            staged_bytes: 7,
+            limit_bytes: 100,
+            ignored: Some(vec![walk::Ignored {
+                path: "build/".into(),
+                pattern: Some("build/".into()),
+                source: None,
+            }]),
+            detail,
+        }
+    }
+
+    #[test]
+    fn porcelain_v1_is_code_and_path() {
+        assert_eq!(
+            porcelain(&view(), Porcelain::V1),
+            "A. a.txt\nMM b.txt\n!! build/\n.M c.txt\n?? d\\nx.txt\n"
+        );
+    }
+
+    #[test]
+    fn porcelain_v2_adds_header_size_and_hash() {
+        let out = porcelain(&view(), Porcelain::V2);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "# porcelain v2");
+        assert_eq!(lines[1], format!("# root {}", "ab".repeat(32)));
+        let new = digstore_crypto::sha256(b"new").to_hex();
+        assert_eq!(lines[2], format!("A. 3 {new} a.txt"));
+        assert_eq!(lines[4], "!! - - build/");
+        let changed = digstore_crypto::sha256(b"changed").to_hex();
+        assert_eq!(lines[5], format!(".M 7 {changed} c.txt"));
+        assert_eq!(lines.len(), 7);
+    }
+}
@@ -1,19 +1,26 @@
 use std::path::Path;
 
-use digstore_core::MAX_STORE_BYTES;
-use digstore_store::{RootHistory, StagingArea};
+use digstore_core::{Visibility, MAX_STORE_BYTES};
+use digstore_store::{RootHistory, StagingArea, Store, SystemClock};
 
+use digstore_chain::config::{GlobalConfig, NamedStore};
+
+use crate::cli::{StoresAction, StoresArgs};
 use crate::context::CliContext;
 use crate::error::CliError;
 use crate::ui::Ui;
-use crate::workspace::Workspace;
+use crate::workspace::{validate_store_name, Workspace};
 
-pub fn run(
-    _ctx: &CliContext,
-    ui: &Ui,
-    ws: &Workspace,
-    _args: crate::cli::StoresArgs,
-) -> Result<(), CliError> {
+pub fn run(_ctx: &CliContext, ui: &Ui, ws: &Workspace, args: StoresArgs) -> Result<(), CliError> {
+    match args.action {
+        Some(StoresAction::Info {
+            name,
+            config,
+            paths,
+        }) => return info(ui, ws, name.as_deref(), config, paths),
+        Some(action) => return run_names(ui, ws, action),
+        None => {}
+    }
     #[derive(serde::Serialize)]
     struct Row<'a> {
         name: &'a str,
@@ -125,3 +132,226 @@ fn current_root_for_dir(store_dir: &Path) -> Option<String> {
         .flatten()
         .map(|g| g.root.to_hex())
 }
+
+/// `stores info`: everything about one workspace store in a single call. JSON
+/// always carries every section; the human view adds the configuration and
+/// path sections on `--config` / `--paths`.
+fn info(
+    ui: &Ui,
+    ws: &Workspace,
+    name: Option<&str>,
+    show_config: bool,
+    show_paths: bool,
+) -> Result<(), CliError> {
+    let name = ws.resolve_store_name(name)?;
+    let entry = &ws.stores[&name];
+    let store_dir = std::path::absolute(ws.store_dir(&name))
+        .map_err(|e| CliError::Other(anyhow::anyhow!("resolve store dir: {e}")))?;
+    let store = Store::open_read_only(&store_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    let fail = |e| CliError::store("stores info", e);
+    let config = store.config();
+    let store_paths = store.paths();
+    let current_root = store.current_root().map_err(fail)?;
+    let report = store.size_report().map_err(fail)?;
+    let logical_bytes = report
+        .generations
+        .iter()
+        .find(|g| Some(g.root) == current_root)
+        .map_or(0, |g| g.logical_bytes);
+    let staged_bytes = staged_total_for_dir(&store_dir, &entry.id);
+    let limit_bytes = limit_for_dir(&store_dir);
+    let chunker = store.chunker_config();
+    let private = matches!(config.visibility, Visibility::Private(_));
+    let writers: Option<Vec<String>> = store
+        .access_policy()
+        .map_err(fail)?
+        .map(|p| p.writers.iter().map(|k| k.to_hex()).collect());
+    let module = current_root.map(|r| store_paths.module_file(&r.to_hex()));
+    let path = |p: std::path::PathBuf| p.display().to_string();
+    let path_rows = [
+        ("store_dir", path(store_paths.root())),
+        ("config", path(store_paths.config_file())),
+        ("roots_log", path(store_paths.history_file())),
+        ("refs", path(store_paths.refs_file())),
+        ("head", path(store_paths.head_file())),
+        ("pending_commit", path(store_paths.pending_commit_file())),
+        ("access", path(store_paths.access_file())),
+        ("staging", path(store_paths.staging_file())),
+        ("generations", path(store_paths.generations_dir())),
+        ("modules", path(store_paths.modules_dir())),
+    ];
+
+    if ui.json() {
+        let mut paths: serde_json::Map<String, serde_json::Value> = path_rows
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone().into()))
+            .collect();
+        paths.insert("content_root".into(), entry.content_root.clone().into());
+        paths.insert("current_module".into(), module.map(path).into());
+        ui.emit_json(&serde_json::json!({
+            "name": name,
+            "store_id": config.store_id.to_hex(),
+            "label": config.label,
+            "description": config.description,
+            "active": ws.active.as_deref() == Some(name.as_str()),
+            "archive_path": path(store_paths.root()),
+            "current_root": current_root.map(|r| r.to_hex()),
+            "current_branch": store.current_branch().map_err(fail)?,
+            "generation_count": report.generations.len(),
+            "size": {
+                "logical_bytes": logical_bytes,
+                "stored_bytes": report.stored_bytes(),
+                "physical_bytes": report.physical_bytes,
+                "unique_chunks": report.unique_chunks,
+                "staged_bytes": staged_bytes,
+                "limit_bytes": limit_bytes,
+            },
+            "chunking": {
+                "min_size": chunker.min_size,
+                "target_size": chunker.target_size,
+                "max_size": chunker.max_size,
+                "mask": chunker.mask,
+                "hash_algorithm": config.hash_algorithm.as_str(),
+            },
+            "compression": {
+                "enabled": false,
+                "algorithm": null,
+            },
+            "encryption": {
+                "enabled": true,
+                "cipher": "aes-256-gcm-siv",
+                "key_derivation": "per-urn",
+                "visibility": if private { "private" } else { "public" },
+                "salted": private,
+            },
+            "access": {
+                "restricted": writers.is_some(),
+                "writers": writers.clone().unwrap_or_default(),
+            },
+            "paths": paths,
+        }));
+        return Ok(());
+    }
+
+    ui.line(format!("{name}  {}", config.store_id.to_hex()));
+    if let Some(label) = config.label.as_deref().filter(|l| !l.is_empty()) {
+        ui.line(format!("  label        {label}"));
+    }
+    ui.line(format!("  path         {}", store_dir.display()));
+    ui.line(format!(
+        "  root         {}",
+        current_root.map_or_else(|| "(empty)".into(), |r| r.to_hex())
+    ));
+    ui.line(format!("  versions     {}", report.generations.len()));
+    ui.line(format!(
+        "  size         {logical_bytes} bytes, {} on disk",
+        report.physical_bytes
+    ));
+    ui.line(format!(
+        "  staged       {}",
+        crate::ui::human_capacity(staged_bytes, limit_bytes)
+    ));
+    ui.line(format!(
+        "  encryption   aes-256-gcm-siv, {}",
+        if private {
+            "private (salted per-URN keys)"
+        } else {
+            "public (per-URN keys)"
+        }
+    ));
+    ui.line(match &writers {
+        None => "  writers      any wallet".to_string(),
+        Some(w) => format!("  writers      {} listed (see `digstore access`)", w.len()),
+    });
+    if show_config {
+        ui.line("config");
+        ui.line(format!("  hash         {}", config.hash_algorithm));
+        ui.line(format!(
+            "  chunking     min {} / target {} / max {} bytes, mask {:#x}",
+            chunker.min_size, chunker.target_size, chunker.max_size, chunker.mask
+        ));
+        ui.line("  compression  none");
+        ui.line(format!("  max size     {limit_bytes} bytes"));
+    }
+    if show_paths {
+        ui.line("paths");
+        for (key, value) in &path_rows {
+            ui.line(format!("  {key:<15} {value}"));
+        }
+        if let Some(root) = &entry.content_root {
+            ui.line(format!("  {:<15} {root}", "content_root"));
+        }
+    }
+    Ok(())
+}
+
+/// `stores add-name | list-names | remove-name`: the machine-wide name
+/// registry in `~/.dig/config.toml`.
+fn run_names(ui: &Ui, ws: &Workspace, action: StoresAction) -> Result<(), CliError> {
+    let home = digstore_chain::config::dig_home()?;
+    let mut global = GlobalConfig::load_file(&home)?;
+    match action {
+        StoresAction::AddName { name, store_id } => {
+            validate_store_name(&name)?;
+            let (ws_name, entry) = ws
+                .stores
+                .iter()
+                .find(|(_, e)| e.id.eq_ignore_ascii_case(&store_id))
+                .ok_or_else(|| {
+                    CliError::NotFound(format!(
+                        "no store {store_id} in this workspace; run `digstore stores` to list them"
+                    ))
+                })?;
+            let dir = std::path::absolute(ws.store_dir(ws_name))
+                .map_err(|e| CliError::Other(anyhow::anyhow!("resolve store dir: {e}")))?;
+            global.names.insert(
+                name.clone(),
+                NamedStore {
+                    id: entry.id.clone(),
+                    path: dir.display().to_string(),
+                },
+            );
+            global.save(&home)?;
+            if ui.json() {
+                ui.emit_json(&serde_json::json!({ "name": name, "store_id": entry.id }));
+            } else {
+                ui.success(format!("registered '{name}' -> {}", entry.id));
+            }
+        }
+        StoresAction::ListNames => {
+            if ui.json() {
+                ui.emit_json(
+                    &global
+                        .names
+                        .iter()
+                        .map(|(name, s)| {
+                            serde_json::json!({ "name": name, "store_id": s.id, "path": s.path })
+                        })
+                        .collect::<Vec<_>>(),
+                );
+            } else if global.names.is_empty() {
+                ui.line("no registered store names; add one with `digstore stores add-name`");
+            } else {
+                for (name, s) in &global.names {
+                    ui.line(format!("{name:<20} {}  {}", s.id, s.path));
+                }
+            }
+        }
+        StoresAction::Info { .. } => unreachable!("handled by run"),
+        StoresAction::RemoveName { name } => {
+            if global.names.remove(&name).is_none() {
+                return Err(CliError::NotFound(format!(
+                    "no registered store name '{name}'"
+                )));
+            }
+            global.save(&home)?;
+            if ui.json() {
+                ui.emit_json(&serde_json::json!({ "name": name, "removed": true }));
+            } else {
+                ui.success(format!("forgot '{name}'"));
+            }
+        }
+    }
+    Ok(())
+}
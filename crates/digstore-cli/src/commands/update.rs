@@ -9,6 +9,7 @@
 
 use std::time::Duration;
 
+use digstore_core::Bytes32;
 use serde::{Deserialize, Serialize};
 
 use crate::cli::UpdateArgs;
@@ -22,8 +23,9 @@ pub const RELEASES_API: &str = "https://api.github.com/repos/DIG-Network/digstor
 pub const USER_AGENT: &str = concat!("digstore-cli/", env!("CARGO_PKG_VERSION"));
 
 /// Short timeout for the network calls. The beacon must never block a command
-/// meaningfully; the explicit `update` command can afford a longer budget.
-const BEACON_TIMEOUT: Duration = Duration::from_secs(2);
+/// meaningfully (a captive portal or dead link costs at most this much); the
+/// explicit `update` command can afford a longer budget.
+const BEACON_TIMEOUT: Duration = Duration::from_millis(800);
 const UPDATE_TIMEOUT: Duration = Duration::from_secs(20);
 
 // ---------------------------------------------------------------------------
@@ -133,6 +135,74 @@ pub fn suggest_manual_asset(assets: &[Asset]) -> Option<&Asset> {
     })
 }
 
+// ---------------------------------------------------------------------------
+// Integrity: the SHA-256 published alongside each installer.
+// ---------------------------------------------------------------------------
+
+/// The release asset that carries `asset_name`'s SHA-256: its own
+/// `<name>.sha256` if published, else a combined `SHA256SUMS` /
+/// `checksums.txt` file.
+pub fn select_checksum_asset<'a>(assets: &'a [Asset], asset_name: &str) -> Option<&'a Asset> {
+    let own = format!("{asset_name}.sha256").to_ascii_lowercase();
+    assets
+        .iter()
+        .find(|a| a.name.to_ascii_lowercase() == own)
+        .or_else(|| {
+            assets.iter().find(|a| {
+                let n = a.name.to_ascii_lowercase();
+                n.contains("sha256sums") || n == "checksums.txt"
+            })
+        })
+}
+
+/// `asset_name`'s SHA-256 from a checksum file: its `<hex>  <name>` line
+/// (`sha256sum` output, `*name` binary marker allowed), or a bare digest when
+/// the file holds nothing else.
+pub fn parse_checksum(text: &str, asset_name: &str) -> Option<Bytes32> {
+    let digest = |hex: &str| Bytes32::from_hex(&hex.to_ascii_lowercase()).ok();
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if let [only] = lines.as_slice() {
+        if !only.contains(char::is_whitespace) {
+            return digest(only);
+        }
+    }
+    lines.iter().find_map(|line| {
+        let (hex, name) = line.split_once(char::is_whitespace)?;
+        if name.trim().trim_start_matches('*') == asset_name {
+            digest(hex)
+        } else {
+            None
+        }
+    })
+}
+
+/// Check a downloaded installer against its published digest and only then
+/// write it to `dir`, so a tampered or truncated download never reaches disk
+/// where it could be launched. `VerificationFailed` on a mismatch.
+pub fn stage_verified_installer(
+    bytes: &[u8],
+    expected: &Bytes32,
+    dir: &std::path::Path,
+    name: &str,
+) -> Result<std::path::PathBuf, CliError> {
+    let actual = digstore_core::sha256(bytes);
+    if actual != *expected {
+        return Err(CliError::VerificationFailed(format!(
+            "{name} has SHA-256 {} but the release publishes {}; refusing to install",
+            actual.to_hex(),
+            expected.to_hex()
+        )));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| CliError::Other(e.into()))?;
+    let dest = dir.join(name);
+    std::fs::write(&dest, bytes).map_err(|e| CliError::Other(e.into()))?;
+    Ok(dest)
+}
+
 // ---------------------------------------------------------------------------
 // Network: fetch the latest release.
 // ---------------------------------------------------------------------------
@@ -182,11 +252,51 @@ pub struct CheckCache {
     /// The latest tag observed at that check (empty if unknown).
     #[serde(default)]
     pub latest_tag: String,
+    /// Beacon checks that failed in a row since the last success; each one
+    /// doubles the wait before the next (see [`check_interval`]).
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl CheckCache {
+    /// Whether the beacon may hit the network again at `now_unix`.
+    pub fn due(&self, now_unix: u64) -> bool {
+        now_unix.saturating_sub(self.last_check_unix) >= check_interval(self.failures)
+    }
+
+    /// The record after a beacon check at `now_unix`: the tag it found, or on
+    /// failure (offline, timeout, bad response) the last known tag with one
+    /// more failure counted.
+    pub fn after_check(&self, now_unix: u64, found: Option<String>) -> CheckCache {
+        match found {
+            Some(latest_tag) => CheckCache {
+                last_check_unix: now_unix,
+                latest_tag,
+                failures: 0,
+            },
+            None => CheckCache {
+                last_check_unix: now_unix,
+                latest_tag: self.latest_tag.clone(),
+                failures: self.failures.saturating_add(1),
+            },
+        }
+    }
 }
 
 /// How often the beacon is allowed to hit the network: once per 24h.
 pub const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
 
+/// Longest wait between beacon checks while the network keeps failing.
+pub const MAX_CHECK_INTERVAL_SECS: u64 = 7 * CHECK_INTERVAL_SECS;
+
+/// The wait before the next beacon check after `failures` failed ones in a
+/// row: 24h, doubling per failure, capped at [`MAX_CHECK_INTERVAL_SECS`].
+pub fn check_interval(failures: u32) -> u64 {
+    CHECK_INTERVAL_SECS
+        .saturating_mul(1u64 << failures.min(16))
+        .min(MAX_CHECK_INTERVAL_SECS)
+}
+
 /// Path of the throttle cache: `<config_dir>/digstore/update-check.json`.
 pub fn cache_path() -> Option<std::path::PathBuf> {
     Some(
@@ -250,6 +360,7 @@ pub fn run(ctx: &CliContext, ui: &crate::ui::Ui, args: UpdateArgs) -> Result<(),
     save_cache(&CheckCache {
         last_check_unix: now_unix(),
         latest_tag: latest.clone(),
+        failures: 0,
     });
 
     if !is_newer(current, &latest) {
@@ -326,7 +437,7 @@ fn perform_update(
             return Ok(());
         }
 
-        let dest = download_asset(asset, ui)?;
+        let dest = download_asset(asset, &release.assets, ui)?;
         ui.verb("Launching", asset.name.clone());
         launch_installer(&dest)?;
         ui.success("installer launched; it will update your DigStore install");
@@ -346,7 +457,12 @@ fn perform_update(
             ui.line(format!("release: {}", release.html_url));
         }
         match suggest_manual_asset(&release.assets) {
-            Some(a) => ui.line(format!("download: {}", a.browser_download_url)),
+            Some(a) => {
+                ui.line(format!("download: {}", a.browser_download_url));
+                if let Some(sum) = select_checksum_asset(&release.assets, &a.name) {
+                    ui.line(format!("sha256:   {}", sum.browser_download_url));
+                }
+            }
             None => ui.line("download the asset for your platform from the release page"),
         }
         Ok(())
@@ -365,42 +481,68 @@ fn confirm(prompt: &str) -> bool {
     matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
 }
 
-/// Download `asset` into a temp directory and return the on-disk path.
+/// Download `asset` and the checksum published for it among `assets`, verify
+/// the installer, and return its on-disk path in a temp directory. A release
+/// without a checksum for the installer is refused rather than trusted.
 #[cfg(target_os = "windows")]
-fn download_asset(asset: &Asset, ui: &crate::ui::Ui) -> Result<std::path::PathBuf, CliError> {
+fn download_asset(
+    asset: &Asset,
+    assets: &[Asset],
+    ui: &crate::ui::Ui,
+) -> Result<std::path::PathBuf, CliError> {
+    let checksum = select_checksum_asset(assets, &asset.name).ok_or_else(|| {
+        CliError::VerificationFailed(format!(
+            "the release publishes no SHA-256 for {}; refusing to install it unverified",
+            asset.name
+        ))
+    })?;
     ui.verb("Downloading", asset.name.clone());
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .map_err(|e| CliError::Other(e.into()))?;
-    let bytes = rt.block_on(async {
+    let (bytes, sums) = rt.block_on(async {
         let client = reqwest::Client::builder()
             .timeout(UPDATE_TIMEOUT)
             .redirect(reqwest::redirect::Policy::limited(5))
             .build()
             .map_err(|e| CliError::Network(format!("http client: {e}")))?;
-        let resp = client
-            .get(&asset.browser_download_url)
-            .header(reqwest::header::USER_AGENT, USER_AGENT)
-            .send()
-            .await
-            .map_err(|e| CliError::Network(format!("download: {e}")))?;
-        if !resp.status().is_success() {
-            return Err(CliError::Network(format!(
-                "download returned status {}",
-                resp.status().as_u16()
-            )));
+        let fetch = |url: &str| {
+            client
+                .get(url)
+                .header(reqwest::header::USER_AGENT, USER_AGENT)
+                .send()
+        };
+        let mut bodies = Vec::new();
+        for url in [&asset.browser_download_url, &checksum.browser_download_url] {
+            let resp = fetch(url.as_str())
+                .await
+                .map_err(|e| CliError::Network(format!("download: {e}")))?;
+            if !resp.status().is_success() {
+                return Err(CliError::Network(format!(
+                    "download returned status {}",
+                    resp.status().as_u16()
+                )));
+            }
+            let body = resp
+                .bytes()
+                .await
+                .map_err(|e| CliError::Network(format!("read body: {e}")))?;
+            bodies.push(body);
         }
-        resp.bytes()
-            .await
-            .map_err(|e| CliError::Network(format!("read body: {e}")))
+        let sums = String::from_utf8_lossy(&bodies[1]).into_owned();
+        Ok((bodies.swap_remove(0), sums))
     })?;
 
+    let expected = parse_checksum(&sums, &asset.name).ok_or_else(|| {
+        CliError::VerificationFailed(format!(
+            "{} does not list a SHA-256 for {}; refusing to install",
+            checksum.name, asset.name
+        ))
+    })?;
+    ui.verb("Verifying", format!("SHA-256 {}", expected.to_hex()));
     let dir = std::env::temp_dir().join("digstore-update");
-    std::fs::create_dir_all(&dir).map_err(|e| CliError::Other(e.into()))?;
-    let dest = dir.join(&asset.name);
-    std::fs::write(&dest, &bytes).map_err(|e| CliError::Other(e.into()))?;
-    Ok(dest)
+    stage_verified_installer(&bytes, &expected, &dir, &asset.name)
 }
 
 /// Launch the downloaded installer and return immediately (the installer takes
@@ -505,6 +647,52 @@ mod tests {
         assert!(select_windows_installer(&assets).is_none());
     }
 
+    #[test]
+    fn checksum_asset_prefers_the_installers_own_file() {
+        let assets = vec![
+            asset("DigStore-Setup-0.4.4-windows-x64.exe"),
+            asset("SHA256SUMS"),
+            asset("DigStore-Setup-0.4.4-windows-x64.exe.sha256"),
+        ];
+        let own = select_checksum_asset(&assets, "DigStore-Setup-0.4.4-windows-x64.exe");
+        assert_eq!(
+            own.unwrap().name,
+            "DigStore-Setup-0.4.4-windows-x64.exe.sha256"
+        );
+        let combined = select_checksum_asset(&assets, "other.msi");
+        assert_eq!(combined.unwrap().name, "SHA256SUMS");
+        assert!(select_checksum_asset(&assets[..1], "other.msi").is_none());
+    }
+
+    #[test]
+    fn parse_checksum_reads_sha256sum_lines_and_bare_digests() {
+        let a = "ab".repeat(32);
+        let b = "CD".repeat(32);
+        let sums = format!("{a}  setup.exe\n{b} *setup.msi\n");
+        assert_eq!(parse_checksum(&sums, "setup.exe").unwrap().to_hex(), a);
+        assert_eq!(
+            parse_checksum(&sums, "setup.msi").unwrap().to_hex(),
+            b.to_ascii_lowercase()
+        );
+        assert!(parse_checksum(&sums, "other.exe").is_none());
+        assert_eq!(parse_checksum(&format!("{a}\n"), "x").unwrap().to_hex(), a);
+        assert!(parse_checksum("not-hex  setup.exe", "setup.exe").is_none());
+    }
+
+    #[test]
+    fn installer_with_a_wrong_checksum_is_refused_and_never_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let installer = b"MZ installer bytes";
+        let wrong = Bytes32([0u8; 32]);
+        let err = stage_verified_installer(installer, &wrong, dir.path(), "setup.exe").unwrap_err();
+        assert!(matches!(err, CliError::VerificationFailed(_)));
+        assert!(!dir.path().join("setup.exe").exists());
+
+        let right = digstore_core::sha256(installer);
+        let dest = stage_verified_installer(installer, &right, dir.path(), "setup.exe").unwrap();
+        assert_eq!(std::fs::read(dest).unwrap(), installer);
+    }
+
     #[test]
     fn should_check_respects_24h_interval() {
         let day = CHECK_INTERVAL_SECS;
@@ -523,11 +711,39 @@ mod tests {
         let c = CheckCache {
             last_check_unix: 12345,
             latest_tag: "v0.4.0".into(),
+            failures: 2,
         };
         let json = serde_json::to_string(&c).unwrap();
         let back: CheckCache = serde_json::from_str(&json).unwrap();
         assert_eq!(back.last_check_unix, 12345);
         assert_eq!(back.latest_tag, "v0.4.0");
+        assert_eq!(back.failures, 2);
+        // Caches written before `failures` existed still load.
+        let old: CheckCache =
+            serde_json::from_str(r#"{"last_check_unix":1,"latest_tag":"v0.1.0"}"#).unwrap();
+        assert_eq!(old.failures, 0);
+    }
+
+    #[test]
+    fn failed_checks_back_off_and_keep_the_known_tag() {
+        let day = CHECK_INTERVAL_SECS;
+        assert_eq!(check_interval(0), day);
+        assert_eq!(check_interval(1), 2 * day);
+        assert_eq!(check_interval(2), 4 * day);
+        assert_eq!(check_interval(3), MAX_CHECK_INTERVAL_SECS);
+        assert_eq!(check_interval(u32::MAX), MAX_CHECK_INTERVAL_SECS);
+
+        let ok = CheckCache::default().after_check(1000, Some("v0.5.0".into()));
+        assert_eq!((ok.latest_tag.as_str(), ok.failures), ("v0.5.0", 0));
+        assert!(!ok.due(1000 + day - 1));
+        assert!(ok.due(1000 + day));
+
+        let offline = ok.after_check(5000, None);
+        assert_eq!(offline.latest_tag, "v0.5.0");
+        assert_eq!(offline.failures, 1);
+        assert!(!offline.due(5000 + day));
+        assert!(offline.due(5000 + 2 * day));
+        assert_eq!(offline.after_check(9000, Some("v0.6.0".into())).failures, 0);
     }
 
     #[test]
@@ -0,0 +1,57 @@
+use digstore_core::Bytes32;
+
+use crate::cli::VerifyArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// Generate and check a merkle proof for every file of a version, listing the
+/// ones whose content no longer hashes into its root. Fails with
+/// `VerificationFailed` when any file does not verify.
+pub fn run(ctx: &CliContext, ui: &Ui, args: VerifyArgs) -> Result<(), CliError> {
+    let root: Bytes32 = match &args.root {
+        Some(hex) => Bytes32::from_hex(hex)
+            .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {hex}")))?,
+        None => store_ops::current_root(ctx)?
+            .ok_or_else(|| CliError::NotFound("no committed root".into()))?,
+    };
+    let results = store_ops::verify_all(ctx, root)?;
+    let failed = results.iter().filter(|r| !r.verified).count();
+
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "verified": failed == 0,
+            "files": results.iter().map(|r| serde_json::json!({
+                "path": r.resource_key,
+                "retrieval_key": r.static_key.to_hex(),
+                "verified": r.verified,
+                "error": r.error,
+            })).collect::<Vec<_>>(),
+        }));
+    } else {
+        for r in results.iter().filter(|r| !r.verified) {
+            ui.line(format!(
+                "FAILED   {}: {}",
+                r.resource_key,
+                r.error.as_deref().unwrap_or("does not verify")
+            ));
+        }
+        if failed == 0 {
+            ui.success(format!(
+                "all {} files verify against {}",
+                results.len(),
+                root.to_hex()
+            ));
+        }
+    }
+    if failed > 0 {
+        return Err(CliError::VerificationFailed(format!(
+            "{failed} of {} files do not verify against {}",
+            results.len(),
+            root.to_hex()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use digstore_core::{Bytes32, Bytes48};
+
+use crate::cli::VerifySignatureArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// List every signature recorded for a version root with its signer public
+/// key and whether it verifies. Fails with `VerificationFailed` unless at least
+/// one (with `--pubkey`, that key's) signature is valid.
+pub fn run(ctx: &CliContext, ui: &Ui, args: VerifySignatureArgs) -> Result<(), CliError> {
+    let root = Bytes32::from_hex(&args.root)
+        .map_err(|_| CliError::InvalidArgument(format!("bad root hex: {}", args.root)))?;
+    let want = args
+        .pubkey
+        .as_deref()
+        .map(|hex| {
+            Bytes48::from_hex(hex)
+                .map_err(|_| CliError::InvalidArgument(format!("bad public key hex: {hex}")))
+        })
+        .transpose()?;
+    let sigs = store_ops::root_signatures(ctx, root)?;
+    let accepted = sigs
+        .iter()
+        .any(|(s, valid)| *valid && want.is_none_or(|pk| pk == s.public_key));
+
+    if ui.json() {
+        ui.emit_json(&serde_json::json!({
+            "root": root.to_hex(),
+            "verified": accepted,
+            "signatures": sigs.iter().map(|(s, valid)| serde_json::json!({
+                "public_key": s.public_key.to_hex(),
+                "valid": valid,
+            })).collect::<Vec<_>>(),
+        }));
+    } else {
+        if sigs.is_empty() {
+            ui.line(format!("{} has no signatures", root.to_hex()));
+        }
+        for (s, valid) in &sigs {
+            let status = if *valid { "valid" } else { "INVALID" };
+            ui.line(format!("{status:<8} {}", s.public_key.to_hex()));
+        }
+    }
+    if !accepted {
+        return Err(CliError::VerificationFailed(match want {
+            Some(pk) => format!("no valid signature by {} on {}", pk.to_hex(), root.to_hex()),
+            None => format!("no valid signature on {}", root.to_hex()),
+        }));
+    }
+    Ok(())
+}
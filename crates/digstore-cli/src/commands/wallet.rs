@@ -0,0 +1,139 @@
+//! `digstore wallet` — named wallet profiles, each with its own encrypted seed
+//! and unlock session (see `digstore_chain::config::wallet_dir`). `default` is
+//! the seed in `~/.dig` itself; every other profile lives in
+//! `~/.dig/profiles/<name>/`. The global `--wallet-profile` flag overrides the
+//! active profile for one command.
+
+use crate::cli::{WalletAction, WalletArgs};
+use crate::error::CliError;
+use crate::ops::wallet::resolve_passphrase;
+use crate::ui::Ui;
+use digstore_chain::config::{self, GlobalConfig, DEFAULT_PROFILE};
+use digstore_chain::keys::derive_wallet_keys;
+use digstore_chain::{seed, unlock};
+use zeroize::Zeroizing;
+
+pub fn run(ui: &Ui, args: WalletArgs) -> Result<(), CliError> {
+    let home = config::dig_home().map_err(CliError::from)?;
+    match args.action {
+        WalletAction::List => {
+            let active = config::active_profile(&home).map_err(CliError::from)?;
+            let mut rows = Vec::new();
+            for name in config::list_profiles(&home).map_err(CliError::from)? {
+                let dir = config::profile_dir(&home, &name);
+                let has_seed = seed::seed_exists(&dir.join("seed.enc"));
+                // Only an unlocked profile can name its key without a prompt.
+                let owner_puzzle_hash = match unlock::read_session(&dir.join("session")) {
+                    Some(phrase) => {
+                        Some(hex::encode(derive_wallet_keys(&phrase)?.owner_puzzle_hash))
+                    }
+                    None => None,
+                };
+                rows.push((name, has_seed, owner_puzzle_hash));
+            }
+            if ui.json() {
+                ui.emit_json(&serde_json::json!(rows
+                    .iter()
+                    .map(|(name, has_seed, ph)| serde_json::json!({
+                        "name": name,
+                        "active": *name == active,
+                        "seed_exists": has_seed,
+                        "unlocked": ph.is_some(),
+                        "owner_puzzle_hash": ph,
+                    }))
+                    .collect::<Vec<_>>()));
+                return Ok(());
+            }
+            for (name, has_seed, ph) in &rows {
+                let mark = if *name == active { "*" } else { " " };
+                let state = match (has_seed, ph) {
+                    (_, Some(ph)) => format!("unlocked  {ph}"),
+                    (true, None) => "locked".to_string(),
+                    (false, None) => "no seed".to_string(),
+                };
+                ui.line(format!("{mark} {name:<16} {state}"));
+            }
+            Ok(())
+        }
+        WalletAction::Create {
+            name,
+            mnemonic,
+            words,
+        } => {
+            bad_name(&name)?;
+            let dir = config::profile_dir(&home, &name);
+            if name == DEFAULT_PROFILE || dir.exists() {
+                return Err(CliError::InvalidArgument(format!(
+                    "wallet profile '{name}' already exists"
+                )));
+            }
+            let generated = mnemonic.is_none();
+            let phrase = match mnemonic {
+                Some(m) => seed::validate_mnemonic(&m).map_err(CliError::from)?,
+                None => seed::generate_mnemonic(words).map_err(CliError::from)?,
+            };
+            let pass: Zeroizing<String> =
+                resolve_passphrase(ui, "Set a passphrase to encrypt this profile's seed")?;
+            if generated && !ui.json() {
+                ui.line("");
+                ui.line("Your new mnemonic — write it down and store it safely:");
+                ui.line("");
+                ui.line(format!("    {}", &*phrase));
+                ui.line("");
+            }
+            let enc = seed::encrypt_seed(&phrase, &pass).map_err(CliError::from)?;
+            seed::save_seed(&dir.join("seed.enc"), &enc).map_err(CliError::from)?;
+            let cfg = GlobalConfig::load(&home).map_err(CliError::from)?;
+            unlock::write_session(&dir.join("session"), &phrase, cfg.unlock_ttl)
+                .map_err(CliError::from)?;
+            ui.success(format!("wallet profile '{name}' created and unlocked"));
+            ui.hint(format!("switch to it with `digstore wallet use {name}`"));
+            Ok(())
+        }
+        WalletAction::Use { name } => {
+            require_profile(&home, &name)?;
+            let mut cfg = GlobalConfig::load_file(&home).map_err(CliError::from)?;
+            cfg.wallet.active_profile = (name != DEFAULT_PROFILE).then(|| name.clone());
+            cfg.save(&home).map_err(CliError::from)?;
+            ui.success(format!("using wallet profile '{name}'"));
+            Ok(())
+        }
+        WalletAction::Delete { name } => {
+            require_profile(&home, &name)?;
+            if name == DEFAULT_PROFILE {
+                return Err(CliError::InvalidArgument(
+                    "the default wallet profile cannot be deleted".into(),
+                ));
+            }
+            if config::active_profile(&home).map_err(CliError::from)? == name {
+                return Err(CliError::InvalidArgument(format!(
+                    "wallet profile '{name}' is in use; `digstore wallet use` another first"
+                )));
+            }
+            ui.confirm_or_fail(&format!(
+                "Delete wallet profile '{name}' and its seed? Back up its mnemonic first"
+            ))?;
+            std::fs::remove_dir_all(config::profile_dir(&home, &name))
+                .map_err(|e| CliError::Other(anyhow::anyhow!("delete profile: {e}")))?;
+            ui.success(format!("wallet profile '{name}' deleted"));
+            Ok(())
+        }
+    }
+}
+
+/// Check a profile name, as a usage error rather than a config error.
+pub(crate) fn bad_name(name: &str) -> Result<(), CliError> {
+    config::validate_profile_name(name).map_err(|e| CliError::InvalidArgument(e.to_string()))
+}
+
+fn require_profile(home: &std::path::Path, name: &str) -> Result<(), CliError> {
+    bad_name(name)?;
+    if !config::list_profiles(home)
+        .map_err(CliError::from)?
+        .iter()
+        .any(|p| p == name)
+    {
+        return Err(CliError::NotFound(format!("wallet profile '{name}'")));
+    }
+    Ok(())
+}
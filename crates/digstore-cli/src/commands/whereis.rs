@@ -0,0 +1,53 @@
+use digstore_core::Bytes32;
+use digstore_store::HashKind;
+
+use crate::cli::WhereisArgs;
+use crate::context::CliContext;
+use crate::error::CliError;
+use crate::ops::store_ops;
+use crate::ui::Ui;
+
+/// List every file, across all versions, that a hash belongs to, and what the
+/// hash is to it (a chunk, the whole file, or its retrieval key). Fails with
+/// `NotFound` when nothing matches.
+pub fn run(ctx: &CliContext, ui: &Ui, args: WhereisArgs) -> Result<(), CliError> {
+    let hash = Bytes32::from_hex(&args.hash)
+        .map_err(|_| CliError::InvalidArgument(format!("bad hash hex: {}", args.hash)))?;
+    let matches = store_ops::find_files_by_hash(ctx, hash)?;
+    let kind_name = |k: HashKind| match k {
+        HashKind::Chunk => "chunk",
+        HashKind::File => "file",
+        HashKind::RetrievalKey => "retrieval-key",
+    };
+
+    if ui.json() {
+        ui.emit_json(
+            &matches
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "kind": kind_name(m.kind),
+                        "root": m.root.to_hex(),
+                        "path": m.resource_key,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+    } else {
+        for m in &matches {
+            ui.line(format!(
+                "{:<13} {}  {}",
+                kind_name(m.kind),
+                m.root.to_hex(),
+                m.resource_key
+            ));
+        }
+    }
+    if matches.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "no file in any version references {}",
+            hash.to_hex()
+        )));
+    }
+    Ok(())
+}
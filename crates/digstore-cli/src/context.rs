@@ -79,6 +79,87 @@ impl CliContext {
         }
     }
 
+    /// Context for `--store <path>`: the store directory itself (one holding
+    /// `config.toml`), outside any workspace. `None` when `value` is a store
+    /// name rather than a path, i.e. has no path separator. op_dir is the
+    /// `-C` directory, else CWD.
+    pub fn for_store_path(
+        value: &str,
+        cwd_flag: Option<PathBuf>,
+        cwd: PathBuf,
+        json: bool,
+        verbose: bool,
+    ) -> Option<Result<Self, CliError>> {
+        if !value.contains('/') && !value.contains(std::path::MAIN_SEPARATOR) {
+            return None;
+        }
+        let dir = cwd.join(value);
+        Some(Self::for_store_dir(dir, None, cwd_flag, cwd, json, verbose))
+    }
+
+    /// Context for a store name registered machine-wide (`digstore stores
+    /// add-name`), or `None` when `name` is not registered. Fails when the
+    /// registered directory no longer holds that store.
+    pub fn for_named_store(
+        name: &str,
+        cwd_flag: Option<PathBuf>,
+        cwd: PathBuf,
+        json: bool,
+        verbose: bool,
+    ) -> Option<Result<Self, CliError>> {
+        let home = digstore_chain::config::dig_home().ok()?;
+        let global = digstore_chain::config::GlobalConfig::load(&home).ok()?;
+        let entry = global.names.get(name)?;
+        let dir = PathBuf::from(&entry.path);
+        Some(
+            Self::for_store_dir(dir, Some(name), cwd_flag, cwd, json, verbose).and_then(|ctx| {
+                let id = ctx.find_store_id()?.to_hex();
+                if id != entry.id {
+                    return Err(CliError::InvalidArgument(format!(
+                        "store name '{name}' is registered to {} but {} holds store {id}; \
+                         re-register it with `digstore stores add-name`",
+                        entry.id, entry.path
+                    )));
+                }
+                Ok(ctx)
+            }),
+        )
+    }
+
+    /// A store directory used as is, outside any workspace; named `name`, else
+    /// after the directory.
+    fn for_store_dir(
+        dir: PathBuf,
+        name: Option<&str>,
+        cwd_flag: Option<PathBuf>,
+        cwd: PathBuf,
+        json: bool,
+        verbose: bool,
+    ) -> Result<Self, CliError> {
+        if !dir.join("config.toml").is_file() {
+            return Err(CliError::NoStore(dir.display().to_string()));
+        }
+        let op_dir = match cwd_flag {
+            Some(p) => cwd.join(p),
+            None => cwd,
+        };
+        let name = match name {
+            Some(n) => n.to_string(),
+            None => dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "default".to_string()),
+        };
+        Ok(CliContext {
+            dig_dir: dir.clone(),
+            workspace_dir: dir,
+            op_dir,
+            store_name: Some(name),
+            json,
+            verbose,
+        })
+    }
+
     /// Workspace-only context (stores/use): no store resolved.
     pub fn workspace_only(workspace_dir: PathBuf, json: bool, verbose: bool) -> Self {
         CliContext {
@@ -156,8 +237,7 @@ impl CliContext {
         if !path.exists() {
             return Err(CliError::NoStore(self.dig_dir.display().to_string()));
         }
-        digstore_store::load_config(&path)
-            .map_err(|e| CliError::Other(anyhow::anyhow!("load config: {e}")))
+        digstore_store::load_config(&path).map_err(|e| CliError::store("load config", e))
     }
 
     pub fn find_store_id(&self) -> Result<Bytes32, CliError> {
@@ -245,6 +325,25 @@ mod tests {
         assert_eq!(b.op_dir, abs);
     }
 
+    #[test]
+    fn store_flag_with_a_separator_is_a_store_path() {
+        let td = TempDir::new().unwrap();
+        let cwd = td.path().to_path_buf();
+        assert!(CliContext::for_store_path("site", None, cwd.clone(), false, false).is_none());
+        let missing = CliContext::for_store_path("./nowhere", None, cwd.clone(), false, false);
+        assert!(matches!(missing, Some(Err(CliError::NoStore(_)))));
+
+        let store = td.path().join("stores").join("site");
+        std::fs::create_dir_all(&store).unwrap();
+        std::fs::write(store.join("config.toml"), "").unwrap();
+        let ctx = CliContext::for_store_path("stores/site", None, cwd.clone(), false, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ctx.dig_dir, store);
+        assert_eq!(ctx.op_dir, cwd);
+        assert_eq!(ctx.store_name.as_deref(), Some("site"));
+    }
+
     #[test]
     fn config_toml_path_is_under_dig_dir() {
         let td = TempDir::new().unwrap();
@@ -82,6 +82,19 @@ pub struct DigToml {
     /// Embedded project metadata (the dighub `Manifest` shape).
     #[serde(default)]
     pub metadata: DigMetadata,
+    /// Commit hooks for this project; each one set here overrides the global
+    /// `hooks.preCommit`/`hooks.postCommit`.
+    #[serde(default)]
+    pub hooks: DigHooks,
+}
+
+/// The `[hooks]` table of `dig.toml`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct DigHooks {
+    #[serde(default, rename = "pre-commit", alias = "pre_commit")]
+    pub pre_commit: Option<String>,
+    #[serde(default, rename = "post-commit", alias = "post_commit")]
+    pub post_commit: Option<String>,
 }
 
 impl DigToml {
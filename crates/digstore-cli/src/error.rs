@@ -40,6 +40,14 @@ pub enum CliError {
     MintFailed(String),
     #[error("update failed: {0}")]
     UpdateFailed(String),
+    #[error("hook failed: {0}")]
+    HookFailed(String),
+    #[error("not enough disk space on {path}: need {need} bytes, have {have}")]
+    InsufficientSpace { need: u64, have: u64, path: String },
+    #[error("store {path} is locked by {}", .pid.map_or("another process".to_string(), |pid| format!("PID {pid}")))]
+    StoreLocked { path: String, pid: Option<u32> },
+    #[error("corrupted store: {0}")]
+    Corrupted(String),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -47,7 +55,8 @@ pub enum CliError {
 impl CliError {
     /// Exit-code contract:
     /// 0 success | 1 other | 2 invalid-argument | 3 no-store | 4 not-found
-    /// 5 verification-failed | 6 network | 7 non-fast-forward | 8 unauthorized.
+    /// 5 verification-failed | 6 network | 7 non-fast-forward | 8 unauthorized,
+    /// then the wallet, chain and store classes of [`Self::exit_code_table`].
     pub fn exit_code(&self) -> i32 {
         match self {
             CliError::NoStore(_) => 3,
@@ -65,6 +74,10 @@ impl CliError {
             CliError::ConfirmTimeout => 14,
             CliError::MintFailed(_) => 15,
             CliError::UpdateFailed(_) => 16,
+            CliError::HookFailed(_) => 17,
+            CliError::InsufficientSpace { .. } => 18,
+            CliError::StoreLocked { .. } => 19,
+            CliError::Corrupted(_) => 20,
             CliError::Other(_) => 1,
         }
     }
@@ -91,6 +104,10 @@ impl CliError {
             CliError::ConfirmTimeout => "CONFIRM_TIMEOUT",
             CliError::MintFailed(_) => "MINT_FAILED",
             CliError::UpdateFailed(_) => "UPDATE_FAILED",
+            CliError::HookFailed(_) => "HOOK_FAILED",
+            CliError::InsufficientSpace { .. } => "INSUFFICIENT_SPACE",
+            CliError::StoreLocked { .. } => "STORE_LOCKED",
+            CliError::Corrupted(_) => "STORE_CORRUPTED",
             CliError::Other(_) => "ERROR",
         }
     }
@@ -146,6 +163,22 @@ impl CliError {
             ),
             ("MINT_FAILED", 15, "the on-chain mint failed"),
             ("UPDATE_FAILED", 16, "the on-chain root update failed"),
+            ("HOOK_FAILED", 17, "a pre-commit hook rejected the commit"),
+            (
+                "INSUFFICIENT_SPACE",
+                18,
+                "not enough free disk space for the add/commit",
+            ),
+            (
+                "STORE_LOCKED",
+                19,
+                "another digstore process is writing to the store",
+            ),
+            (
+                "STORE_CORRUPTED",
+                20,
+                "store files are damaged (manifest, history or staging)",
+            ),
         ]
     }
 
@@ -177,6 +210,73 @@ impl CliError {
             CliError::Chain(_) => Some("check your connection to coinset.org and retry".into()),
             CliError::ConfirmTimeout => Some("the transaction may still confirm; run `digstore anchor status`".into()),
             CliError::MintFailed(_) | CliError::UpdateFailed(_) => Some("retry; if it persists, check wallet funds and coinset.org".into()),
+            CliError::HookFailed(_) => Some("fix what the hook reported, or re-run with `--no-verify` to skip hooks".into()),
+            CliError::InsufficientSpace { .. } => Some("free some space, or re-run with `--force` to skip the check".into()),
+            CliError::StoreLocked { .. } => Some("wait for the other process to finish; if it is hung, re-run with `--force-unlock`".into()),
+            CliError::Corrupted(_) => Some("run `digstore verify` to see what is damaged; re-fetch the store with `digstore pull` if it has a remote".into()),
+            _ => None,
+        }
+    }
+
+    /// Classify a store failure while doing `what` into the exit-code
+    /// category it belongs to: a missing store, generation or resource, a
+    /// verification failure, a held lock, damaged store files or a bad
+    /// request. Anything else is `Other`, keeping the
+    /// [`digstore_store::StoreError`] so [`Self::store_code`] can report it.
+    pub fn store(what: &str, e: digstore_store::StoreError) -> Self {
+        use digstore_store::StoreError as S;
+        match e {
+            S::NotFound(path) => CliError::NoStore(path),
+            S::GenerationNotFound(root) => CliError::NotFound(format!("generation {root}")),
+            S::ResourceNotFound {
+                key,
+                root,
+                found_in: Some(other),
+            } => CliError::NotFound(format!(
+                "{key} in generation {root}; found at root {other} (read it with `--at {other}`)"
+            )),
+            S::ResourceNotFound { key, root, .. } => {
+                CliError::NotFound(format!("{key} in generation {root}"))
+            }
+            S::ChunkNotFound(hash) => CliError::NotFound(format!("chunk {hash}")),
+            e @ (S::RootMismatch { .. } | S::DecryptFailed(_)) => {
+                CliError::VerificationFailed(e.to_string())
+            }
+            S::Unauthorized(why) => CliError::Unauthorized(why),
+            S::Locked { path, pid } => CliError::StoreLocked { path, pid },
+            S::Corrupted { entry, detail } => CliError::Corrupted(format!("{entry}: {detail}")),
+            e @ (S::CorruptStaging(_) | S::Manifest(_) | S::NonMonotonicHistory { .. }) => {
+                CliError::Corrupted(e.to_string())
+            }
+            e @ (S::InvalidRange(_)
+            | S::InvalidBranch(_)
+            | S::InvalidUrn(_)
+            | S::InvalidSquash(_)
+            | S::InvalidPrune(_)
+            | S::InvalidDelta(_)
+            | S::InvalidTar(_)
+            | S::TarTooLarge { .. }
+            | S::BinaryFile(_)
+            | S::UnsafeResourceKey(_)
+            | S::PathEscape(_)
+            | S::NothingToAmend
+            | S::EmptyStaging
+            | S::DirtyStaging) => CliError::InvalidArgument(e.to_string()),
+            e => {
+                let message = format!("{what}: {e}");
+                CliError::Other(anyhow::Error::new(e).context(message))
+            }
+        }
+    }
+
+    /// The [`digstore_store::StoreError::code`] behind an unclassified error,
+    /// when it came from the store (emitted as `store_code` in `--json`
+    /// errors, next to the CLI-level [`Self::code`]).
+    pub fn store_code(&self) -> Option<&'static str> {
+        match self {
+            CliError::Other(e) => e
+                .downcast_ref::<digstore_store::StoreError>()
+                .map(digstore_store::StoreError::code),
             _ => None,
         }
     }
@@ -235,6 +335,17 @@ mod tests {
             CliError::ConfirmTimeout,
             CliError::MintFailed("x".into()),
             CliError::UpdateFailed("x".into()),
+            CliError::HookFailed("x".into()),
+            CliError::InsufficientSpace {
+                need: 2,
+                have: 1,
+                path: "/".into(),
+            },
+            CliError::StoreLocked {
+                path: "/".into(),
+                pid: Some(1),
+            },
+            CliError::Corrupted("x".into()),
         ];
         let mut codes: Vec<i32> = errs.iter().map(|e| e.exit_code()).collect();
         let n = codes.len();
@@ -244,6 +355,42 @@ mod tests {
         assert!(codes.iter().all(|c| *c != 0), "exit codes must be nonzero");
     }
 
+    #[test]
+    fn store_failures_keep_their_message_and_code() {
+        let e = CliError::store(
+            "open store",
+            digstore_store::StoreError::Backend("x".into()),
+        );
+        assert_eq!(e.to_string(), "open store: chunk backend error: x");
+        assert_eq!(e.code(), "ERROR");
+        assert_eq!(e.store_code(), Some("BACKEND"));
+        assert_eq!(CliError::NotFound("x".into()).store_code(), None);
+    }
+
+    #[test]
+    fn store_failures_land_in_their_exit_code_category() {
+        use digstore_store::StoreError as S;
+        let exit = |e: S| CliError::store("op", e).exit_code();
+        assert_eq!(exit(S::NotFound("/x".into())), 3);
+        assert_eq!(exit(S::GenerationNotFound("r".into())), 4);
+        assert_eq!(exit(S::DecryptFailed("c".into())), 5);
+        assert_eq!(exit(S::EmptyStaging), 2);
+        assert_eq!(
+            exit(S::Locked {
+                path: "/x".into(),
+                pid: None
+            }),
+            19
+        );
+        let corrupted = S::Corrupted {
+            entry: "generation r".into(),
+            detail: "the head generation's manifest is missing".into(),
+        };
+        assert_eq!(exit(corrupted), 20);
+        assert_eq!(exit(S::Manifest("bad json".into())), 20);
+        assert_eq!(exit(S::Io(std::io::Error::other("disk"))), 1);
+    }
+
     #[test]
     fn maps_not_found_error_code() {
         let e = CliError::from_error_code(ErrorCode::NotFound, "urn:dig:...");
@@ -273,6 +420,17 @@ mod tests {
             CliError::ConfirmTimeout,
             CliError::MintFailed("x".into()),
             CliError::UpdateFailed("x".into()),
+            CliError::HookFailed("x".into()),
+            CliError::InsufficientSpace {
+                need: 2,
+                have: 1,
+                path: "/".into(),
+            },
+            CliError::StoreLocked {
+                path: "/".into(),
+                pid: Some(1),
+            },
+            CliError::Corrupted("x".into()),
         ];
         let mut codes: Vec<&str> = errs.iter().map(|e| e.code()).collect();
         let n = codes.len();
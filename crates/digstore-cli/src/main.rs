@@ -12,7 +12,12 @@ fn main() {
         commands::completion::print_help_json();
         std::process::exit(0);
     }
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let configured = digstore_chain::config::dig_home()
+        .and_then(|home| digstore_chain::config::GlobalConfig::load(&home))
+        .map(|cfg| cfg.output.format)
+        .unwrap_or_default();
+    cli.resolve_output_format(configured);
     if cli.verbose {
         let _ = tracing_subscriber::fmt()
             .with_env_filter(
@@ -32,6 +37,7 @@ fn main() {
         cli.quiet,
         cli.non_interactive,
         cli.yes,
+        cli.no_progress,
     );
     match commands::dispatch(cli) {
         Ok(()) => {
@@ -66,8 +66,20 @@ pub fn decrypt_and_verify(
     verify_chunk_inclusion(&resp.ciphertext, &resp.merkle_proof, trusted_root)?;
 
     // 2) confidentiality: split the plain concat by known chunk lengths, open each.
+    decrypt_chunks(&resp.ciphertext, urn, secret_salt, chunk_lens)
+}
+
+/// Step 2 of [`decrypt_and_verify`] on its own: split `buf`, a resource's
+/// plain-concatenated chunk ciphertexts, by `chunk_lens` (empty = one chunk)
+/// and open each under the URN's key. Used where the sealed bytes came from
+/// elsewhere, e.g. `cat <retrieval-key> --out blob.enc` then `decrypt`.
+pub fn decrypt_chunks(
+    buf: &[u8],
+    urn: &Urn,
+    secret_salt: Option<&[u8; 32]>,
+    chunk_lens: &[usize],
+) -> Result<Vec<u8>, CliError> {
     let key = derive_decryption_key(urn, secret_salt);
-    let buf = &resp.ciphertext;
 
     // Build the split plan. Empty `chunk_lens` => one chunk == the whole blob.
     let plan: Vec<usize> = if chunk_lens.is_empty() {
@@ -78,7 +90,7 @@ pub fn decrypt_and_verify(
     let total: usize = plan.iter().sum();
     if total != buf.len() {
         return Err(CliError::VerificationFailed(format!(
-            "served ciphertext length {} does not match expected chunk total {}",
+            "ciphertext length {} does not match expected chunk total {}",
             buf.len(),
             total
         )));
@@ -0,0 +1,160 @@
+//! Per-commit note, persisted to `<dig_dir>/notes/<id>.json` for history
+//! entry `id`.
+//!
+//! The generation manifest is owned by the shared stage engine and its bytes
+//! feed the served module, so the human-facing details of a commit (`-m`
+//! message, author, `--date`) live in this CLI-owned file instead. It is
+//! keyed by history entry, not root: a checkpoint (`commit --allow-empty`)
+//! adds an entry for a root that is already listed, with a note of its own.
+//! It is local only: never embedded, never pushed, and absent for versions
+//! that arrived by clone/pull.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use digstore_core::GenerationState;
+
+use crate::error::CliError;
+
+/// What `digstore commit` recorded about one history entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitNote {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// `commit --date`, as RFC 3339 in the offset it was given in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl CommitNote {
+    /// Path of the note for history entry `id` under `dig_dir`.
+    pub fn path(dig_dir: &Path, id: u64) -> PathBuf {
+        dig_dir.join("notes").join(format!("{id}.json"))
+    }
+
+    /// Load the note for history entry `id`, or `None` if it has none.
+    pub fn load(dig_dir: &Path, id: u64) -> Result<Option<CommitNote>, CliError> {
+        let path = Self::path(dig_dir, id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("read commit note: {e}")))?;
+        let note = serde_json::from_str(&text)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("parse commit note: {e}")))?;
+        Ok(Some(note))
+    }
+
+    /// Persist this note for history entry `id`. An empty note writes nothing.
+    pub fn save(&self, dig_dir: &Path, id: u64) -> Result<(), CliError> {
+        if *self == CommitNote::default() {
+            return Ok(());
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| CliError::Other(e.into()))?;
+        let path = Self::path(dig_dir, id);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| CliError::Other(anyhow::anyhow!("create notes dir: {e}")))?;
+        }
+        std::fs::write(path, text)
+            .map_err(|e| CliError::Other(anyhow::anyhow!("write commit note: {e}")))?;
+        Ok(())
+    }
+
+    /// Follow a squash or prune: `after` is `before` with some entries
+    /// dropped and the rest renumbered in order. Kept entries' notes move to
+    /// their new ids; dropped entries' notes are deleted.
+    pub fn renumber(
+        dig_dir: &Path,
+        before: &[GenerationState],
+        after: &[GenerationState],
+    ) -> Result<(), CliError> {
+        let io = |e: std::io::Error| CliError::Other(anyhow::anyhow!("renumber notes: {e}"));
+        let mut kept = after.iter().peekable();
+        let mut moves = Vec::new();
+        for old in before {
+            match kept.peek() {
+                Some(new) if new.root == old.root && new.timestamp == old.timestamp => {
+                    moves.push((old.id, new.id));
+                    kept.next();
+                }
+                _ => {
+                    let path = Self::path(dig_dir, old.id);
+                    if path.exists() {
+                        std::fs::remove_file(path).map_err(io)?;
+                    }
+                }
+            }
+        }
+        // Ids only move down, so in ascending order every target is free.
+        for (from, to) in moves {
+            let path = Self::path(dig_dir, from);
+            if from != to && path.exists() {
+                std::fs::rename(path, Self::path(dig_dir, to)).map_err(io)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The default author of a commit: the logged-in dighub handle, if any.
+pub fn default_author() -> Option<String> {
+    crate::ops::dighub::load_session()
+        .and_then(|s| s.handle)
+        .map(|h| format!("@{h}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn note(message: &str) -> CommitNote {
+        CommitNote {
+            message: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let td = TempDir::new().unwrap();
+        let note = CommitNote {
+            message: Some("first".into()),
+            author: Some("@alice".into()),
+            date: Some("2023-11-14T17:13:20-05:00".into()),
+        };
+        note.save(td.path(), 3).unwrap();
+        assert_eq!(CommitNote::load(td.path(), 3).unwrap(), Some(note));
+    }
+
+    #[test]
+    fn empty_note_is_not_written() {
+        let td = TempDir::new().unwrap();
+        CommitNote::default().save(td.path(), 3).unwrap();
+        assert!(CommitNote::load(td.path(), 3).unwrap().is_none());
+    }
+
+    #[test]
+    fn renumber_follows_the_kept_entries() {
+        let td = TempDir::new().unwrap();
+        let entry = |id, root: u8, timestamp| GenerationState {
+            id,
+            root: digstore_core::Bytes32([root; 32]),
+            timestamp,
+        };
+        // Two entries share root 1 (a checkpoint); the first one is dropped.
+        let before = [entry(0, 1, 10), entry(1, 2, 20), entry(2, 1, 30)];
+        for (id, message) in ["a", "b", "c"].into_iter().enumerate() {
+            note(message).save(td.path(), id as u64).unwrap();
+        }
+        let after = [entry(0, 2, 20), entry(1, 1, 30)];
+        CommitNote::renumber(td.path(), &before, &after).unwrap();
+        assert_eq!(CommitNote::load(td.path(), 0).unwrap(), Some(note("b")));
+        assert_eq!(CommitNote::load(td.path(), 1).unwrap(), Some(note("c")));
+        assert!(CommitNote::load(td.path(), 2).unwrap().is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! Date arguments (`log --since/--until`, `commit --date`).
+//!
+//! Accepted forms:
+//!
+//! - `1700000000` or `@1700000000` — Unix seconds (UTC)
+//! - `@1700000000 -0500` — git's raw form: Unix seconds plus a `±HHMM` offset
+//! - `2024-05-01` — midnight UTC that day
+//! - `2024-05-01T12:30:00Z` / `2024-05-01T12:30:00+02:00` — RFC 3339
+//!   (a space may replace the `T`; fractional seconds are ignored)
+//!
+//! Anything else is an error; there is no fallback to "now".
+
+use std::fmt;
+
+/// A point in time plus the UTC offset it was written in, so a commit date
+/// round-trips exactly. Displays as RFC 3339 in its own offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitDate {
+    /// Unix seconds (UTC).
+    pub secs: u64,
+    /// Offset east of UTC, in seconds.
+    pub offset: i32,
+}
+
+/// Parse a date argument to Unix seconds. Dates before 1970 are rejected.
+pub fn parse_date(s: &str) -> Result<u64, String> {
+    parse_commit_date(s).map(|d| d.secs)
+}
+
+/// Parse a date argument, keeping the offset it was written in.
+pub fn parse_commit_date(s: &str) -> Result<CommitDate, String> {
+    let (secs, offset) = parse(s)?;
+    let secs = u64::try_from(secs).map_err(|_| format!("invalid date '{s}': before 1970"))?;
+    Ok(CommitDate {
+        secs,
+        offset: offset as i32,
+    })
+}
+
+fn parse(s: &str) -> Result<(i64, i64), String> {
+    let bad = || {
+        format!(
+            "invalid date '{s}' (expected YYYY-MM-DD, an RFC 3339 time, Unix seconds, \
+             or '@<seconds> ±HHMM')"
+        )
+    };
+    let t = s.trim();
+    let is_digits = |v: &str| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit());
+    let digits = t.strip_prefix('@').unwrap_or(t);
+    if is_digits(digits) {
+        return Ok((digits.parse().map_err(|_| bad())?, 0));
+    }
+    if let Some((epoch, zone)) = t.strip_prefix('@').and_then(|r| r.split_once(' ')) {
+        let zone = zone.trim();
+        if !is_digits(epoch) || zone.len() != 5 {
+            return Err(bad());
+        }
+        let offset = zone_offset(&zone[..3], &zone[3..]).ok_or_else(bad)?;
+        return Ok((epoch.parse().map_err(|_| bad())?, offset));
+    }
+
+    let (date, time) = match t.find(['T', 't', ' ']) {
+        Some(i) => (&t[..i], Some(&t[i + 1..])),
+        None => (t, None),
+    };
+    let mut ymd = date.splitn(3, '-');
+    let (Some(y), Some(m), Some(d)) = (ymd.next(), ymd.next(), ymd.next()) else {
+        return Err(bad());
+    };
+    if y.len() != 4 || m.len() != 2 || d.len() != 2 {
+        return Err(bad());
+    }
+    let num = |v: &str| v.parse::<i64>().map_err(|_| bad());
+    let (y, m, d) = (num(y)?, num(m)?, num(d)?);
+    if !(1..=12).contains(&m) || d < 1 || d > days_in_month(y, m) {
+        return Err(bad());
+    }
+
+    let mut secs = days_from_civil(y, m, d) * 86_400;
+    let mut zone = 0;
+    if let Some(time) = time {
+        let (clock, offset) = split_offset(time).ok_or_else(bad)?;
+        let clock = clock.split('.').next().unwrap_or_default();
+        let mut hms = clock.split(':');
+        let (Some(h), Some(mi), Some(se)) = (hms.next(), hms.next(), hms.next()) else {
+            return Err(bad());
+        };
+        if hms.next().is_some() || [h, mi, se].iter().any(|p| p.len() != 2) {
+            return Err(bad());
+        }
+        let (h, mi, se) = (num(h)?, num(mi)?, num(se)?);
+        if h > 23 || mi > 59 || se > 60 {
+            return Err(bad());
+        }
+        secs += h * 3600 + mi * 60 + se - offset;
+        zone = offset;
+    }
+    Ok((secs, zone))
+}
+
+/// Split `HH:MM:SS[.frac](Z|±HH:MM)` into the clock part and the offset in
+/// seconds east of UTC.
+fn split_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0));
+    }
+    let i = time.rfind(['+', '-'])?;
+    let (clock, zone) = time.split_at(i);
+    let (h, m) = zone.split_once(':')?;
+    Some((clock, zone_offset(h, m)?))
+}
+
+/// `±HH` and `MM` to seconds east of UTC.
+fn zone_offset(signed_hours: &str, minutes: &str) -> Option<i64> {
+    let sign = match signed_hours.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let h = &signed_hours[1..];
+    if h.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let (h, m) = (h.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?);
+    if h > 23 || m > 59 {
+        return None;
+    }
+    Some(sign * (h * 3600 + m * 60))
+}
+
+impl fmt::Display for CommitDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let local = self.secs as i64 + i64::from(self.offset);
+        let (y, m, d) = civil_from_days(local.div_euclid(86_400));
+        let t = local.rem_euclid(86_400);
+        write!(
+            f,
+            "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}",
+            t / 3600,
+            t / 60 % 60,
+            t % 60
+        )?;
+        if self.offset == 0 {
+            return f.write_str("Z");
+        }
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let off = self.offset.unsigned_abs();
+        write!(f, "{sign}{:02}:{:02}", off / 3600, off / 60 % 60)
+    }
+}
+
+fn days_in_month(y: i64, m: i64) -> i64 {
+    match m {
+        2 if (y % 4 == 0 && y % 100 != 0) || y % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (H. Hinnant's algorithm).
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_documented_form() {
+        assert_eq!(parse_date("1700000000").unwrap(), 1_700_000_000);
+        assert_eq!(parse_date("@1700000000").unwrap(), 1_700_000_000);
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("2024-02-29").unwrap(), 1_709_164_800);
+        assert_eq!(parse_date("2023-11-14T22:13:20Z").unwrap(), 1_700_000_000);
+        assert_eq!(
+            parse_date("2023-11-15T00:13:20.5+02:00").unwrap(),
+            1_700_000_000
+        );
+        assert_eq!(
+            parse_date("2023-11-14 17:13:20-05:00").unwrap(),
+            1_700_000_000
+        );
+    }
+
+    #[test]
+    fn commit_dates_keep_their_offset_and_round_trip() {
+        let git = parse_commit_date("@1700000000 -0500").unwrap();
+        assert_eq!(
+            git,
+            CommitDate {
+                secs: 1_700_000_000,
+                offset: -5 * 3600
+            }
+        );
+        assert_eq!(git.to_string(), "2023-11-14T17:13:20-05:00");
+        for s in [
+            "2023-11-14T17:13:20-05:00",
+            "2024-02-29T23:59:59+05:30",
+            "2023-11-14T22:13:20Z",
+            "1970-01-01T00:00:00Z",
+        ] {
+            let d = parse_commit_date(s).unwrap();
+            assert_eq!(d.to_string(), s);
+            assert_eq!(parse_commit_date(&d.to_string()).unwrap(), d);
+        }
+        assert_eq!(
+            parse_commit_date("1700000000").unwrap().to_string(),
+            "2023-11-14T22:13:20Z"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_dates() {
+        for bad in [
+            "",
+            "yesterday",
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-01",
+            "2024-05-01T25:00:00Z",
+            "2024-05-01T12:00",
+            "2024-05-01T12:00:00+2",
+            "1969-12-31",
+            "@1700000000 0500",
+            "@1700000000 +05",
+            "@17x +0500",
+        ] {
+            assert!(parse_date(bad).is_err(), "{bad} should not parse");
+        }
+    }
+}
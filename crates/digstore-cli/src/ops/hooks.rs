@@ -0,0 +1,184 @@
+//! Commit hooks: user shell commands `digstore commit` runs around a commit.
+//!
+//! A hook comes from the project's `dig.toml` `[hooks]` table (`pre-commit`,
+//! `post-commit`) or, failing that, the global `~/.dig/config.toml` `[hooks]`
+//! table (`preCommit`, `postCommit`). Each hook is resolved on its own, so a
+//! project can override just one of them.
+//!
+//! A hook runs through the platform shell (`sh -c` / `cmd /C`) in the project
+//! directory. It gets the staged resource keys on stdin, one per line, and
+//! `DIGSTORE_HOOK` (`pre-commit`/`post-commit`) plus `DIGSTORE_ROOT` (the new
+//! root, hex) in its environment. Its stdout is sent to stderr so `--json`
+//! output stays parseable.
+//!
+//! A failing pre-commit hook aborts the commit before anything is anchored
+//! (`HOOK_FAILED`). A failing post-commit hook only warns: the commit has
+//! already been anchored and finalized.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use digstore_chain::config::{self, GlobalConfig};
+use digstore_core::Bytes32;
+
+use crate::dig_toml::DigToml;
+use crate::error::CliError;
+use crate::ui::Ui;
+
+/// Which commit hook to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreCommit,
+    PostCommit,
+}
+
+impl Hook {
+    /// The hook's name, as used in `dig.toml` and `DIGSTORE_HOOK`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::PostCommit => "post-commit",
+        }
+    }
+}
+
+/// The command configured for `hook`: `dig.toml` first, then the global config.
+/// `None` when neither sets one.
+pub fn resolve(op_dir: &Path, hook: Hook) -> Result<Option<String>, CliError> {
+    let local = DigToml::read(op_dir)?.hooks;
+    let local = match hook {
+        Hook::PreCommit => local.pre_commit,
+        Hook::PostCommit => local.post_commit,
+    };
+    if let Some(cmd) = local {
+        return Ok(Some(cmd));
+    }
+    let global = GlobalConfig::load(&config::dig_home()?)?.hooks;
+    Ok(match hook {
+        Hook::PreCommit => global.pre_commit,
+        Hook::PostCommit => global.post_commit,
+    })
+}
+
+/// Run `hook` if one is configured. `staged` is written to the hook's stdin.
+/// A pre-commit failure is an error; a post-commit failure is only reported.
+pub fn run(
+    ui: &Ui,
+    op_dir: &Path,
+    hook: Hook,
+    staged: &[String],
+    root: &Bytes32,
+) -> Result<(), CliError> {
+    let Some(cmd) = resolve(op_dir, hook)? else {
+        return Ok(());
+    };
+    if !ui.json() {
+        ui.line(format!("▶ {}: {cmd}", hook.name()));
+    }
+    match spawn(op_dir, hook, &cmd, staged, root) {
+        Ok(()) => Ok(()),
+        Err(msg) if hook == Hook::PreCommit => Err(CliError::HookFailed(msg)),
+        Err(msg) => {
+            // The commit is already on chain and finalized; nothing to undo.
+            eprintln!("warning: {msg}");
+            Ok(())
+        }
+    }
+}
+
+fn spawn(
+    op_dir: &Path,
+    hook: Hook,
+    cmd: &str,
+    staged: &[String],
+    root: &Bytes32,
+) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut c = Command::new("sh");
+        c.args(["-c", cmd]);
+        c
+    };
+    let mut child = command
+        .current_dir(op_dir)
+        .env("DIGSTORE_HOOK", hook.name())
+        .env("DIGSTORE_ROOT", root.to_hex())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(std::io::stderr()))
+        .spawn()
+        .map_err(|e| format!("{} hook: spawn `{cmd}`: {e}", hook.name()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut list = staged.join("\n");
+        if !list.is_empty() {
+            list.push('\n');
+        }
+        // A hook that never reads stdin closes the pipe early; that is fine.
+        match stdin.write_all(list.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(format!("{} hook: write stdin: {e}", hook.name()));
+            }
+            _ => {}
+        }
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("{} hook: wait: {e}", hook.name()))?;
+    if !status.success() {
+        return Err(format!("{} hook `{cmd}` exited with {status}", hook.name()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dig_toml_hook_overrides_and_receives_staged_keys_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("dig.toml"),
+            "[hooks]\npre-commit = \"cat > seen.txt; echo $DIGSTORE_HOOK >> seen.txt\"\n",
+        )
+        .unwrap();
+        let cmd = resolve(dir.path(), Hook::PreCommit).unwrap().unwrap();
+        assert!(cmd.starts_with("cat"));
+        if cfg!(windows) {
+            return;
+        }
+        spawn(
+            dir.path(),
+            Hook::PreCommit,
+            &cmd,
+            &["a.txt".into(), "b/c.txt".into()],
+            &Bytes32([7; 32]),
+        )
+        .unwrap();
+        let seen = std::fs::read_to_string(dir.path().join("seen.txt")).unwrap();
+        assert_eq!(seen, "a.txt\nb/c.txt\npre-commit\n");
+    }
+
+    #[test]
+    fn nonzero_exit_is_reported_with_the_status() {
+        if cfg!(windows) {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let err = spawn(
+            dir.path(),
+            Hook::PreCommit,
+            "exit 3",
+            &[],
+            &Bytes32([0; 32]),
+        )
+        .unwrap_err();
+        assert!(err.contains("exit 3"), "{err}");
+    }
+}
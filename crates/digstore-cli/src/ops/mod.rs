@@ -5,9 +5,13 @@ pub mod anchor_state;
 pub mod anchor_ux;
 pub mod assets;
 pub mod client_crypto;
+pub mod commit_note;
+pub mod date;
 pub mod dighub;
 pub mod discovery;
+pub mod hooks;
 pub mod identity;
+pub mod range;
 pub mod remote_ops;
 pub mod serve;
 pub mod store_ops;
@@ -0,0 +1,374 @@
+//! Byte and line ranges for partial reads (`cat --bytes` / `cat --lines`).
+//!
+//! Byte ranges use the HTTP `Range` grammar (RFC 9110 §14.1.2) so the same
+//! spec works on the command line and over the wire:
+//!
+//! - `START-END` — bytes `START..=END` (both inclusive, zero-based)
+//! - `START-`    — from `START` to the end of the resource
+//! - `-N`        — the LAST `N` bytes (a suffix, never "the first N")
+//!
+//! plus two forms for sampling a resource without knowing its length:
+//!
+//! - `A%-B%`     — from `A` to `B` percent of the length (`0 <= A < B <= 100`;
+//!   offsets round down, so `0%-50%` and `50%-100%` tile the resource)
+//! - `last:N`    — the same suffix as `-N`
+//!
+//! There is no "first N bytes" shorthand: write `0-(N-1)`. A suffix of `0`
+//! bytes is rejected when parsed; one longer than the resource selects all of
+//! it, and any suffix of an empty resource selects nothing.
+//!
+//! Line ranges are `START:END`, one-based and inclusive like `sed -n START,ENDp`;
+//! either side may be omitted (`:20` is the first twenty lines, `5:` is line five
+//! onward).
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::error::CliError;
+
+/// A parsed byte range; resolve it against a length with [`ByteRange::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// `START-END`, inclusive on both ends.
+    Span { start: u64, end: u64 },
+    /// `START-`, open-ended.
+    From(u64),
+    /// `-N` (or `last:N`), the final `N` bytes; `N >= 1`.
+    Last(u64),
+    /// `A%-B%`, a share of the resource's length.
+    Percent { start: u8, end: u8 },
+}
+
+impl ByteRange {
+    /// The concrete half-open byte range this spec selects from a resource of
+    /// `len` bytes:
+    ///
+    /// - `START-END` selects `START..=END`, so `N-N` is the single byte `N`;
+    ///   an end past the last byte is clamped.
+    /// - A range that starts at or past the end — `START` is `len` or more,
+    ///   including any start on an empty resource — is unsatisfiable.
+    /// - A suffix (`-N`) is always satisfiable: longer than the resource it
+    ///   selects all of it, and on an empty resource it selects nothing.
+    /// - A percentage whose offsets round to the same byte selects nothing.
+    pub fn resolve(&self, len: u64) -> Result<Range<usize>, CliError> {
+        let (start, end) = match *self {
+            ByteRange::Span { start, end } => (start, end.saturating_add(1)),
+            ByteRange::From(start) => (start, len),
+            ByteRange::Last(n) => (len.saturating_sub(n), len),
+            ByteRange::Percent { start, end } => {
+                let at = |pct: u8| (u128::from(len) * u128::from(pct) / 100) as u64;
+                (at(start), at(end))
+            }
+        };
+        if start >= len && !matches!(self, ByteRange::Last(_)) {
+            return Err(CliError::InvalidArgument(format!(
+                "byte range {self} starts past the end of the {len}-byte resource"
+            )));
+        }
+        Ok(start as usize..end.min(len) as usize)
+    }
+}
+
+impl FromStr for ByteRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || {
+            format!("invalid byte range '{s}' (expected START-END, START-, -N, A%-B% or last:N)")
+        };
+        let suffix = |n: u64| match n {
+            0 => Err(format!(
+                "invalid byte range '{s}': a suffix must be at least 1 byte"
+            )),
+            n => Ok(ByteRange::Last(n)),
+        };
+        if let Some(n) = s.strip_prefix("last:") {
+            return suffix(n.parse::<u64>().map_err(|_| bad())?);
+        }
+        let (lo, hi) = s.split_once('-').ok_or_else(bad)?;
+        let num = |v: &str| v.parse::<u64>().map_err(|_| bad());
+        if let (Some(lo), Some(hi)) = (lo.strip_suffix('%'), hi.strip_suffix('%')) {
+            let pct = |v: &str| match v.parse::<u8>() {
+                Ok(p) if p <= 100 => Ok(p),
+                _ => Err(format!(
+                    "invalid byte range '{s}': percentages must be whole numbers from 0 to 100"
+                )),
+            };
+            let (start, end) = (pct(lo)?, pct(hi)?);
+            if end <= start {
+                return Err(format!(
+                    "invalid byte range '{s}': END% must be above START%"
+                ));
+            }
+            return Ok(ByteRange::Percent { start, end });
+        }
+        match (lo.is_empty(), hi.is_empty()) {
+            (true, true) => Err(bad()),
+            (true, false) => suffix(num(hi)?),
+            (false, true) => Ok(ByteRange::From(num(lo)?)),
+            (false, false) => {
+                let (start, end) = (num(lo)?, num(hi)?);
+                if end < start {
+                    return Err(format!("invalid byte range '{s}': END is before START"));
+                }
+                Ok(ByteRange::Span { start, end })
+            }
+        }
+    }
+}
+
+impl fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ByteRange::Span { start, end } => write!(f, "{start}-{end}"),
+            ByteRange::From(start) => write!(f, "{start}-"),
+            ByteRange::Last(n) => write!(f, "-{n}"),
+            ByteRange::Percent { start, end } => write!(f, "{start}%-{end}%"),
+        }
+    }
+}
+
+/// A one-based, inclusive line range; `None` ends are open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+}
+
+impl LineRange {
+    /// The first line number this range displays (for `--number`).
+    pub fn first_line(&self) -> u64 {
+        self.start.unwrap_or(1)
+    }
+
+    /// The half-open byte range of `content` covering the selected lines, each
+    /// kept with its trailing newline. Lines past the end select nothing.
+    pub fn resolve(&self, content: &[u8]) -> Range<usize> {
+        let first = self.first_line();
+        let mut line = 1u64;
+        let mut start = None;
+        let mut end = content.len();
+        if first == 1 {
+            start = Some(0);
+        }
+        for (i, b) in content.iter().enumerate() {
+            if *b != b'\n' {
+                continue;
+            }
+            if self.end == Some(line) {
+                end = i + 1;
+                break;
+            }
+            line += 1;
+            if line == first {
+                start = Some(i + 1);
+            }
+        }
+        match start {
+            Some(s) if s < content.len() => s..end,
+            _ => content.len()..content.len(),
+        }
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || format!("invalid line range '{s}' (expected START:END, START: or :END)");
+        let (lo, hi) = s.split_once(':').ok_or_else(bad)?;
+        let num = |v: &str| -> Result<Option<u64>, String> {
+            if v.is_empty() {
+                return Ok(None);
+            }
+            match v.parse::<u64>() {
+                Ok(0) => Err(format!(
+                    "invalid line range '{s}': lines are numbered from 1"
+                )),
+                Ok(n) => Ok(Some(n)),
+                Err(_) => Err(bad()),
+            }
+        };
+        let (start, end) = (num(lo)?, num(hi)?);
+        if let (Some(a), Some(b)) = (start, end) {
+            if b < a {
+                return Err(format!("invalid line range '{s}': END is before START"));
+            }
+        }
+        Ok(LineRange { start, end })
+    }
+}
+
+/// Prefix each line of `content` with its number, starting at `first`, in the
+/// `cat -n` layout (right-aligned to six columns, then a tab).
+pub fn number_lines(content: &[u8], first: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + content.len() / 8);
+    for (n, line) in (first..).zip(content.split_inclusive(|b| *b == b'\n')) {
+        out.extend_from_slice(format!("{n:>6}\t").as_bytes());
+        out.extend_from_slice(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn br(s: &str) -> ByteRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn byte_range_grammar_round_trips() {
+        for s in ["0-9", "5-", "-4", "7-7", "50%-75%", "0%-100%"] {
+            assert_eq!(br(s).to_string(), s);
+        }
+        assert_eq!(br("last:4096"), ByteRange::Last(4096));
+        for bad in [
+            "", "-", "abc", "4", "9-3", "-0", "1-2-3", "last:0", "last:", "50%-101%", "75%-50%",
+            "10%-10%", "-5%-10%", "10%-20", "1.5%-2%",
+        ] {
+            assert!(bad.parse::<ByteRange>().is_err(), "{bad} should not parse");
+        }
+    }
+
+    /// What `spec` selects from `data`, written as a filter over byte
+    /// offsets rather than slice arithmetic. `None` when it is unsatisfiable.
+    fn reference(spec: ByteRange, data: &[u8]) -> Option<Vec<u8>> {
+        let len = data.len() as u64;
+        let pct = |p: u8| len * u64::from(p) / 100;
+        let (first, keep): (u64, Box<dyn Fn(u64) -> bool>) = match spec {
+            ByteRange::Span { start, end } => (start, Box::new(move |i| start <= i && i <= end)),
+            ByteRange::From(start) => (start, Box::new(move |i| start <= i)),
+            ByteRange::Last(n) => (0, Box::new(move |i| len - i <= n)),
+            ByteRange::Percent { start, end } => {
+                let (a, b) = (pct(start), pct(end));
+                (a, Box::new(move |i| a <= i && i < b))
+            }
+        };
+        if first >= len && !matches!(spec, ByteRange::Last(_)) {
+            return None;
+        }
+        Some(
+            (0..len)
+                .filter(|&i| keep(i))
+                .map(|i| data[i as usize])
+                .collect(),
+        )
+    }
+
+    fn any_byte_range() -> impl Strategy<Value = ByteRange> {
+        prop_oneof![
+            (0u64..300, 0u64..300).prop_map(|(a, b)| ByteRange::Span {
+                start: a.min(b),
+                end: a.max(b)
+            }),
+            (0u64..300).prop_map(ByteRange::From),
+            (1u64..300).prop_map(ByteRange::Last),
+            (0u8..100, 1u8..=100)
+                .prop_filter("END% above START%", |(a, b)| a < b)
+                .prop_map(|(start, end)| ByteRange::Percent { start, end }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn resolve_matches_the_reference(
+            spec in any_byte_range(),
+            data in proptest::collection::vec(any::<u8>(), 0..256),
+        ) {
+            let got = spec.resolve(data.len() as u64).ok().map(|r| data[r].to_vec());
+            prop_assert_eq!(got, reference(spec, &data));
+            // Every range printed back parses to itself.
+            prop_assert_eq!(spec.to_string().parse::<ByteRange>(), Ok(spec));
+        }
+    }
+
+    #[test]
+    fn byte_range_edge_cases() {
+        // Single byte, first and last.
+        assert_eq!(br("0-0").resolve(1).unwrap(), 0..1);
+        assert_eq!(br("99-99").resolve(100).unwrap(), 99..100);
+        // Start just past EOF, and any start on an empty resource.
+        assert!(br("100-100").resolve(100).is_err());
+        assert!(br("101-").resolve(100).is_err());
+        assert!(br("0-").resolve(0).is_err());
+        // An end at u64::MAX clamps rather than overflowing.
+        assert_eq!(
+            ByteRange::Span {
+                start: 3,
+                end: u64::MAX
+            }
+            .resolve(5)
+            .unwrap(),
+            3..5
+        );
+        // Percent offsets that round to the same byte select nothing.
+        assert_eq!(br("0%-1%").resolve(7).unwrap(), 0..0);
+    }
+
+    #[test]
+    fn byte_range_resolves_inclusive_and_clamps() {
+        assert_eq!(br("0-9").resolve(100).unwrap(), 0..10);
+        assert_eq!(br("90-200").resolve(100).unwrap(), 90..100);
+        assert_eq!(br("7-7").resolve(100).unwrap(), 7..8);
+        assert_eq!(br("40-").resolve(100).unwrap(), 40..100);
+        assert_eq!(br("-10").resolve(100).unwrap(), 90..100);
+        assert_eq!(br("-500").resolve(100).unwrap(), 0..100);
+        assert_eq!(br("-5").resolve(0).unwrap(), 0..0);
+        assert!(br("100-").resolve(100).is_err());
+        assert!(br("0-0").resolve(0).is_err());
+    }
+
+    #[test]
+    fn suffix_ranges_never_mean_the_first_bytes() {
+        for spec in ["-3", "last:3"] {
+            assert_eq!(br(spec), ByteRange::Last(3));
+            assert_eq!(br(spec).resolve(10).unwrap(), 7..10);
+            // Longer than the resource: all of it, not an error.
+            assert_eq!(br(spec).resolve(2).unwrap(), 0..2);
+            // An empty resource has no last bytes to give.
+            assert_eq!(br(spec).resolve(0).unwrap(), 0..0);
+        }
+        assert_eq!(ByteRange::Last(u64::MAX).resolve(5).unwrap(), 0..5);
+        for zero in ["-0", "last:0"] {
+            let err = zero.parse::<ByteRange>().unwrap_err();
+            assert!(err.contains("at least 1 byte"), "{err}");
+        }
+        // The first N bytes are spelled as a span.
+        assert_eq!(br("0-2").resolve(10).unwrap(), 0..3);
+    }
+
+    #[test]
+    fn percent_ranges_resolve_against_the_length() {
+        assert_eq!(br("50%-75%").resolve(100).unwrap(), 50..75);
+        assert_eq!(br("0%-100%").resolve(7).unwrap(), 0..7);
+        assert_eq!(br("0%-50%").resolve(7).unwrap(), 0..3);
+        assert_eq!(br("50%-100%").resolve(7).unwrap(), 3..7);
+        assert_eq!(br("last:10").resolve(100).unwrap(), 90..100);
+        assert!(br("0%-100%").resolve(0).is_err());
+    }
+
+    #[test]
+    fn line_range_selects_whole_lines() {
+        let text = b"one\ntwo\nthree\nfour";
+        let pick = |s: &str| {
+            let r = s.parse::<LineRange>().unwrap().resolve(text);
+            String::from_utf8(text[r].to_vec()).unwrap()
+        };
+        assert_eq!(pick("2:3"), "two\nthree\n");
+        assert_eq!(pick(":1"), "one\n");
+        assert_eq!(pick("3:"), "three\nfour");
+        assert_eq!(pick("4:9"), "four");
+        assert_eq!(pick("5:"), "");
+        assert!("0:2".parse::<LineRange>().is_err());
+        assert!("3:2".parse::<LineRange>().is_err());
+    }
+
+    #[test]
+    fn numbering_starts_at_the_range_start() {
+        assert_eq!(number_lines(b"a\nb\n", 9), b"     9\ta\n    10\tb\n");
+    }
+}
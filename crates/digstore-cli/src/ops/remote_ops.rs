@@ -7,8 +7,8 @@ use std::fs;
 
 use digstore_core::tombstone::TombstoneScope;
 use digstore_core::{
-    Bytes32, Bytes48, Bytes96, Decode, GenerationState, StoreConfig, Tombstone, Visibility,
-    MAX_STORE_BYTES,
+    Bytes32, Bytes48, Bytes96, Decode, GenerationState, HashAlgorithm, StoreConfig, Tombstone,
+    Visibility, MAX_STORE_BYTES,
 };
 use digstore_remote::wire::TombstoneEntry;
 use digstore_remote::{
@@ -456,9 +456,11 @@ pub async fn clone_from(
         // starts without them (displays fall back to the store id until refreshed).
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     };
     digstore_store::save_config(ctx.config_path(), &cfg)
-        .map_err(|e| CliError::Other(anyhow::anyhow!("save config: {e}")))?;
+        .map_err(|e| CliError::store("save config", e))?;
 
     // §12.2: the downloaded module trusts the ORIGIN's host key, which this clone
     // does not possess. To serve the module locally (the clone's `dig cat` drives
@@ -504,6 +506,7 @@ pub async fn push_to(ctx: &CliContext, ui: &Ui, store_url: &str) -> Result<Bytes
     let cfg = ctx.load_config()?;
     let root = store_ops::current_root(ctx)?
         .ok_or_else(|| CliError::NotFound("no committed root to push".into()))?;
+    store_ops::ensure_publishable(ctx, &root)?;
     let module_path = store_ops::module_path_for(ctx, &cfg.store_id, Some(root))?;
     let module = fs::read(&module_path).map_err(|e| CliError::Other(e.into()))?;
 
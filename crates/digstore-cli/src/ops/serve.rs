@@ -213,8 +213,8 @@ pub fn serve_content(
 /// This is the shared serve+decrypt path used by both `cat` and `compute_status`.
 /// It builds the canonical root-independent URN for `key`, drives the compiled
 /// module through the host runtime via [`serve_content`], verifies the merkle
-/// proof against `root`, and AES-256-GCM-opens the ciphertext using the store
-/// salt — exactly the steps `commands/cat.rs` performs.
+/// proof against `root`, and AES-256-GCM-opens the ciphertext using the salt
+/// of the generation's key epoch — the steps `commands/cat.rs` performs.
 pub fn read_resource_plaintext(
     ctx: &crate::context::CliContext,
     cfg: &digstore_core::StoreConfig,
@@ -222,11 +222,18 @@ pub fn read_resource_plaintext(
     key: &str,
 ) -> anyhow::Result<Vec<u8>> {
     let urn = store_ops::canonical_resource_urn(cfg.store_id, key);
+    let root =
+        &store_ops::generation_holding(ctx, root, key).map_err(|e| anyhow::anyhow!("{e}"))?;
     let module_path = store_ops::module_path_for(ctx, &cfg.store_id, Some(*root))
         .map_err(|e| anyhow::anyhow!("{e}"))?;
     let resp = serve_content(ctx, &module_path, &urn, *root).map_err(|e| anyhow::anyhow!("{e}"))?;
     let chunk_lens = store_ops::resource_chunk_lens(ctx, root, key).unwrap_or_default();
-    let salt: Option<[u8; 32]> = match &cfg.visibility {
+    // Sealed under the salt of the holding generation's key epoch, which a
+    // rotation since may have moved past.
+    let key_epoch = store_ops::load_generation_manifest(ctx, root)
+        .map_err(|e| anyhow::anyhow!("{e}"))?
+        .key_epoch;
+    let salt: Option<[u8; 32]> = match &cfg.epoch_visibility(key_epoch) {
         digstore_core::Visibility::Private(s) => Some(s.0),
         digstore_core::Visibility::Public => None,
     };
@@ -53,14 +53,17 @@ use std::path::{Path, PathBuf};
 
 use digstore_chunker::chunk_slice;
 use digstore_core::{
-    Bytes32, Bytes48, GenerationState, SecretSalt, StoreConfig, TrustedHostKey, Urn, Visibility,
-    CHAIN, MAX_STORE_BYTES,
+    Bytes32, Bytes48, GenerationState, HashAlgorithm, SecretSalt, StoreConfig, TrustedHostKey, Urn,
+    Visibility, CHAIN, MAX_STORE_BYTES,
 };
 use digstore_store::{GenerationManifest, RootHistory, StagingArea, Store, SystemClock};
+use indicatif::ProgressBar;
 
 use crate::context::CliContext;
 use crate::error::CliError;
-use crate::output::{DiffEntry, LogEntry, StatusView};
+use crate::ops::commit_note::CommitNote;
+use crate::output::{DiffEntry, FileDigest, LogEntry, StatusDetail, StatusView};
+use crate::ui::BarSink;
 
 /// The canonical root-INDEPENDENT URN for a resource (used for both the
 /// retrieval key and the AES key). Re-exported from the shared stage→compile
@@ -127,11 +130,12 @@ pub fn init_store(
         visibility,
         label,
         description,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     };
 
     // Real store init: writes config.toml + the §4.4 directory tree + staging + roots.log.
-    Store::init(cfg.clone(), SystemClock)
-        .map_err(|e| CliError::Other(anyhow::anyhow!("store init: {e}")))?;
+    Store::init(cfg.clone(), SystemClock).map_err(|e| CliError::store("store init", e))?;
 
     // Persist the host signing key SEED (never embedded in modules). The BLS
     // SecretKey is not extractable, so we persist the deterministic seed and
@@ -230,11 +234,12 @@ pub fn adopt_existing_store(
         // on-chain metadata via the singleton update).
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     };
 
     // Real store scaffold: config.toml + §4.4 tree + staging + roots.log.
-    Store::init(cfg.clone(), SystemClock)
-        .map_err(|e| CliError::Other(anyhow::anyhow!("store init: {e}")))?;
+    Store::init(cfg.clone(), SystemClock).map_err(|e| CliError::store("store init", e))?;
 
     // Persist the PROVIDED publisher signing seed (same on-disk shape as `init`).
     write_secret_file(&ctx.dig_dir.join("signing_key.bin"), signing_seed)
@@ -359,10 +364,10 @@ pub fn add_path(ctx: &CliContext, path: &Path, key: Option<String>) -> Result<Ad
     // Enforce StoreConfig.max_size (§20.2).
     if cfg.max_size != 0 {
         let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-            .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+            .map_err(|e| CliError::store("load staging", e))?;
         let already: u64 = staging
             .records()
-            .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+            .map_err(|e| CliError::store("read staging", e))?
             .iter()
             .map(|r| r.content.len() as u64)
             .sum();
@@ -375,7 +380,7 @@ pub fn add_path(ctx: &CliContext, path: &Path, key: Option<String>) -> Result<Ad
         }
         staging
             .append(&resource_key, &data)
-            .map_err(|e| CliError::Other(anyhow::anyhow!("stage: {e}")))?;
+            .map_err(|e| CliError::store("stage", e))?;
     }
 
     let chunk_count = chunk_slice(&data, &digstore_stage::chunker_config())
@@ -397,6 +402,15 @@ pub struct AddOutcome {
     pub limit_bytes: u64,
 }
 
+/// A step of [`add_files_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddProgress {
+    /// The walk visited one more path.
+    Found,
+    /// `done` of `total` resolved files have been read.
+    Read { done: u64, total: u64 },
+}
+
 /// Resolve `paths`/`all` and stage each file under its store-root-relative key.
 pub fn add_files(
     ctx: &CliContext,
@@ -404,6 +418,22 @@ pub fn add_files(
     all: bool,
     dry_run: bool,
     key: Option<String>,
+) -> Result<AddOutcome, CliError> {
+    add_files_with_progress(ctx, paths, all, dry_run, key, false, &|_| {})
+}
+
+/// [`add_files`], reporting the walk and the file reads to `on_progress`.
+/// The walk runs on several threads, so `on_progress` may be called from any
+/// of them; files are then read by a small worker pool. Unless `force`, the
+/// store's volume must have room for the new staged bytes.
+pub fn add_files_with_progress(
+    ctx: &CliContext,
+    paths: &[PathBuf],
+    all: bool,
+    dry_run: bool,
+    key: Option<String>,
+    force: bool,
+    on_progress: &(dyn Fn(AddProgress) + Sync),
 ) -> Result<AddOutcome, CliError> {
     use crate::ops::walk::{self, Resolved};
 
@@ -416,13 +446,14 @@ pub fn add_files(
     let skip = ctx.workspace_dir.clone();
 
     // Resolve the file set.
+    let on_entry = |_: &Path| on_progress(AddProgress::Found);
     let mut resolved: Vec<Resolved> = Vec::new();
     if all {
-        resolved = walk::resolve_all(&root, &skip);
+        resolved = walk::resolve_all_with_progress(&root, &skip, &on_entry);
     } else {
         for p in paths {
             let arg = p.to_string_lossy();
-            walk::resolve_arg(&root, &skip, &arg, &mut resolved)
+            walk::resolve_arg_with_progress(&root, &skip, &arg, &mut resolved, &on_entry)
                 .map_err(CliError::InvalidArgument)?;
         }
     }
@@ -437,10 +468,10 @@ pub fn add_files(
     }
 
     let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     let already: HashMap<String, Vec<u8>> = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+        .map_err(|e| CliError::store("read staging", e))?
         .into_iter()
         .map(|r| (r.resource_key, r.content))
         .collect();
@@ -453,8 +484,10 @@ pub fn add_files(
     }
     let mut incoming: Vec<Incoming> = Vec::new();
     let mut unchanged = 0usize;
-    for r in resolved {
-        let data = fs::read(&r.path).map_err(|e| CliError::Other(e.into()))?;
+    let contents = read_files(&resolved, &mut |done, total| {
+        on_progress(AddProgress::Read { done, total })
+    })?;
+    for (r, data) in resolved.into_iter().zip(contents) {
         let effective_key = key.clone().unwrap_or_else(|| r.key.clone());
         if already
             .get(&effective_key)
@@ -496,11 +529,14 @@ pub fn add_files(
         limit_bytes: cap,
     };
     if !dry_run {
+        if !force {
+            check_free_space(ctx, incoming_bytes)?;
+        }
         for i in incoming {
             let size = i.data.len() as u64;
             staging
                 .append(&i.key, &i.data)
-                .map_err(|e| CliError::Other(anyhow::anyhow!("stage: {e}")))?;
+                .map_err(|e| CliError::store("stage", e))?;
             outcome.staged.push((i.key, size));
         }
     } else {
@@ -512,6 +548,180 @@ pub fn add_files(
     Ok(outcome)
 }
 
+/// Read every resolved file, in order, on a pool of worker threads. Workers
+/// hand contents back over a bounded channel, so a slow consumer throttles
+/// them; the first read error is returned once the pool has wound down.
+fn read_files(
+    resolved: &[crate::ops::walk::Resolved],
+    on_read: &mut dyn FnMut(u64, u64),
+) -> Result<Vec<Vec<u8>>, CliError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let total = resolved.len();
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .clamp(1, 8)
+        .min(total.max(1));
+    let next = AtomicUsize::new(0);
+    let mut slots: Vec<Option<Vec<u8>>> = vec![None; total];
+    std::thread::scope(|scope| {
+        let (tx, rx) = std::sync::mpsc::sync_channel(workers * 4);
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(r) = resolved.get(i) else { break };
+                if tx.send((i, fs::read(&r.path))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+        for (done, (i, read)) in rx.into_iter().enumerate() {
+            let path = &resolved[i].path;
+            let data =
+                read.map_err(|e| CliError::Other(anyhow::anyhow!("read {}: {e}", path.display())))?;
+            slots[i] = Some(data);
+            on_read(done as u64 + 1, total as u64);
+        }
+        Ok::<_, CliError>(())
+    })?;
+    Ok(slots.into_iter().flatten().collect())
+}
+
+/// Stage everything `reader` yields under resource key `key` (`add --stdin-as`),
+/// streaming it into staging without buffering it whole. The store's cap is
+/// enforced while copying: past it, the partial entry is rolled back. With
+/// `dry_run` the input is only counted.
+pub fn add_reader(
+    ctx: &CliContext,
+    key: &str,
+    reader: &mut dyn std::io::Read,
+    dry_run: bool,
+) -> Result<AddOutcome, CliError> {
+    let key = key.trim_start_matches("./");
+    if key.is_empty() || key.starts_with('/') || key.split(['/', '\\']).any(|seg| seg == "..") {
+        return Err(CliError::InvalidArgument(format!(
+            "'{key}' is not a relative path inside the store"
+        )));
+    }
+    let cfg = ctx.load_config()?;
+    let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
+        .map_err(|e| CliError::store("load staging", e))?;
+    let base_bytes: u64 = staging
+        .records()
+        .map_err(|e| CliError::store("read staging", e))?
+        .iter()
+        .filter(|r| r.resource_key != key)
+        .map(|r| r.content.len() as u64)
+        .sum();
+    let cap = cap_of(cfg.max_size);
+    let mut capped = CappedReader {
+        inner: reader,
+        left: cap.saturating_sub(base_bytes),
+        over: false,
+    };
+    let copied = if dry_run {
+        std::io::copy(&mut capped, &mut std::io::sink()).map_err(anyhow::Error::from)
+    } else {
+        staging
+            .append_reader(key, &mut capped)
+            .map_err(anyhow::Error::from)
+    };
+    let size = match copied {
+        Ok(size) => size,
+        Err(_) if capped.over => {
+            let store = ctx.store_name.clone().unwrap_or_else(|| "this".into());
+            return Err(CliError::InvalidArgument(format!(
+                "stdin is over the {} store's {} MB limit ({} MB free); nothing was staged",
+                store,
+                mb(cap),
+                mb(cap.saturating_sub(base_bytes))
+            )));
+        }
+        Err(e) => return Err(CliError::Other(e.context("stage stdin"))),
+    };
+    Ok(AddOutcome {
+        staged: vec![(key.to_string(), size)],
+        unchanged: 0,
+        dry_run,
+        staged_bytes: base_bytes + size,
+        limit_bytes: cap,
+    })
+}
+
+/// Stage the regular files of a tar / .tar.gz stream (`add --tar`) under their
+/// in-archive paths. The whole archive is staged or none of it: a malformed
+/// archive, an escaping path, or content past the store's cap stages nothing.
+/// Returns the entries that were skipped (links, devices) alongside.
+pub fn add_tar(
+    ctx: &CliContext,
+    reader: &mut dyn std::io::Read,
+) -> Result<(AddOutcome, Vec<digstore_store::TarSkipped>), CliError> {
+    let cfg = ctx.load_config()?;
+    let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
+        .map_err(|e| CliError::store("load staging", e))?;
+    let staged_total = |staging: &StagingArea| -> Result<u64, CliError> {
+        Ok(staging
+            .records()
+            .map_err(|e| CliError::store("read staging", e))?
+            .iter()
+            .map(|r| r.content.len() as u64)
+            .sum())
+    };
+    let base_bytes = staged_total(&staging)?;
+    let cap = cap_of(cfg.max_size);
+    let import = match staging.append_tar(reader, cap.saturating_sub(base_bytes)) {
+        Ok(import) => import,
+        Err(digstore_store::StoreError::TarTooLarge { .. }) => {
+            let store = ctx.store_name.clone().unwrap_or_else(|| "this".into());
+            return Err(CliError::InvalidArgument(format!(
+                "the archive is over the {} store's {} MB limit ({} MB free); nothing was staged",
+                store,
+                mb(cap),
+                mb(cap.saturating_sub(base_bytes))
+            )));
+        }
+        Err(
+            e @ (digstore_store::StoreError::InvalidTar(_)
+            | digstore_store::StoreError::PathEscape(_)),
+        ) => {
+            return Err(CliError::InvalidArgument(format!(
+                "{e}; nothing was staged"
+            )))
+        }
+        Err(e) => return Err(CliError::store("stage archive", e)),
+    };
+    let outcome = AddOutcome {
+        staged: import.staged,
+        unchanged: 0,
+        dry_run: false,
+        staged_bytes: staged_total(&staging)?,
+        limit_bytes: cap,
+    };
+    Ok((outcome, import.skipped))
+}
+
+/// A reader that fails once more than `left` bytes have come through.
+struct CappedReader<'a> {
+    inner: &'a mut dyn std::io::Read,
+    left: u64,
+    over: bool,
+}
+
+impl std::io::Read for CappedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n as u64 > self.left {
+            self.over = true;
+            return Err(std::io::Error::other("over the store's size limit"));
+        }
+        self.left -= n as u64;
+        Ok(n)
+    }
+}
+
 /// Decimal MB, one decimal place.
 fn mb(bytes: u64) -> String {
     format!("{:.1}", bytes as f64 / 1_000_000.0)
@@ -531,14 +741,14 @@ fn cap_of(max_size: u64) -> u64 {
 pub fn clear_staging(ctx: &CliContext) -> Result<usize, CliError> {
     let cfg = ctx.load_config()?;
     let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     let n = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+        .map_err(|e| CliError::store("read staging", e))?
         .len();
     staging
         .clear()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("clear staging: {e}")))?;
+        .map_err(|e| CliError::store("clear staging", e))?;
     Ok(n)
 }
 
@@ -548,10 +758,10 @@ pub fn clear_staging(ctx: &CliContext) -> Result<usize, CliError> {
 pub fn list_staged(ctx: &CliContext) -> Result<(Vec<(String, u64)>, u64, u64), CliError> {
     let cfg = ctx.load_config()?;
     let staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     let mut entries: Vec<(String, u64)> = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+        .map_err(|e| CliError::store("read staging", e))?
         .into_iter()
         .map(|r| (r.resource_key, r.content.len() as u64))
         .collect();
@@ -561,6 +771,176 @@ pub fn list_staged(ctx: &CliContext) -> Result<(Vec<(String, u64)>, u64, u64), C
     Ok((entries, total, cap))
 }
 
+/// Take the store's writer lock for the rest of the command, first breaking
+/// a stale one when `force_unlock` is set, then roll back a commit an earlier
+/// run left half-written (see `Store::recover`). `None` when there is no
+/// store yet (the command reports that itself). Another process holding the
+/// lock is `StoreLocked`, naming its PID.
+pub fn lock_store(
+    ctx: &CliContext,
+    force_unlock: bool,
+) -> Result<Option<digstore_store::StoreLock>, CliError> {
+    if !ctx.dig_dir.join("config.toml").exists() {
+        return Ok(None);
+    }
+    if force_unlock {
+        digstore_store::StoreLock::force_unlock(&ctx.dig_dir)
+            .map_err(|e| CliError::store("unlock store", e))?;
+    }
+    let lock = digstore_store::StoreLock::acquire(&ctx.dig_dir)
+        .map_err(|e| CliError::store("lock store", e))?;
+    Store::recover_at(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("recover store", e))?;
+    Ok(Some(lock))
+}
+
+/// Fail with [`CliError::InsufficientSpace`] when the volume holding the store
+/// has fewer than `need` bytes free, so an add or commit stops before writing
+/// anything instead of dying halfway with an IO error. A volume whose free
+/// space cannot be read is not checked.
+pub fn check_free_space(ctx: &CliContext, need: u64) -> Result<(), CliError> {
+    let Some(existing) = ctx.dig_dir.ancestors().find(|d| d.exists()) else {
+        return Ok(());
+    };
+    match fs4::available_space(existing) {
+        Ok(have) => space_verdict(need, have, existing),
+        Err(_) => Ok(()),
+    }
+}
+
+fn space_verdict(need: u64, have: u64, path: &Path) -> Result<(), CliError> {
+    if have < need {
+        return Err(CliError::InsufficientSpace {
+            need,
+            have,
+            path: path.display().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Bytes a commit matching `estimate` writes: the new chunk files, plus the
+/// compiled module, whose data section carries every chunk of the version.
+pub fn commit_space_needed(estimate: &digstore_store::CommitEstimate) -> u64 {
+    let chunk_files = estimate.new_bytes;
+    // The module embeds every chunk of the version, new and reused alike.
+    let module = estimate.new_bytes + estimate.reused_bytes;
+    chunk_files + module
+}
+
+/// What committing the staging area would write (see
+/// [`Store::estimate_commit_size`]).
+pub fn estimate_commit_size(ctx: &CliContext) -> Result<digstore_store::CommitEstimate, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .estimate_commit_size()
+        .map_err(|e| CliError::store("estimate", e))
+}
+
+/// Every file in the history that `hash` identifies (see
+/// [`Store::find_files_by_hash`]).
+pub fn find_files_by_hash(
+    ctx: &CliContext,
+    hash: Bytes32,
+) -> Result<Vec<digstore_store::HashMatch>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .find_files_by_hash(hash)
+        .map_err(|e| CliError::store("whereis", e))
+}
+
+/// The versions where file `path` changed (see [`Store::get_file_versions`]).
+pub fn file_versions(
+    ctx: &CliContext,
+    path: &str,
+) -> Result<Vec<digstore_store::FileVersion>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .get_file_versions(path)
+        .map_err(|e| CliError::store("file-history", e))
+}
+
+/// Each line of text file `path` with the version that introduced it (see
+/// [`Store::blame`]).
+pub fn blame(ctx: &CliContext, path: &str) -> Result<Vec<digstore_store::BlameLine>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.blame(path).map_err(|e| CliError::store("blame", e))
+}
+
+/// Every distinct version of file `path` with its content (see
+/// [`Store::read_file_versions`]).
+pub fn read_file_versions(
+    ctx: &CliContext,
+    path: &str,
+) -> Result<Vec<(digstore_store::FileVersion, Vec<u8>)>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .read_file_versions(path)
+        .map_err(|e| CliError::store("read file versions", e))
+}
+
+/// Per-version and on-disk storage use (see [`Store::size_report`]).
+pub fn size_report(ctx: &CliContext) -> Result<digstore_store::SizeReport, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.size_report().map_err(|e| CliError::store("size", e))
+}
+
+/// Chunk-size distribution of version `root`, or of every version (see
+/// [`Store::chunk_size_distribution`]).
+pub fn chunk_size_distribution(
+    ctx: &CliContext,
+    root: Option<Bytes32>,
+) -> Result<digstore_store::ChunkSizeDistribution, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.chunk_size_distribution(root).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(r) => {
+            CliError::NotFound(format!("version {r} not found"))
+        }
+        e => CliError::store("size", e),
+    })
+}
+
+/// Write version `root` to `writer` as a tar stream (see [`Store::export_tar`]),
+/// advancing `progress` by bytes. Returns the number of files written.
+pub fn export_tar(
+    ctx: &CliContext,
+    root: Bytes32,
+    writer: &mut dyn std::io::Write,
+    progress: Option<&ProgressBar>,
+) -> Result<usize, CliError> {
+    let mut store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.set_progress_sink(progress.map(|pb| {
+        Box::new(BarSink {
+            pb: pb.clone(),
+            files: false,
+        }) as Box<dyn digstore_store::ProgressSink>
+    }));
+    store.export_tar(root, writer).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(_) => {
+            CliError::NotFound(format!("version {} not found", root.to_hex()))
+        }
+        e => CliError::store("export tar", e),
+    })
+}
+
+/// What committing the staging area would change against the current root
+/// (see [`Store::staged_diff_summary`]).
+pub fn staged_diff_summary(ctx: &CliContext) -> Result<digstore_store::DiffSummary, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .staged_diff_summary()
+        .map_err(|e| CliError::store("staged diff", e))
+}
+
 /// A previewed URN for a resource that would be staged from `op_dir`.
 #[derive(Debug, Clone)]
 pub struct UrnPreview {
@@ -641,13 +1021,13 @@ pub fn stage_discovery_manifest(
 
     let cfg = ctx.load_config()?;
     let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
 
     // Publisher-elected resources = everything staged so far, except the
     // discovery manifest itself (so re-staging is idempotent in content).
     let mut entries: Vec<DiscoveryEntry> = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+        .map_err(|e| CliError::store("read staging", e))?
         .into_iter()
         .map(|r| r.resource_key)
         .filter(|k| k != DISCOVERY_RESOURCE_KEY)
@@ -668,7 +1048,7 @@ pub fn stage_discovery_manifest(
     if cfg.max_size != 0 {
         let already: u64 = staging
             .records()
-            .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+            .map_err(|e| CliError::store("read staging", e))?
             .iter()
             .filter(|r| r.resource_key != DISCOVERY_RESOURCE_KEY)
             .map(|r| r.content.len() as u64)
@@ -682,7 +1062,7 @@ pub fn stage_discovery_manifest(
 
     staging
         .append(DISCOVERY_RESOURCE_KEY, &body)
-        .map_err(|e| CliError::Other(anyhow::anyhow!("stage discovery: {e}")))?;
+        .map_err(|e| CliError::store("stage discovery", e))?;
     Ok(manifest)
 }
 
@@ -711,10 +1091,10 @@ pub fn read_discovery_manifest(
 pub fn status(ctx: &CliContext) -> Result<StatusView, CliError> {
     let cfg = ctx.load_config()?;
     let staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     let records = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?;
+        .map_err(|e| CliError::store("read staging", e))?;
     let staged_bytes: u64 = records.iter().map(|r| r.content.len() as u64).sum();
     let staged = records.into_iter().map(|r| r.resource_key).collect();
     let root = current_root(ctx)?.map(|r| r.to_hex());
@@ -724,8 +1104,12 @@ pub fn status(ctx: &CliContext) -> Result<StatusView, CliError> {
         staged,
         modified: Vec::new(),
         untracked: Vec::new(),
+        stale: Vec::new(),
+        missing: Vec::new(),
         staged_bytes,
         limit_bytes,
+        ignored: None,
+        detail: Default::default(),
     })
 }
 
@@ -748,7 +1132,7 @@ pub fn compute_status(ctx: &CliContext) -> Result<StatusView, CliError> {
         match StagingArea::open(ctx.staging_path(&cfg.store_id)) {
             Ok(s) => s
                 .records()
-                .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?
+                .map_err(|e| CliError::store("read staging", e))?
                 .into_iter()
                 .map(|r| (r.resource_key, r.content))
                 .collect(),
@@ -777,14 +1161,62 @@ pub fn compute_status(ctx: &CliContext) -> Result<StatusView, CliError> {
     modified.sort();
     untracked.sort();
 
+    // Staging drift: the staging area holds a snapshot of each file's bytes, so
+    // an edit or delete after `add` goes unnoticed until commit. A staged key
+    // with no working file of its own whose bytes still match some working file
+    // was staged under an alias (`add --key`) and is not drift.
+    let mut stale = Vec::new();
+    let mut missing = Vec::new();
+    for (key, staged) in &staged_map {
+        if key == crate::ops::discovery::DISCOVERY_RESOURCE_KEY {
+            continue;
+        }
+        // An explicitly added file can be ignored, so fall back to the disk.
+        let on_disk = match working.get(key) {
+            Some(content) => Some(content.clone()),
+            None => fs::read(root_dir.join(key)).ok(),
+        };
+        match on_disk {
+            Some(content) if content != *staged => stale.push(key.clone()),
+            Some(_) => {}
+            None if working.values().any(|c| c == staged) => {}
+            None => missing.push(key.clone()),
+        }
+    }
+
+    let committed_keys: std::collections::BTreeSet<String> = match &current {
+        Some(root) => list_generation_resources(ctx, root)?.into_iter().collect(),
+        None => Default::default(),
+    };
+    let detail = StatusDetail {
+        staged: staged_map
+            .iter()
+            .map(|(k, c)| (k.clone(), FileDigest::of(c)))
+            .collect(),
+        committed: staged_map
+            .keys()
+            .filter(|k| committed_keys.contains(*k))
+            .cloned()
+            .collect(),
+        working: modified
+            .iter()
+            .chain(&untracked)
+            .map(|k| (k.clone(), FileDigest::of(&working[k])))
+            .collect(),
+    };
+
     let limit_bytes = cap_of(cfg.max_size);
     Ok(StatusView {
         root: current.map(|r| r.to_hex()),
         staged: staged_keys,
         modified,
         untracked,
+        stale,
+        missing,
         staged_bytes,
         limit_bytes,
+        ignored: None,
+        detail,
     })
 }
 
@@ -817,6 +1249,8 @@ fn committed_content(
 #[derive(Debug)]
 pub struct CommitOutcome {
     pub roothash: Bytes32,
+    /// Id of the new history entry.
+    pub generation: u64,
     pub output_path: PathBuf,
     pub output_size: u64,
 }
@@ -840,11 +1274,17 @@ pub use digstore_stage::PreparedCommit;
 /// `commands::commit`), so local history never advances past the chain.
 pub fn commit(
     ctx: &CliContext,
-    _message: Option<String>,
+    message: Option<String>,
     metadata: digstore_core::MetadataManifest,
 ) -> Result<CommitOutcome, CliError> {
     let prepared = stage_to_root(ctx)?;
-    finalize_commit(ctx, prepared, None, metadata)
+    let outcome = finalize_commit(ctx, prepared, None, metadata)?;
+    CommitNote {
+        message,
+        ..Default::default()
+    }
+    .save(&ctx.dig_dir, outcome.generation)?;
+    Ok(outcome)
 }
 
 /// Local commit of PRE-ENCRYPTED staged content: each staged resource is already sealed under its
@@ -874,7 +1314,7 @@ pub fn stage_to_root(ctx: &CliContext) -> Result<PreparedCommit, CliError> {
 /// reject it. Reuses the exact same build as a real commit, so the previewed root
 /// is the one a commit would produce.
 pub fn staged_root_or_noop(ctx: &CliContext) -> Result<(Bytes32, bool), CliError> {
-    let prepared = build_prepared(ctx, false)?;
+    let prepared = build_prepared(ctx, false, &mut |_, _| {})?;
     let is_noop = current_root(ctx)? == Some(prepared.root);
     Ok((prepared.root, is_noop))
 }
@@ -888,7 +1328,17 @@ pub fn stage_to_root_with(
     ctx: &CliContext,
     pre_encrypted: bool,
 ) -> Result<PreparedCommit, CliError> {
-    let prepared = build_prepared(ctx, pre_encrypted)?;
+    stage_to_root_with_progress(ctx, pre_encrypted, &mut |_, _| {})
+}
+
+/// [`stage_to_root_with`], reporting `(done, total)` staged plaintext bytes to
+/// `on_progress` as chunks are sealed. Drives `digstore commit`'s progress bar.
+pub fn stage_to_root_with_progress(
+    ctx: &CliContext,
+    pre_encrypted: bool,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<PreparedCommit, CliError> {
+    let prepared = build_prepared(ctx, pre_encrypted, on_progress)?;
 
     // Refuse a no-op commit. Committing clears staging, so re-`add`ing identical
     // content re-stages it and produces the SAME root as the current head. Without
@@ -906,6 +1356,55 @@ pub fn stage_to_root_with(
     Ok(prepared)
 }
 
+/// `commit --allow-empty`: [`stage_to_root_with_progress`] without the no-op
+/// guard. `None` when the commit is a checkpoint of the head (nothing staged,
+/// or staged content that reproduces the head's root), which
+/// [`record_checkpoint`] records without sealing anything.
+pub fn stage_to_root_allow_empty(
+    ctx: &CliContext,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<Option<PreparedCommit>, CliError> {
+    let head = current_root(ctx)?;
+    if head.is_some() && list_staged(ctx)?.0.is_empty() {
+        return Ok(None);
+    }
+    let prepared = build_prepared(ctx, false, on_progress)?;
+    Ok(Some(prepared).filter(|p| head != Some(p.root)))
+}
+
+/// Record a checkpoint of the head (`commit --allow-empty` with nothing
+/// new): a history entry for the head's root at `timestamp` (default now),
+/// and nothing else. The head's generation and module are left as they are,
+/// and whatever was staged (content identical to the head's) is cleared.
+/// Returns the new entry.
+pub fn record_checkpoint(
+    ctx: &CliContext,
+    timestamp: Option<u64>,
+) -> Result<GenerationState, CliError> {
+    let cfg = ctx.load_config()?;
+    let root = current_root(ctx)?.ok_or_else(|| {
+        CliError::InvalidArgument(
+            "nothing staged to commit; run `digstore add <paths>` to stage files first".into(),
+        )
+    })?;
+    let mut history =
+        RootHistory::open(ctx.history_path()).map_err(|e| CliError::store("history open", e))?;
+    let entry = GenerationState {
+        id: history
+            .next_id()
+            .map_err(|e| CliError::store("history", e))?,
+        root,
+        timestamp: timestamp.unwrap_or_else(current_time),
+    };
+    history
+        .append(&entry)
+        .map_err(|e| CliError::store("history append", e))?;
+    StagingArea::open(ctx.staging_path(&cfg.store_id))
+        .and_then(|mut staging| staging.clear())
+        .map_err(|e| CliError::store("clear staging", e))?;
+    Ok(entry)
+}
+
 /// Build the [`PreparedCommit`] (encrypt chunks, build the merkle tree, compute
 /// the root) from staging WITHOUT applying the no-op guard. Shared by
 /// [`stage_to_root_with`] (which adds the guard) and [`staged_root_or_noop`]
@@ -918,39 +1417,64 @@ pub fn stage_to_root_with(
 /// in-process node uses. The error wording (empty/over-cap) is preserved
 /// CLI-side so the staged-content guidance ("digstore add"/"digstore unstage")
 /// is unchanged.
-fn build_prepared(ctx: &CliContext, pre_encrypted: bool) -> Result<PreparedCommit, CliError> {
+fn build_prepared(
+    ctx: &CliContext,
+    pre_encrypted: bool,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<PreparedCommit, CliError> {
     let cfg = ctx.load_config()?;
+    // Served modules and the browser verifier check SHA-256 leaves and nodes,
+    // so a store initialized with another hash can't be compiled or anchored.
+    if cfg.hash_algorithm != HashAlgorithm::Sha256 {
+        return Err(CliError::InvalidArgument(format!(
+            "this store uses {} content hashing; only sha256 stores can be published",
+            cfg.hash_algorithm
+        )));
+    }
 
     let staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     let records = staging
         .records()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read staging: {e}")))?;
+        .map_err(|e| CliError::store("read staging", e))?;
+    let head = current_root(ctx)?;
+    // Seal under the newest salt epoch, as `Store::commit` does: the config's,
+    // or the head's when a rotation predates the keyring.
+    let key_epoch = match head {
+        Some(head) => load_generation_manifest(ctx, &head)?.key_epoch,
+        None => 0,
+    }
+    .max(cfg.key_epoch());
     if records.is_empty() {
         return Err(CliError::InvalidArgument(
             "nothing staged to commit; run `digstore add <paths>` to stage files first".into(),
         ));
     }
+    let mut files: Vec<(String, Vec<u8>)> = records
+        .into_iter()
+        .map(|r| (r.resource_key, r.content))
+        .collect();
+    // Staging keeps first-add order. The root is order-independent, but the
+    // chunk pool and key table are not, so seal in key order to make the
+    // generation manifest independent of the order files were added in.
+    files.sort_by(|a, b| a.0.cmp(&b.0));
 
     let next_id = RootHistory::open(ctx.history_path())
         .and_then(|h| h.next_id())
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history: {e}")))?;
+        .map_err(|e| CliError::store("history", e))?;
     let timestamp = current_time();
 
-    let files: Vec<(String, Vec<u8>)> = records
-        .into_iter()
-        .map(|r| (r.resource_key, r.content))
-        .collect();
-
-    digstore_stage::build_prepared(
+    digstore_stage::build_prepared_with_progress(
         &files,
         cfg.store_id,
-        &cfg.visibility,
+        &cfg.epoch_visibility(key_epoch),
         cfg.max_size,
         pre_encrypted,
         next_id,
         timestamp,
+        on_progress,
     )
+    .map(|prepared| prepared.with_key_epoch(key_epoch))
     .map_err(|e| match e {
         // Map the engine's stable error variants back to the CLI's exact wording
         // so the staged-content guidance (unstage/add) and over-cap message are
@@ -983,6 +1507,16 @@ pub fn finalize_commit(
 ) -> Result<CommitOutcome, CliError> {
     let cfg = ctx.load_config()?;
 
+    // Until the history entry lands, `Store::recover` (run when the next
+    // writing command takes the lock, see [`lock_store`]) treats this
+    // generation as partial and removes it.
+    let pending_id = RootHistory::open(ctx.history_path())
+        .and_then(|h| h.next_id())
+        .map_err(|e| CliError::store("history", e))?;
+    let marker = digstore_store::StorePaths::new(&ctx.dig_dir, cfg.store_id).pending_commit_file();
+    fs::write(&marker, format!("{} {pending_id}", prepared.root.to_hex()))
+        .map_err(|e| CliError::Other(e.into()))?;
+
     // Persist the generation (chunk bodies + manifest) and compile the serving
     // module via the SHARED stage→compile engine ([`digstore_stage::finalize`]) —
     // the SAME engine the in-process node uses (no fork). The engine writes
@@ -1007,15 +1541,22 @@ pub fn finalize_commit(
     let manifest = &compiled.manifest;
 
     // Append history.
-    let mut history = RootHistory::open(ctx.history_path())
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history open: {e}")))?;
+    let mut history =
+        RootHistory::open(ctx.history_path()).map_err(|e| CliError::store("history open", e))?;
     history
         .append(&GenerationState {
             id: next_id,
             root,
             timestamp,
         })
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history append: {e}")))?;
+        .map_err(|e| CliError::store("history append", e))?;
+    // Once branches exist, the commit advances the current one.
+    let refs = branch_refs(ctx);
+    if refs.exists() {
+        refs.current()
+            .and_then(|branch| refs.set(&branch, root))
+            .map_err(|e| CliError::store("branch update", e))?;
+    }
 
     // Local URN manifest (§6.1): the publisher's index of shareable URNs. Local
     // only — not embedded, not pushed. Root-pinned URN, rootless retrieval key.
@@ -1064,6 +1605,7 @@ pub fn finalize_commit(
         fs::write(ctx.dig_dir.join("urns.json"), json).map_err(|e| CliError::Other(e.into()))?;
         fs::write(ctx.dig_dir.join("urns.txt"), txt).map_err(|e| CliError::Other(e.into()))?;
     }
+    fs::remove_file(&marker).map_err(|e| CliError::Other(e.into()))?;
 
     // The shared engine already compiled the module to `<dig_dir>/modules/…`.
     let output_path = compiled.module_path.clone();
@@ -1071,13 +1613,14 @@ pub fn finalize_commit(
 
     // Clear staging.
     let mut staging = StagingArea::open(ctx.staging_path(&cfg.store_id))
-        .map_err(|e| CliError::Other(anyhow::anyhow!("load staging: {e}")))?;
+        .map_err(|e| CliError::store("load staging", e))?;
     staging
         .clear()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("clear staging: {e}")))?;
+        .map_err(|e| CliError::store("clear staging", e))?;
 
     Ok(CommitOutcome {
         roothash: root,
+        generation: next_id,
         output_path,
         output_size,
     })
@@ -1093,28 +1636,156 @@ pub fn read_module_chain_state(
         .map_err(|e| CliError::Other(anyhow::anyhow!("decode chain state: {e:?}")))
 }
 
-pub fn log(ctx: &CliContext, limit: Option<usize>) -> Result<Vec<LogEntry>, CliError> {
-    let states = read_history(ctx)?;
-    let mut states = states;
+/// Which deployments `log` lists. Every set predicate must hold; `limit`
+/// applies last.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub limit: Option<usize>,
+    /// Substring of the recorded author.
+    pub author: Option<String>,
+    /// Regex over the recorded commit message.
+    pub grep: Option<regex::Regex>,
+    /// Inclusive lower bound on the date (Unix seconds): the `commit --date`
+    /// when recorded, else the timestamp.
+    pub since: Option<u64>,
+    /// Inclusive upper bound on the date (Unix seconds).
+    pub until: Option<u64>,
+}
+
+impl LogFilter {
+    fn matches(&self, e: &LogEntry) -> bool {
+        let text = |field: &Option<String>| field.clone().unwrap_or_default();
+        // A recorded `commit --date` is the date the deployment claims.
+        let when = e
+            .date
+            .as_deref()
+            .and_then(|d| crate::ops::date::parse_date(d).ok())
+            .unwrap_or(e.timestamp);
+        self.since.is_none_or(|t| when >= t)
+            && self.until.is_none_or(|t| when <= t)
+            && self
+                .author
+                .as_ref()
+                .is_none_or(|a| text(&e.author).contains(a.as_str()))
+            && self
+                .grep
+                .as_ref()
+                .is_none_or(|re| re.is_match(&text(&e.message)))
+    }
+}
+
+pub fn log(ctx: &CliContext, filter: &LogFilter) -> Result<Vec<LogEntry>, CliError> {
+    let mut states = read_history(ctx)?;
     states.sort_by(|a, b| b.id.cmp(&a.id));
-    let iter = states.into_iter().map(|s| LogEntry {
-        id: s.id,
-        root: s.root.to_hex(),
-        timestamp: s.timestamp,
-    });
-    Ok(match limit {
-        Some(n) => iter.take(n).collect(),
-        None => iter.collect(),
+    let entries = states
+        .into_iter()
+        .map(|s| {
+            let note = CommitNote::load(&ctx.dig_dir, s.id)?.unwrap_or_default();
+            // A generation missing locally (e.g. squashed away) still logs.
+            let files = match load_generation_manifest(ctx, &s.root) {
+                Ok(m) => Some(m.key_table.len()),
+                Err(CliError::NotFound(_)) => None,
+                Err(e) => return Err(e),
+            };
+            Ok(LogEntry {
+                id: s.id,
+                root: s.root.to_hex(),
+                timestamp: s.timestamp,
+                message: note.message,
+                author: note.author,
+                date: note.date,
+                files,
+            })
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+    let matching = entries.into_iter().filter(|e| filter.matches(e));
+    Ok(match filter.limit {
+        Some(n) => matching.take(n).collect(),
+        None => matching.collect(),
     })
 }
 
+/// The head root: the current branch's tip once a branch exists, otherwise
+/// the latest generation.
 pub fn current_root(ctx: &CliContext) -> Result<Option<Bytes32>, CliError> {
+    let refs = branch_refs(ctx);
+    if refs.exists() {
+        return refs
+            .current()
+            .and_then(|branch| Ok(refs.entries()?.get(&branch).copied()))
+            .map_err(|e| CliError::store("branches", e));
+    }
     Ok(read_history(ctx)?
         .iter()
         .max_by_key(|s| s.id)
         .map(|s| s.root))
 }
 
+/// Resolve a version reference to a root. Tried in order: a full 64-hex root;
+/// `HEAD` (the current root) or `HEAD~N` (N versions before it in the
+/// history); a branch name; a unique hex prefix (at least 4 characters) of a
+/// root in the history.
+pub fn resolve_version(ctx: &CliContext, spec: &str) -> Result<Bytes32, CliError> {
+    if let Ok(root) = Bytes32::from_hex(spec) {
+        return Ok(root);
+    }
+    let history = read_history(ctx)?;
+    if let Some(back) = spec.strip_prefix("HEAD") {
+        let back: usize = match back.strip_prefix('~') {
+            None if back.is_empty() => 0,
+            Some("") => 1,
+            Some(n) => n.parse().map_err(|_| {
+                CliError::InvalidArgument(format!("{spec}: expected HEAD~<number>"))
+            })?,
+            None => {
+                return Err(CliError::InvalidArgument(format!(
+                    "{spec}: expected HEAD or HEAD~<number>"
+                )))
+            }
+        };
+        let head =
+            current_root(ctx)?.ok_or_else(|| CliError::NotFound("no committed root".into()))?;
+        let mut ordered: Vec<&GenerationState> = history.iter().collect();
+        ordered.sort_by_key(|s| s.id);
+        let at = ordered
+            .iter()
+            .rposition(|s| s.root == head)
+            .ok_or_else(|| CliError::NotFound(format!("{} in history", head.to_hex())))?;
+        return at
+            .checked_sub(back)
+            .map(|i| ordered[i].root)
+            .ok_or_else(|| CliError::NotFound(format!("{spec}: only {} earlier versions", at)));
+    }
+    if let Some(tip) = branch_refs(ctx)
+        .entries()
+        .ok()
+        .and_then(|b| b.get(spec).copied())
+    {
+        return Ok(tip);
+    }
+    let prefix = spec.to_ascii_lowercase();
+    if prefix.len() < 4 || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(CliError::InvalidArgument(format!(
+            "{spec} is not a root, HEAD~N, branch or hex prefix of at least 4 characters"
+        )));
+    }
+    let mut matches: Vec<Bytes32> = history
+        .iter()
+        .map(|s| s.root)
+        .filter(|r| r.to_hex().starts_with(&prefix))
+        .collect();
+    matches.sort_by_key(|r| r.0);
+    matches.dedup();
+    match matches.as_slice() {
+        [root] => Ok(*root),
+        [] => Err(CliError::NotFound(format!("no version matches {spec}"))),
+        _ => Err(CliError::InvalidArgument(format!(
+            "{spec} is ambiguous: {} versions match",
+            matches.len()
+        ))),
+    }
+}
+
 fn read_history(ctx: &CliContext) -> Result<Vec<GenerationState>, CliError> {
     let path = ctx.history_path();
     if !path.exists() {
@@ -1122,24 +1793,38 @@ fn read_history(ctx: &CliContext) -> Result<Vec<GenerationState>, CliError> {
     }
     RootHistory::open(&path)
         .and_then(|h| h.entries())
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history: {e}")))
+        .map_err(|e| CliError::store("history", e))
 }
 
 /// Append a generation state to history (used by clone/pull).
 pub(crate) fn append_history(ctx: &CliContext, state: GenerationState) -> Result<(), CliError> {
-    let mut history = RootHistory::open(ctx.history_path())
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history open: {e}")))?;
+    let mut history =
+        RootHistory::open(ctx.history_path()).map_err(|e| CliError::store("history open", e))?;
     // Tolerate an out-of-order id by realigning to the expected next id.
     let expected = history
         .next_id()
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history next_id: {e}")))?;
+        .map_err(|e| CliError::store("history next_id", e))?;
     history
         .append(&GenerationState {
             id: expected,
             root: state.root,
             timestamp: state.timestamp,
         })
-        .map_err(|e| CliError::Other(anyhow::anyhow!("history append: {e}")))
+        .map_err(|e| CliError::store("history append", e))
+}
+
+/// Refuse to publish version `root` (push, serve) when it was committed with
+/// `--changes-only`: its module holds only that commit's files and a remote
+/// cannot fall through to the parent chain, so every other file would 404.
+pub fn ensure_publishable(ctx: &CliContext, root: &Bytes32) -> Result<(), CliError> {
+    if load_generation_manifest(ctx, root)?.parent.is_some() {
+        return Err(CliError::InvalidArgument(format!(
+            "version {} was committed with --changes-only and is local-only: its module holds \
+             only that commit's files; commit a full version (without --changes-only) to publish",
+            root.to_hex()
+        )));
+    }
+    Ok(())
 }
 
 pub fn module_path_for(
@@ -1235,6 +1920,279 @@ pub fn list_resource_keys(
     Ok(out)
 }
 
+/// True when a resource key contains glob metacharacters (`*`, `?`, `[`, `{`).
+pub fn is_glob(key: &str) -> bool {
+    key.contains(['*', '?', '[', '{'])
+}
+
+/// The resource keys in `root` matching the glob `pattern`, sorted. Matching
+/// uses the same `globset` syntax as `add` (`**` spans directories). A pattern
+/// that matches nothing is a `NotFound`.
+pub fn match_resources(
+    ctx: &CliContext,
+    root: &Bytes32,
+    pattern: &str,
+) -> Result<Vec<String>, CliError> {
+    let glob = globset::Glob::new(pattern)
+        .map_err(|e| CliError::InvalidArgument(format!("bad pattern '{pattern}': {e}")))?
+        .compile_matcher();
+    let mut keys: Vec<String> = list_generation_resources(ctx, root)?
+        .into_iter()
+        .filter(|k| glob.is_match(k))
+        .collect();
+    if keys.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "no resources in {} matched '{pattern}'",
+            root.to_hex()
+        )));
+    }
+    keys.sort();
+    Ok(keys)
+}
+
+/// One committed resource as `ls` shows it: its key, plaintext size, chunk
+/// count, and retrieval key (hex).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub size: u64,
+    pub chunks: usize,
+    pub retrieval_key: String,
+}
+
+/// List every resource served at `root`, sorted by resource key: for a
+/// `commit --changes-only` layer, with the files it reads through its parent
+/// chain (see [`Store::list_files`]).
+pub fn list_files(ctx: &CliContext, root: &Bytes32) -> Result<Vec<FileEntry>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    let records = store.list_files(Some(*root)).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("list files", e),
+    })?;
+    Ok(records
+        .into_iter()
+        .map(|k| FileEntry {
+            path: k.resource_key,
+            size: k.total_size,
+            chunks: k.chunk_indices.len(),
+            retrieval_key: k.static_key.to_hex(),
+        })
+        .collect())
+}
+
+/// `root`'s canonical snapshot manifest JSON (see `Store::export_manifest`).
+pub fn export_manifest(ctx: &CliContext, root: Bytes32) -> Result<String, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .export_manifest(root)
+        .and_then(|m| m.to_canonical_json())
+        .map_err(|e| match e {
+            digstore_store::StoreError::GenerationNotFound(root) => {
+                CliError::NotFound(format!("generation {root}"))
+            }
+            e @ digstore_store::StoreError::RootMismatch { .. } => {
+                CliError::VerificationFailed(e.to_string())
+            }
+            e => CliError::store("export manifest", e),
+        })
+}
+
+fn delta_error(context: &str, e: digstore_store::StoreError) -> CliError {
+    match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        digstore_store::StoreError::InvalidDelta(why) => CliError::InvalidArgument(why),
+        e @ digstore_store::StoreError::RootMismatch { .. } => {
+            CliError::VerificationFailed(e.to_string())
+        }
+        e => CliError::store(context, e),
+    }
+}
+
+/// Write everything committed after `base` to `out` (see `Store::export_since`).
+pub fn export_since(
+    ctx: &CliContext,
+    base: Bytes32,
+    out: &std::path::Path,
+) -> Result<digstore_store::DeltaBundle, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    let bundle = store
+        .export_since(base)
+        .map_err(|e| delta_error("export", e))?;
+    let file = std::fs::File::create(out).map_err(|e| CliError::Other(e.into()))?;
+    bundle
+        .write_to(std::io::BufWriter::new(file))
+        .map_err(|e| delta_error("export", e))?;
+    Ok(bundle)
+}
+
+/// Apply the delta bundle at `path` (see `Store::import_delta`).
+pub fn import_delta(ctx: &CliContext, path: &std::path::Path) -> Result<Vec<Bytes32>, CliError> {
+    let file = std::fs::File::open(path).map_err(|e| CliError::Other(e.into()))?;
+    let bundle = digstore_store::DeltaBundle::read_from(std::io::BufReader::new(file))
+        .map_err(|e| delta_error("import", e))?;
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    store
+        .import_delta(&bundle)
+        .map_err(|e| delta_error("import", e))
+}
+
+/// Sign generation `root` with `sk` and record it (see `Store::sign_root`).
+pub fn sign_root(
+    ctx: &CliContext,
+    root: Bytes32,
+    sk: &digstore_crypto::bls::SecretKey,
+) -> Result<digstore_store::RootSignature, CliError> {
+    let store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    store.sign_root(root, sk).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("sign root", e),
+    })
+}
+
+/// The store's write policy, or `None` when any wallet may write (see
+/// `Store::access_policy`).
+pub fn access_policy(ctx: &CliContext) -> Result<Option<digstore_store::AccessPolicy>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .access_policy()
+        .map_err(|e| CliError::store("access", e))
+}
+
+/// Fail with `Unauthorized` when the store has a write policy that does not
+/// list `public_key`.
+pub fn check_writer(ctx: &CliContext, public_key: &Bytes48) -> Result<(), CliError> {
+    match access_policy(ctx)? {
+        Some(policy) if !policy.allows(public_key) => Err(CliError::Unauthorized(format!(
+            "wallet key {} may not write to this store; see `digstore access`",
+            public_key.to_hex()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Replace (or with `None` drop) the store's write policy as the wallet
+/// holding `sk` (see `Store::set_access_policy`).
+pub fn set_access_policy(
+    ctx: &CliContext,
+    sk: &digstore_crypto::bls::SecretKey,
+    policy: Option<&digstore_store::AccessPolicy>,
+) -> Result<(), CliError> {
+    let map = |e: digstore_store::StoreError| match e {
+        digstore_store::StoreError::Unauthorized(msg) => CliError::Unauthorized(msg),
+        e => CliError::store("access", e),
+    };
+    let mut store = Store::open_as(&ctx.dig_dir, SystemClock, sk).map_err(map)?;
+    store.set_access_policy(policy).map_err(map)
+}
+
+/// Every signature recorded for `root`, each paired with whether it verifies.
+pub fn root_signatures(
+    ctx: &CliContext,
+    root: Bytes32,
+) -> Result<Vec<(digstore_store::RootSignature, bool)>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    let map = |e: digstore_store::StoreError| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("read signatures", e),
+    };
+    let sigs = store.root_signatures(root).map_err(map)?;
+    sigs.into_iter()
+        .map(|s| {
+            let valid = store
+                .verify_root_signature(root, &s.public_key)
+                .map_err(map)?;
+            Ok((s, valid))
+        })
+        .collect()
+}
+
+/// Describe `resource_key` in `root` from its manifest (see `Store::stat_file`).
+pub fn stat_file(
+    ctx: &CliContext,
+    root: Bytes32,
+    resource_key: &str,
+) -> Result<digstore_store::FileStat, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.stat_file(root, resource_key).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("stat", e),
+    })
+}
+
+/// Content type of `resource_key` at `root`, sniffed from its first chunk
+/// (see [`Store::content_type`]).
+pub fn content_type(
+    ctx: &CliContext,
+    root: Bytes32,
+    resource_key: &str,
+) -> Result<digstore_store::ContentType, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.content_type(root, resource_key).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("stat", e),
+    })
+}
+
+/// Search generation `root`'s text resources for `pattern`, streaming each
+/// match to `on_match` (see `Store::grep`).
+pub fn grep(
+    ctx: &CliContext,
+    pattern: &regex::Regex,
+    root: Bytes32,
+    text: bool,
+    on_match: impl FnMut(digstore_store::GrepMatch) -> bool,
+) -> Result<(), CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .grep(pattern, root, text, on_match)
+        .map_err(|e| match e {
+            digstore_store::StoreError::GenerationNotFound(root) => {
+                CliError::NotFound(format!("generation {root}"))
+            }
+            e @ digstore_store::StoreError::RootMismatch { .. } => {
+                CliError::VerificationFailed(e.to_string())
+            }
+            e => CliError::store("grep", e),
+        })
+}
+
+/// Prove every resource of `root` into it (see [`Store::verify_all_proofs`]).
+pub fn verify_all(
+    ctx: &CliContext,
+    root: Bytes32,
+) -> Result<Vec<digstore_store::VerificationResult>, CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store.verify_all_proofs(root).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        e => CliError::store("verify", e),
+    })
+}
+
 /// Resolve the resource key whose retrieval (static) key equals `retrieval_key`
 /// within `root`. Used to stream raw encrypted bytes by retrieval key alone.
 pub fn resource_key_for_retrieval_key(
@@ -1285,6 +2243,31 @@ pub fn resource_chunk_lens(
     Ok(lens)
 }
 
+/// The generation that serves `resource_key` at `root`: `root` when its key
+/// table lists the key, else the nearest generation up its parent chain (a
+/// `commit --changes-only` layer) that does (see
+/// [`Store::generation_holding`]). A version fetched by clone or pull has a
+/// module but no local manifest; changes-only layers are never pushed, so
+/// such a version holds all of its files itself.
+pub fn generation_holding(
+    ctx: &CliContext,
+    root: &Bytes32,
+    resource_key: &str,
+) -> Result<Bytes32, CliError> {
+    let manifest = ctx
+        .generations_dir()
+        .join(root.to_hex())
+        .join("manifest.json");
+    if !manifest.exists() {
+        return Ok(*root);
+    }
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    store
+        .generation_holding(*root, resource_key)
+        .map_err(|e| CliError::store("resolve version", e))
+}
+
 pub(crate) fn load_generation_manifest(
     ctx: &CliContext,
     root: &Bytes32,
@@ -1296,8 +2279,102 @@ pub(crate) fn load_generation_manifest(
     if !path.exists() {
         return Err(CliError::NotFound(format!("generation {}", root.to_hex())));
     }
-    GenerationManifest::read_from(&path)
-        .map_err(|e| CliError::Other(anyhow::anyhow!("read manifest: {e}")))
+    GenerationManifest::read_from(&path).map_err(|e| CliError::store("read manifest", e))
+}
+
+fn branch_refs(ctx: &CliContext) -> digstore_store::BranchRefs {
+    digstore_store::BranchRefs::open(ctx.dig_dir.join("refs"), ctx.dig_dir.join("HEAD"))
+}
+
+fn branch_error(e: digstore_store::StoreError) -> CliError {
+    match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        digstore_store::StoreError::InvalidBranch(why) => CliError::InvalidArgument(why),
+        e => CliError::store("branch", e),
+    }
+}
+
+/// Every branch and its tip, plus the current branch (see `Store::branches`).
+pub fn branches(
+    ctx: &CliContext,
+) -> Result<(std::collections::BTreeMap<String, Bytes32>, String), CliError> {
+    let store = Store::open_read_only(&ctx.dig_dir, SystemClock)
+        .map_err(|e| CliError::store("open store", e))?;
+    let branches = store.branches().map_err(branch_error)?;
+    let current = store.current_branch().map_err(branch_error)?;
+    Ok((branches, current))
+}
+
+/// Create branch `name` at `at`, default the current root (see `Store::create_branch`).
+pub fn create_branch(
+    ctx: &CliContext,
+    name: &str,
+    at: Option<Bytes32>,
+) -> Result<Bytes32, CliError> {
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    store.create_branch(name, at).map_err(branch_error)
+}
+
+/// Make `name` the current branch, returning its tip (see `Store::switch_branch`).
+pub fn switch_branch(ctx: &CliContext, name: &str) -> Result<Bytes32, CliError> {
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    store.switch_branch(name).map_err(branch_error)
+}
+
+/// Collapse the local history range `from..=to` into `to` (see `Store::squash`).
+pub fn squash(
+    ctx: &CliContext,
+    from: Bytes32,
+    to: Bytes32,
+    dry_run: bool,
+) -> Result<digstore_store::Squash, CliError> {
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    let before = read_history(ctx)?;
+    let squashed = store.squash(from, to, dry_run).map_err(|e| match e {
+        digstore_store::StoreError::GenerationNotFound(root) => {
+            CliError::NotFound(format!("generation {root}"))
+        }
+        digstore_store::StoreError::InvalidSquash(why) => CliError::InvalidArgument(why),
+        digstore_store::StoreError::InvalidBranch(why) => CliError::InvalidArgument(why),
+        e => CliError::store("squash", e),
+    })?;
+    CommitNote::renumber(&ctx.dig_dir, &before, &read_history(ctx)?)?;
+    Ok(squashed)
+}
+
+/// Preview a secret-salt rotation (see `Store::rotate_salt`): what it would
+/// re-encrypt. The salt is never used; nothing is written.
+pub fn rotate_salt_preview(ctx: &CliContext) -> Result<digstore_store::KeyRotation, CliError> {
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    store
+        .rotate_salt(SecretSalt(random_seed()), true)
+        .map_err(|e| CliError::store("rotate salt", e))
+}
+
+/// Remove old local versions (see `Store::prune`).
+pub fn prune(
+    ctx: &CliContext,
+    before: Option<digstore_store::PruneBefore>,
+    keep_last: Option<usize>,
+    dry_run: bool,
+) -> Result<digstore_store::Prune, CliError> {
+    let mut store =
+        Store::open(&ctx.dig_dir, SystemClock).map_err(|e| CliError::store("open store", e))?;
+    let history = read_history(ctx)?;
+    let pruned = store
+        .prune(before, keep_last, dry_run)
+        .map_err(|e| match e {
+            digstore_store::StoreError::InvalidPrune(why) => CliError::InvalidArgument(why),
+            e => CliError::store("prune", e),
+        })?;
+    CommitNote::renumber(&ctx.dig_dir, &history, &read_history(ctx)?)?;
+    Ok(pruned)
 }
 
 pub fn diff(ctx: &CliContext, from: &Bytes32, to: &Bytes32) -> Result<Vec<DiffEntry>, CliError> {
@@ -1489,6 +2566,27 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn space_verdict_names_need_and_have() {
+        assert!(space_verdict(10, 10, Path::new("/data")).is_ok());
+        let err = space_verdict(11, 10, Path::new("/data")).unwrap_err();
+        assert_eq!(err.code(), "INSUFFICIENT_SPACE");
+        assert_eq!(
+            err.to_string(),
+            "not enough disk space on /data: need 11 bytes, have 10"
+        );
+    }
+
+    #[test]
+    fn commit_needs_room_for_new_chunks_and_the_module() {
+        let estimate = digstore_store::CommitEstimate {
+            new_bytes: 100,
+            reused_bytes: 40,
+            ..Default::default()
+        };
+        assert_eq!(commit_space_needed(&estimate), 240);
+    }
+
     fn ctx(private: bool) -> (tempfile::TempDir, CliContext) {
         let td = tempdir().unwrap();
         let ctx = CliContext::workspace_only(td.path().to_path_buf(), false, false);
@@ -1583,7 +2681,7 @@ mod tests {
     #[test]
     fn log_is_empty_before_any_commit() {
         let (_td, ctx) = ctx(false);
-        assert!(log(&ctx, None).unwrap().is_empty());
+        assert!(log(&ctx, &LogFilter::default()).unwrap().is_empty());
     }
 
     #[test]
@@ -1599,11 +2697,99 @@ mod tests {
         )
         .unwrap();
         assert!(res.output_path.exists());
-        let entries = log(&ctx, None).unwrap();
+        let entries = log(&ctx, &LogFilter::default()).unwrap();
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].root, res.roothash.to_hex());
     }
 
+    #[test]
+    fn log_filters_by_author_message_and_date_together() {
+        let (td, ctx) = ctx(false);
+        let commits = [
+            ("alice", "fix: typo in readme"),
+            ("bob", "feat: add logo"),
+            ("alice", "feat: dark mode"),
+        ];
+        for (i, (author, message)) in commits.iter().enumerate() {
+            let f = td.path().join(format!("{i}.txt"));
+            std::fs::write(&f, format!("content {i}")).unwrap();
+            add_path(&ctx, &f, None).unwrap();
+            let res = commit(&ctx, None, crate::ops::serve::empty_manifest()).unwrap();
+            CommitNote {
+                message: Some(message.to_string()),
+                author: Some(author.to_string()),
+                date: None,
+            }
+            .save(&ctx.dig_dir, &res.roothash)
+            .unwrap();
+        }
+        // Spread the commits out in time: ids 0, 1, 2 at t = 100, 200, 300.
+        let mut history = RootHistory::open(ctx.history_path()).unwrap();
+        let spread: Vec<GenerationState> = history
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|g| GenerationState {
+                timestamp: 100 * (g.id + 1),
+                ..g
+            })
+            .collect();
+        history.rewrite(&spread).unwrap();
+
+        let ids = |filter: LogFilter| -> Vec<u64> {
+            log(&ctx, &filter).unwrap().iter().map(|e| e.id).collect()
+        };
+        let author = |a: &str| Some(a.to_string());
+        let grep = |r: &str| Some(regex::Regex::new(r).unwrap());
+        assert_eq!(ids(LogFilter::default()), vec![2, 1, 0]);
+        assert_eq!(
+            ids(LogFilter {
+                author: author("ali"),
+                ..Default::default()
+            }),
+            vec![2, 0]
+        );
+        assert_eq!(
+            ids(LogFilter {
+                grep: grep("^feat"),
+                ..Default::default()
+            }),
+            vec![2, 1]
+        );
+        assert_eq!(
+            ids(LogFilter {
+                author: author("alice"),
+                grep: grep("^feat"),
+                ..Default::default()
+            }),
+            vec![2]
+        );
+        assert_eq!(
+            ids(LogFilter {
+                since: Some(200),
+                until: Some(200),
+                ..Default::default()
+            }),
+            vec![1]
+        );
+        assert_eq!(
+            ids(LogFilter {
+                author: author("alice"),
+                until: Some(250),
+                ..Default::default()
+            }),
+            vec![0]
+        );
+        assert_eq!(
+            ids(LogFilter {
+                author: author("alice"),
+                limit: Some(1),
+                ..Default::default()
+            }),
+            vec![2]
+        );
+    }
+
     #[test]
     fn commit_with_nothing_staged_errors() {
         let (_td, ctx) = ctx(false);
@@ -1,11 +1,30 @@
 //! Resolve `add` path arguments into a concrete list of files + their resource
 //! keys, honoring `.digignore`/`.gitignore` and always skipping the `.dig/` store
 //! directory. Keys are the file path relative to the store root, forward-slashed.
+//!
+//! Ignore files follow gitignore semantics exactly (the `ignore` crate is the
+//! one engine): the last matching pattern wins, `!pat` re-includes, a leading
+//! or middle `/` anchors to the ignore file's directory, a trailing `/` matches
+//! directories only, and `**` spans separators. Deeper ignore files override
+//! shallower ones, and `.digignore` overrides `.gitignore` at the same level.
+//! `.gitignore` applies whether or not the content root is a git checkout.
+//!
+//! Beneath all of those sits the user-global ignore file (`core.excludesFile`
+//! in `~/.dig/config.toml`, default `~/.dig/ignore`), so editor swap files and
+//! the like can be excluded from every store; any per-directory file can still
+//! re-include with `!pat`.
+//!
+//! The walk runs on the `ignore` crate's parallel walker; results are sorted by
+//! key, so callers see the same order whatever the thread count.
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
+use digstore_chain::config::{self, GlobalConfig};
 use globset::Glob;
-use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder, WalkState};
 
 /// A resolved file to stage: absolute path + portable resource key.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,27 +42,60 @@ fn key_for(root: &Path, path: &Path) -> String {
         .join("/")
 }
 
+/// The user-global ignore file, if one is configured and present.
+fn global_excludes() -> Option<PathBuf> {
+    let home = config::dig_home().ok()?;
+    GlobalConfig::load(&home).ok()?.excludes_file(&home)
+}
+
 /// Walk `dir` (under `root`) collecting non-ignored files, skipping anything
-/// under `skip` (the `.dig/` workspace dir).
-fn walk_dir(root: &Path, skip: &Path, dir: &Path, out: &mut Vec<Resolved>) {
+/// under `skip` (the `.dig/` workspace dir). Ignored directories and `skip`
+/// are pruned, never read. `on_entry` sees every path the walker yields, from
+/// whichever walker thread found it.
+fn walk_dir(
+    root: &Path,
+    skip: &Path,
+    dir: &Path,
+    out: &mut Vec<Resolved>,
+    on_entry: &(dyn Fn(&Path) + Sync),
+) {
     let mut wb = WalkBuilder::new(dir);
+    if let Some(global) = global_excludes() {
+        // Lowest precedence of every ignore source. A malformed line is
+        // skipped; the rest of the file still applies.
+        let _ = wb.add_ignore(global);
+    }
     wb.hidden(false) // include dotfiles (Git stages them unless ignored)
         .git_ignore(true)
         .git_global(true)
         .git_exclude(true)
+        .require_git(false) // honor .gitignore outside a git checkout too
         .add_custom_ignore_filename(".digignore");
-    for entry in wb.build().flatten() {
-        let p = entry.path();
-        if p.starts_with(skip) {
-            continue;
-        }
-        if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
-            out.push(Resolved {
-                path: p.to_path_buf(),
-                key: key_for(root, p),
-            });
-        }
-    }
+    let found = Mutex::new(Vec::new());
+    wb.build_parallel().run(|| {
+        Box::new(|entry| {
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            let p = entry.path();
+            on_entry(p);
+            // Never descend into the workspace dir; ignored directories are
+            // already pruned by the walker itself.
+            if p.starts_with(skip) {
+                return WalkState::Skip;
+            }
+            if entry.file_type().is_some_and(|t| t.is_file()) {
+                found.lock().unwrap().push(Resolved {
+                    path: p.to_path_buf(),
+                    key: key_for(root, p),
+                });
+            }
+            WalkState::Continue
+        })
+    });
+    let mut found = found.into_inner().unwrap();
+    found.sort_by(|a, b| a.key.cmp(&b.key));
+    out.extend(found);
 }
 
 /// Resolve one argument (file, directory, or glob) relative to `root`, skipping
@@ -53,6 +105,18 @@ pub fn resolve_arg(
     skip: &Path,
     arg: &str,
     out: &mut Vec<Resolved>,
+) -> Result<(), String> {
+    resolve_arg_with_progress(root, skip, arg, out, &|_| {})
+}
+
+/// [`resolve_arg`], reporting every path a directory or glob walk visits to
+/// `on_entry`.
+pub fn resolve_arg_with_progress(
+    root: &Path,
+    skip: &Path,
+    arg: &str,
+    out: &mut Vec<Resolved>,
+    on_entry: &(dyn Fn(&Path) + Sync),
 ) -> Result<(), String> {
     let as_path = root.join(arg);
     // Reject any path that escapes the operating directory (§2.8) — both `../x`
@@ -82,7 +146,7 @@ pub fn resolve_arg(
         if !within(&as_path) {
             return Err(format!("'{arg}' is outside the operating directory"));
         }
-        walk_dir(root, skip, &as_path, out);
+        walk_dir(root, skip, &as_path, out, on_entry);
         return Ok(());
     }
     // Treat as a glob relative to root.
@@ -90,7 +154,7 @@ pub fn resolve_arg(
         .map_err(|e| format!("bad pattern '{arg}': {e}"))?
         .compile_matcher();
     let mut all = Vec::new();
-    walk_dir(root, skip, root, &mut all);
+    walk_dir(root, skip, root, &mut all, on_entry);
     let before = out.len();
     for r in all {
         if glob.is_match(&r.key) {
@@ -105,11 +169,146 @@ pub fn resolve_arg(
 
 /// Resolve `--all`: every non-ignored file under `root`, skipping `skip`.
 pub fn resolve_all(root: &Path, skip: &Path) -> Vec<Resolved> {
+    resolve_all_with_progress(root, skip, &|_| {})
+}
+
+/// [`resolve_all`], reporting every path the walk visits to `on_entry`.
+pub fn resolve_all_with_progress(
+    root: &Path,
+    skip: &Path,
+    on_entry: &(dyn Fn(&Path) + Sync),
+) -> Vec<Resolved> {
     let mut out = Vec::new();
-    walk_dir(root, skip, root, &mut out);
+    walk_dir(root, skip, root, &mut out, on_entry);
     out
 }
 
+/// A path `add -A` skips, and the rule that skipped it (`status --ignored`).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Ignored {
+    /// Resource-key form of the path; an ignored directory ends in `/` and
+    /// stands for everything under it.
+    pub path: String,
+    /// The deciding pattern as written, when it came from a `.digignore`,
+    /// `.gitignore` or the global excludes file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// The ignore file that pattern lives in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+}
+
+/// Every path under `root` that [`resolve_all`] leaves out, each with the rule
+/// responsible. An ignored directory is reported once (like `git status
+/// --ignored`) rather than file by file. Membership comes from the walk itself,
+/// so this never disagrees with what `add -A` stages; the explanation replays
+/// the same precedence (deepest ignore file first, `.digignore` before
+/// `.gitignore`, the global file last).
+pub fn list_ignored(root: &Path, skip: &Path) -> Vec<Ignored> {
+    let kept: HashSet<PathBuf> = resolve_all(root, skip)
+        .into_iter()
+        .map(|r| r.path)
+        .collect();
+    let mut why = Explainer::new(root);
+    let mut out = Vec::new();
+    collect_ignored(root, skip, root, &kept, &mut why, &mut out);
+    out
+}
+
+fn collect_ignored(
+    root: &Path,
+    skip: &Path,
+    dir: &Path,
+    kept: &HashSet<PathBuf>,
+    why: &mut Explainer,
+    out: &mut Vec<Ignored>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.starts_with(skip) || path.file_name().is_some_and(|n| n == ".git") {
+            continue;
+        }
+        if path.is_dir() {
+            match why.explain(&path, true) {
+                Some((pattern, source)) => out.push(Ignored {
+                    path: format!("{}/", key_for(root, &path)),
+                    pattern: Some(pattern),
+                    source,
+                }),
+                None => collect_ignored(root, skip, &path, kept, why, out),
+            }
+        } else if path.is_file() && !kept.contains(&path) {
+            let (pattern, source) = why.explain(&path, false).unzip();
+            out.push(Ignored {
+                path: key_for(root, &path),
+                pattern,
+                source: source.flatten(),
+            });
+        }
+    }
+}
+
+/// Replays ignore-file precedence for one path to name the deciding pattern.
+struct Explainer {
+    root: PathBuf,
+    global: Option<Gitignore>,
+    /// Per-directory `[.digignore, .gitignore]` matchers, loaded on demand.
+    dirs: HashMap<PathBuf, Vec<Gitignore>>,
+}
+
+impl Explainer {
+    fn new(root: &Path) -> Self {
+        let global = global_excludes().and_then(|file| {
+            let mut b = GitignoreBuilder::new(root);
+            let _ = b.add(file);
+            b.build().ok()
+        });
+        Self {
+            root: root.to_path_buf(),
+            global,
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// The pattern (and its file) that ignores `path`, or `None` when no rule
+    /// does or the deciding rule is a `!` re-include.
+    fn explain(&mut self, path: &Path, is_dir: bool) -> Option<(String, Option<PathBuf>)> {
+        let decide = |m: Match<&ignore::gitignore::Glob>| match m {
+            Match::Ignore(g) => Some(Some((
+                g.original().to_string(),
+                g.from().map(Path::to_path_buf),
+            ))),
+            Match::Whitelist(_) => Some(None),
+            Match::None => None,
+        };
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| d.starts_with(&self.root)) {
+            let matchers = self.dirs.entry(d.to_path_buf()).or_insert_with(|| {
+                [".digignore", ".gitignore"]
+                    .iter()
+                    .map(|name| d.join(name))
+                    .filter(|f| f.is_file())
+                    .map(|f| Gitignore::new(f).0)
+                    .collect()
+            });
+            for gi in matchers.iter() {
+                if let Some(decided) = decide(gi.matched(path, is_dir)) {
+                    return decided;
+                }
+            }
+            dir = d.parent();
+        }
+        self.global
+            .as_ref()
+            .and_then(|gi| decide(gi.matched(path, is_dir)))
+            .flatten()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +344,86 @@ mod tests {
         // treatment of the custom ignore file; either outcome is acceptable.
     }
 
+    #[test]
+    fn parallel_walk_finds_every_file_in_key_order() {
+        let d = tempfile::tempdir().unwrap();
+        let mut expected = Vec::new();
+        for i in 0..20 {
+            let dir = d.path().join(format!("d{i:02}/sub"));
+            fs::create_dir_all(&dir).unwrap();
+            for j in 0..10 {
+                fs::write(dir.join(format!("f{j}.txt")), b"x").unwrap();
+                expected.push(format!("d{i:02}/sub/f{j}.txt"));
+            }
+        }
+        expected.sort();
+        let seen = std::sync::atomic::AtomicUsize::new(0);
+        let out = resolve_all_with_progress(d.path(), &d.path().join(".dig"), &|_| {
+            seen.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+        let keys: Vec<String> = out.into_iter().map(|r| r.key).collect();
+        assert_eq!(keys, expected);
+        // Every file and directory, plus the root itself.
+        assert_eq!(seen.into_inner(), 200 + 40 + 1);
+    }
+
+    #[test]
+    fn ignored_and_workspace_dirs_are_never_descended() {
+        let d = scratch();
+        let deep = d.path().join("target/debug/build/a/b/c");
+        fs::create_dir_all(&deep).unwrap();
+        for i in 0..50 {
+            fs::write(deep.join(format!("o{i}.rlib")), b"o").unwrap();
+        }
+        fs::write(d.path().join(".gitignore"), "target/\n").unwrap();
+        fs::write(d.path().join(".dig/modules/m.wasm"), b"m").unwrap();
+
+        let visited = Mutex::new(Vec::new());
+        let skip = d.path().join(".dig");
+        let keys: Vec<String> = resolve_all_with_progress(d.path(), &skip, &|p| {
+            visited.lock().unwrap().push(p.to_path_buf())
+        })
+        .into_iter()
+        .map(|r| r.key)
+        .collect();
+        assert!(keys.contains(&"sub/b.md".to_string()));
+        let visited = visited.into_inner().unwrap();
+        let under = |dir: &str| {
+            let dir = d.path().join(dir);
+            visited.iter().any(|p| p.starts_with(&dir) && *p != dir)
+        };
+        assert!(!under("target"), "ignored dir was descended");
+        assert!(!under(".dig"), "workspace dir was descended");
+    }
+
+    #[test]
+    fn list_ignored_names_the_deciding_pattern_and_file() {
+        let d = scratch();
+        fs::create_dir_all(d.path().join("build/deep")).unwrap();
+        fs::write(d.path().join("build/deep/out.js"), b"o").unwrap();
+        fs::write(d.path().join(".gitignore"), "build/\n").unwrap();
+        let ignored = list_ignored(d.path(), &d.path().join(".dig"));
+        let by_path: HashMap<&str, &Ignored> =
+            ignored.iter().map(|i| (i.path.as_str(), i)).collect();
+
+        let log = by_path["c.log"];
+        assert_eq!(log.pattern.as_deref(), Some("*.log"));
+        assert_eq!(
+            log.source.as_deref(),
+            Some(d.path().join(".digignore").as_path())
+        );
+        // The ignored directory is reported once, not file by file.
+        let build = by_path["build/"];
+        assert_eq!(build.pattern.as_deref(), Some("build/"));
+        assert_eq!(
+            build.source.as_deref(),
+            Some(d.path().join(".gitignore").as_path())
+        );
+        assert!(!by_path.contains_key("build/deep/out.js"));
+        assert!(!by_path.contains_key("a.txt"));
+        assert!(!ignored.iter().any(|i| i.path.starts_with(".dig/")));
+    }
+
     #[test]
     fn resolve_glob_matches_relative_keys() {
         let d = scratch();
@@ -1,5 +1,8 @@
 //! Human + JSON rendering of command results.
 
+use std::collections::{BTreeMap, BTreeSet};
+
+use digstore_core::Bytes32;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -8,8 +11,46 @@ pub struct StatusView {
     pub staged: Vec<String>,
     pub modified: Vec<String>,
     pub untracked: Vec<String>,
+    /// Staged keys whose working file changed after `add` (the staged bytes
+    /// are what `commit` will seal).
+    pub stale: Vec<String>,
+    /// Staged keys whose working file has since been deleted.
+    pub missing: Vec<String>,
     pub staged_bytes: u64,
     pub limit_bytes: u64,
+    /// Paths `add -A` skips; only filled in by `status --ignored`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignored: Option<Vec<crate::ops::walk::Ignored>>,
+    /// Sizes and hashes behind the lists above (`status --porcelain=v2`).
+    #[serde(skip)]
+    pub detail: StatusDetail,
+}
+
+/// Size and SHA-256 of one copy of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileDigest {
+    pub size: u64,
+    pub sha256: Bytes32,
+}
+
+impl FileDigest {
+    pub fn of(bytes: &[u8]) -> Self {
+        Self {
+            size: bytes.len() as u64,
+            sha256: digstore_crypto::sha256(bytes),
+        }
+    }
+}
+
+/// Per-key detail for [`StatusView`].
+#[derive(Debug, Clone, Default)]
+pub struct StatusDetail {
+    /// The staged copy of every staged key.
+    pub staged: BTreeMap<String, FileDigest>,
+    /// Staged keys that already exist in the current root.
+    pub committed: BTreeSet<String>,
+    /// The working copy of every `modified` and `untracked` key.
+    pub working: BTreeMap<String, FileDigest>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +58,13 @@ pub struct LogEntry {
     pub id: u64,
     pub root: String,
     pub timestamp: u64,
+    /// The `commit -m` message, if one was recorded.
+    pub message: Option<String>,
+    pub author: Option<String>,
+    /// `commit --date` (RFC 3339, original offset), when one was given.
+    pub date: Option<String>,
+    /// Resources in the generation; `None` when it is not stored locally.
+    pub files: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +99,36 @@ pub fn render_status(s: &StatusView, ui: &crate::ui::Ui) {
     if !s.untracked.is_empty() {
         ui.hint("digstore add -A   # stage untracked files");
     }
+    group(
+        ui,
+        "warning: staged content is stale (file changed after add)",
+        Marker::Modified,
+        &s.stale,
+    );
+    group(
+        ui,
+        "warning: staged file deleted from disk",
+        Marker::Removed,
+        &s.missing,
+    );
+    if !s.stale.is_empty() || !s.missing.is_empty() {
+        ui.hint("digstore add <path>   # restage, or `digstore unstage` to drop staging");
+    }
+    if let Some(ignored) = &s.ignored {
+        if !ignored.is_empty() {
+            ui.line(format!("ignored ({})", ignored.len()));
+        }
+        for i in ignored {
+            match (&i.pattern, &i.source) {
+                (Some(p), Some(src)) => ui.item(
+                    Marker::Ignored,
+                    format!("{}  ({p} in {})", i.path, src.display()),
+                ),
+                (Some(p), None) => ui.item(Marker::Ignored, format!("{}  ({p})", i.path)),
+                _ => ui.item(Marker::Ignored, &i.path),
+            }
+        }
+    }
     if s.staged.is_empty() && s.modified.is_empty() && s.untracked.is_empty() {
         ui.line("nothing to commit; working directory clean");
     }
@@ -66,6 +144,60 @@ pub fn render_log(entries: &[LogEntry], json: bool) -> String {
             "deployment {}  root {}  ts {}\n",
             e.id, e.root, e.timestamp
         ));
+        if let Some(author) = &e.author {
+            out.push_str(&format!("    author: {author}\n"));
+        }
+        if let Some(date) = &e.date {
+            out.push_str(&format!("    date: {date}\n"));
+        }
+        if let Some(files) = e.files {
+            out.push_str(&format!("    files: {files}\n"));
+        }
+        if let Some(message) = &e.message {
+            for line in message.lines() {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+    }
+    out
+}
+
+/// `log --graph`: `entries` (newest first) as a chain of `*` nodes, each
+/// joined to its parent (the previous deployment) by `|`. Versions are
+/// strictly linear, so the graph is one line; where deployment ids skip (the
+/// versions between were squashed, pruned or filtered out) the edge is drawn
+/// as `:`.
+pub fn render_log_graph(entries: &[LogEntry]) -> String {
+    let mut out = String::new();
+    for (i, e) in entries.iter().enumerate() {
+        let parent = entries.get(i + 1);
+        let edge = match parent {
+            None => ' ',
+            Some(p) if e.id > p.id + 1 => ':',
+            Some(_) => '|',
+        };
+        let short = &e.root[..e.root.len().min(12)];
+        let subject = e
+            .message
+            .as_deref()
+            .and_then(|m| m.lines().next())
+            .unwrap_or("");
+        out.push_str(&format!("* deployment {}  {short}  {subject}", e.id).trim_end());
+        out.push('\n');
+        if let Some(author) = &e.author {
+            out.push_str(&format!("{edge}   author: {author}\n"));
+        }
+        if let Some(date) = &e.date {
+            out.push_str(&format!("{edge}   date: {date}\n"));
+        }
+        if let Some(p) = parent {
+            if edge == ':' {
+                let skipped = e.id - p.id - 1;
+                out.push_str(&format!(":   ({skipped} deployments not shown)\n"));
+            } else {
+                out.push_str("|\n");
+            }
+        }
     }
     out
 }
@@ -97,8 +229,12 @@ mod tests {
             staged: vec!["readme".into()],
             modified: vec![],
             untracked: vec!["new_file.txt".into()],
+            stale: vec![],
+            missing: vec![],
             staged_bytes: 0,
             limit_bytes: 100_000_000,
+            ignored: None,
+            detail: Default::default(),
         };
         // Serialize directly to verify the JSON shape contains "untracked".
         let json = serde_json::to_string_pretty(&s).expect("serialize");
@@ -115,8 +251,12 @@ mod tests {
             staged: vec!["a".into()],
             modified: vec!["b".into()],
             untracked: vec!["c".into()],
+            stale: vec![],
+            missing: vec![],
             staged_bytes: 0,
             limit_bytes: 100_000_000,
+            ignored: None,
+            detail: Default::default(),
         };
         let json = serde_json::to_string_pretty(&s).expect("serialize");
         assert!(json.contains("\"root\""));
@@ -131,11 +271,42 @@ mod tests {
             id: 1,
             root: "aa".into(),
             timestamp: 100,
+            message: None,
+            author: None,
+            date: None,
+            files: Some(2),
         }];
         let out = render_log(&v, true);
         assert!(out.trim_start().starts_with('['));
     }
 
+    #[test]
+    fn render_log_graph_is_a_line_with_gaps_marked() {
+        let entry = |id: u64, message: Option<&str>| LogEntry {
+            id,
+            root: format!("{id:02}").repeat(32),
+            timestamp: id,
+            message: message.map(str::to_string),
+            author: None,
+            date: None,
+            files: None,
+        };
+        let v = vec![
+            entry(4, Some("fix\nbody")),
+            entry(3, None),
+            entry(0, Some("first")),
+        ];
+        assert_eq!(
+            render_log_graph(&v),
+            "* deployment 4  040404040404  fix\n\
+             |\n\
+             * deployment 3  030303030303\n\
+             :   (2 deployments not shown)\n\
+             * deployment 0  000000000000  first\n"
+        );
+        assert_eq!(render_log_graph(&[]), "");
+    }
+
     #[test]
     fn render_diff_human_uses_plus_for_added() {
         let v = vec![DiffEntry {
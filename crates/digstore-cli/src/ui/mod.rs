@@ -6,6 +6,7 @@ pub mod theme;
 use std::io::{IsTerminal, Write};
 use std::time::Duration;
 
+use digstore_store::ProgressSink;
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::Serialize;
 
@@ -17,7 +18,43 @@ use crate::ui::theme::{marker_line, verb_line, Marker};
 pub(crate) const TRANSFER_SPINNER_TEMPLATE: &str =
     "{spinner:.cyan} {msg} {bytes} ({bytes_per_sec})";
 
+/// Template for the DETERMINATE byte bar (see [`Ui::byte_progress`]), used when the
+/// total is known up front (e.g. the staged bytes a commit seals): progress against
+/// the total, throughput, and an ETA.
+pub(crate) const BYTE_BAR_TEMPLATE: &str =
+    "{msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta})";
+
+/// Templates for the file counter (see [`Ui::file_progress`]): a ticking count
+/// while the total is unknown, then a bar against it.
+pub(crate) const FILE_COUNT_TEMPLATE: &str = "{spinner:.cyan} {msg} {human_pos} files";
+pub(crate) const FILE_BAR_TEMPLATE: &str =
+    "{msg} [{bar:30.cyan/blue}] {human_pos}/{human_len} files ({per_sec}, ETA {eta})";
+
 // ---------------------------------------------------------------------------
+/// Feeds a store operation's [`ProgressSink`] reports into a bar from
+/// [`Ui::byte_progress`] (bytes) or [`Ui::file_progress`] (files): whichever
+/// the bar was made for, set `files` to match.
+pub struct BarSink {
+    pub pb: ProgressBar,
+    pub files: bool,
+}
+
+impl ProgressSink for BarSink {
+    fn on_file(&self, done: u64, total: u64) {
+        if self.files {
+            self.pb.set_length(total);
+            self.pb.set_position(done);
+        }
+    }
+
+    fn on_bytes(&self, done: u64, total: u64) {
+        if !self.files {
+            self.pb.set_length(total);
+            self.pb.set_position(done);
+        }
+    }
+}
+
 // Spinner — RAII handle wrapping indicatif's ProgressBar.
 // ---------------------------------------------------------------------------
 
@@ -66,6 +103,8 @@ pub struct Ui {
     non_interactive: bool,
     /// Auto-approve confirmations (the `--yes`/`-y` flag).
     assume_yes: bool,
+    /// Never draw spinners or progress bars (the `--no-progress` flag).
+    no_progress: bool,
 }
 
 /// Plain capacity string: "47.2 MB staged · 52.8 MB free of 100.0 MB".
@@ -110,6 +149,7 @@ impl Ui {
             quiet,
             non_interactive: false,
             assume_yes: false,
+            no_progress: false,
         }
     }
 
@@ -120,6 +160,7 @@ impl Ui {
         quiet: bool,
         non_interactive: bool,
         assume_yes: bool,
+        no_progress: bool,
     ) -> Self {
         let no_color = std::env::var_os("NO_COLOR").is_some()
             || std::env::var("CLICOLOR").map(|v| v == "0").unwrap_or(false);
@@ -134,6 +175,7 @@ impl Ui {
         );
         ui.non_interactive = non_interactive;
         ui.assume_yes = assume_yes;
+        ui.no_progress = no_progress;
         ui
     }
 
@@ -227,6 +269,9 @@ impl Ui {
                     "message": e.to_string(),
                 },
             });
+            if let Some(code) = e.store_code() {
+                obj["error"]["store_code"] = serde_json::json!(code);
+            }
             if let Some(h) = e.hint() {
                 obj["error"]["hint"] = serde_json::json!(h);
             }
@@ -390,12 +435,10 @@ impl Ui {
     /// `_total_bytes` argument is ignored (kept so callers need not change) — it exists
     /// only because the underlying transfer callbacks still report a (often-zero) total.
     ///
-    /// **Gating**: identical to [`Ui::spinner`] — hidden when `--json` is set,
-    /// stdout is not a TTY, or color is disabled. The returned [`ProgressBar`]
+    /// **Gating**: identical to [`Ui::spinner`]. The returned [`ProgressBar`]
     /// is always safe to call `set_position`/`inc`/`finish_and_clear` on regardless.
     pub fn progress_bar(&self, _total_bytes: u64, msg: &str) -> ProgressBar {
-        let tty = std::io::stdout().is_terminal();
-        if !tty || self.json || !self.color {
+        if !self.show_progress(false) {
             return ProgressBar::hidden();
         }
         // A no-length (indeterminate) spinner-style bar: bytes + rate, no total/percent.
@@ -410,15 +453,81 @@ impl Ui {
         pb
     }
 
+    /// Return a DETERMINATE byte bar for work whose total is known up front:
+    ///
+    /// Template: `msg [#####·····] 42.1 MB/96.0 MB (12.3 MB/s, ETA 4s)`
+    ///
+    /// Feed it with `pb.set_position(done)`. `force` (a command's `--progress` flag)
+    /// draws it even when stdout is not a TTY or color is off; the bar itself goes
+    /// to stderr, so piped stdout stays clean.
+    ///
+    /// **Gating**: otherwise identical to [`Ui::spinner`]. `--json`, `--quiet` and
+    /// `--no-progress` always hide it, even when forced.
+    pub fn byte_progress(&self, total_bytes: u64, msg: &str, force: bool) -> ProgressBar {
+        if !self.show_progress(force) {
+            return ProgressBar::hidden();
+        }
+        let pb = ProgressBar::new(total_bytes);
+        pb.set_style(
+            ProgressStyle::with_template(BYTE_BAR_TEMPLATE)
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        pb.set_message(msg.to_owned());
+        pb
+    }
+
+    /// Return a file counter: a ticking `msg 12,345 files` count when `total` is
+    /// unknown (a directory walk), or a bar against `total` (reading what the
+    /// walk found). Feed it with `pb.inc(1)` or `pb.set_position(done)`.
+    ///
+    /// **Gating**: identical to [`Ui::spinner`].
+    pub fn file_progress(&self, msg: &str, total: Option<u64>) -> ProgressBar {
+        if !self.show_progress(false) {
+            return ProgressBar::hidden();
+        }
+        let pb = match total {
+            None => {
+                let pb = ProgressBar::new_spinner();
+                pb.set_style(
+                    ProgressStyle::with_template(FILE_COUNT_TEMPLATE)
+                        .unwrap()
+                        .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+                );
+                pb.enable_steady_tick(Duration::from_millis(90));
+                pb
+            }
+            Some(total) => {
+                let pb = ProgressBar::new(total);
+                pb.set_style(
+                    ProgressStyle::with_template(FILE_BAR_TEMPLATE)
+                        .unwrap()
+                        .progress_chars("#>-"),
+                );
+                pb
+            }
+        };
+        pb.set_message(msg.to_owned());
+        pb
+    }
+
+    /// Whether spinners and progress bars may draw: never under `--json`,
+    /// `--quiet` or `--no-progress`; otherwise only on a colored TTY unless `force`d.
+    fn show_progress(&self, force: bool) -> bool {
+        if self.json || self.quiet || self.no_progress {
+            return false;
+        }
+        force || (std::io::stdout().is_terminal() && self.color)
+    }
+
     /// Return an animated spinner that runs until the returned [`Spinner`] is
     /// dropped or explicitly finished.
     ///
-    /// **Gating**: returns a hidden (no-op) spinner when `--json` is set, when
-    /// stdout is not a TTY, or when color is disabled — so JSON/non-interactive
-    /// output is never polluted.
+    /// **Gating**: returns a hidden (no-op) spinner when `--json`, `--quiet` or
+    /// `--no-progress` is set, when stdout is not a TTY, or when color is
+    /// disabled — so JSON/non-interactive output is never polluted.
     pub fn spinner(&self, msg: &str) -> Spinner {
-        let tty = std::io::stdout().is_terminal();
-        let pb = if !tty || self.json || !self.color {
+        let pb = if !self.show_progress(false) {
             ProgressBar::hidden()
         } else {
             let pb = ProgressBar::new_spinner();
@@ -546,4 +655,35 @@ mod tests {
         pb.inc(7);
         pb.finish_and_clear();
     }
+
+    #[test]
+    fn byte_bar_template_shows_total_rate_and_eta() {
+        let t = BYTE_BAR_TEMPLATE;
+        assert!(t.contains("{total_bytes}"));
+        assert!(t.contains("{bytes_per_sec}"));
+        assert!(t.contains("{eta"));
+        assert!(ProgressStyle::with_template(t).is_ok());
+    }
+
+    #[test]
+    fn file_templates_are_valid_and_hidden_counter_is_drivable() {
+        assert!(ProgressStyle::with_template(FILE_COUNT_TEMPLATE).is_ok());
+        assert!(ProgressStyle::with_template(FILE_BAR_TEMPLATE).is_ok());
+        let ui = Ui::resolve(ColorChoice::Never, false, false, false, false, false);
+        let pb = ui.file_progress("Scanning", None);
+        pb.inc(3);
+        pb.finish_and_clear();
+    }
+
+    /// `--quiet` and `--no-progress` hide the bar even when `--progress` forces it.
+    #[test]
+    fn forced_byte_progress_respects_quiet_and_no_progress() {
+        let quiet = Ui::resolve(ColorChoice::Never, false, true, false, false, false);
+        assert!(quiet.byte_progress(10, "Sealing", true).is_hidden());
+        let mut ui = Ui::resolve(ColorChoice::Never, false, false, false, false, false);
+        ui.no_progress = true;
+        assert!(ui.byte_progress(10, "Sealing", true).is_hidden());
+        let json = Ui::resolve(ColorChoice::Never, true, false, false, false, false);
+        assert!(json.byte_progress(10, "Sealing", true).is_hidden());
+    }
 }
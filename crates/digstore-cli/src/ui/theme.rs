@@ -11,6 +11,7 @@ pub enum Marker {
     Modified,  // '~'
     Untracked, // '?'
     Removed,   // '-'
+    Ignored,   // '!'
 }
 
 impl Marker {
@@ -20,6 +21,7 @@ impl Marker {
             Marker::Modified => '~',
             Marker::Untracked => '?',
             Marker::Removed => '-',
+            Marker::Ignored => '!',
         }
     }
     fn style(self) -> Style {
@@ -28,6 +30,7 @@ impl Marker {
             Marker::Modified => Style::new().fg_color(Some(AnsiColor::Yellow.into())),
             Marker::Untracked => Style::new().dimmed(),
             Marker::Removed => Style::new().fg_color(Some(AnsiColor::Red.into())),
+            Marker::Ignored => Style::new().dimmed(),
         }
     }
 }
@@ -122,7 +122,7 @@ impl Workspace {
                 .map_err(|e| CliError::Other(anyhow::anyhow!("migrate move {name:?}: {e}")))?;
         }
         let cfg = digstore_store::load_config(dest.join("config.toml"))
-            .map_err(|e| CliError::Other(anyhow::anyhow!("migrate read config: {e}")))?;
+            .map_err(|e| CliError::store("migrate read config", e))?;
         let mut ws = Workspace {
             dir: dir.to_path_buf(),
             active: None,
@@ -0,0 +1,70 @@
+mod common;
+use common::{dig, tmp_dig};
+
+/// Swap the cached-unlock session to another wallet (a public BIP-39 test
+/// vector), so later commands run as a key the policy does not list.
+fn switch_wallet(dir: &tempfile::TempDir) {
+    let body = serde_json::json!({
+        "expires_at": 4_102_444_800u64,
+        "phrase": "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    });
+    std::fs::write(
+        dir.path().join(".dighome").join("session"),
+        serde_json::to_vec(&body).unwrap(),
+    )
+    .unwrap();
+}
+
+fn policy(dir: &tempfile::TempDir) -> serde_json::Value {
+    let out = dig(dir).args(["access", "--json"]).output().unwrap();
+    assert!(out.status.success());
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+#[test]
+fn a_policy_admits_its_writers_and_refuses_other_wallets() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"hello").unwrap();
+    assert_eq!(policy(&dir)["restricted"], false);
+
+    let other = "ab".repeat(48);
+    dig(&dir).args(["access", "allow"]).assert().success();
+    dig(&dir)
+        .args(["access", "allow", &other])
+        .assert()
+        .success();
+    let shown = policy(&dir);
+    assert_eq!(shown["restricted"], true);
+    let writers = shown["writers"].as_array().unwrap();
+    assert_eq!(writers.len(), 2);
+    assert_eq!(writers[1], other.as_str());
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+
+    switch_wallet(&dir);
+    dig(&dir).args(["add", "a.txt"]).assert().code(8);
+    dig(&dir).args(["access", "clear"]).assert().code(8);
+    // Reading is never restricted.
+    assert_eq!(policy(&dir)["writers"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn revoke_removes_a_writer_but_not_your_own_key() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let other = "cd".repeat(48);
+    dig(&dir)
+        .args(["access", "allow", &other])
+        .assert()
+        .success();
+    let own = policy(&dir)["writers"][0].as_str().unwrap().to_string();
+
+    dig(&dir).args(["access", "revoke", &own]).assert().code(2);
+    dig(&dir)
+        .args(["access", "revoke", &other])
+        .assert()
+        .success();
+    assert_eq!(policy(&dir)["writers"].as_array().unwrap().len(), 1);
+    dig(&dir).args(["access", "clear"]).assert().success();
+    assert_eq!(policy(&dir)["restricted"], false);
+}
@@ -128,3 +128,154 @@ fn add_key_with_multiple_paths_errors() {
         .assert()
         .failure();
 }
+
+#[test]
+fn add_all_honors_the_global_excludes_file_beneath_digignore() {
+    let d = tmp_dig();
+    std::fs::write(d.path().join("a.txt"), b"a").unwrap();
+    std::fs::write(d.path().join("a.txt.swp"), b"swap").unwrap();
+    std::fs::write(d.path().join("keep.swp"), b"keep").unwrap();
+    std::fs::write(d.path().join("notes.bak"), b"bak").unwrap();
+    std::fs::write(d.path().join(".digignore"), "!keep.swp\n").unwrap();
+    init(d.path());
+    // `dig_in` points DIGSTORE_HOME at <dir>/.dighome; its `ignore` file is the
+    // default global excludes file.
+    let home = d.path().join(".dighome");
+    std::fs::write(home.join("ignore"), "*.swp\n").unwrap();
+
+    let staged = |d: &tempfile::TempDir| -> Vec<String> {
+        let out = dig_in(d.path())
+            .args(["--json", "add", "-A", "--dry-run"])
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "add -A failed: {:?}", out);
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        v["staged"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x.as_str().unwrap().to_string())
+            .collect()
+    };
+    let keys = staged(&d);
+    assert!(keys.contains(&"a.txt".to_string()), "staged = {keys:?}");
+    assert!(
+        !keys.contains(&"a.txt.swp".to_string()),
+        "staged = {keys:?}"
+    );
+    // The store's own .digignore outranks the global file.
+    assert!(keys.contains(&"keep.swp".to_string()), "staged = {keys:?}");
+    assert!(keys.contains(&"notes.bak".to_string()), "staged = {keys:?}");
+
+    // core.excludesFile replaces the default location.
+    let custom = d.path().join("my-excludes");
+    std::fs::write(&custom, "*.bak\n").unwrap();
+    std::fs::write(
+        home.join("config.toml"),
+        format!(
+            "coinset_url = \"https://api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n\n[core]\nexcludesFile = {:?}\n",
+            custom.display().to_string()
+        ),
+    )
+    .unwrap();
+    let keys = staged(&d);
+    assert!(
+        !keys.contains(&"notes.bak".to_string()),
+        "staged = {keys:?}"
+    );
+    assert!(keys.contains(&"a.txt.swp".to_string()), "staged = {keys:?}");
+}
+
+#[cfg(unix)]
+#[test]
+fn add_from_stdin_null_keeps_newlines_in_names() {
+    let d = tmp_dig();
+    std::fs::write(d.path().join("odd\nname.txt"), b"x").unwrap();
+    std::fs::write(d.path().join("plain.txt"), b"y").unwrap();
+    init(d.path());
+    let out = dig_in(d.path())
+        .args(["--json", "add", "--from-stdin", "-0"])
+        .write_stdin("odd\nname.txt\0plain.txt\0")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "add --from-stdin -0 failed: {:?}",
+        out
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(
+        v["staged"],
+        serde_json::json!(["odd\nname.txt", "plain.txt"])
+    );
+
+    // Newline-delimited input splits that name in two, neither of which exists.
+    let out = dig_in(d.path())
+        .args(["add", "--from-stdin"])
+        .write_stdin("odd\nname.txt\n")
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+#[test]
+fn add_stdin_as_stages_piped_output_under_a_virtual_path() {
+    let d = tmp_dig();
+    init(d.path());
+    let out = dig_in(d.path())
+        .args(["--json", "add", "--stdin-as", "reports/latest.txt"])
+        .write_stdin("generated report\n")
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "add --stdin-as failed: {:?}", out);
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["staged"], serde_json::json!(["reports/latest.txt"]));
+    assert_eq!(v["staged_bytes"], 17);
+    assert!(!d.path().join("reports").exists());
+
+    dig_in(d.path())
+        .args(["add", "--stdin-as", "../outside.txt"])
+        .write_stdin("x")
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn add_tar_stages_archive_files_and_warns_about_links() {
+    let d = tmp_dig();
+    init(d.path());
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(0o644);
+    header.set_size(5);
+    builder
+        .append_data(&mut header, "site/index.html", &b"hello"[..])
+        .unwrap();
+    let mut link = tar::Header::new_gnu();
+    link.set_entry_type(tar::EntryType::Symlink);
+    link.set_size(0);
+    builder
+        .append_link(&mut link, "site/home.html", "index.html")
+        .unwrap();
+    let archive = builder.into_inner().unwrap();
+
+    let out = dig_in(d.path())
+        .args(["--json", "add", "--tar", "-"])
+        .write_stdin(archive)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "add --tar failed: {:?}", out);
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["staged"], serde_json::json!(["site/index.html"]));
+    assert_eq!(v["skipped"][0]["kind"], "symlink");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("skipped symlink site/home.html"));
+    assert!(!d.path().join("site").exists());
+
+    std::fs::write(d.path().join("junk.tar"), b"not an archive").unwrap();
+    dig_in(d.path())
+        .args(["add", "--tar", "junk.tar"])
+        .assert()
+        .failure()
+        .code(2);
+}
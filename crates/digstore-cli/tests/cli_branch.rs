@@ -0,0 +1,81 @@
+mod common;
+use common::{dig, tmp_dig};
+
+fn commit_file(dir: &tempfile::TempDir, key: &str, content: &str) {
+    let f = dir.path().join(key);
+    std::fs::write(&f, content).unwrap();
+    dig(dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", key])
+        .assert()
+        .success();
+    dig(dir).args(["commit"]).assert().success();
+}
+
+fn branches(dir: &tempfile::TempDir) -> serde_json::Value {
+    let out = dig(dir).args(["--json", "branch"]).output().unwrap();
+    assert!(out.status.success());
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+fn tip(v: &serde_json::Value, name: &str) -> String {
+    v["branches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|b| b["name"] == name)
+        .unwrap()["root"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn commits_advance_only_the_current_branch() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_file(&dir, "a.txt", "first");
+    let base = tip(&branches(&dir), "main");
+
+    dig(&dir).args(["branch", "experiment"]).assert().success();
+    dig(&dir)
+        .args(["checkout", "experiment"])
+        .assert()
+        .success();
+    commit_file(&dir, "b.txt", "on the experiment");
+
+    let v = branches(&dir);
+    assert_eq!(v["current"], "experiment");
+    assert_eq!(tip(&v, "main"), base);
+    let experiment = tip(&v, "experiment");
+    assert_ne!(experiment, base);
+
+    dig(&dir).args(["checkout", "main"]).assert().success();
+    commit_file(&dir, "c.txt", "on main");
+    let v = branches(&dir);
+    assert_eq!(v["current"], "main");
+    assert_eq!(tip(&v, "experiment"), experiment);
+    assert_ne!(tip(&v, "main"), base);
+
+    // A branch name also works where a root is expected.
+    let out_dir = dir.path().join("out");
+    dig(&dir)
+        .args(["checkout", "experiment", "--out"])
+        .arg(&out_dir)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(out_dir.join("b.txt")).unwrap(),
+        "on the experiment"
+    );
+}
+
+#[test]
+fn switching_to_a_missing_branch_fails() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_file(&dir, "a.txt", "first");
+    dig(&dir).args(["checkout", "nope"]).assert().failure();
+    dig(&dir).args(["branch", "main"]).assert().failure();
+}
@@ -139,3 +139,328 @@ fn cat_unknown_resource_decoy_fails_verification_exit_5() {
     let urn = format!("urn:dig:chia:{}:{}/does-not-exist", store_id, root);
     dig(&dir).args(["cat", &urn]).assert().failure().code(5);
 }
+
+#[test]
+fn cat_bytes_and_lines_select_a_range() {
+    let dir = tmp_dig();
+    let content = b"alpha\nbravo\ncharlie\ndelta\necho\n";
+    let f = dir.path().join("doc.txt");
+    std::fs::write(&f, content).unwrap();
+    dig(&dir).arg("init").assert().success();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "doc"])
+        .assert()
+        .success();
+    dig(&dir).args(["commit"]).assert().success();
+    let (store_id, root) = store_id_and_root(&dir);
+    let urn = format!("urn:dig:chia:{}:{}/doc", store_id, root);
+
+    let out = dig(&dir)
+        .args(["cat", &urn, "--bytes", "6-10"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"bravo");
+
+    let out = dig(&dir)
+        .args(["cat", &urn, "--bytes", "-5"])
+        .output()
+        .unwrap();
+    assert_eq!(out.stdout, b"echo\n");
+
+    let out = dig(&dir)
+        .args(["cat", &urn, "--lines", "2:3", "--number"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"     2\tbravo\n     3\tcharlie\n");
+
+    // A range starting past the end is an invalid argument (exit 2).
+    dig(&dir)
+        .args(["cat", &urn, "--bytes", "500-"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn cat_binary_is_byte_exact_on_stdout_and_base64_in_json() {
+    use base64::Engine;
+
+    let dir = tmp_dig();
+    let content: Vec<u8> = (0..=255u8)
+        .chain([0x89, b'P', b'N', b'G', 0, b'\n'])
+        .collect();
+    let f = dir.path().join("image.png");
+    std::fs::write(&f, &content).unwrap();
+    dig(&dir).arg("init").assert().success();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "image.png"])
+        .assert()
+        .success();
+    dig(&dir).args(["commit"]).assert().success();
+    let (store_id, root) = store_id_and_root(&dir);
+    let urn = format!("urn:dig:chia:{}:{}/image.png", store_id, root);
+
+    // Piped stdout and an explicit `--out -` both carry the raw bytes.
+    let out = dig(&dir).args(["cat", &urn]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, content);
+    let out = dig(&dir)
+        .args(["cat", &urn, "--out", "-"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, content);
+
+    let out = dig(&dir).args(["--json", "cat", &urn]).output().unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["encoding"], "base64");
+    assert_eq!(v["bytes"], content.len());
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(v["content"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(decoded, content);
+}
+
+#[test]
+fn cat_rootless_urn_reads_the_latest_deployment() {
+    // A URN without a root resolves against the store's current root, so `cat`
+    // follows new commits of the same resource.
+    let dir = tmp_dig();
+    let f = dir.path().join("doc.txt");
+    std::fs::write(&f, b"first version").unwrap();
+    dig(&dir).arg("init").assert().success();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "doc"])
+        .assert()
+        .success();
+    dig(&dir).args(["commit"]).assert().success();
+
+    std::fs::write(&f, b"second version").unwrap();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "doc"])
+        .assert()
+        .success();
+    dig(&dir).args(["commit"]).assert().success();
+
+    let (store_id, _) = store_id_and_root(&dir);
+    let urn = format!("urn:dig:chia:{}/doc", store_id);
+    let out = dig(&dir).args(["cat", &urn]).output().unwrap();
+    assert!(
+        out.status.success(),
+        "cat failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(out.stdout, b"second version");
+}
+
+#[test]
+fn cat_glob_urn_writes_matches_to_out_dir() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    for (key, body) in [
+        ("src/lib.rs", "lib"),
+        ("src/bin/main.rs", "main"),
+        ("README.md", "readme"),
+    ] {
+        let f = dir.path().join(key.replace('/', "_"));
+        std::fs::write(&f, body).unwrap();
+        dig(&dir)
+            .args(["add"])
+            .arg(&f)
+            .args(["--key", key])
+            .assert()
+            .success();
+    }
+    dig(&dir).args(["commit"]).assert().success();
+    let (store_id, root) = store_id_and_root(&dir);
+    let urn = format!("urn:dig:chia:{}:{}/src/**/*.rs", store_id, root);
+
+    // --json without --out-dir lists the matches.
+    let out = dig(&dir).args(["--json", "cat", &urn]).output().unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(
+        v["matched"],
+        serde_json::json!(["src/bin/main.rs", "src/lib.rs"])
+    );
+
+    let dest = dir.path().join("restored");
+    dig(&dir)
+        .args(["cat", &urn, "--out-dir"])
+        .arg(&dest)
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(dest.join("src/lib.rs")).unwrap(), b"lib");
+    assert_eq!(
+        std::fs::read(dest.join("src/bin/main.rs")).unwrap(),
+        b"main"
+    );
+    assert!(!dest.join("README.md").exists());
+
+    // A pattern that matches nothing is a not-found error.
+    let none = format!("urn:dig:chia:{}:{}/*.zip", store_id, root);
+    dig(&dir)
+        .args(["cat", &none, "--out-dir"])
+        .arg(&dest)
+        .assert()
+        .failure()
+        .code(4);
+}
+
+#[test]
+fn cat_batch_restores_listed_keys_into_out_dir() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    for (key, body) in [("a/one.txt", "one"), ("b/two.txt", "two"), ("c.txt", "c")] {
+        let f = dir.path().join(key.replace('/', "_"));
+        std::fs::write(&f, body).unwrap();
+        dig(&dir)
+            .args(["add"])
+            .arg(&f)
+            .args(["--key", key])
+            .assert()
+            .success();
+    }
+    dig(&dir).args(["commit"]).assert().success();
+    let (store_id, root) = store_id_and_root(&dir);
+    let list = dir.path().join("paths.txt");
+    std::fs::write(&list, "# restore these\na/one.txt\n\n  b/two.txt  \n").unwrap();
+
+    let dest = dir.path().join("restore");
+    let out = dig(&dir)
+        .args(["--json", "cat", &format!("urn:dig:chia:{store_id}:{root}")])
+        .arg("--batch")
+        .arg(&list)
+        .arg("--out-dir")
+        .arg(&dest)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["matched"], serde_json::json!(["a/one.txt", "b/two.txt"]));
+    assert_eq!(v["bytes"], 6);
+    assert_eq!(std::fs::read(dest.join("a/one.txt")).unwrap(), b"one");
+    assert_eq!(std::fs::read(dest.join("b/two.txt")).unwrap(), b"two");
+    assert!(!dest.join("c.txt").exists());
+
+    // --batch needs --out-dir.
+    dig(&dir)
+        .args(["cat", &format!("urn:dig:chia:{store_id}:{root}"), "--batch"])
+        .arg(&list)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cat_at_reads_a_hash_named_path_at_an_older_version() {
+    // With --at the argument is always a resource path, so a file literally
+    // named like a 64-hex hash is not taken for a retrieval key.
+    let dir = tmp_dig();
+    let name = "deadbeef".repeat(8);
+    let f = dir.path().join("f.txt");
+    dig(&dir).arg("init").assert().success();
+    for body in ["first version", "second version"] {
+        std::fs::write(&f, body).unwrap();
+        dig(&dir)
+            .args(["add"])
+            .arg(&f)
+            .args(["--key", &name])
+            .assert()
+            .success();
+        dig(&dir).args(["commit"]).assert().success();
+    }
+    let (_, head) = store_id_and_root(&dir);
+
+    let cat_at = |at: &str| {
+        let out = dig(&dir).args(["cat", &name, "--at", at]).output().unwrap();
+        assert!(
+            out.status.success(),
+            "cat --at {at} failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        out.stdout
+    };
+    assert_eq!(cat_at("HEAD"), b"second version");
+    assert_eq!(cat_at(&head), b"second version");
+    assert_eq!(cat_at(&head[..12]), b"second version");
+    assert_eq!(cat_at("HEAD~1"), b"first version");
+
+    dig(&dir)
+        .args(["cat", &name, "--at", "HEAD~2"])
+        .assert()
+        .failure();
+    // Without --at the same argument is a retrieval key, which nothing has.
+    dig(&dir).args(["cat", &name]).assert().failure();
+}
+
+#[test]
+fn cat_all_versions_writes_each_version_and_a_manifest() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let f = dir.path().join("notes.txt");
+    for content in ["first", "second"] {
+        std::fs::write(&f, content).unwrap();
+        dig(&dir)
+            .arg("add")
+            .arg(&f)
+            .args(["--key", "docs/notes.txt"])
+            .assert()
+            .success();
+        dig(&dir).arg("commit").assert().success();
+    }
+
+    let out_dir = dir.path().join("versions");
+    let out = dig(&dir)
+        .args([
+            "--json",
+            "cat",
+            "docs/notes.txt",
+            "--all-versions",
+            "--out-dir",
+        ])
+        .arg(&out_dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let versions = v["versions"].as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    for (entry, content) in versions.iter().zip(["first", "second"]) {
+        let file = entry["file"].as_str().unwrap();
+        let hash = entry["hash"].as_str().unwrap();
+        assert_eq!(file, format!("docs/notes.txt.{}", &hash[..8]));
+        assert_eq!(
+            std::fs::read(out_dir.join(file)).unwrap(),
+            content.as_bytes()
+        );
+    }
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(out_dir.join("versions.json")).unwrap()).unwrap();
+    assert_eq!(&manifest, &v["versions"]);
+
+    let missing = dig(&dir)
+        .args(["cat", "nope.txt", "--all-versions", "--out-dir"])
+        .arg(&out_dir)
+        .output()
+        .unwrap();
+    assert_eq!(missing.status.code(), Some(4));
+}
@@ -31,3 +31,40 @@ fn checkout_materializes_generation() {
         .success();
     assert_eq!(std::fs::read(out_dir.join("file.txt")).unwrap(), content);
 }
+
+#[test]
+fn checkout_to_tar_streams_the_version_as_an_archive() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::create_dir_all(dir.path().join("docs")).unwrap();
+    std::fs::write(dir.path().join("docs/guide.md"), b"# guide").unwrap();
+    std::fs::write(dir.path().join("index.html"), b"<p>home</p>").unwrap();
+    dig(&dir)
+        .args(["add", "docs/guide.md", "index.html"])
+        .assert()
+        .success();
+    dig(&dir).args(["commit"]).assert().success();
+    let root = root_hex(&dir);
+
+    let out = dig(&dir)
+        .args(["checkout", &root, "--to-tar", "-"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "checkout --to-tar failed: {:?}", out);
+    let mut archive = tar::Archive::new(out.stdout.as_slice());
+    let mut files = Vec::new();
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().into_owned();
+        let mut body = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut body).unwrap();
+        files.push((path, body));
+    }
+    assert_eq!(
+        files,
+        [
+            ("docs/guide.md".to_string(), "# guide".to_string()),
+            ("index.html".to_string(), "<p>home</p>".to_string())
+        ]
+    );
+}
@@ -87,3 +87,46 @@ fn dry_run_empty_staging_errors() {
         .failure()
         .stderr(predicate::str::contains("nothing staged"));
 }
+
+/// The dry run (and `staged --estimate`) say how much the commit would add to
+/// disk; a second file whose content is already stored adds nothing new.
+#[test]
+fn dry_run_estimates_new_chunks_after_dedup() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let f = dir.path().join("a.txt");
+    std::fs::write(&f, b"alpha beta gamma").unwrap();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "a"])
+        .assert()
+        .success();
+
+    let json = |args: &[&str]| -> serde_json::Value {
+        let out = dig(&dir).args(args).arg("--json").output().unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    let e = json(&["commit", "--dry-run"])["estimate"].clone();
+    assert_eq!(e["files"], 1);
+    assert_eq!(e["logical_bytes"], 16);
+    assert_eq!(e["new_chunk_count"], 1);
+    assert!(e["new_bytes"].as_u64().unwrap() > 0);
+    assert_eq!(json(&["staged", "--estimate"])["estimate"], e);
+
+    dig(&dir).args(["commit"]).assert().success();
+    dig(&dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", "a"])
+        .assert()
+        .success();
+    let e = json(&["staged", "--estimate"])["estimate"].clone();
+    assert_eq!(e["new_chunk_count"], 0);
+    assert_eq!(e["new_bytes"], 0);
+}
@@ -93,6 +93,303 @@ fn commit_unchanged_content_is_rejected_not_reanchored() {
     );
 }
 
+/// `--allow-empty` records a checkpoint of the head when nothing is staged: a new
+/// local `log` entry, with its own message, for the same root. Without a head
+/// there is still nothing to commit.
+#[test]
+fn commit_allow_empty_records_a_checkpoint_of_the_head() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    dig(&dir)
+        .args(["commit", "--allow-empty"])
+        .assert()
+        .failure()
+        .code(2);
+    std::fs::write(dir.path().join("a.txt"), b"release bytes").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir).args(["commit", "-m", "g1"]).assert().success();
+
+    let anchor_toml = common::store_dir(&dir).join("anchor.toml");
+    let anchored = std::fs::read(&anchor_toml).unwrap();
+
+    // Checkpoints are never anchored: a wallet that could not pay for an
+    // update does not stop them.
+    for message in ["checkpoint 1", "checkpoint 2"] {
+        dig(&dir)
+            .env("DIGSTORE_ANCHOR_MOCK_BALANCE", "0")
+            .env("DIGSTORE_ANCHOR_MOCK_DIG", "0")
+            .args(["commit", "--allow-empty", "-m", message])
+            .assert()
+            .success();
+    }
+    assert_eq!(std::fs::read(&anchor_toml).unwrap(), anchored);
+    let out = dig(&dir).args(["log", "--json"]).output().unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let log = v.as_array().unwrap();
+    assert_eq!(log.len(), 3);
+    assert_eq!(log[0]["root"], log[2]["root"]);
+    assert_eq!(log[1]["root"], log[2]["root"]);
+    let messages: Vec<&str> = log.iter().map(|e| e["message"].as_str().unwrap()).collect();
+    assert_eq!(messages, ["checkpoint 2", "checkpoint 1", "g1"]);
+}
+
+/// `--changes-only` commits just the staged files as a layer on the head: `ls`
+/// and `cat` of any other file fall through to the parent.
+#[test]
+fn commit_changes_only_layers_the_staged_files_on_the_head() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"alpha").unwrap();
+    std::fs::write(dir.path().join("b.txt"), b"bravo").unwrap();
+    dig(&dir).args(["add", "a.txt", "b.txt"]).assert().success();
+    dig(&dir).args(["commit", "-m", "base"]).assert().success();
+
+    std::fs::write(dir.path().join("a.txt"), b"alpha v2").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "--no-cumulative", "-m", "layer"])
+        .assert()
+        .success();
+
+    dig(&dir)
+        .arg("ls")
+        .assert()
+        .success()
+        .stdout("a.txt\nb.txt\n");
+    dig(&dir)
+        .args(["cat", "a.txt", "--at", "HEAD"])
+        .assert()
+        .success()
+        .stdout("alpha v2");
+    dig(&dir)
+        .args(["cat", "b.txt", "--at", "HEAD"])
+        .assert()
+        .success()
+        .stdout("bravo");
+}
+
+/// A `--changes-only` layer is never anchored: with a wallet that could not pay
+/// for an update the commit still succeeds, and `anchor.toml` keeps the last
+/// anchored root.
+#[test]
+fn commit_changes_only_never_calls_the_anchor_backend() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"alpha").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir).args(["commit", "-m", "base"]).assert().success();
+    let anchor_toml = common::store_dir(&dir).join("anchor.toml");
+    let anchored = std::fs::read(&anchor_toml).unwrap();
+
+    std::fs::write(dir.path().join("a.txt"), b"alpha v2").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    let out = dig(&dir)
+        .env("DIGSTORE_ANCHOR_MOCK_BALANCE", "0")
+        .env("DIGSTORE_ANCHOR_MOCK_DIG", "0")
+        .args(["--json", "commit", "--changes-only", "-m", "layer"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["anchor_status"], "local");
+    assert_eq!(std::fs::read(&anchor_toml).unwrap(), anchored);
+}
+
+/// A `--changes-only` layer is local-only: its module holds only the staged
+/// files, so `push` refuses it and the remote keeps serving the full version.
+#[test]
+fn push_refuses_a_changes_only_layer() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let pk = host_pubkey(&dir);
+    let cfg = std::fs::read_to_string(common::store_dir(&dir).join("config.toml")).unwrap();
+    let store_id = cfg
+        .lines()
+        .find(|l| l.contains("store_id"))
+        .unwrap()
+        .split('"')
+        .nth(1)
+        .unwrap()
+        .to_string();
+    let server = TestServer::start_empty(&store_id, pk);
+    let store_url = format!("{}/stores/{}", server.base_url(), store_id);
+    dig(&dir)
+        .args(["remote", "add", "origin", &store_url])
+        .assert()
+        .success();
+
+    std::fs::write(dir.path().join("a.txt"), b"alpha").unwrap();
+    std::fs::write(dir.path().join("b.txt"), b"bravo").unwrap();
+    dig(&dir).args(["add", "a.txt", "b.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "-m", "base", "--push"])
+        .assert()
+        .success();
+    let (_, base) = store_id_and_root(&dir);
+
+    std::fs::write(dir.path().join("a.txt"), b"alpha v2").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "--changes-only", "--push", "-m", "layer"])
+        .assert()
+        .failure();
+    dig(&dir)
+        .args(["commit", "--changes-only", "-m", "layer"])
+        .assert()
+        .success();
+    dig(&dir)
+        .args(["push", "origin"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--changes-only"));
+    assert_eq!(
+        server_served_root(&server, &store_id).as_deref(),
+        Some(base.as_str())
+    );
+}
+
+/// `blame` credits each line to the commit (and its author) that added it, and
+/// refuses binary files.
+#[test]
+fn blame_credits_each_line_to_its_commit() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let f = dir.path().join("notes.txt");
+    std::fs::write(&f, "one\ntwo\n").unwrap();
+    dig(&dir).args(["add", "notes.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "-m", "first", "--author", "ann"])
+        .assert()
+        .success();
+    std::fs::write(&f, "one\n2\n").unwrap();
+    dig(&dir).args(["add", "notes.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "-m", "second", "--author", "bob"])
+        .assert()
+        .success();
+
+    let out = dig(&dir)
+        .args(["--json", "blame", "notes.txt"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let lines: Vec<(&str, &str)> = v
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| (l["text"].as_str().unwrap(), l["author"].as_str().unwrap()))
+        .collect();
+    assert_eq!(lines, [("one", "ann"), ("2", "bob")]);
+
+    std::fs::write(dir.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0data").unwrap();
+    dig(&dir).args(["add", "logo.png"]).assert().success();
+    dig(&dir).args(["commit", "-m", "logo"]).assert().success();
+    dig(&dir)
+        .args(["blame", "logo.png"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("binary"));
+    dig(&dir).args(["blame", "nope.txt"]).assert().code(4);
+}
+
+fn copy_tree(from: &std::path::Path, to: &std::path::Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let dest = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_tree(&entry.path(), &dest);
+        } else {
+            std::fs::copy(entry.path(), &dest).unwrap();
+        }
+    }
+}
+
+/// `--deterministic` reproduces a commit exactly: two copies of one store that
+/// commit the same files (added in a different order, with a different message
+/// and author) at the same `--date` get the same root and generation manifest.
+#[test]
+fn deterministic_commits_of_the_same_tree_are_identical() {
+    let a = tmp_dig();
+    dig(&a).arg("init").assert().success();
+    let b = tmp_dig();
+    copy_tree(&a.path().join(".dig"), &b.path().join(".dig"));
+
+    for (dir, order, who) in [
+        (&a, ["x.txt", "y.txt"], "alice"),
+        (&b, ["y.txt", "x.txt"], "bob"),
+    ] {
+        std::fs::write(dir.path().join("x.txt"), b"x bytes").unwrap();
+        std::fs::write(dir.path().join("y.txt"), b"y bytes").unwrap();
+        for f in order {
+            dig(dir).args(["add", f]).assert().success();
+        }
+        dig(dir)
+            .args([
+                "commit",
+                "--deterministic",
+                "--date",
+                "2024-05-01T00:00:00Z",
+            ])
+            .args(["-m", who, "--author", who])
+            .assert()
+            .success();
+    }
+
+    let (_, root_a) = store_id_and_root(&a);
+    let (_, root_b) = store_id_and_root(&b);
+    assert_eq!(root_a, root_b);
+    let manifest = |dir: &TempDir| {
+        std::fs::read(
+            common::store_dir(dir)
+                .join("generations")
+                .join(&root_a)
+                .join("manifest.json"),
+        )
+        .unwrap()
+    };
+    assert_eq!(manifest(&a), manifest(&b));
+}
+
+/// A failing `pre-commit` hook in `dig.toml` aborts the commit with
+/// `HOOK_FAILED` before anything is anchored; `--no-verify` skips it.
+#[test]
+fn failing_pre_commit_hook_blocks_the_commit_unless_no_verify() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(
+        dir.path().join("dig.toml"),
+        "[hooks]\npre-commit = \"exit 3\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("a.txt"), b"x").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+
+    dig(&dir)
+        .args(["commit", "-m", "g1"])
+        .assert()
+        .failure()
+        .code(17)
+        .stderr(predicate::str::contains("--no-verify"));
+    let out = dig(&dir).args(["log", "--json"]).output().unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(v.as_array().unwrap().is_empty());
+
+    dig(&dir)
+        .args(["commit", "--no-verify", "-m", "g1"])
+        .assert()
+        .success();
+}
+
 /// Committing on a store whose INITIAL mint never confirmed (pending init) must be
 /// refused with a clear pointer to `digstore anchor`, not a confusing chain error —
 /// and must not finalize any generation. (On a real chain the update would fail at
@@ -588,3 +885,64 @@ fn commit_help_mentions_capsule() {
         "`commit --help` should mention 'capsule'; got:\n{out}"
     );
 }
+
+#[test]
+fn log_json_carries_message_author_and_file_count() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"alpha").unwrap();
+    std::fs::write(dir.path().join("b.txt"), b"beta").unwrap();
+    dig(&dir).args(["add", "a.txt", "b.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "-m", "first\nsecond line", "--author", "alice"])
+        .assert()
+        .success();
+
+    let out = dig(&dir).args(["--json", "log"]).output().unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let head = &v[0];
+    assert_eq!(head["id"], 0);
+    assert_eq!(head["message"], "first\nsecond line");
+    assert_eq!(head["author"], "alice");
+    assert!(head["files"].as_u64().unwrap() >= 2);
+    assert!(head["timestamp"].as_u64().unwrap() > 0);
+
+    dig(&dir)
+        .arg("log")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("author: alice"))
+        .stdout(predicate::str::contains("    second line"));
+}
+
+#[test]
+fn commit_date_round_trips_through_log_json_with_its_offset() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"imported").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir)
+        .args(["commit", "-m", "import", "--date", "@1600000000 -0700"])
+        .assert()
+        .success();
+
+    let out = dig(&dir).args(["--json", "log"]).output().unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v[0]["date"], "2020-09-13T05:26:40-07:00");
+
+    // Date filters follow the recorded date, not the wall-clock commit time.
+    let out = dig(&dir)
+        .args(["--json", "log", "--until", "2020-12-31"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v.as_array().unwrap().len(), 1);
+
+    dig(&dir)
+        .args(["commit", "--date", "not a date"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("invalid date"));
+}
@@ -0,0 +1,47 @@
+mod common;
+use common::{dig, tmp_dig};
+use predicates::prelude::*;
+
+/// `cat <retrieval-key>` hands out the sealed bytes; `decrypt --urn` opens
+/// them again, splitting a multi-chunk blob with the local manifest.
+#[test]
+fn decrypt_opens_the_sealed_bytes_cat_hands_out_by_retrieval_key() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let content: Vec<u8> = (0..600_000u32).map(|i| (i * 7919 % 251) as u8).collect();
+    std::fs::write(dir.path().join("big.bin"), &content).unwrap();
+    dig(&dir).args(["add", "big.bin"]).assert().success();
+    dig(&dir).args(["commit", "-m", "big"]).assert().success();
+
+    let out = dig(&dir)
+        .args(["urn", "big.bin", "--json"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let urn = v[0]["urn"].as_str().unwrap().to_string();
+    let rk = v[0]["retrieval_key"].as_str().unwrap().to_string();
+
+    dig(&dir)
+        .args(["cat", &rk, "--out", "blob.enc"])
+        .assert()
+        .success();
+    let sealed = std::fs::read(dir.path().join("blob.enc")).unwrap();
+    assert_ne!(sealed, content);
+
+    dig(&dir)
+        .args(["decrypt", "blob.enc", "--urn", &urn, "--out", "plain.bin"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read(dir.path().join("plain.bin")).unwrap(),
+        content
+    );
+
+    // The key comes from the URN: another resource's URN cannot open it.
+    let other = urn.replace("big.bin", "other.bin");
+    dig(&dir)
+        .args(["decrypt", "blob.enc", "--urn", &other])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tag verification failed"));
+}
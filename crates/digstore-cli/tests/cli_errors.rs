@@ -14,3 +14,91 @@ fn missing_store_shows_help_hint() {
         .failure()
         .stderr(predicate::str::contains("error:").and(predicate::str::contains("digstore init")));
 }
+
+#[test]
+fn cat_strict_names_the_malformed_urn_field() {
+    let d = tmp_dig();
+    common::dig(&d).arg("init").assert().success();
+    common::dig(&d)
+        .args(["cat", "--strict", "urn:dig:chia:0123456789/readme"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "store ID must be 64 hex chars, got 10",
+        ));
+}
+
+#[test]
+fn a_damaged_store_exits_with_its_own_code() {
+    let d = tmp_dig();
+    common::dig(&d).arg("init").assert().success();
+    let f = d.path().join("a.txt");
+    std::fs::write(&f, b"alpha").unwrap();
+    common::dig(&d)
+        .arg("add")
+        .arg(&f)
+        .args(["--key", "a.txt"])
+        .assert()
+        .success();
+    common::dig(&d).arg("commit").assert().success();
+    let (_, root) = common::store_id_and_root(&d);
+    std::fs::remove_file(
+        common::store_dir(&d)
+            .join("generations")
+            .join(&root)
+            .join("manifest.json"),
+    )
+    .unwrap();
+
+    let out = common::dig(&d)
+        .args(["--json", "prune", "--keep-last", "1", "--dry-run"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(20));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["error"]["code"], "STORE_CORRUPTED");
+    assert!(v["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("manifest is missing"));
+}
+
+#[test]
+fn a_corrupt_staging_file_or_history_exits_as_a_damaged_store() {
+    let d = tmp_dig();
+    common::dig(&d).arg("init").assert().success();
+    let f = d.path().join("a.txt");
+    std::fs::write(&f, b"alpha").unwrap();
+    common::dig(&d)
+        .arg("add")
+        .arg(&f)
+        .args(["--key", "a.txt"])
+        .assert()
+        .success();
+
+    // Cut the last staged byte off: the record's payload is now truncated.
+    let staging = std::fs::read_dir(common::store_dir(&d))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.to_string_lossy().ends_with(".staging.bin"))
+        .unwrap();
+    let len = std::fs::metadata(&staging).unwrap().len();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&staging)
+        .unwrap()
+        .set_len(len - 1)
+        .unwrap();
+    let out = common::dig(&d).args(["--json", "staged"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(20));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["error"]["code"], "STORE_CORRUPTED");
+
+    std::fs::write(
+        common::store_dir(&d).join("roots.log"),
+        "not a history line\n",
+    )
+    .unwrap();
+    let out = common::dig(&d).args(["--json", "log"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(20));
+}
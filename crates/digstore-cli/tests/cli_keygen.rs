@@ -0,0 +1,71 @@
+mod common;
+use common::{dig, tmp_dig};
+use digstore_core::Urn;
+use predicates::prelude::*;
+
+fn urn(store: &str, path: &str) -> String {
+    format!("urn:dig:chia:{store}/{path}")
+}
+
+#[test]
+fn keygen_batch_keeps_input_order_and_matches_the_single_derivation() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let store = "ab".repeat(32);
+    let urns = [
+        urn(&store, "z.txt"),
+        urn(&store, "a.txt"),
+        format!("urn:dig:chia:{store}:{}/a.txt", "cd".repeat(32)),
+    ];
+    std::fs::write(
+        dir.path().join("urns.txt"),
+        format!("# first two\n{}\n\n{}\n", urns[0], urns[1]),
+    )
+    .unwrap();
+
+    let out = dig(&dir)
+        .args([
+            "keygen",
+            "--json",
+            "--urns-file",
+            "urns.txt",
+            "--from-stdin",
+        ])
+        .write_stdin(format!("{}\n", urns[2]))
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let rows = v.as_array().unwrap();
+    assert_eq!(rows.len(), 3);
+    for (row, text) in rows.iter().zip(&urns) {
+        assert_eq!(row["urn"], text.as_str());
+    }
+
+    // Each row is what a single derivation gives; the root does not matter.
+    let rootless = Urn::parse(&urns[1]).unwrap();
+    let key = digstore_crypto::derive_decryption_key(&rootless.canonical(), None);
+    assert_eq!(
+        rows[1]["storage_address"],
+        rootless.retrieval_key().to_hex()
+    );
+    assert_eq!(rows[1]["encryption_key"], hex::encode(key));
+    assert_eq!(rows[2]["storage_address"], rows[1]["storage_address"]);
+    assert_ne!(rows[0]["storage_address"], rows[1]["storage_address"]);
+}
+
+#[test]
+fn keygen_rejects_a_urn_without_a_resource() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    dig(&dir)
+        .args(["keygen", &format!("urn:dig:chia:{}", "ab".repeat(32))])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("names no resource"));
+    dig(&dir)
+        .args(["keygen"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--urns-file"));
+}
@@ -0,0 +1,76 @@
+mod common;
+use common::{dig, store_dir, tmp_dig};
+
+#[test]
+fn writers_fail_fast_while_another_process_holds_the_store() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    let f = dir.path().join("a.txt");
+    std::fs::write(&f, b"alpha").unwrap();
+
+    // This test process stands in for a second digstore writing the store.
+    let held = digstore_store::StoreLock::acquire(store_dir(&dir)).unwrap();
+    let out = dig(&dir)
+        .args(["--json", "add"])
+        .arg(&f)
+        .args(["--key", "a.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(19));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["error"]["code"], "STORE_LOCKED");
+    let message = v["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains(&format!("PID {}", std::process::id())),
+        "{message}"
+    );
+
+    // Readers do not need the lock.
+    dig(&dir).arg("staged").assert().success();
+
+    // --force-unlock breaks it; afterwards the store is writable again.
+    dig(&dir)
+        .args(["--force-unlock", "add"])
+        .arg(&f)
+        .args(["--key", "a.txt"])
+        .assert()
+        .success();
+    drop(held);
+    dig(&dir).args(["commit"]).assert().success();
+}
+
+#[test]
+fn the_next_writer_rolls_back_an_interrupted_commit() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    std::fs::write(dir.path().join("a.txt"), b"alpha").unwrap();
+    dig(&dir).args(["add", "a.txt"]).assert().success();
+    dig(&dir).args(["commit", "-m", "g1"]).assert().success();
+    let (store_id, head) = common::store_id_and_root(&dir);
+
+    // A commit killed after `finalize` laid down its generation and module
+    // but before its roots.log entry landed.
+    let store = store_dir(&dir);
+    let cut = "77".repeat(32);
+    let generation = store.join("generations").join(&cut);
+    let module = store.join("modules").join(format!("{store_id}-{cut}.dig"));
+    std::fs::create_dir_all(&generation).unwrap();
+    std::fs::write(generation.join("manifest.json"), b"{").unwrap();
+    std::fs::write(&module, b"partial").unwrap();
+    std::fs::write(store.join("commit.pending"), &cut).unwrap();
+
+    // Readers leave it alone; the next writer rolls it back.
+    dig(&dir).arg("staged").assert().success();
+    assert!(generation.exists());
+    std::fs::write(dir.path().join("b.txt"), b"bravo").unwrap();
+    dig(&dir).args(["add", "b.txt"]).assert().success();
+    assert!(!generation.exists());
+    assert!(!module.exists());
+    assert!(!store.join("commit.pending").exists());
+
+    let out = dig(&dir).args(["log", "--json"]).output().unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(log.as_array().unwrap().len(), 1);
+    assert_eq!(log[0]["root"], head.as_str());
+    dig(&dir).args(["commit", "-m", "g2"]).assert().success();
+}
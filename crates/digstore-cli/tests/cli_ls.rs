@@ -0,0 +1,226 @@
+mod common;
+use common::{dig, store_id_and_root, tmp_dig};
+
+fn commit_files(dir: &tempfile::TempDir, files: &[(&str, &[u8])]) {
+    for (key, body) in files {
+        let f = dir.path().join(key.replace('/', "_"));
+        std::fs::write(&f, body).unwrap();
+        dig(dir)
+            .args(["add"])
+            .arg(&f)
+            .args(["--key", key])
+            .assert()
+            .success();
+    }
+    dig(dir).args(["commit"]).assert().success();
+}
+
+#[test]
+fn ls_lists_paths_long_and_tree() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(
+        &dir,
+        &[("index.html", b"<html/>"), ("assets/logo.svg", b"<svg/>!")],
+    );
+
+    let out = dig(&dir).arg("ls").output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "assets/logo.svg\nindex.html\n"
+    );
+
+    let out = dig(&dir).args(["ls", "--long"]).output().unwrap();
+    let long = String::from_utf8(out.stdout).unwrap();
+    let first = long.lines().next().unwrap();
+    assert!(first.trim_start().starts_with("7 "), "size column: {first}");
+    assert!(first.ends_with("assets/logo.svg"));
+
+    let out = dig(&dir).args(["ls", "--tree"]).output().unwrap();
+    let tree = String::from_utf8(out.stdout).unwrap();
+    assert!(tree.contains("assets/"), "{tree}");
+    assert!(tree.contains("└── logo.svg"), "{tree}");
+}
+
+#[test]
+fn ls_at_an_older_root_and_json() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(&dir, &[("old.txt", b"old")]);
+    let (_, first_root) = store_id_and_root(&dir);
+    commit_files(&dir, &[("new.txt", b"new")]);
+
+    let out = dig(&dir)
+        .args(["--json", "ls", "--at", &first_root])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let files = v.as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0]["path"], "old.txt");
+    assert_eq!(files[0]["size"], 3);
+}
+
+#[test]
+fn stat_reports_size_retrieval_key_and_chunks() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(&dir, &[("docs/readme.md", b"# hello stat")]);
+
+    let out = dig(&dir)
+        .args(["--json", "stat", "docs/readme.md"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["path"], "docs/readme.md");
+    assert_eq!(v["size"], 12);
+    assert_eq!(v["chunk_count"], 1);
+    assert_eq!(v["chunks"].as_array().unwrap().len(), 1);
+    assert_eq!(v["histogram"][0]["count"], 1);
+
+    let ls = dig(&dir).args(["--json", "ls"]).output().unwrap();
+    let ls: serde_json::Value = serde_json::from_slice(&ls.stdout).unwrap();
+    let listed = ls
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"] == "docs/readme.md")
+        .unwrap();
+    assert_eq!(v["retrieval_key"], listed["retrieval_key"]);
+
+    let out = dig(&dir).args(["stat", "docs/readme.md"]).output().unwrap();
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.contains("size           12 bytes"), "{text}");
+
+    dig(&dir)
+        .args(["stat", "missing.txt"])
+        .assert()
+        .failure()
+        .code(4);
+}
+
+#[test]
+fn stat_missing_at_a_root_points_at_one_that_has_it() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(&dir, &[("a.txt", b"alpha")]);
+    let (_, first) = store_id_and_root(&dir);
+    commit_files(&dir, &[("b.txt", b"bravo")]);
+    let (_, second) = store_id_and_root(&dir);
+
+    let out = dig(&dir)
+        .args(["stat", "b.txt", "--at", &first])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(4));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!("found at root {second}")),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn manifest_export_is_sorted_and_byte_identical_across_runs() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(&dir, &[("b.txt", b"bee"), ("a/one.txt", b"one")]);
+
+    let first = dig(&dir).arg("manifest").output().unwrap();
+    assert!(first.status.success());
+    let second = dig(&dir).arg("manifest").output().unwrap();
+    assert_eq!(first.stdout, second.stdout);
+
+    let v: serde_json::Value = serde_json::from_slice(&first.stdout).unwrap();
+    let paths: Vec<&str> = v["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert!(paths.contains(&"a/one.txt") && paths.contains(&"b.txt"));
+
+    let out = dir.path().join("snapshot.json");
+    dig(&dir)
+        .args(["manifest", "--out"])
+        .arg(&out)
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(&out).unwrap(), first.stdout);
+}
+
+#[test]
+fn sign_then_verify_signature_reports_the_signer() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(&dir, &[("index.html", b"<h1>signed</h1>")]);
+    let (_, root) = store_id_and_root(&dir);
+
+    // Nothing signed yet: verification fails with exit 5.
+    dig(&dir).args(["verify-signature", &root]).assert().code(5);
+
+    let out = dig(&dir).args(["--json", "sign"]).output().unwrap();
+    assert!(out.status.success());
+    let signed: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(signed["root"], root.as_str());
+    let signer = signed["public_key"].as_str().unwrap().to_string();
+
+    let out = dig(&dir)
+        .args(["--json", "verify-signature", &root, "--pubkey", &signer])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["verified"], true);
+    assert_eq!(v["signatures"][0]["public_key"], signer.as_str());
+    assert_eq!(v["signatures"][0]["valid"], true);
+
+    dig(&dir)
+        .args(["verify-signature", &root, "--pubkey", &"ab".repeat(48)])
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn grep_prints_matches_with_paths_and_line_numbers() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_files(
+        &dir,
+        &[
+            ("src/main.rs", b"fn main() {\n    // TODO: args\n}\n"),
+            ("README.md", b"# demo\nTODO: docs\n"),
+            ("logo.bin", b"TODO\0\x89PNG"),
+        ],
+    );
+
+    let out = dig(&dir).args(["grep", "-n", "TODO"]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "README.md:2:TODO: docs\nsrc/main.rs:2:    // TODO: args\n"
+    );
+
+    let out = dig(&dir)
+        .args(["grep", "-l", "--text", "TODO"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "README.md\nlogo.bin\nsrc/main.rs\n"
+    );
+
+    let out = dig(&dir)
+        .args(["--json", "grep", "fn main"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v[0]["path"], "src/main.rs");
+    assert_eq!(v[0]["line_number"], 1);
+}
@@ -1,6 +1,7 @@
 //! Multi-store workspace integration coverage (assert_cmd): store listing &
 //! switching, `--store` isolation, the per-store stage cap, `unstage`/`staged`,
-//! the per-store content root + rootless URN stability, and legacy migration.
+//! the per-store content root + rootless URN stability, legacy migration, and
+//! the `stores info --json` schema.
 //!
 //! These drive the real `digstore` binary against a temp project whose workspace
 //! lives at `<project>/.dig` (so the on-disk layout — `.dig/stores/<name>/...`,
@@ -374,3 +375,76 @@ fn legacy_flat_layout_is_migrated_into_default_store() {
         "migration must create .dig/workspace.toml"
     );
 }
+
+#[test]
+fn stores_info_json_is_one_object_with_every_section() {
+    let tmp = TempDir::new().unwrap();
+    dig_at(tmp.path()).args(["init", "a"]).assert().success();
+    dig_at(tmp.path()).args(["init", "b"]).assert().success();
+    std::fs::write(tmp.path().join("index.html"), b"<p>hi</p>").unwrap();
+    dig_at(tmp.path())
+        .args(["add", "index.html"])
+        .assert()
+        .success();
+    dig_at(tmp.path())
+        .args(["commit", "-m", "x"])
+        .assert()
+        .success();
+
+    let out = dig_at(tmp.path())
+        .args(["stores", "info", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "stores info --json failed: {out:?}");
+    let v = json_stdout(&out);
+    assert_eq!(v["name"], "a");
+    assert_eq!(v["active"], true);
+    assert_eq!(v["store_id"].as_str().unwrap().len(), 64);
+    assert_eq!(v["current_root"].as_str().unwrap().len(), 64);
+    assert!(v["generation_count"].as_u64().unwrap() >= 1);
+    assert!(v["archive_path"].as_str().unwrap().ends_with("a"));
+    for key in [
+        "logical_bytes",
+        "stored_bytes",
+        "physical_bytes",
+        "unique_chunks",
+        "staged_bytes",
+        "limit_bytes",
+    ] {
+        assert!(v["size"][key].is_u64(), "size.{key}");
+    }
+    assert!(v["size"]["logical_bytes"].as_u64().unwrap() >= 9);
+    assert_eq!(v["chunking"]["target_size"], 64 * 1024);
+    assert_eq!(v["chunking"]["hash_algorithm"], "sha256");
+    assert_eq!(v["compression"]["enabled"], false);
+    assert_eq!(v["encryption"]["enabled"], true);
+    assert_eq!(v["encryption"]["visibility"], "public");
+    assert_eq!(v["access"]["restricted"], false);
+    for key in [
+        "store_dir",
+        "config",
+        "roots_log",
+        "refs",
+        "head",
+        "pending_commit",
+        "access",
+        "staging",
+        "generations",
+        "modules",
+    ] {
+        assert!(v["paths"][key].is_string(), "paths.{key}");
+    }
+    assert!(Path::new(v["paths"]["config"].as_str().unwrap()).exists());
+
+    let other = dig_at(tmp.path())
+        .args(["stores", "info", "b", "--json"])
+        .output()
+        .unwrap();
+    let other = json_stdout(&other);
+    assert_eq!(other["name"], "b");
+    assert_ne!(other["store_id"], v["store_id"]);
+    dig_at(tmp.path())
+        .args(["stores", "info", "nope"])
+        .assert()
+        .code(2);
+}
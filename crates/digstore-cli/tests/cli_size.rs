@@ -0,0 +1,114 @@
+mod common;
+use common::{dig, tmp_dig};
+
+fn commit_file(dir: &tempfile::TempDir, key: &str, body: &[u8]) {
+    let f = dir.path().join(key);
+    std::fs::write(&f, body).unwrap();
+    dig(dir)
+        .args(["add"])
+        .arg(&f)
+        .args(["--key", key])
+        .assert()
+        .success();
+    dig(dir).args(["commit"]).assert().success();
+}
+
+fn size_json(dir: &tempfile::TempDir, args: &[&str]) -> serde_json::Value {
+    let out = dig(dir)
+        .arg("size")
+        .args(args)
+        .arg("--json")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+#[test]
+fn size_layers_json_has_one_entry_per_version() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_file(&dir, "a", b"alpha alpha alpha");
+    commit_file(&dir, "b", b"bravo");
+
+    let totals = size_json(&dir, &[]);
+    assert_eq!(totals["versions"], 2);
+    assert!(totals.get("layers").is_none());
+
+    let v = size_json(&dir, &["--layers"]);
+    let layers = v["layers"].as_array().unwrap();
+    assert_eq!(layers.len(), 2);
+    for layer in layers {
+        for field in [
+            "generation",
+            "root",
+            "logical_bytes",
+            "stored_bytes",
+            "chunk_count",
+            "unique_chunk_count",
+        ] {
+            assert!(layer.get(field).is_some(), "layer is missing {field}");
+        }
+        assert!(layer["unique_chunk_count"].as_u64() <= layer["chunk_count"].as_u64());
+    }
+    assert_eq!(layers[1]["logical_bytes"], v["logical_bytes"]);
+    let stored: u64 = layers
+        .iter()
+        .map(|l| l["stored_bytes"].as_u64().unwrap())
+        .sum();
+    assert_eq!(v["stored_bytes"].as_u64().unwrap(), stored);
+    assert!(v["physical_bytes"].as_u64().unwrap() <= stored);
+}
+
+#[test]
+fn size_efficiency_json_splits_savings() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_file(&dir, "a", b"alpha alpha alpha");
+    commit_file(&dir, "b", b"bravo");
+
+    let v = size_json(&dir, &["--efficiency"]);
+    let e = &v["efficiency"];
+    let intra = e["intra_layer_saved_bytes"].as_u64().unwrap();
+    let inter = e["inter_layer_saved_bytes"].as_u64().unwrap();
+    assert_eq!(e["saved_bytes"].as_u64().unwrap(), intra + inter);
+    assert_eq!(
+        v["stored_bytes"].as_u64().unwrap(),
+        v["physical_bytes"].as_u64().unwrap() + intra + inter
+    );
+    assert!(e["saved_ratio"].as_f64().unwrap() >= 0.0);
+}
+
+#[test]
+fn size_detailed_json_reports_chunk_size_distribution() {
+    let dir = tmp_dig();
+    dig(&dir).arg("init").assert().success();
+    commit_file(&dir, "a", b"alpha alpha alpha");
+    commit_file(&dir, "b", b"bravo");
+
+    let v = size_json(&dir, &["--detailed"]);
+    let d = &v["chunk_sizes"];
+    assert_eq!(d["count"], v["unique_chunks"]);
+    let (min, max) = (d["min"].as_u64().unwrap(), d["max"].as_u64().unwrap());
+    assert!(min <= d["median"].as_u64().unwrap() && d["median"].as_u64().unwrap() <= max);
+    let buckets = d["histogram"].as_array().unwrap();
+    let counted: u64 = buckets.iter().map(|b| b["count"].as_u64().unwrap()).sum();
+    assert_eq!(counted, d["count"].as_u64().unwrap());
+
+    let root = size_json(&dir, &["--layers"])["layers"][0]["root"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let first = size_json(&dir, &["--detailed", "--at", &root]);
+    assert!(first["chunk_sizes"]["count"].as_u64() < d["count"].as_u64());
+
+    dig(&dir)
+        .args(["size", "--detailed", "--at"])
+        .arg("ab".repeat(32))
+        .assert()
+        .failure();
+}
@@ -72,3 +72,92 @@ fn status_reports_untracked_then_staged_then_modified() {
         "edited committed file shows modified; got {v}"
     );
 }
+
+#[test]
+fn status_ignored_lists_skipped_paths_with_their_pattern() {
+    let d = tmp_dig();
+    std::fs::write(d.path().join("a.txt"), b"one").unwrap();
+    std::fs::write(d.path().join("debug.log"), b"noise").unwrap();
+    std::fs::write(d.path().join(".digignore"), "*.log\n").unwrap();
+    dig_in(d.path()).arg("init").assert().success();
+
+    let status = |args: &[&str]| -> serde_json::Value {
+        let out = dig_in(d.path()).args(args).output().unwrap();
+        assert!(out.status.success(), "{:?}", out);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    // Without the flag the JSON shape is unchanged.
+    assert!(status(&["--json", "status"]).get("ignored").is_none());
+
+    let v = status(&["--json", "status", "--ignored"]);
+    let ignored = v["ignored"].as_array().unwrap();
+    let log = ignored
+        .iter()
+        .find(|i| i["path"] == "debug.log")
+        .unwrap_or_else(|| panic!("debug.log should be listed; got {ignored:?}"));
+    assert_eq!(log["pattern"], "*.log");
+    assert!(log["source"].as_str().unwrap().ends_with(".digignore"));
+    assert!(!ignored.iter().any(|i| i["path"] == "a.txt"));
+}
+
+#[test]
+fn status_warns_when_staged_files_drift_from_disk() {
+    let d = tmp_dig();
+    std::fs::write(d.path().join("edited.txt"), b"v1").unwrap();
+    std::fs::write(d.path().join("gone.txt"), b"bye").unwrap();
+    std::fs::write(d.path().join("same.txt"), b"same").unwrap();
+    dig_in(d.path()).arg("init").assert().success();
+    dig_in(d.path())
+        .args(["add", "edited.txt", "gone.txt", "same.txt"])
+        .assert()
+        .success();
+
+    std::fs::write(d.path().join("edited.txt"), b"v2").unwrap();
+    std::fs::remove_file(d.path().join("gone.txt")).unwrap();
+
+    let out = dig_in(d.path())
+        .args(["--json", "status"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{:?}", out);
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["stale"], serde_json::json!(["edited.txt"]));
+    assert_eq!(v["missing"], serde_json::json!(["gone.txt"]));
+
+    let out = dig_in(d.path()).args(["status"]).output().unwrap();
+    let text = String::from_utf8_lossy(&out.stdout);
+    assert!(text.contains("staged content is stale"), "{text}");
+    assert!(text.contains("staged file deleted from disk"), "{text}");
+}
+
+#[test]
+fn status_porcelain_v2_lists_code_size_hash_and_path() {
+    let d = tmp_dig();
+    std::fs::write(d.path().join("a.txt"), b"one").unwrap();
+    std::fs::write(d.path().join("b file.txt"), b"later").unwrap();
+    dig_in(d.path()).arg("init").assert().success();
+    dig_in(d.path()).args(["add", "a.txt"]).assert().success();
+
+    // The test harness keeps its wallet home in the project (`.dighome/`).
+    let porcelain = |arg: &str| {
+        let out = dig_in(d.path()).args(["status", arg]).output().unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout)
+            .unwrap()
+            .lines()
+            .filter(|l| !l.contains(" .dig"))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let sha = |b: &[u8]| digstore_crypto::sha256(b).to_hex();
+    assert_eq!(
+        porcelain("--porcelain=v2"),
+        vec![
+            "# porcelain v2".to_string(),
+            "# root -".to_string(),
+            format!("A. 3 {} a.txt", sha(b"one")),
+            format!("?? 5 {} b file.txt", sha(b"later")),
+        ]
+    );
+    assert_eq!(porcelain("--porcelain"), vec!["A. a.txt", "?? b file.txt"]);
+}
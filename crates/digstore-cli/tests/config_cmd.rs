@@ -0,0 +1,134 @@
+//! `digstore config`: typed get/set of the global settings, with each value
+//! validated before `config.toml` is written, and `--validate` for a
+//! hand-edited file; `DIGSTORE_<KEY>` overrides a key without touching the
+//! file. `DIGSTORE_HOME` points at a tempdir.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+fn digstore(home: &std::path::Path) -> Command {
+    let mut cmd = Command::cargo_bin("digstore").unwrap();
+    cmd.env("DIGSTORE_HOME", home);
+    cmd
+}
+
+#[test]
+fn set_then_get_round_trips_through_config_toml() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .args(["config", "output.format", "human"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["config", "output.format"])
+        .assert()
+        .success()
+        .stdout("human\n");
+    let toml = std::fs::read_to_string(home.path().join("config.toml")).unwrap();
+    assert!(toml.contains("format = \"human\""), "{toml}");
+
+    // An optional key that was never set is not found.
+    digstore(home.path())
+        .args(["config", "hooks.preCommit"])
+        .assert()
+        .code(4);
+    digstore(home.path())
+        .args(["config", "--unset", "output.format"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["config", "output.format"])
+        .assert()
+        .stdout("auto\n");
+}
+
+#[test]
+fn a_bad_value_or_key_is_rejected_and_nothing_is_written() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .args(["config", "unlock_ttl", "an hour"])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid unlock_ttl"));
+    digstore(home.path())
+        .args(["config", "crypto.public_key", "zzz"])
+        .assert()
+        .code(2)
+        .stderr(contains("unknown config key"));
+    assert!(!home.path().join("config.toml").exists());
+}
+
+#[test]
+fn validate_reports_each_bad_setting_in_a_hand_edited_file() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .args(["config", "--validate"])
+        .assert()
+        .success();
+    std::fs::write(
+        home.path().join("config.toml"),
+        "coinset_url = \"api.coinset.org\"\nunlock_ttl = 3600\nfee = 0\n\n\
+         [names.site]\nid = \"xyz\"\npath = \"/srv/site\"\n",
+    )
+    .unwrap();
+    digstore(home.path())
+        .args(["config", "--validate"])
+        .assert()
+        .code(2)
+        .stderr(contains("2 invalid setting(s)"))
+        .stderr(contains("invalid coinset_url"))
+        .stderr(contains("invalid names.site.id"));
+}
+
+#[test]
+fn env_overrides_the_file_and_list_shows_where_each_value_came_from() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .args(["config", "fee", "5"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .env("DIGSTORE_FEE", "9")
+        .args(["config", "fee"])
+        .assert()
+        .success()
+        .stdout("9\n");
+    let file = format!("file:{}", home.path().join("config.toml").display());
+    digstore(home.path())
+        .env("DIGSTORE_FEE", "9")
+        .args(["config", "--list", "--show-origin"])
+        .assert()
+        .success()
+        .stdout(contains("env:DIGSTORE_FEE\tfee=9"))
+        .stdout(contains(format!("{file}\tunlock_ttl=3600")))
+        .stdout(contains("default\toutput.format=auto"));
+
+    // Writing while an override is set stores only the written key.
+    digstore(home.path())
+        .env("DIGSTORE_FEE", "9")
+        .args(["config", "unlock_ttl", "60"])
+        .assert()
+        .success();
+    let toml = std::fs::read_to_string(home.path().join("config.toml")).unwrap();
+    assert!(
+        toml.contains("fee = 5") && toml.contains("unlock_ttl = 60"),
+        "{toml}"
+    );
+}
+
+#[test]
+fn a_bad_env_override_is_named_in_the_error() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .env("DIGSTORE_UNLOCK_TTL", "soon")
+        .args(["config", "unlock_ttl"])
+        .assert()
+        .code(2)
+        .stderr(contains("DIGSTORE_UNLOCK_TTL"));
+    digstore(home.path())
+        .env("DIGSTORE_UNLOCK_TTL", "soon")
+        .args(["config", "--validate"])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid unlock_ttl"));
+}
@@ -76,3 +76,29 @@ fn import_rejects_bad_mnemonic() {
         .failure()
         .stderr(contains("invalid mnemonic"));
 }
+
+#[test]
+fn export_prints_the_imported_mnemonic_only_when_forced() {
+    let home = tempfile::tempdir().unwrap();
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+    digstore(home.path())
+        .args(["seed", "import", "--mnemonic", PHRASE])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["seed", "export"])
+        .assert()
+        .failure()
+        .stderr(contains("--show-mnemonic"));
+    digstore(home.path())
+        .args(["seed", "export", "--show-mnemonic"])
+        .assert()
+        .failure()
+        .stderr(contains("--force-print"));
+    digstore(home.path()).args(["lock"]).assert().success();
+    digstore(home.path())
+        .args(["seed", "export", "--show-mnemonic", "--force-print"])
+        .assert()
+        .success()
+        .stdout(contains(PHRASE));
+}
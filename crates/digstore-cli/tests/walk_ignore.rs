@@ -0,0 +1,70 @@
+//! Table-driven `.digignore` / `.gitignore` semantics for `add --all`: negation,
+//! last-match-wins, anchoring, directory-only patterns, `**`, and nested files.
+
+use std::path::Path;
+
+use digstore_cli::ops::walk::resolve_all;
+
+fn write(root: &Path, rel: &str, body: &str) {
+    let p = root.join(rel);
+    std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+    std::fs::write(p, body).unwrap();
+}
+
+#[test]
+fn digignore_follows_gitignore_precedence() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        ".digignore",
+        "*.log\n!keep.log\n/build\ndocs/**/draft.md\ncache/\n!keep.tmp\n",
+    );
+    write(root, "nested/.digignore", "!special.log\n");
+    write(root, ".gitignore", "*.tmp\n");
+
+    // (resource key, staged?)
+    let table = [
+        // Negation: a later `!keep.log` re-includes what `*.log` excluded, at
+        // any depth (both patterns are unanchored).
+        ("a.log", false),
+        ("keep.log", true),
+        ("sub/keep.log", true),
+        ("sub/x.log", false),
+        // A leading `/` anchors to the .digignore's directory.
+        ("build/out.js", false),
+        ("sub/build/out.js", true),
+        // A middle `/` anchors too; `**` spans zero or more directories.
+        ("docs/draft.md", false),
+        ("docs/a/b/draft.md", false),
+        ("docs/a/final.md", true),
+        ("other/docs/draft.md", true),
+        // A trailing `/` matches directories only.
+        ("cache/blob.bin", false),
+        ("sub/cache/blob.bin", false),
+        ("notes/cache", true),
+        // A deeper .digignore overrides the root one for its subtree.
+        ("nested/special.log", true),
+        ("nested/other.log", false),
+        // .digignore overrides .gitignore at the same level.
+        ("a.tmp", false),
+        ("keep.tmp", true),
+        ("plain.txt", true),
+    ];
+    for (key, _) in &table {
+        write(root, key, "x");
+    }
+
+    let staged: Vec<String> = resolve_all(root, &root.join(".dig"))
+        .into_iter()
+        .map(|r| r.key)
+        .collect();
+    for (key, expected) in table {
+        assert_eq!(
+            staged.iter().any(|k| k == key),
+            expected,
+            "{key} should {}be staged",
+            if expected { "" } else { "not " }
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! `digstore wallet` profiles: each profile has its own seed, so two profiles
+//! derive different keys, and `wallet use` / `--wallet-profile` pick which one
+//! `seed` and the anchoring commands see. `DIGSTORE_HOME` points at a tempdir.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+fn digstore(home: &std::path::Path) -> Command {
+    let mut cmd = Command::cargo_bin("digstore").unwrap();
+    cmd.env("DIGSTORE_HOME", home);
+    cmd.env("DIGSTORE_PASSPHRASE", "test-pass");
+    cmd.env_remove("DIGSTORE_WALLET_PROFILE");
+    cmd
+}
+
+fn list(home: &std::path::Path) -> Vec<serde_json::Value> {
+    let out = digstore(home)
+        .args(["wallet", "list", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+#[test]
+fn two_profiles_derive_different_keys() {
+    let home = tempfile::tempdir().unwrap();
+    for name in ["work", "personal"] {
+        digstore(home.path())
+            .args(["wallet", "create", name, "--words", "12"])
+            .assert()
+            .success();
+    }
+    let rows = list(home.path());
+    let names: Vec<_> = rows.iter().map(|r| r["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["default", "personal", "work"]);
+    assert_eq!(rows[0]["active"], true);
+    assert_eq!(rows[0]["seed_exists"], false);
+    let work = rows[2]["owner_puzzle_hash"].as_str().unwrap();
+    let personal = rows[1]["owner_puzzle_hash"].as_str().unwrap();
+    assert_eq!(work.len(), 64);
+    assert_ne!(work, personal);
+    assert!(home.path().join("profiles/work/seed.enc").exists());
+    assert!(!home.path().join("seed.enc").exists());
+}
+
+#[test]
+fn use_switches_the_seed_that_seed_commands_see() {
+    let home = tempfile::tempdir().unwrap();
+    digstore(home.path())
+        .args(["seed", "status"])
+        .assert()
+        .stdout(contains("no seed"));
+    digstore(home.path())
+        .args(["wallet", "create", "work"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["--wallet-profile", "work", "seed", "status"])
+        .assert()
+        .stdout(contains("present, unlocked"));
+
+    digstore(home.path())
+        .args(["wallet", "use", "work"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["seed", "status"])
+        .assert()
+        .stdout(contains("present, unlocked"));
+    assert_eq!(list(home.path())[1]["active"], true);
+
+    digstore(home.path())
+        .args(["--yes", "wallet", "delete", "work"])
+        .assert()
+        .failure()
+        .stderr(contains("in use"));
+    digstore(home.path())
+        .args(["wallet", "use", "default"])
+        .assert()
+        .success();
+    digstore(home.path())
+        .args(["--yes", "wallet", "delete", "work"])
+        .assert()
+        .success();
+    assert!(!home.path().join("profiles/work").exists());
+    digstore(home.path())
+        .args(["wallet", "use", "work"])
+        .assert()
+        .failure();
+}
@@ -1,7 +1,9 @@
 //! Store/generation/compiler configuration types (paper 5.2 structs).
 
 use crate::bytes::Bytes32;
+use crate::hash::HashAlgorithm;
 use crate::merkle::MerkleTree;
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 
 /// Per-store hard cap on staged plaintext content (§3). 128 MB, decimal.
@@ -38,6 +40,32 @@ pub struct StoreConfig {
     pub label: Option<String>,
     /// Project description, written to the singleton metadata `description`.
     pub description: Option<String>,
+    /// Content-addressing hash chosen at init (chunk ids, leaves, merkle
+    /// nodes). Fixed for the life of the store.
+    pub hash_algorithm: HashAlgorithm,
+    /// Salt each key epoch sealed under (`None` = public), filled in by salt
+    /// rotation. Every generation names its epoch in the manifest, so keys
+    /// for old generations stay derivable after the salt moves on. Empty for
+    /// a never-rotated store: every epoch then uses `visibility`.
+    pub salt_keyring: BTreeMap<u32, Option<SecretSalt>>,
+}
+
+impl StoreConfig {
+    /// The key epoch new generations seal under: the newest keyring entry,
+    /// or 0 for a never-rotated store.
+    pub fn key_epoch(&self) -> u32 {
+        self.salt_keyring.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// The visibility (and so the salt) generations of `epoch` were sealed
+    /// under. Epochs absent from the keyring fall back to `visibility`.
+    pub fn epoch_visibility(&self, epoch: u32) -> Visibility {
+        match self.salt_keyring.get(&epoch) {
+            Some(Some(salt)) => Visibility::Private(*salt),
+            Some(None) => Visibility::Public,
+            None => self.visibility,
+        }
+    }
 }
 
 /// Logical generation identifier.
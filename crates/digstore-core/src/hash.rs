@@ -1,4 +1,5 @@
-//! SHA-256 helper (no_std via the `sha2` crate).
+//! SHA-256 helper (no_std via the `sha2` crate) and the per-store
+//! [`HashAlgorithm`] choice.
 
 use crate::bytes::Bytes32;
 use sha2::{Digest, Sha256};
@@ -12,3 +13,51 @@ pub fn sha256(data: &[u8]) -> Bytes32 {
     arr.copy_from_slice(&out);
     Bytes32(arr)
 }
+
+/// The content-addressing hash a store was initialized with: chunk ids, D5
+/// resource leaves and merkle nodes all use it. Recorded in the store config
+/// at init and never changed afterwards; both choices produce 32 bytes.
+///
+/// Served modules and the browser verifier are pinned to [`HashAlgorithm::Sha256`]
+/// (paper §9), so only SHA-256 stores can be compiled and anchored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Hash `data` with this algorithm.
+    pub fn digest(self, data: &[u8]) -> Bytes32 {
+        match self {
+            HashAlgorithm::Sha256 => sha256(data),
+            HashAlgorithm::Blake3 => Bytes32(*blake3::hash(data).as_bytes()),
+        }
+    }
+
+    /// The config spelling: `sha256` or `blake3`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Parse the config spelling (case-insensitive). `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.eq_ignore_ascii_case("sha256") || s.eq_ignore_ascii_case("sha-256") {
+            Some(HashAlgorithm::Sha256)
+        } else if s.eq_ignore_ascii_case("blake3") {
+            Some(HashAlgorithm::Blake3)
+        } else {
+            None
+        }
+    }
+}
+
+impl core::fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
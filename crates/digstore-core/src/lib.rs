@@ -25,7 +25,7 @@ pub use capsule::Capsule;
 pub use codec::{Decode, DecodeError, Decoder, Encode, Encoder};
 pub use crypto::{decrypt_chunk, derive_decryption_key, encrypt_chunk, CHIA_BLS_SCHEME};
 pub use error::{CoreError, ErrorCode};
-pub use hash::sha256;
+pub use hash::{sha256, HashAlgorithm};
 
 /// Alias module so `digstore_core::types::Bytes32` resolves (host/guest use this path).
 pub mod types {
@@ -58,7 +58,7 @@ pub use config::{
 };
 pub use keytable::{KeyTableEntry, PathWalk};
 pub use manifest::{Author, MetadataManifest};
-pub use merkle::{resource_leaf, MerkleProof, MerkleTree, ProofStep};
+pub use merkle::{resource_leaf, resource_leaf_with, MerkleProof, MerkleTree, ProofStep};
 pub use tombstone::{RevocationReason, Tombstone, TombstoneScope};
 pub use urn::Urn;
 
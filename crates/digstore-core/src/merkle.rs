@@ -17,6 +17,10 @@
 //! `SHA-256(ciphertext)`); those are the leaf layer itself and are NOT re-tagged
 //! — the leaf/node separation there is provided entirely by the node tag.
 //!
+//! Stores initialized with a non-default [`HashAlgorithm`] use it in place of
+//! SHA-256 for both leaves and nodes (same tags, same shape); the `_with`
+//! variants below take it explicitly and the plain forms are SHA-256.
+//!
 //! Proof size (§9.5): a carried-up leaf skips a level, so its inclusion path is
 //! `<= ceil(log2 n)` siblings; the bound is attained by the full-spine leaf
 //! (index 0). The `<=` bound is the binding contract (see
@@ -26,7 +30,7 @@
 
 use crate::bytes::Bytes32;
 use crate::codec::{Decode, DecodeError, Decoder, Encode, Encoder};
-use crate::hash::sha256;
+use crate::hash::{sha256, HashAlgorithm};
 use alloc::vec::Vec;
 
 /// Domain-separation prefix for a merkle LEAF (`leaf = SHA-256(LEAF_TAG || chunk)`).
@@ -77,18 +81,117 @@ pub struct MerkleProof {
 impl MerkleProof {
     /// Recompute the root from `leaf` + `path` and compare to `root`.
     pub fn verify(&self) -> bool {
+        self.verify_with(HashAlgorithm::Sha256)
+    }
+
+    /// [`MerkleProof::verify`] for a tree built with `algorithm`.
+    pub fn verify_with(&self, algorithm: HashAlgorithm) -> bool {
         let mut acc = self.leaf;
         for step in &self.path {
             acc = if step.is_left {
-                hash_pair(&step.hash, &acc)
+                hash_pair_with(algorithm, &step.hash, &acc)
             } else {
-                hash_pair(&acc, &step.hash)
+                hash_pair_with(algorithm, &acc, &step.hash)
             };
         }
         acc == self.root
     }
 }
 
+/// Leading version byte of the [`MerkleProof::to_compact_bytes`] encoding.
+pub const COMPACT_PROOF_VERSION: u8 = 1;
+
+impl MerkleProof {
+    /// Compact binary encoding, for transports where proof size matters:
+    ///
+    /// `version(1) || leaf(32) || root(32) || varint(path_len) ||
+    ///  side_bitmap(ceil(path_len / 8)) || path_len * sibling_hash(32)`
+    ///
+    /// The path length is an unsigned LEB128 varint and every step's `is_left`
+    /// flag is packed into one bitmap (bit `i` = step `i`, least-significant bit
+    /// first) instead of a tag byte per step. Hashes are raw 32 bytes.
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let n = self.path.len();
+        let mut out = Vec::with_capacity(1 + 64 + 10 + n.div_ceil(8) + n * 32);
+        out.push(COMPACT_PROOF_VERSION);
+        out.extend_from_slice(&self.leaf.0);
+        out.extend_from_slice(&self.root.0);
+        let mut len = n as u64;
+        loop {
+            let byte = (len & 0x7f) as u8;
+            len >>= 7;
+            if len == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+        let mut bitmap = alloc::vec![0u8; n.div_ceil(8)];
+        for (i, step) in self.path.iter().enumerate() {
+            if step.is_left {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out.extend_from_slice(&bitmap);
+        for step in &self.path {
+            out.extend_from_slice(&step.hash.0);
+        }
+        out
+    }
+
+    /// Decode a [`MerkleProof::to_compact_bytes`] encoding. The input must be
+    /// consumed exactly; unknown versions, overlong varints, set padding bits and
+    /// trailing bytes are all rejected so one proof has exactly one encoding.
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<MerkleProof, DecodeError> {
+        let mut dec = Decoder::new(bytes);
+        let version = dec.read_bytes(1)?[0];
+        if version != COMPACT_PROOF_VERSION {
+            return Err(DecodeError::InvalidTag(version));
+        }
+        let leaf = Bytes32::decode(&mut dec)?;
+        let root = Bytes32::decode(&mut dec)?;
+
+        let mut len: u64 = 0;
+        let mut shift = 0u32;
+        loop {
+            let byte = dec.read_bytes(1)?[0];
+            if shift == 63 && byte > 1 {
+                return Err(DecodeError::Invalid("compact proof path length overflows"));
+            }
+            len |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(DecodeError::Invalid("non-minimal compact proof varint"));
+                }
+                break;
+            }
+            shift += 7;
+        }
+        // Every step costs 32 hash bytes, so a length the input cannot hold is
+        // rejected before allocating for it.
+        let n = usize::try_from(len)
+            .ok()
+            .filter(|n| n.checked_mul(32).is_some_and(|b| b <= dec.remaining()))
+            .ok_or(DecodeError::UnexpectedEof)?;
+
+        let bitmap = dec.read_bytes(n.div_ceil(8))?;
+        if n % 8 != 0 && bitmap[n / 8] >> (n % 8) != 0 {
+            return Err(DecodeError::Invalid("compact proof bitmap padding is set"));
+        }
+        let mut path = Vec::with_capacity(n);
+        for i in 0..n {
+            path.push(ProofStep {
+                hash: Bytes32::decode(&mut dec)?,
+                is_left: bitmap[i / 8] & (1 << (i % 8)) != 0,
+            });
+        }
+        if dec.remaining() != 0 {
+            return Err(DecodeError::Invalid("trailing bytes after compact proof"));
+        }
+        Ok(MerkleProof { leaf, path, root })
+    }
+}
+
 impl Encode for MerkleProof {
     fn encode(&self, enc: &mut Encoder) {
         self.leaf.encode(enc);
@@ -107,15 +210,15 @@ impl Decode for MerkleProof {
     }
 }
 
-/// Internal-node hash with the NODE domain tag: `SHA-256(NODE_TAG || left || right)`.
-/// Used identically on the produce side (`build` / `from_leaves`) and the verify
-/// side (`MerkleProof::verify`).
-fn hash_pair(left: &Bytes32, right: &Bytes32) -> Bytes32 {
+/// Internal-node hash with the NODE domain tag: `SHA-256(NODE_TAG || left || right)`
+/// (or the store's [`HashAlgorithm`]). Used identically on the produce side
+/// (`build` / `from_leaves`) and the verify side (`MerkleProof::verify`).
+fn hash_pair_with(algorithm: HashAlgorithm, left: &Bytes32, right: &Bytes32) -> Bytes32 {
     let mut buf = Vec::with_capacity(NODE_TAG.len() + 64);
     buf.extend_from_slice(NODE_TAG);
     buf.extend_from_slice(&left.0);
     buf.extend_from_slice(&right.0);
-    sha256(&buf)
+    algorithm.digest(&buf)
 }
 
 /// Leaf hash with the LEAF domain tag: `SHA-256(LEAF_TAG || chunk)`. Applied only
@@ -141,11 +244,17 @@ pub fn resource_leaf(ciphertext: &[u8]) -> Bytes32 {
     sha256(ciphertext)
 }
 
+/// [`resource_leaf`] under a store's [`HashAlgorithm`].
+pub fn resource_leaf_with(algorithm: HashAlgorithm, ciphertext: &[u8]) -> Bytes32 {
+    algorithm.digest(ciphertext)
+}
+
 /// A built Merkle tree retaining every level so proofs can be generated.
 #[derive(Debug, Clone)]
 pub struct MerkleTree {
     /// `levels[0]` are leaves; the last level is the single-element root.
     levels: Vec<Vec<Bytes32>>,
+    algorithm: HashAlgorithm,
 }
 
 impl MerkleTree {
@@ -157,10 +266,15 @@ impl MerkleTree {
 
     /// Build a tree directly from precomputed leaf hashes.
     pub fn from_leaves(leaves: Vec<Bytes32>) -> MerkleTree {
+        Self::from_leaves_with(HashAlgorithm::Sha256, leaves)
+    }
+
+    /// [`MerkleTree::from_leaves`] with internal nodes hashed by `algorithm`.
+    pub fn from_leaves_with(algorithm: HashAlgorithm, leaves: Vec<Bytes32>) -> MerkleTree {
         let mut levels: Vec<Vec<Bytes32>> = Vec::new();
         let first = if leaves.is_empty() {
-            // Empty tree: root is SHA-256 of nothing, kept as a single level.
-            alloc::vec![sha256(&[])]
+            // Empty tree: root is the hash of nothing, kept as a single level.
+            alloc::vec![algorithm.digest(&[])]
         } else {
             leaves
         };
@@ -172,7 +286,7 @@ impl MerkleTree {
             let mut i = 0;
             while i < prev.len() {
                 if i + 1 < prev.len() {
-                    next.push(hash_pair(&prev[i], &prev[i + 1]));
+                    next.push(hash_pair_with(algorithm, &prev[i], &prev[i + 1]));
                     i += 2;
                 } else {
                     // Odd node carried up unchanged.
@@ -182,7 +296,12 @@ impl MerkleTree {
             }
             levels.push(next);
         }
-        MerkleTree { levels }
+        MerkleTree { levels, algorithm }
+    }
+
+    /// The hash the internal nodes were built with.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
     }
 
     /// The generation root (last level, single element).
@@ -195,6 +314,36 @@ impl MerkleTree {
         self.levels[0].len()
     }
 
+    /// The leaf level, in tree order.
+    pub fn leaves(&self) -> &[Bytes32] {
+        &self.levels[0]
+    }
+
+    /// Replace leaf `index` and recompute only the nodes on its path to the
+    /// root (`O(log n)` hashes), leaving every other subtree as built. The
+    /// result equals [`MerkleTree::from_leaves`] over the updated leaves.
+    /// Returns `false` (tree unchanged) if `index` is out of range.
+    pub fn update_leaf(&mut self, index: usize, leaf: Bytes32) -> bool {
+        if index >= self.leaf_count() {
+            return false;
+        }
+        self.levels[0][index] = leaf;
+        let mut idx = index;
+        for depth in 1..self.levels.len() {
+            let below = &self.levels[depth - 1];
+            let left = idx & !1;
+            let parent = if left + 1 < below.len() {
+                hash_pair_with(self.algorithm, &below[left], &below[left + 1])
+            } else {
+                // Odd node carried up unchanged.
+                below[left]
+            };
+            idx /= 2;
+            self.levels[depth][idx] = parent;
+        }
+        true
+    }
+
     /// Generate an inclusion proof for leaf `index`, or `None` if out of range.
     pub fn prove(&self, index: usize) -> Option<MerkleProof> {
         if index >= self.leaf_count() {
@@ -248,7 +397,7 @@ mod domain_separation_tests {
 
         // A node over (leaf, leaf) must differ from a leaf computed over the
         // 64-byte concatenation — i.e. an internal node can't be reread as a leaf.
-        let node = hash_pair(&leaf, &leaf);
+        let node = hash_pair_with(HashAlgorithm::Sha256, &leaf, &leaf);
         let mut cat = Vec::new();
         cat.extend_from_slice(&leaf.0);
         cat.extend_from_slice(&leaf.0);
@@ -59,6 +59,37 @@ impl Urn {
         })
     }
 
+    /// Parse a URN for interactive use, rejecting what [`Urn::parse`] would
+    /// accept or report only vaguely: a chain outside `ALPHA / DIGIT / "-"`,
+    /// and a store ID or root hash that is not exactly 64 lowercase hex
+    /// characters. Each error names the field ("store ID must be 64 hex
+    /// chars, got 10"). The zero-knowledge retrieval path keeps the lenient
+    /// `parse`.
+    pub fn parse_strict(input: &str) -> Result<Urn, CoreError> {
+        let rest = input
+            .strip_prefix("urn:dig:")
+            .ok_or_else(|| CoreError::Parse("missing 'urn:dig:' prefix".to_string()))?;
+        let head = rest.split_once('/').map_or(rest, |(h, _)| h);
+        let mut parts = head.split(':');
+        if let Some(chain) = parts.next() {
+            if let Some(c) = chain
+                .chars()
+                .find(|c| !(c.is_ascii_alphanumeric() || *c == '-'))
+            {
+                return Err(CoreError::Parse(format!(
+                    "chain may only hold letters, digits and '-', got {c:?}"
+                )));
+            }
+        }
+        if let Some(store_id) = parts.next() {
+            check_hex_field("store ID", store_id)?;
+        }
+        if let Some(root_hash) = parts.next() {
+            check_hex_field("root hash", root_hash)?;
+        }
+        Urn::parse(input)
+    }
+
     /// Render the canonical URN string.
     pub fn canonical(&self) -> String {
         let mut s = format!("urn:dig:{}:{}", self.chain, self.store_id.to_hex());
@@ -93,6 +124,30 @@ impl Urn {
     }
 }
 
+/// A 32-byte URN field must be exactly 64 lowercase hex characters.
+fn check_hex_field(field: &str, value: &str) -> Result<(), CoreError> {
+    if value.len() != 64 {
+        return Err(CoreError::Parse(format!(
+            "{field} must be 64 hex chars, got {}",
+            value.chars().count()
+        )));
+    }
+    if let Some((i, c)) = value
+        .char_indices()
+        .find(|(_, c)| !matches!(c, '0'..='9' | 'a'..='f'))
+    {
+        let why = if c.is_ascii_hexdigit() {
+            "must be lowercase hex"
+        } else {
+            "must be hex"
+        };
+        return Err(CoreError::Parse(format!(
+            "{field} {why}, got {c:?} at position {i}"
+        )));
+    }
+    Ok(())
+}
+
 impl Encode for Urn {
     fn encode(&self, enc: &mut Encoder) {
         self.chain.encode(enc);
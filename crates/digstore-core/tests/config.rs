@@ -2,7 +2,7 @@ use digstore_core::config::{
     ChunkerConfig, CompilerError, GenerationId, GenerationState, HostImportsConfig, SecretSalt,
     StoreConfig, TrustedHostKey, Visibility,
 };
-use digstore_core::Bytes32;
+use digstore_core::{Bytes32, HashAlgorithm};
 
 #[test]
 fn chunker_config_defaults() {
@@ -40,6 +40,8 @@ fn store_config_constructs() {
         visibility: Visibility::Public,
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     };
     assert_eq!(cfg.max_size, 1024);
 }
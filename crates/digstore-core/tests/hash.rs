@@ -0,0 +1,22 @@
+use digstore_core::{sha256, HashAlgorithm};
+
+#[test]
+fn algorithms_hash_known_vectors() {
+    assert_eq!(HashAlgorithm::Sha256.digest(b"abc"), sha256(b"abc"));
+    assert_eq!(
+        HashAlgorithm::Blake3.digest(b"abc").to_hex(),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+    assert_eq!(HashAlgorithm::default(), HashAlgorithm::Sha256);
+}
+
+#[test]
+fn algorithm_names_round_trip() {
+    for alg in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+        assert_eq!(HashAlgorithm::parse(alg.as_str()), Some(alg));
+        assert_eq!(alg.to_string(), alg.as_str());
+    }
+    assert_eq!(HashAlgorithm::parse("BLAKE3"), Some(HashAlgorithm::Blake3));
+    assert_eq!(HashAlgorithm::parse("sha-256"), Some(HashAlgorithm::Sha256));
+    assert_eq!(HashAlgorithm::parse("md5"), None);
+}
@@ -1,4 +1,4 @@
-use digstore_core::merkle::{MerkleTree, LEAF_TAG, NODE_TAG};
+use digstore_core::merkle::{MerkleProof, MerkleTree, LEAF_TAG, NODE_TAG};
 use digstore_core::sha256;
 use digstore_core::Bytes32;
 
@@ -155,3 +155,97 @@ fn ceil_log2(n: usize) -> usize {
     }
     levels
 }
+
+#[test]
+fn compact_proof_round_trips_for_every_leaf() {
+    for n in [1usize, 2, 3, 9, 300] {
+        let tree = MerkleTree::build(&chunks(n));
+        for i in 0..n {
+            let proof = tree.prove(i).unwrap();
+            let bytes = proof.to_compact_bytes();
+            let back = MerkleProof::from_compact_bytes(&bytes).unwrap();
+            assert_eq!(back, proof);
+            assert!(back.verify());
+        }
+    }
+}
+
+#[test]
+fn compact_proof_is_about_half_the_json_size_for_deep_trees() {
+    let tree = MerkleTree::build(&chunks(1 << 12));
+    let proof = tree.prove(0).unwrap();
+    assert_eq!(proof.path.len(), 12);
+
+    let json = serde_json::json!({
+        "leaf": proof.leaf.to_hex(),
+        "root": proof.root.to_hex(),
+        "path": proof
+            .path
+            .iter()
+            .map(|s| serde_json::json!({ "hash": s.hash.to_hex(), "is_left": s.is_left }))
+            .collect::<Vec<_>>(),
+    })
+    .to_string();
+    let compact = proof.to_compact_bytes();
+    // 1 + 32 + 32 + 1 (varint) + 2 (bitmap) + 12 * 32.
+    assert_eq!(compact.len(), 452);
+    assert!(
+        compact.len() * 2 <= json.len(),
+        "compact {} bytes vs json {} bytes",
+        compact.len(),
+        json.len()
+    );
+}
+
+#[test]
+fn compact_proof_rejects_malformed_input() {
+    let proof = MerkleTree::build(&chunks(5)).prove(4).unwrap();
+    let bytes = proof.to_compact_bytes();
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(MerkleProof::from_compact_bytes(&trailing).is_err());
+    assert!(MerkleProof::from_compact_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut bad_version = bytes.clone();
+    bad_version[0] = 9;
+    assert!(MerkleProof::from_compact_bytes(&bad_version).is_err());
+
+    // Bit 7 of the side bitmap lies beyond a short path: padding must be zero.
+    let mut padded = bytes;
+    padded[66] |= 0x80;
+    assert!(MerkleProof::from_compact_bytes(&padded).is_err());
+}
+
+#[test]
+fn update_leaf_matches_a_full_rebuild_at_every_size_and_index() {
+    for n in 1..=17 {
+        let leaves: Vec<Bytes32> = (0..n).map(|i| sha256(&[i as u8])).collect();
+        for i in 0..n {
+            let mut tree = MerkleTree::from_leaves(leaves.clone());
+            let mut updated = leaves.clone();
+            updated[i] = sha256(b"changed");
+            assert!(tree.update_leaf(i, updated[i]));
+            let rebuilt = MerkleTree::from_leaves(updated.clone());
+            assert_eq!(tree.root(), rebuilt.root(), "n={n} i={i}");
+            assert_eq!(tree.leaves(), updated.as_slice());
+            assert_eq!(tree.prove(i).unwrap(), rebuilt.prove(i).unwrap());
+        }
+        let mut tree = MerkleTree::from_leaves(leaves.clone());
+        assert!(!tree.update_leaf(n, sha256(b"x")));
+        assert_eq!(tree.root(), MerkleTree::from_leaves(leaves).root());
+    }
+}
+
+#[test]
+fn blake3_tree_verifies_only_under_blake3() {
+    use digstore_core::HashAlgorithm;
+    let leaves: Vec<Bytes32> = (0..5u8).map(|i| sha256(&[i])).collect();
+    let sha = MerkleTree::from_leaves(leaves.clone());
+    let b3 = MerkleTree::from_leaves_with(HashAlgorithm::Blake3, leaves);
+    assert_eq!(b3.algorithm(), HashAlgorithm::Blake3);
+    assert_ne!(sha.root(), b3.root());
+    let proof = b3.prove(3).unwrap();
+    assert!(proof.verify_with(HashAlgorithm::Blake3));
+    assert!(!proof.verify());
+}
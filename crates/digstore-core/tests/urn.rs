@@ -98,6 +98,41 @@ fn parse_rejects_bad_store_id_hex() {
     assert!(Urn::parse("urn:dig:mainnet:zz").is_err());
 }
 
+#[test]
+fn parse_strict_names_the_bad_field() {
+    let sid = store_id().to_hex();
+    let rh = root_hash().to_hex();
+    let strict = format!("urn:dig:chia:{sid}:{rh}/a.txt");
+    assert_eq!(
+        Urn::parse_strict(&strict).unwrap(),
+        Urn::parse(&strict).unwrap()
+    );
+    assert!(Urn::parse_strict(&format!("urn:dig:chia:{sid}")).is_ok());
+
+    let err = |s: &str| Urn::parse_strict(s).unwrap_err().to_string();
+    assert_eq!(
+        err("urn:dig:chia:0123456789/a"),
+        "parse error: store ID must be 64 hex chars, got 10"
+    );
+    assert_eq!(
+        err(&format!("urn:dig:chia:{sid}:{}", &rh[..63])),
+        "parse error: root hash must be 64 hex chars, got 63"
+    );
+    let upper = format!("A{}", &sid[1..]);
+    assert_eq!(
+        err(&format!("urn:dig:chia:{upper}")),
+        "parse error: store ID must be lowercase hex, got 'A' at position 0"
+    );
+    let bad = format!("{}g", &rh[..63]);
+    assert_eq!(
+        err(&format!("urn:dig:chia:{sid}:{bad}")),
+        "parse error: root hash must be hex, got 'g' at position 63"
+    );
+    assert!(err(&format!("urn:dig:ch ia:{sid}")).contains("chain"));
+    // The lenient parser still takes uppercase hex.
+    assert!(Urn::parse(&format!("urn:dig:chia:{upper}")).is_ok());
+}
+
 /// REGRESSION LOCK (frozen wire format): the Capsule naming layer is purely a
 /// view over the existing `(store_id, root_hash)` pair and MUST NOT perturb the
 /// URN `canonical()` string or the `retrieval_key()` bytes. These goldens are
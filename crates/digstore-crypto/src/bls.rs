@@ -44,6 +44,15 @@ impl SecretKey {
         PublicKey(self.0.public_key())
     }
 
+    /// Parse a raw 32-byte secret scalar, e.g. a wallet key derived by another
+    /// `chia-bls` version. Returns `BlsError::InvalidSecretKey` when the bytes
+    /// are not a valid scalar.
+    pub fn from_bytes(b: &[u8; 32]) -> Result<Self, CryptoError> {
+        ChiaSecretKey::from_bytes(b)
+            .map(SecretKey)
+            .map_err(|_| CryptoError::Bls(BlsError::InvalidSecretKey))
+    }
+
     /// Sign `msg` under the Chia AugScheme (public key prepended, Chia DST).
     pub fn sign(&self, msg: &[u8]) -> Signature {
         Signature(aug_sign(&self.0, msg))
@@ -179,6 +188,11 @@ pub const TOMB_DST: &[u8] = b"digstore:tomb:v1";
 /// request signature from ever being replayable as a push / node-proof /
 /// attestation / tombstone signature, and vice-versa.
 pub const REQ_DST: &[u8] = b"digstore:req:v1";
+/// Role tag for publisher root signatures (`sign_root` / `verify_root`): a
+/// wallet holder vouching that a store root is theirs, checked offline by a
+/// recipient. Distinct from the push tag even though both sign
+/// `root || store_id`, so one can never stand in for the other.
+pub const ROOT_DST: &[u8] = b"digstore:root:v1";
 /// Role tag for host attestation signatures (`sign_attestation`). Re-exported
 /// from `digstore_core` so the producer here and the guest's `build_challenge`
 /// verifier share ONE definition and stay byte-identical.
@@ -255,6 +269,17 @@ pub fn tombstone_signing_message(t: &Tombstone) -> [u8; 32] {
     crate::sha256(&buf).0
 }
 
+/// Canonical publisher root-signature message:
+/// `SHA-256(ROOT_DST || root || store_id)` (32 bytes). Same shape as
+/// [`push_signing_message`] under its own role tag.
+pub fn root_signing_message(root: &Bytes32, store_id: &Bytes32) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(ROOT_DST.len() + 64);
+    buf.extend_from_slice(ROOT_DST);
+    buf.extend_from_slice(&root.0);
+    buf.extend_from_slice(&store_id.0);
+    crate::sha256(&buf).0
+}
+
 /// Canonical per-request authentication signing message (paper §21.9):
 /// `SHA-256(REQ_DST || len(method) || method || store_id(32) || timestamp_be(8) || nonce(32))`
 /// (32 bytes). `method` is the logical operation (`"fetch"`, `"roots"`, `"module"`,
@@ -385,6 +410,17 @@ pub fn verify_tombstone(pk: &PublicKey, t: &Tombstone, sig: &Bytes96) -> bool {
     pk.verify(&tombstone_signing_message(t), &sig)
 }
 
+/// Publisher root signature: sign [`root_signing_message`] with `sk`.
+pub fn sign_root(sk: &SecretKey, root: &Bytes32, store_id: &Bytes32) -> Bytes96 {
+    bls_sign(sk, &root_signing_message(root, store_id))
+}
+
+/// Verify a publisher root signature against 48-byte public-key bytes. The
+/// identity key and any malformed input verify as `false`.
+pub fn verify_root(pk: &Bytes48, root: &Bytes32, store_id: &Bytes32, sig: &Bytes96) -> bool {
+    validate_public_key(pk).is_ok() && bls_verify(pk, &root_signing_message(root, store_id), sig)
+}
+
 #[cfg(test)]
 mod request_auth_tests {
     use super::*;
@@ -410,8 +446,8 @@ mod request_auth_tests {
         ));
     }
 
-    /// #131: pin the BLS role-tag invariant the doc comments promise — the FIVE
-    /// BLS role DSTs (push / node / attestation / tomb / req) are pairwise
+    /// #131: pin the BLS role-tag invariant the doc comments promise — the
+    /// BLS role DSTs (push / node / attestation / tomb / req / root) are pairwise
     /// DISTINCT, so a signature in one role can never be replayed as another. The
     /// ONE documented, intentional byte-coincidence — `NODE_DST == NODE_TAG` (the
     /// merkle node tag) — is asserted here as a KNOWN, non-colliding exception
@@ -419,12 +455,13 @@ mod request_auth_tests {
     /// coincidence an explicitly-tested invariant rather than a silent one.
     #[test]
     fn bls_role_dsts_are_pairwise_distinct_and_node_tag_coincidence_is_intentional() {
-        let role_dsts: [(&str, &[u8]); 5] = [
+        let role_dsts: [(&str, &[u8]); 6] = [
             ("push", PUSH_DST),
             ("node", NODE_DST),
             ("attest", ATTEST_DST),
             ("tomb", TOMB_DST),
             ("req", REQ_DST),
+            ("root", ROOT_DST),
         ];
         for (i, (na, a)) in role_dsts.iter().enumerate() {
             for (nb, b) in role_dsts.iter().skip(i + 1) {
@@ -475,6 +512,27 @@ mod request_auth_tests {
         assert!(!verify_request(&pk, "module", &store, ts, &[4u8; 32], &sig));
     }
 
+    #[test]
+    fn root_signature_round_trips_and_is_not_a_push_signature() {
+        let (sk, pk) = bls_keygen(&[5u8; 32]);
+        let (root, store) = (Bytes32([1u8; 32]), Bytes32([2u8; 32]));
+        let sig = sign_root(&sk, &root, &store);
+        assert!(verify_root(&pk, &root, &store, &sig));
+        assert!(!verify_root(&pk, &Bytes32([3u8; 32]), &store, &sig));
+        assert!(!verify_root(&bls_keygen(&[6u8; 32]).1, &root, &store, &sig));
+        let push = sign_push(&sk, &root, &store);
+        assert!(!verify_root(&pk, &root, &store, &push));
+    }
+
+    #[test]
+    fn secret_key_from_bytes_matches_the_seeded_key() {
+        let sk = SecretKey::from_seed(&[8u8; 32]);
+        let raw = sk.0.to_bytes();
+        let again = SecretKey::from_bytes(&raw).unwrap();
+        assert_eq!(again.public_key().to_bytes(), sk.public_key().to_bytes());
+        assert!(SecretKey::from_bytes(&[0xff; 32]).is_err());
+    }
+
     #[test]
     fn request_message_differs_from_push_message_for_same_bytes() {
         // Defense-in-depth: the REQ_DST tag keeps a request message distinct from a
@@ -10,6 +10,8 @@ pub struct TamperError;
 pub enum BlsError {
     #[error("invalid BLS public key bytes")]
     InvalidPublicKey,
+    #[error("invalid BLS secret key bytes")]
+    InvalidSecretKey,
     #[error("invalid BLS signature bytes")]
     InvalidSignature,
 }
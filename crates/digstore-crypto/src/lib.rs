@@ -32,13 +32,14 @@ pub mod bls;
 pub mod error;
 pub mod fixtures;
 pub mod kdf;
+pub mod seal;
 
 pub use aead::{decrypt_chunk, encrypt_chunk};
 pub use bls::{
     attestation_signing_message, bls_keygen, bls_sign, bls_verify, node_signing_message,
-    push_signing_message, request_signing_message, sign_attestation, sign_node, sign_push,
-    sign_request, sign_tombstone, tombstone_signing_message, validate_public_key, verify_push,
-    verify_request, verify_tombstone,
+    push_signing_message, request_signing_message, root_signing_message, sign_attestation,
+    sign_node, sign_push, sign_request, sign_root, sign_tombstone, tombstone_signing_message,
+    validate_public_key, verify_push, verify_request, verify_root, verify_tombstone,
 };
 pub use digstore_core::hash::sha256;
 pub use error::{BlsError, CryptoError, TamperError};
@@ -46,6 +47,7 @@ pub use fixtures::{
     write_bls_fixtures, write_kdf_fixtures, BlsFixture, BlsFixtureSet, KdfFixture, KdfFixtureSet,
 };
 pub use kdf::derive_decryption_key;
+pub use seal::UrnSealer;
 
 use digstore_core::Bytes48;
 
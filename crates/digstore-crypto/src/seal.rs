@@ -0,0 +1,61 @@
+//! Per-URN sealing as one round trip (paper §11.1/§11.2).
+//!
+//! Every resource's chunks are sealed with [`encrypt_chunk`] under the key
+//! [`derive_decryption_key`] derives from the resource's canonical URN, with
+//! the root hash dropped so a resource keeps its key across generations, plus
+//! the store's secret salt when it is private. [`UrnSealer`] is that pipeline
+//! as a public pair: `seal(data, urn)` / `unseal(ciphertext, urn)`. The
+//! producer (`digstore-store`, `digstore-stage`) derives its chunk keys through
+//! [`UrnSealer::key_for`], so the pair opens exactly what a commit stores.
+
+use digstore_core::{SecretSalt, Urn, Visibility};
+
+use crate::aead::{decrypt_chunk, encrypt_chunk};
+use crate::error::TamperError;
+use crate::kdf::derive_decryption_key;
+
+/// Seals and opens resource bytes under their URN's key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UrnSealer {
+    salt: Option<SecretSalt>,
+}
+
+impl UrnSealer {
+    /// Sealer for a public store (no secret salt).
+    pub fn public() -> Self {
+        Self { salt: None }
+    }
+
+    /// Sealer for a private store with this secret salt.
+    pub fn private(salt: SecretSalt) -> Self {
+        Self { salt: Some(salt) }
+    }
+
+    /// Sealer for a store of this visibility.
+    pub fn for_visibility(visibility: &Visibility) -> Self {
+        match visibility {
+            Visibility::Private(salt) => Self::private(*salt),
+            Visibility::Public => Self::public(),
+        }
+    }
+
+    /// The AES-256 key for `urn`'s resource, independent of its root hash.
+    pub fn key_for(&self, urn: &Urn) -> [u8; 32] {
+        let rootless = Urn {
+            root_hash: None,
+            ..urn.clone()
+        };
+        derive_decryption_key(&rootless.canonical(), self.salt.as_ref())
+    }
+
+    /// Seal `data` (one chunk) for `urn`. Deterministic: the same bytes and
+    /// URN always give the same ciphertext.
+    pub fn seal(&self, data: &[u8], urn: &Urn) -> Vec<u8> {
+        encrypt_chunk(&self.key_for(urn), data)
+    }
+
+    /// Open a chunk sealed for `urn`. The wrong URN or salt fails the tag check.
+    pub fn unseal(&self, ciphertext: &[u8], urn: &Urn) -> Result<Vec<u8>, TamperError> {
+        decrypt_chunk(&self.key_for(urn), ciphertext)
+    }
+}
@@ -0,0 +1,69 @@
+use digstore_core::{Bytes32, SecretSalt, Urn};
+use digstore_crypto::{derive_decryption_key, encrypt_chunk, TamperError, UrnSealer};
+
+fn urn(store: u8, root: Option<u8>, path: &str) -> Urn {
+    Urn {
+        chain: "chia".into(),
+        store_id: Bytes32([store; 32]),
+        root_hash: root.map(|r| Bytes32([r; 32])),
+        resource_key: Some(path.into()),
+    }
+}
+
+#[test]
+fn unseal_inverts_seal_for_any_urn() {
+    let sealer = UrnSealer::public();
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    for (store, path) in [
+        (1, "index.html"),
+        (2, "a/b/c.bin"),
+        (3, ""),
+        (4, "ünï cödé"),
+    ] {
+        let u = urn(store, None, path);
+        let ct = sealer.seal(&data, &u);
+        assert_ne!(ct, data);
+        assert_eq!(sealer.unseal(&ct, &u).unwrap(), data);
+    }
+    let empty = sealer.seal(b"", &urn(5, None, "empty"));
+    assert!(sealer
+        .unseal(&empty, &urn(5, None, "empty"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn different_urns_or_salts_give_different_ciphertext() {
+    let data = b"same bytes, different resources";
+    let public = UrnSealer::public();
+    let a = public.seal(data, &urn(1, None, "a.txt"));
+    let b = public.seal(data, &urn(1, None, "b.txt"));
+    let other_store = public.seal(data, &urn(2, None, "a.txt"));
+    assert_ne!(a, b);
+    assert_ne!(a, other_store);
+    assert_eq!(
+        public.unseal(&a, &urn(1, None, "b.txt")),
+        Err(TamperError),
+        "the wrong URN cannot open a sealed chunk"
+    );
+
+    let private = UrnSealer::private(SecretSalt([9; 32]));
+    let salted = private.seal(data, &urn(1, None, "a.txt"));
+    assert_ne!(salted, a);
+    assert_eq!(
+        public.unseal(&salted, &urn(1, None, "a.txt")),
+        Err(TamperError)
+    );
+}
+
+#[test]
+fn sealing_ignores_the_root_and_matches_the_commit_derivation() {
+    let sealer = UrnSealer::public();
+    let data = b"stable across generations";
+    let pinned = sealer.seal(data, &urn(1, Some(7), "a.txt"));
+    assert_eq!(pinned, sealer.seal(data, &urn(1, None, "a.txt")));
+    assert_eq!(sealer.seal(data, &urn(1, Some(8), "a.txt")), pinned);
+
+    let key = derive_decryption_key(&urn(1, None, "a.txt").canonical(), None);
+    assert_eq!(encrypt_chunk(&key, data), pinned);
+}
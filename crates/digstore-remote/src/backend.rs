@@ -123,6 +123,11 @@ pub trait RemoteBackend: Send + Sync + 'static {
         mode: PushMode,
     ) -> Result<PushOutcome, RemoteError>;
 
+    /// The sealed bytes of one content-addressed chunk, or UnknownChunk. Backs
+    /// the ranged `GET /stores/{id}/chunks/{hash}` route that remote
+    /// [`ChunkBackend`](digstore_store::ChunkBackend) readers use.
+    fn chunk(&self, store_id: &Bytes32, hash: &Bytes32) -> Result<Vec<u8>, RemoteError>;
+
     /// Compute the delta from `from` to `to` along linear ancestry (§21.5).
     fn delta(
         &self,
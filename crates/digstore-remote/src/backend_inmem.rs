@@ -326,6 +326,15 @@ impl RemoteBackend for InMemoryBackend {
         }
     }
 
+    fn chunk(&self, store_id: &Bytes32, hash: &Bytes32) -> Result<Vec<u8>, RemoteError> {
+        let stores = self.stores.lock().unwrap();
+        let st = stores.get(store_id).ok_or(RemoteError::UnknownStore)?;
+        st.generations
+            .values()
+            .find_map(|g| g.chunks.get(hash).cloned())
+            .ok_or(RemoteError::UnknownChunk)
+    }
+
     fn delta(
         &self,
         store_id: &Bytes32,
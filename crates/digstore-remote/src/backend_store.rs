@@ -385,6 +385,17 @@ impl RemoteBackend for StoreBackend {
         }
     }
 
+    fn chunk(&self, store_id: &Bytes32, hash: &Bytes32) -> Result<Vec<u8>, RemoteError> {
+        self.ensure_store(store_id)?;
+        let store = Store::open_read_only(&self.data_dir, SystemClock)
+            .map_err(|_| RemoteError::UnknownStore)?;
+        match store.resolve_chunk(*hash) {
+            Ok(bytes) => Ok(bytes),
+            Err(digstore_store::StoreError::ChunkNotFound(_)) => Err(RemoteError::UnknownChunk),
+            Err(e) => Err(RemoteError::Internal(format!("chunk read: {e}"))),
+        }
+    }
+
     fn delta(
         &self,
         store_id: &Bytes32,
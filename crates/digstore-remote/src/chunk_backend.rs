@@ -0,0 +1,74 @@
+//! Read-only [`ChunkBackend`] over a remote node's ranged chunk route.
+//!
+//! Lets `Store::read_resource_from` reassemble a resource from a local
+//! manifest while fetching only that resource's chunks from
+//! `GET /stores/{id}/chunks/{hash}`, instead of cloning every generation.
+
+use crate::client::DigClient;
+use digstore_core::Bytes32;
+use digstore_store::{ChunkBackend, Result, StoreError};
+use std::future::Future;
+use std::ops::Range;
+
+/// A [`ChunkBackend`] that reads one store's chunks from a remote node with
+/// HTTP range requests. Writes are refused: pushing goes through the module
+/// protocol, never chunk by chunk.
+///
+/// The trait is synchronous, so each call drives its request on a private
+/// single-threaded runtime. Do not call it from inside another tokio runtime.
+pub struct HttpChunkBackend {
+    client: DigClient,
+    store_id: Bytes32,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl HttpChunkBackend {
+    /// Read `store_id`'s chunks through `client` (which carries the base URL
+    /// and, when set, the request-signing identity).
+    pub fn new(client: DigClient, store_id: Bytes32) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(HttpChunkBackend {
+            client,
+            store_id,
+            runtime,
+        })
+    }
+
+    fn block_on<T>(
+        &self,
+        hash: Bytes32,
+        fut: impl Future<Output = std::result::Result<T, crate::ClientError>>,
+    ) -> Result<T> {
+        self.runtime.block_on(fut).map_err(|e| match e {
+            crate::ClientError::Status(404) => StoreError::ChunkNotFound(hash.to_hex()),
+            other => StoreError::Backend(other.to_string()),
+        })
+    }
+}
+
+impl ChunkBackend for HttpChunkBackend {
+    fn len(&self, hash: Bytes32) -> Result<u64> {
+        self.block_on(hash, self.client.chunk_len(&self.store_id, &hash))
+    }
+
+    fn read_range(&self, hash: Bytes32, range: Range<u64>) -> Result<Vec<u8>> {
+        self.block_on(
+            hash,
+            self.client.get_chunk_range(&self.store_id, &hash, range),
+        )
+    }
+
+    /// One request: an open-ended range is clamped to the chunk by the server.
+    fn read(&self, hash: Bytes32) -> Result<Vec<u8>> {
+        self.read_range(hash, 0..u64::MAX)
+    }
+
+    fn write(&self, _hash: Bytes32, _data: &[u8]) -> Result<bool> {
+        Err(StoreError::ReadOnly(format!(
+            "remote chunks of store {}",
+            self.store_id.to_hex()
+        )))
+    }
+}
@@ -117,6 +117,7 @@ pub struct DigClient {
     base_url: String,
     http: reqwest::Client,
     identity: Option<RequestIdentity>,
+    bearer: Option<String>,
 }
 
 impl DigClient {
@@ -139,6 +140,7 @@ impl DigClient {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             http,
             identity: None,
+            bearer: None,
         }
     }
 
@@ -147,6 +149,7 @@ impl DigClient {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             http,
             identity: None,
+            bearer: None,
         }
     }
 
@@ -158,6 +161,13 @@ impl DigClient {
         self
     }
 
+    /// Present `Authorization: Bearer <token>` on every request, for a node
+    /// started with a read token (`digstore serve --token-file`). Builder-style.
+    pub fn with_bearer(mut self, token: impl Into<String>) -> Self {
+        self.bearer = Some(token.into());
+        self
+    }
+
     fn url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
@@ -168,13 +178,18 @@ impl DigClient {
     /// signature for one operation can never be replayed as another. A fresh
     /// timestamp + random nonce make every signed request unique (server-side
     /// freshness window + nonce defeat replay). No-op when no identity is set
-    /// (anonymous client / tests against an open server).
+    /// (anonymous client / tests against an open server). A configured node
+    /// bearer token ([`DigClient::with_bearer`]) is attached either way.
     fn authed(
         &self,
         req: reqwest::RequestBuilder,
         method: &str,
         store_id: &Bytes32,
     ) -> reqwest::RequestBuilder {
+        let req = match &self.bearer {
+            Some(t) => req.header(reqwest::header::AUTHORIZATION, format!("Bearer {t}")),
+            None => req,
+        };
         let Some(identity) = &self.identity else {
             return req;
         };
@@ -784,6 +799,74 @@ impl DigClient {
             chunk_lens,
         })
     }
+
+    /// `HEAD /stores/{id}/chunks/{hash}`: the stored size of one sealed chunk.
+    pub async fn chunk_len(&self, store_id: &Bytes32, hash: &Bytes32) -> Result<u64, ClientError> {
+        let url = self.url(&format!(
+            "/stores/{}/chunks/{}",
+            store_id.to_hex(),
+            hash.to_hex()
+        ));
+        let resp = self
+            .authed(self.http.head(url), "chunk", store_id)
+            .send()
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(ClientError::Status(resp.status().as_u16()));
+        }
+        resp.headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| ClientError::Decode("chunk HEAD without Content-Length".into()))
+    }
+
+    /// `GET /stores/{id}/chunks/{hash}` with a `Range` header: bytes `range` of
+    /// one sealed chunk (clamped to its end; empty when `range` is empty). The
+    /// bytes are unverified; a caller reading a whole chunk re-hashes it.
+    pub async fn get_chunk_range(
+        &self,
+        store_id: &Bytes32,
+        hash: &Bytes32,
+        range: std::ops::Range<u64>,
+    ) -> Result<Vec<u8>, ClientError> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = self.url(&format!(
+            "/stores/{}/chunks/{}",
+            store_id.to_hex(),
+            hash.to_hex()
+        ));
+        let resp = self
+            .authed(self.http.get(url), "chunk", store_id)
+            .header(
+                reqwest::header::RANGE,
+                format!("bytes={}-{}", range.start, range.end - 1),
+            )
+            .send()
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        match resp.status().as_u16() {
+            200 | 206 => {}
+            // Starts past the end: an empty read, like a clamped local range.
+            416 => return Ok(Vec::new()),
+            status => return Err(ClientError::Status(status)),
+        }
+        let full = resp.status().as_u16() == 200;
+        let body = resp
+            .bytes()
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        // A server that ignores `Range` answers 200 with the whole chunk.
+        if full {
+            let start = (range.start as usize).min(body.len());
+            let end = (range.end as usize).clamp(start, body.len());
+            return Ok(body[start..end].to_vec());
+        }
+        Ok(body.to_vec())
+    }
 }
 
 /// Decode a base64'd, codec-encoded [`MerkleProof`] (the `inclusion_proof` field / the host's
@@ -8,6 +8,8 @@ pub enum RemoteError {
     UnknownStore,
     #[error("unknown root")]
     UnknownRoot,
+    #[error("unknown chunk")]
+    UnknownChunk,
     #[error("push not authorized: {0}")]
     Unauthorized(String),
     #[error("request authentication failed: {0}")]
@@ -33,7 +35,9 @@ impl RemoteError {
     /// (it returns 200 with a decoy); only structural errors map.
     pub fn status(&self) -> StatusCode {
         match self {
-            RemoteError::UnknownStore | RemoteError::UnknownRoot => StatusCode::NOT_FOUND,
+            RemoteError::UnknownStore | RemoteError::UnknownRoot | RemoteError::UnknownChunk => {
+                StatusCode::NOT_FOUND
+            }
             RemoteError::Unauthorized(_) => StatusCode::FORBIDDEN,
             RemoteError::AuthFailed(_) => StatusCode::UNAUTHORIZED,
             RemoteError::MissingBearer => StatusCode::UNAUTHORIZED,
@@ -74,6 +78,7 @@ mod tests {
     fn status_mapping_matches_spec_21_8() {
         assert_eq!(RemoteError::UnknownStore.status(), StatusCode::NOT_FOUND);
         assert_eq!(RemoteError::UnknownRoot.status(), StatusCode::NOT_FOUND);
+        assert_eq!(RemoteError::UnknownChunk.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             RemoteError::Unauthorized("bad sig".into()).status(),
             StatusCode::FORBIDDEN
@@ -0,0 +1,134 @@
+use crate::error::RemoteError;
+use crate::server::{parse_store_id, run_blocking, AppState};
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use digstore_core::Bytes32;
+use std::ops::Range;
+
+/// GET /stores/{id}/chunks/{hash} — one sealed chunk body. Honors a single
+/// `Range: bytes=…` (206 + `Content-Range`; 416 when it starts past the end) so
+/// a remote `ChunkBackend` reads only what it needs. Chunks are ciphertext
+/// addressed by their own hash, so serving them discloses nothing a delta
+/// does not; readers re-hash what they receive.
+pub async fn get_chunk(
+    State(s): State<AppState>,
+    Path((id, hash)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let (store_id, hash) = match parse_ids(&id, &hash) {
+        Ok(v) => v,
+        Err(e) => return e.into_response(),
+    };
+    let backend = s.backend.clone();
+    let body = match run_blocking(move || backend.chunk(&store_id, &hash)).await {
+        Ok(b) => b,
+        Err(e) => return e.into_response(),
+    };
+    let len = body.len() as u64;
+    let requested = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    match requested.map(|r| parse_range(r, len)) {
+        None => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+            ],
+            Body::from(body),
+        )
+            .into_response(),
+        Some(Some(r)) => {
+            let content_range = format!("bytes {}-{}/{len}", r.start, r.end - 1);
+            let part = body[r.start as usize..r.end as usize].to_vec();
+            (
+                StatusCode::PARTIAL_CONTENT,
+                [
+                    (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                    (header::CONTENT_RANGE, content_range),
+                ],
+                Body::from(part),
+            )
+                .into_response()
+        }
+        Some(None) => (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            [(header::CONTENT_RANGE, format!("bytes */{len}"))],
+        )
+            .into_response(),
+    }
+}
+
+/// HEAD /stores/{id}/chunks/{hash} — the chunk's length, no body.
+pub async fn head_chunk(
+    State(s): State<AppState>,
+    Path((id, hash)): Path<(String, String)>,
+) -> Response {
+    let (store_id, hash) = match parse_ids(&id, &hash) {
+        Ok(v) => v,
+        Err(e) => return e.into_response(),
+    };
+    let backend = s.backend.clone();
+    match run_blocking(move || backend.chunk(&store_id, &hash)).await {
+        Ok(body) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_LENGTH, body.len().to_string()),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+            ],
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+fn parse_ids(id: &str, hash: &str) -> Result<(Bytes32, Bytes32), RemoteError> {
+    let store_id = parse_store_id(id)?;
+    let hash =
+        Bytes32::from_hex(hash).map_err(|_| RemoteError::BadRequest("bad chunk hash".into()))?;
+    Ok((store_id, hash))
+}
+
+/// Resolve a single-range `bytes=START-END` / `START-` / `-N` header against a
+/// body of `len` bytes (END inclusive, clamped). `None` when unsatisfiable or
+/// malformed; multi-range requests are answered with the first range only.
+fn parse_range(value: &str, len: u64) -> Option<Range<u64>> {
+    let spec = value.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (lo, hi) = spec.split_once('-')?;
+    let (start, end) = match (lo.is_empty(), hi.is_empty()) {
+        (true, false) => {
+            let n: u64 = hi.parse().ok()?;
+            (len.saturating_sub(n), len)
+        }
+        (false, true) => (lo.parse().ok()?, len),
+        (false, false) => {
+            let (start, end): (u64, u64) = (lo.parse().ok()?, hi.parse().ok()?);
+            if end < start {
+                return None;
+            }
+            (start, end.saturating_add(1).min(len))
+        }
+        (true, true) => return None,
+    };
+    (start < end).then_some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_range;
+
+    #[test]
+    fn range_header_resolves_like_rfc_9110() {
+        assert_eq!(parse_range("bytes=0-9", 100), Some(0..10));
+        assert_eq!(parse_range("bytes=90-500", 100), Some(90..100));
+        assert_eq!(parse_range("bytes=40-", 100), Some(40..100));
+        assert_eq!(parse_range("bytes=-10", 100), Some(90..100));
+        assert_eq!(parse_range("bytes=-500", 100), Some(0..100));
+        assert_eq!(parse_range("bytes=0-1,5-6", 100), Some(0..2));
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(parse_range("bytes=9-3", 100), None);
+        assert_eq!(parse_range("items=0-1", 100), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod chunk;
 pub mod content;
 pub mod delta;
 pub mod descriptor;
@@ -15,6 +15,7 @@ pub mod auth;
 pub mod backend;
 pub mod backend_inmem;
 pub mod backend_store;
+pub mod chunk_backend;
 pub mod client;
 pub mod error;
 pub mod etag;
@@ -34,5 +35,6 @@ pub use backend::{
 };
 pub use backend_inmem::InMemoryBackend;
 pub use backend_store::StoreBackend;
+pub use chunk_backend::HttpChunkBackend;
 pub use error::{ClientError, RemoteError};
 pub use etag::{etag_for_root, matches_current, parse_if_none_match};
@@ -26,6 +26,12 @@ pub struct AppState {
     /// key) or it is rejected 401. `allow_anonymous()` turns this off for a
     /// fully-public read mirror or in-process tests.
     pub require_auth: bool,
+    /// When true, every write route (push, push negotiation, tombstone) is
+    /// refused 403: the node only serves what is already on disk.
+    pub read_only: bool,
+    /// When set, every `/stores/…` request must also present
+    /// `Authorization: Bearer <token>` (401 otherwise). `/health` is exempt.
+    pub bearer_token: Option<String>,
 }
 
 /// The Digstore remote server. Wraps an axum Router over a RemoteBackend.
@@ -40,6 +46,8 @@ impl RemoteServer {
                 backend,
                 rate_limiter: Arc::new(RateLimiter::new(10_000)),
                 require_auth: true,
+                read_only: false,
+                bearer_token: None,
             },
         }
     }
@@ -50,6 +58,8 @@ impl RemoteServer {
                 backend,
                 rate_limiter: rl,
                 require_auth: true,
+                read_only: false,
+                bearer_token: None,
             },
         }
     }
@@ -62,9 +72,24 @@ impl RemoteServer {
         self
     }
 
+    /// Refuse every write route (push, push negotiation, tombstone) with 403,
+    /// for a node that only mirrors an existing store. Builder-style.
+    pub fn read_only(mut self) -> Self {
+        self.state.read_only = true;
+        self
+    }
+
+    /// Require `Authorization: Bearer <token>` on every store route, on top of
+    /// (or, with `allow_anonymous`, instead of) §21.9 request auth. Builder-style.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.state.bearer_token = Some(token.into());
+        self
+    }
+
     /// Build the axum Router exposing the full §21.2 surface.
     pub fn router(&self) -> Router {
         Router::new()
+            .route("/health", get(health))
             .route(
                 "/stores/:id",
                 get(crate::handlers::descriptor::get_descriptor),
@@ -90,6 +115,10 @@ impl RemoteServer {
                 "/stores/:id/module/complete",
                 post(crate::handlers::module::post_complete),
             )
+            .route(
+                "/stores/:id/chunks/:hash",
+                get(crate::handlers::chunk::get_chunk).head(crate::handlers::chunk::head_chunk),
+            )
             .route(
                 "/stores/:id/content",
                 post(crate::handlers::content::post_content),
@@ -128,8 +157,17 @@ impl RemoteServer {
 /// Per-request authentication middleware (paper §21.9). When `require_auth`, every
 /// request must carry a fresh, valid signature from the caller's identity key over
 /// the canonical request message; otherwise 401. No-op when auth is disabled
-/// (`allow_anonymous`).
+/// (`allow_anonymous`). The node-level bearer token and read-only gate are
+/// checked first, whether or not §21.9 auth is on.
 async fn auth_mw(State(s): State<AppState>, req: AxRequest, next: Next) -> Response {
+    if let Some(token) = &s.bearer_token {
+        if req.uri().path().starts_with("/stores/") && !presents_bearer(&req, token) {
+            return RemoteError::MissingBearer.into_response();
+        }
+    }
+    if s.read_only && is_write_route(req.uri().path(), req.method()) {
+        return RemoteError::Unauthorized("this node is read-only".into()).into_response();
+    }
     if !s.require_auth {
         return next.run(req).await;
     }
@@ -139,6 +177,37 @@ async fn auth_mw(State(s): State<AppState>, req: AxRequest, next: Next) -> Respo
     }
 }
 
+/// GET /health — liveness probe for load balancers and `serve` supervisors.
+/// Unauthenticated and not rate-limited (it names no store).
+async fn health() -> Response {
+    axum::Json(serde_json::json!({ "status": "ok" })).into_response()
+}
+
+/// True when any `Authorization: Bearer …` header on `req` carries `token`.
+/// Compared in constant time so the token cannot be probed byte by byte.
+fn presents_bearer(req: &AxRequest, token: &str) -> bool {
+    req.headers()
+        .get_all(http::header::AUTHORIZATION)
+        .iter()
+        .filter_map(|v| v.to_str().ok()?.strip_prefix("Bearer "))
+        .any(|got| {
+            got.len() == token.len()
+                && got
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        })
+}
+
+/// True for the routes that change remote state.
+fn is_write_route(path: &str, http_method: &http::Method) -> bool {
+    matches!(
+        request_method_tag(path, http_method),
+        Some("push" | "push-init" | "push-complete" | "tombstone")
+    )
+}
+
 /// The logical operation a `(path, http method)` maps to — must be byte-identical
 /// to the `method` the `DigClient` signs (so the verified message matches). Returns
 /// None for a path the auth layer does not guard (it then passes through).
@@ -157,6 +226,7 @@ fn request_method_tag(path: &str, http_method: &http::Method) -> Option<&'static
         ("module/upload", &Method::POST) => Some("push-init"),
         ("module/complete", &Method::POST) => Some("push-complete"),
         ("content", &Method::POST) => Some("content"),
+        (r, &Method::GET) | (r, &Method::HEAD) if r.starts_with("chunks/") => Some("chunk"),
         ("proof", &Method::POST) => Some("proof"),
         ("delta", &Method::GET) | ("delta", &Method::POST) => Some("delta"),
         ("tombstone", &Method::POST) => Some("tombstone"),
@@ -117,3 +117,59 @@ async fn stale_timestamp_is_rejected() {
     let resp = app.oneshot(req).await.unwrap();
     assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 }
+
+async fn status_of(app: axum::Router, method: Method, uri: String, bearer: Option<&str>) -> u16 {
+    let mut req = Request::builder().method(method).uri(uri);
+    if let Some(t) = bearer {
+        req = req.header("authorization", format!("Bearer {t}"));
+    }
+    app.oneshot(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn health_is_open_even_when_every_store_route_is_gated() {
+    let (be, _id, _hex) = one_store();
+    let app = RemoteServer::new(be).with_bearer_token("s3cret").router();
+    assert_eq!(
+        status_of(app, Method::GET, "/health".into(), None).await,
+        200
+    );
+}
+
+#[tokio::test]
+async fn bearer_token_gates_store_routes() {
+    let (be, _id, id_hex) = one_store();
+    let app = RemoteServer::new(be)
+        .allow_anonymous()
+        .with_bearer_token("s3cret")
+        .router();
+    let uri = format!("/stores/{id_hex}");
+    assert_eq!(
+        status_of(app.clone(), Method::GET, uri.clone(), None).await,
+        401
+    );
+    assert_eq!(
+        status_of(app.clone(), Method::GET, uri.clone(), Some("s3creT")).await,
+        401
+    );
+    assert_eq!(status_of(app, Method::GET, uri, Some("s3cret")).await, 200);
+}
+
+#[tokio::test]
+async fn read_only_node_refuses_writes_but_serves_reads() {
+    let (be, _id, id_hex) = one_store();
+    let app = RemoteServer::new(be).allow_anonymous().read_only().router();
+    let push = format!("/stores/{id_hex}/module?root={}", "11".repeat(32));
+    assert_eq!(status_of(app.clone(), Method::PUT, push, None).await, 403);
+    let tombstone = format!("/stores/{id_hex}/tombstone");
+    assert_eq!(
+        status_of(app.clone(), Method::POST, tombstone, None).await,
+        403
+    );
+    let module = format!("/stores/{id_hex}/module");
+    assert_eq!(status_of(app, Method::GET, module, None).await, 200);
+}
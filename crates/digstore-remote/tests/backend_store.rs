@@ -1,7 +1,7 @@
 mod test_helpers;
 use test_helpers::*;
 
-use digstore_core::{Bytes32, StoreConfig, Visibility};
+use digstore_core::{Bytes32, HashAlgorithm, StoreConfig, Visibility};
 use digstore_remote::{backend::RemoteBackend, PushMode, PushOutcome, RemoteError, StoreBackend};
 
 fn unique_tmp(tag: &str) -> std::path::PathBuf {
@@ -26,6 +26,8 @@ fn config_at(tmp: &std::path::Path, store_id: Bytes32) -> StoreConfig {
         visibility: Visibility::Public,
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     }
 }
 
@@ -0,0 +1,86 @@
+mod test_helpers;
+use test_helpers::*;
+
+use digstore_core::{Bytes32, HashAlgorithm, StoreConfig, Visibility};
+use digstore_remote::{DigClient, HttpChunkBackend, RemoteServer, StoreBackend};
+use digstore_store::{ChunkBackend, FixedClock, Store, StoreError};
+use std::sync::Arc;
+
+/// Serve `backend` anonymously on an ephemeral port from a background runtime
+/// (the chunk backend is synchronous, so the test thread must not be async).
+fn spawn_server(backend: StoreBackend) -> String {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let app = RemoteServer::new(Arc::new(backend))
+                .allow_anonymous()
+                .router();
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            tx.send(listener.local_addr().unwrap()).unwrap();
+            axum::serve(listener, app).await.unwrap();
+        });
+    });
+    format!("http://{}", rx.recv().unwrap())
+}
+
+#[test]
+fn http_chunk_backend_reads_a_resource_with_range_requests() {
+    let dir = tempfile::tempdir().unwrap();
+    let store_id = b32(0x51);
+    let config = StoreConfig {
+        store_id,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        max_size: 16 * 1024 * 1024,
+        visibility: Visibility::Public,
+        label: None,
+        description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
+    };
+    let mut store = Store::init(config, FixedClock::new(1)).unwrap();
+    let body: Vec<u8> = (0..400_000u32).map(|i| (i * 31 % 251) as u8).collect();
+    store.stage_file("video.bin", &body).unwrap();
+    let root = store.commit().unwrap();
+    let first = store.stat_file(root, "video.bin").unwrap().chunks[0].clone();
+
+    let base = spawn_server(StoreBackend::open(
+        dir.path().to_string_lossy(),
+        store_id,
+        b48(7),
+        16 * 1024 * 1024,
+    ));
+    let remote = HttpChunkBackend::new(DigClient::new(base), store_id).unwrap();
+
+    assert_eq!(remote.len(first.hash).unwrap(), first.size);
+    let local = store.resolve_chunk(first.hash).unwrap();
+    assert_eq!(
+        remote.read_range(first.hash, 10..20).unwrap(),
+        local[10..20]
+    );
+    assert_eq!(
+        remote
+            .read_range(first.hash, first.size - 4..first.size + 100)
+            .unwrap(),
+        local[local.len() - 4..]
+    );
+    assert!(remote
+        .read_range(first.hash, first.size + 1..first.size + 5)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store
+            .read_resource_from(&remote, root, "video.bin")
+            .unwrap(),
+        body
+    );
+
+    assert!(matches!(
+        remote.len(Bytes32([0xEE; 32])),
+        Err(StoreError::ChunkNotFound(_))
+    ));
+    assert!(matches!(
+        remote.write(first.hash, b"x"),
+        Err(StoreError::ReadOnly(_))
+    ));
+}
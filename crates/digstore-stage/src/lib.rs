@@ -32,8 +32,8 @@ use std::path::{Path, PathBuf};
 
 use digstore_chunker::{chunk_slice, Chunk};
 use digstore_core::{
-    AuthenticationInfo, Bytes32, Bytes48, ChunkerConfig, MerkleTree, MetadataManifest, SecretSalt,
-    StoreConfig, TrustedHostKey, Urn, Visibility, CHAIN, MAX_STORE_BYTES,
+    AuthenticationInfo, Bytes32, Bytes48, ChunkerConfig, HashAlgorithm, MerkleTree,
+    MetadataManifest, StoreConfig, TrustedHostKey, Urn, Visibility, CHAIN, MAX_STORE_BYTES,
 };
 use digstore_store::{ChunkRef, GenerationManifest, KeyTableRecord};
 
@@ -89,13 +89,6 @@ pub fn canonical_resource_urn(store_id: Bytes32, resource_key: &str) -> Urn {
     }
 }
 
-fn salt_of(visibility: &Visibility) -> Option<SecretSalt> {
-    match visibility {
-        Visibility::Private(s) => Some(*s),
-        Visibility::Public => None,
-    }
-}
-
 /// The effective per-store cap: the configured `max_size`, or the workspace
 /// default ([`MAX_STORE_BYTES`]) when it is unset (`0`).
 fn cap_of(max_size: u64) -> u64 {
@@ -125,6 +118,35 @@ pub struct PreparedCommit {
     timestamp: u64,
     /// The store id these resources belong to (for the key table URNs).
     store_id: Bytes32,
+    /// The generation this one layers its changes on, if any.
+    parent: Option<Bytes32>,
+    /// The salt epoch the chunks were sealed under.
+    key_epoch: u32,
+}
+
+impl PreparedCommit {
+    /// Record `timestamp` as the commit time instead of the one it was built
+    /// with. The root does not depend on it; the generation manifest does, so a
+    /// caller pinning the time gets a reproducible manifest.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Record `parent` as the generation this one holds only the changes of
+    /// (see `GenerationManifest::parent`). The root does not depend on it.
+    pub fn with_parent(mut self, parent: Bytes32) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Record `key_epoch` as the salt epoch the resources were sealed under
+    /// (see `GenerationManifest::key_epoch`); the caller sealed them with that
+    /// epoch's visibility. The root does not depend on it.
+    pub fn with_key_epoch(mut self, key_epoch: u32) -> Self {
+        self.key_epoch = key_epoch;
+        self
+    }
 }
 
 /// Compute the staged generation's merkle `root` + the in-memory state
@@ -157,7 +179,33 @@ pub fn build_prepared(
     next_id: u64,
     timestamp: u64,
 ) -> Result<PreparedCommit, StageError> {
-    let salt = salt_of(visibility);
+    build_prepared_with_progress(
+        files,
+        store_id,
+        visibility,
+        max_size,
+        pre_encrypted,
+        next_id,
+        timestamp,
+        &mut |_, _| {},
+    )
+}
+
+/// [`build_prepared`], calling `on_progress(done, total)` as each chunk is
+/// sealed. Both are plaintext byte counts and `total` is the whole staged size,
+/// so a caller can drive a determinate progress bar from it.
+#[allow(clippy::too_many_arguments)]
+pub fn build_prepared_with_progress(
+    files: &[(String, Vec<u8>)],
+    store_id: Bytes32,
+    visibility: &Visibility,
+    max_size: u64,
+    pre_encrypted: bool,
+    next_id: u64,
+    timestamp: u64,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<PreparedCommit, StageError> {
+    let sealer = digstore_crypto::UrnSealer::for_visibility(visibility);
 
     if files.is_empty() {
         return Err(StageError::EmptyStaging);
@@ -178,6 +226,8 @@ pub fn build_prepared(
     let mut key_records: Vec<(String, Vec<u32>, u64)> = Vec::new();
     // (static_key, leaf) so we can sort leaves ascending by static_key (D5).
     let mut keyed_leaves: Vec<([u8; 32], Bytes32)> = Vec::new();
+    let mut done: u64 = 0;
+    on_progress(done, staged_total);
 
     for (resource_key, content) in files {
         let urn = canonical_resource_urn(store_id, resource_key);
@@ -186,9 +236,11 @@ pub fn build_prepared(
             // PRE-ENCRYPTED: the bytes ARE the resource's already-sealed ciphertext (the client
             // sealed it under the per-URN key; the server never sees plaintext or the key). Stored
             // as ONE chunk — D5 leaf = SHA-256(these bytes). No chunking, no encryption here.
+            done += content.len() as u64;
+            on_progress(done, staged_total);
             vec![content.clone()]
         } else {
-            let aes_key = digstore_crypto::derive_decryption_key(&urn.canonical(), salt.as_ref());
+            let aes_key = sealer.key_for(&urn);
             let chunks: Vec<Chunk> = chunk_slice(content, &chunker_config());
             let chunks = if chunks.is_empty() {
                 vec![Chunk::new(0, Vec::new())]
@@ -197,7 +249,12 @@ pub fn build_prepared(
             };
             chunks
                 .iter()
-                .map(|c| digstore_crypto::encrypt_chunk(&aes_key, &c.data))
+                .map(|c| {
+                    let ct = digstore_crypto::encrypt_chunk(&aes_key, &c.data);
+                    done += c.data.len() as u64;
+                    on_progress(done, staged_total);
+                    ct
+                })
                 .collect()
         };
         let mut indices = Vec::with_capacity(chunk_cts.len());
@@ -241,6 +298,8 @@ pub fn build_prepared(
         next_id,
         timestamp,
         store_id,
+        parent: None,
+        key_epoch: 0,
     })
 }
 
@@ -325,6 +384,8 @@ pub fn finalize(
         next_id,
         timestamp,
         store_id,
+        parent,
+        key_epoch,
     } = prepared;
     let root_hex = root.to_hex();
     let generations_dir = opts.data_dir.join("generations");
@@ -361,6 +422,8 @@ pub fn finalize(
         timestamp,
         chunks: chunk_refs,
         key_table,
+        key_epoch,
+        parent,
     };
     manifest
         .write_to(generations_dir.join(&root_hex).join("manifest.json"))
@@ -507,6 +570,8 @@ pub fn ephemeral_config(store_id: Bytes32, visibility: Visibility, data_dir: &Pa
         visibility,
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     }
 }
 
@@ -745,4 +810,39 @@ mod tests {
         .root;
         assert_eq!(r1, r2);
     }
+
+    #[test]
+    fn progress_reports_every_staged_byte() {
+        let files = vec![
+            ("a.bin".to_string(), vec![7u8; 300_000]),
+            ("b.txt".to_string(), b"beta".to_vec()),
+        ];
+        let mut seen: Vec<(u64, u64)> = Vec::new();
+        let prepared = build_prepared_with_progress(
+            &files,
+            Bytes32([3u8; 32]),
+            &Visibility::Public,
+            MAX_STORE_BYTES,
+            false,
+            0,
+            0,
+            &mut |done, total| seen.push((done, total)),
+        )
+        .unwrap();
+        let plain = build_prepared(
+            &files,
+            Bytes32([3u8; 32]),
+            &Visibility::Public,
+            MAX_STORE_BYTES,
+            false,
+            0,
+            0,
+        )
+        .unwrap();
+        assert_eq!(prepared.root, plain.root);
+        assert!(seen.iter().all(|&(_, total)| total == 300_004));
+        assert!(seen.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert_eq!(seen.first(), Some(&(0, 300_004)));
+        assert_eq!(seen.last(), Some(&(300_004, 300_004)));
+    }
 }
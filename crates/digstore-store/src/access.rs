@@ -0,0 +1,96 @@
+use crate::error::{Result, StoreError};
+use digstore_core::Bytes48;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Who may write to a store, kept in its `access.json`: the BLS public keys
+/// of the wallets allowed to stage, commit and otherwise change it. A store
+/// without the file is open to any writer; see
+/// [`Store::set_access_policy`](crate::Store::set_access_policy).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessPolicy {
+    pub writers: Vec<Bytes48>,
+}
+
+impl AccessPolicy {
+    /// A policy admitting exactly `writers`.
+    pub fn writers(writers: impl IntoIterator<Item = Bytes48>) -> Self {
+        let mut policy = Self::default();
+        for key in writers {
+            policy.allow(key);
+        }
+        policy
+    }
+
+    pub fn allows(&self, key: &Bytes48) -> bool {
+        self.writers.contains(key)
+    }
+
+    /// Admit `key`; `false` if it already was.
+    pub fn allow(&mut self, key: Bytes48) -> bool {
+        if self.allows(&key) {
+            return false;
+        }
+        self.writers.push(key);
+        true
+    }
+
+    /// Stop admitting `key`; `false` if it never was.
+    pub fn revoke(&mut self, key: &Bytes48) -> bool {
+        let before = self.writers.len();
+        self.writers.retain(|k| k != key);
+        self.writers.len() != before
+    }
+}
+
+/// Read an `access.json`; a missing file means no policy.
+pub(crate) fn read_policy(path: &Path) -> Result<Option<AccessPolicy>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| StoreError::InvalidConfig(format!("access.json: {e}")))
+}
+
+/// Write `policy` to `path` via a temp sibling and a rename, or remove the
+/// file when `policy` is `None`.
+pub(crate) fn write_policy(path: &Path, policy: Option<&AccessPolicy>) -> Result<()> {
+    let Some(policy) = policy else {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    };
+    let text = serde_json::to_string_pretty(policy)
+        .map_err(|e| StoreError::InvalidConfig(format!("access.json: {e}")))?;
+    let tmp = path.with_extension("json.tmp");
+    crate::chunkstore::write_synced(&tmp, text.as_bytes())?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn policy_round_trips_and_clears() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("access.json");
+        assert_eq!(read_policy(&path).unwrap(), None);
+
+        let mut policy = AccessPolicy::writers([Bytes48([1; 48]), Bytes48([1; 48])]);
+        assert_eq!(policy.writers.len(), 1);
+        assert!(policy.allow(Bytes48([2; 48])));
+        assert!(policy.revoke(&Bytes48([1; 48])));
+        assert!(!policy.revoke(&Bytes48([1; 48])));
+        write_policy(&path, Some(&policy)).unwrap();
+        assert_eq!(read_policy(&path).unwrap(), Some(policy));
+
+        write_policy(&path, None).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::chunkstore::ChunkStore;
+use crate::error::{Result, StoreError};
+use digstore_core::Bytes32;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::sync::RwLock;
+
+/// Where sealed chunk bodies live. The local [`ChunkStore`] is one backend; a
+/// remote one (an HTTP range server, an object store) lets
+/// [`Store::read_resource_from`](crate::Store::read_resource_from) reassemble a
+/// resource from its manifest without copying the store's chunks locally.
+///
+/// Chunks are content-addressed and write-once (§8.2), so a backend never needs
+/// to reconcile concurrent writers: a second `write` of the same hash is a no-op.
+pub trait ChunkBackend: Send + Sync {
+    /// Size in bytes of chunk `hash`, or `ChunkNotFound`.
+    fn len(&self, hash: Bytes32) -> Result<u64>;
+
+    /// Bytes `range` of chunk `hash`. A range running past the end of the
+    /// chunk is clamped to it.
+    fn read_range(&self, hash: Bytes32, range: Range<u64>) -> Result<Vec<u8>>;
+
+    /// Store `data` under `hash`. Returns `true` if newly written, `false` if
+    /// it already existed. Read-only backends return [`StoreError::ReadOnly`].
+    fn write(&self, hash: Bytes32, data: &[u8]) -> Result<bool>;
+
+    /// The whole of chunk `hash`.
+    fn read(&self, hash: Bytes32) -> Result<Vec<u8>> {
+        let len = self.len(hash)?;
+        self.read_range(hash, 0..len)
+    }
+}
+
+impl ChunkBackend for ChunkStore {
+    fn len(&self, hash: Bytes32) -> Result<u64> {
+        match std::fs::metadata(self.chunk_path(hash)) {
+            Ok(meta) => Ok(meta.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StoreError::ChunkNotFound(hash.to_hex()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn read_range(&self, hash: Bytes32, range: Range<u64>) -> Result<Vec<u8>> {
+        let len = self.len(hash)?;
+        let start = range.start.min(len);
+        let end = range.end.clamp(start, len);
+        let mut file = std::fs::File::open(self.chunk_path(hash))?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut out = Vec::with_capacity((end - start) as usize);
+        file.take(end - start).read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    fn write(&self, hash: Bytes32, data: &[u8]) -> Result<bool> {
+        self.put(hash, data)
+    }
+}
+
+/// A [`ChunkBackend`] that keeps chunks in memory: for tests, and for
+/// embedders that want a transient content store. Fill it with
+/// [`Store::copy_chunks_to`](crate::Store::copy_chunks_to) and read from it
+/// with [`Store::read_resource_from`](crate::Store::read_resource_from).
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    chunks: RwLock<HashMap<Bytes32, Vec<u8>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many chunks it holds.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks().len()
+    }
+
+    /// Bytes those chunks hold.
+    pub fn total_bytes(&self) -> u64 {
+        self.chunks().values().map(|c| c.len() as u64).sum()
+    }
+
+    fn chunks(&self) -> std::sync::RwLockReadGuard<'_, HashMap<Bytes32, Vec<u8>>> {
+        self.chunks.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ChunkBackend for MemoryBackend {
+    fn len(&self, hash: Bytes32) -> Result<u64> {
+        self.chunks()
+            .get(&hash)
+            .map(|c| c.len() as u64)
+            .ok_or_else(|| StoreError::ChunkNotFound(hash.to_hex()))
+    }
+
+    fn read_range(&self, hash: Bytes32, range: Range<u64>) -> Result<Vec<u8>> {
+        let chunks = self.chunks();
+        let chunk = chunks
+            .get(&hash)
+            .ok_or_else(|| StoreError::ChunkNotFound(hash.to_hex()))?;
+        let len = chunk.len() as u64;
+        let start = range.start.min(len);
+        let end = range.end.clamp(start, len);
+        Ok(chunk[start as usize..end as usize].to_vec())
+    }
+
+    fn write(&self, hash: Bytes32, data: &[u8]) -> Result<bool> {
+        let mut chunks = self.chunks.write().unwrap_or_else(|e| e.into_inner());
+        if chunks.contains_key(&hash) {
+            return Ok(false);
+        }
+        chunks.insert(hash, data.to_vec());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn local_backend_reads_clamped_ranges() {
+        let dir = tempdir().unwrap();
+        let backend: &dyn ChunkBackend = &ChunkStore::new(dir.path());
+        let hash = Bytes32([7; 32]);
+        assert!(matches!(
+            backend.len(hash),
+            Err(StoreError::ChunkNotFound(_))
+        ));
+        assert!(backend.write(hash, b"0123456789").unwrap());
+        assert!(!backend.write(hash, b"0123456789").unwrap());
+        assert_eq!(backend.len(hash).unwrap(), 10);
+        assert_eq!(backend.read_range(hash, 2..5).unwrap(), b"234");
+        assert_eq!(backend.read_range(hash, 8..100).unwrap(), b"89");
+        assert!(backend.read_range(hash, 20..30).unwrap().is_empty());
+        assert_eq!(backend.read(hash).unwrap(), b"0123456789");
+    }
+
+    #[test]
+    fn memory_backend_matches_the_local_one() {
+        let memory = MemoryBackend::new();
+        let backend: &dyn ChunkBackend = &memory;
+        let hash = Bytes32([7; 32]);
+        assert!(matches!(
+            backend.len(hash),
+            Err(StoreError::ChunkNotFound(_))
+        ));
+        assert!(backend.write(hash, b"0123456789").unwrap());
+        assert!(!backend.write(hash, b"other").unwrap());
+        assert_eq!(backend.read_range(hash, 2..5).unwrap(), b"234");
+        assert_eq!(backend.read_range(hash, 8..100).unwrap(), b"89");
+        assert!(backend.read_range(hash, 20..30).unwrap().is_empty());
+        assert_eq!(backend.read(hash).unwrap(), b"0123456789");
+        assert_eq!((memory.chunk_count(), memory.total_bytes()), (1, 10));
+    }
+}
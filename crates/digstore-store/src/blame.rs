@@ -0,0 +1,95 @@
+use digstore_core::Bytes32;
+
+/// One line of a file's newest version, with the version that introduced it,
+/// from [`Store::blame`](crate::Store::blame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// 1-based line number in the newest version.
+    pub line: usize,
+    pub text: String,
+    /// Root of the generation whose version of the file added the line.
+    pub root: Bytes32,
+    pub generation_id: u64,
+    pub timestamp: u64,
+}
+
+/// For each line of `new`, the index of the line of `old` it was kept from,
+/// or `None` when `new` added it. Kept lines are a longest common subsequence
+/// of the two, found after trimming the common prefix and suffix, so the
+/// quadratic table only spans the edited middle.
+pub(crate) fn matched_lines(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut out = vec![None; new.len()];
+    for (i, slot) in out.iter_mut().enumerate().take(prefix) {
+        *slot = Some(i);
+    }
+    for i in 1..=suffix {
+        out[new.len() - i] = Some(old.len() - i);
+    }
+
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let width = b.len() + 1;
+    // lcs[i * width + j]: length of the LCS of a[i..] and b[j..].
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out[prefix + j] = Some(prefix + i);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_lines_keep_their_index() {
+        let old = ["a", "b", "c"];
+        assert_eq!(matched_lines(&old, &old), vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn insertions_and_edits_are_new_lines() {
+        let old = ["fn main() {", "    one();", "}"];
+        let new = ["// header", "fn main() {", "    two();", "    one();", "}"];
+        assert_eq!(
+            matched_lines(&old, &new),
+            vec![None, Some(0), None, Some(1), Some(2)]
+        );
+    }
+
+    #[test]
+    fn deletions_and_moves_keep_the_longest_common_run() {
+        let old = ["a", "b", "c", "d", "e"];
+        let new = ["a", "d", "c", "e"];
+        let matched = matched_lines(&old, &new);
+        assert_eq!(matched.iter().filter(|m| m.is_some()).count(), 3);
+        assert_eq!((matched[0], matched[3]), (Some(0), Some(4)));
+        assert_eq!(matched_lines(&[], &["x"]), vec![None]);
+        assert!(matched_lines(&["x"], &[]).is_empty());
+    }
+}
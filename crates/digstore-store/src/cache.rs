@@ -0,0 +1,163 @@
+use digstore_core::Bytes32;
+use std::collections::{BTreeMap, HashMap};
+
+/// Default byte budget of a [`Store`](crate::Store)'s chunk cache (64 MiB).
+pub const DEFAULT_CHUNK_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Default byte budget of a [`Store`](crate::Store)'s read-ahead window of
+/// decrypted chunks used by range reads (16 MiB).
+pub const DEFAULT_READ_AHEAD_BYTES: usize = 16 * 1024 * 1024;
+
+/// Hit/miss counters and occupancy of a [`ChunkCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+/// In-process LRU cache of chunk bodies keyed by content hash, bounded by total
+/// bytes. Chunks are content-addressed and write-once (§8.2), so a cached body
+/// can never go stale; the cache only saves the generation-dir scan and the
+/// file read on repeated lookups. A capacity of `0` disables caching.
+#[derive(Debug)]
+pub struct ChunkCache {
+    capacity: usize,
+    used: usize,
+    tick: u64,
+    /// hash -> (body, last-use tick).
+    entries: HashMap<[u8; 32], (Vec<u8>, u64)>,
+    /// last-use tick -> hash, oldest first (the eviction order).
+    recency: BTreeMap<u64, [u8; 32]>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ChunkCache {
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            capacity: capacity_bytes,
+            used: 0,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up a chunk, counting a hit or a miss and refreshing its recency.
+    pub fn get(&mut self, hash: Bytes32) -> Option<Vec<u8>> {
+        self.tick += 1;
+        match self.entries.get_mut(&hash.0) {
+            Some((body, last)) => {
+                self.recency.remove(last);
+                *last = self.tick;
+                self.recency.insert(self.tick, hash.0);
+                self.hits += 1;
+                Some(body.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Insert a chunk body, evicting least-recently-used chunks to stay within
+    /// capacity. A body larger than the whole capacity is not cached.
+    pub fn insert(&mut self, hash: Bytes32, body: Vec<u8>) {
+        if body.len() > self.capacity || self.entries.contains_key(&hash.0) {
+            return;
+        }
+        while self.used + body.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.used -= evicted.len();
+            }
+        }
+        self.tick += 1;
+        self.used += body.len();
+        self.recency.insert(self.tick, hash.0);
+        self.entries.insert(hash.0, (body, self.tick));
+    }
+
+    /// Change the byte budget, evicting down to it if it shrank.
+    pub fn set_capacity(&mut self, capacity_bytes: usize) {
+        self.capacity = capacity_bytes;
+        while self.used > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.used -= evicted.len();
+            }
+        }
+    }
+
+    /// Drop every cached chunk. The capacity and the hit/miss counters are
+    /// kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.used = 0;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            bytes: self.used,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(x: u8) -> Bytes32 {
+        Bytes32([x; 32])
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let mut c = ChunkCache::new(100);
+        assert!(c.get(h(1)).is_none());
+        c.insert(h(1), vec![7; 10]);
+        assert_eq!(c.get(h(1)).unwrap(), vec![7; 10]);
+        let s = c.stats();
+        assert_eq!((s.hits, s.misses, s.entries, s.bytes), (1, 1, 1, 10));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        let mut c = ChunkCache::new(30);
+        c.insert(h(1), vec![0; 10]);
+        c.insert(h(2), vec![0; 10]);
+        c.insert(h(3), vec![0; 10]);
+        // Touch 1 so 2 becomes the oldest.
+        assert!(c.get(h(1)).is_some());
+        c.insert(h(4), vec![0; 10]);
+        assert!(c.get(h(2)).is_none());
+        assert!(c.get(h(1)).is_some());
+        assert!(c.get(h(3)).is_some());
+        assert!(c.get(h(4)).is_some());
+        assert_eq!(c.stats().bytes, 30);
+    }
+
+    #[test]
+    fn oversized_bodies_and_zero_capacity_are_not_cached() {
+        let mut c = ChunkCache::new(5);
+        c.insert(h(1), vec![0; 6]);
+        assert_eq!(c.stats().entries, 0);
+        c.insert(h(2), vec![0; 5]);
+        c.set_capacity(0);
+        assert_eq!(c.stats().entries, 0);
+        assert_eq!(c.stats().bytes, 0);
+    }
+}
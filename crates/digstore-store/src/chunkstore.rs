@@ -1,5 +1,6 @@
 use crate::error::{Result, StoreError};
 use digstore_core::Bytes32;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Per-directory content-addressed, write-once chunk store. One file per unique
@@ -17,7 +18,7 @@ impl ChunkStore {
         }
     }
 
-    fn chunk_path(&self, hash: Bytes32) -> PathBuf {
+    pub(crate) fn chunk_path(&self, hash: Bytes32) -> PathBuf {
         self.chunks_dir.join(hash.to_hex())
     }
 
@@ -29,9 +30,9 @@ impl ChunkStore {
         if path.exists() {
             return Ok(false);
         }
-        // Atomic-ish: write to temp then rename within the same dir.
+        // Atomic: write and sync a temp file, then rename within the same dir.
         let tmp = path.with_extension("tmp");
-        std::fs::write(&tmp, data)?;
+        write_synced(&tmp, data)?;
         std::fs::rename(&tmp, &path)?;
         Ok(true)
     }
@@ -71,6 +72,14 @@ impl ChunkStore {
     }
 }
 
+/// Write `data` to `path` and flush it to disk before returning.
+pub(crate) fn write_synced(path: &Path, data: &[u8]) -> Result<()> {
+    let mut f = std::fs::File::create(path)?;
+    f.write_all(data)?;
+    f.sync_all()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,7 @@
 use crate::error::{Result, StoreError};
-use digstore_core::{Bytes32, SecretSalt, StoreConfig, Visibility};
+use digstore_core::{Bytes32, HashAlgorithm, SecretSalt, StoreConfig, Visibility};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// TOML-friendly mirror of `StoreConfig`. Visibility is flattened to a string
@@ -17,6 +18,12 @@ struct ConfigToml {
     label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    /// `sha256` (omitted, the default) or `blake3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    /// Key epoch (decimal) → `public` or the hex salt it sealed under.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    salt_keyring: BTreeMap<String, String>,
 }
 
 impl ConfigToml {
@@ -33,6 +40,16 @@ impl ConfigToml {
             secret_salt,
             label: cfg.label.clone(),
             description: cfg.description.clone(),
+            hash: (cfg.hash_algorithm != HashAlgorithm::Sha256)
+                .then(|| cfg.hash_algorithm.as_str().to_string()),
+            salt_keyring: cfg
+                .salt_keyring
+                .iter()
+                .map(|(epoch, salt)| {
+                    let salt = salt.map_or_else(|| "public".to_string(), |s| hex::encode(s.0));
+                    (epoch.to_string(), salt)
+                })
+                .collect(),
         }
     }
 
@@ -46,12 +63,7 @@ impl ConfigToml {
                 let salt_hex = self.secret_salt.ok_or_else(|| {
                     StoreError::InvalidConfig("private store missing secret_salt".into())
                 })?;
-                let bytes = hex::decode(&salt_hex)
-                    .map_err(|_| StoreError::InvalidConfig("bad secret_salt hex".into()))?;
-                let arr: [u8; 32] = bytes.try_into().map_err(|_| {
-                    StoreError::InvalidConfig("secret_salt must be 32 bytes".into())
-                })?;
-                Visibility::Private(SecretSalt(arr))
+                Visibility::Private(parse_salt("secret_salt", &salt_hex)?)
             }
             other => {
                 return Err(StoreError::InvalidConfig(format!(
@@ -59,6 +71,26 @@ impl ConfigToml {
                 )))
             }
         };
+        let hash_algorithm = match self.hash.as_deref() {
+            None => HashAlgorithm::Sha256,
+            Some(name) => HashAlgorithm::parse(name).ok_or_else(|| {
+                StoreError::InvalidConfig(format!("unknown hash algorithm: {name}"))
+            })?,
+        };
+        let salt_keyring = self
+            .salt_keyring
+            .iter()
+            .map(|(epoch, salt)| {
+                let epoch = epoch.parse::<u32>().map_err(|_| {
+                    StoreError::InvalidConfig(format!("bad salt_keyring epoch: {epoch}"))
+                })?;
+                let salt = match salt.as_str() {
+                    "public" => None,
+                    hex => Some(parse_salt("salt_keyring", hex)?),
+                };
+                Ok((epoch, salt))
+            })
+            .collect::<Result<_>>()?;
         Ok(StoreConfig {
             store_id,
             data_dir: self.data_dir,
@@ -66,10 +98,22 @@ impl ConfigToml {
             visibility,
             label: self.label,
             description: self.description,
+            hash_algorithm,
+            salt_keyring,
         })
     }
 }
 
+/// Decode a 32-byte hex salt; `field` names it in the error.
+fn parse_salt(field: &str, salt_hex: &str) -> Result<SecretSalt> {
+    let bytes =
+        hex::decode(salt_hex).map_err(|_| StoreError::InvalidConfig(format!("bad {field} hex")))?;
+    let arr: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| StoreError::InvalidConfig(format!("{field} must be 32 bytes")))?;
+    Ok(SecretSalt(arr))
+}
+
 /// Serialize a `StoreConfig` to `config.toml` at `path`.
 ///
 /// A private store embeds its `SecretSalt` (master key material) here, so the
@@ -114,7 +158,7 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<StoreConfig> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use digstore_core::{Bytes32, SecretSalt, StoreConfig, Visibility};
+    use digstore_core::{Bytes32, HashAlgorithm, SecretSalt, StoreConfig, Visibility};
     use tempfile::tempdir;
 
     fn public_cfg() -> StoreConfig {
@@ -125,6 +169,8 @@ mod tests {
             visibility: Visibility::Public,
             label: None,
             description: None,
+            hash_algorithm: HashAlgorithm::Sha256,
+            salt_keyring: Default::default(),
         }
     }
 
@@ -194,6 +240,65 @@ mod tests {
         let loaded = load_config(&path).unwrap();
         assert_eq!(loaded.label, None);
         assert_eq!(loaded.description, None);
+        assert_eq!(loaded.hash_algorithm, HashAlgorithm::Sha256);
+    }
+
+    #[test]
+    fn hash_algorithm_is_recorded_only_when_not_sha256() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        save_config(&path, &public_cfg()).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("hash"));
+
+        let mut cfg = public_cfg();
+        cfg.hash_algorithm = HashAlgorithm::Blake3;
+        save_config(&path, &cfg).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("hash = \"blake3\""));
+        assert_eq!(
+            load_config(&path).unwrap().hash_algorithm,
+            HashAlgorithm::Blake3
+        );
+
+        let text = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("blake3", "md5");
+        std::fs::write(&path, text).unwrap();
+        assert!(matches!(
+            load_config(&path).unwrap_err(),
+            StoreError::InvalidConfig(_)
+        ));
+    }
+
+    #[test]
+    fn salt_keyring_roundtrips_and_is_omitted_when_empty() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        save_config(&path, &public_cfg()).unwrap();
+        assert!(!std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("salt_keyring"));
+
+        let mut cfg = public_cfg();
+        cfg.salt_keyring.insert(0, None);
+        cfg.salt_keyring.insert(1, Some(SecretSalt([0x07u8; 32])));
+        cfg.visibility = Visibility::Private(SecretSalt([0x07u8; 32]));
+        save_config(&path, &cfg).unwrap();
+        let loaded = load_config(&path).unwrap();
+        assert_eq!(loaded.salt_keyring, cfg.salt_keyring);
+        assert_eq!(loaded.key_epoch(), 1);
+        assert_eq!(loaded.epoch_visibility(0), Visibility::Public);
+        assert_eq!(loaded.epoch_visibility(1), cfg.visibility);
+
+        let text = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("\n1 = ", "\nx = ");
+        std::fs::write(&path, text).unwrap();
+        assert!(matches!(
+            load_config(&path).unwrap_err(),
+            StoreError::InvalidConfig(_)
+        ));
     }
 
     #[test]
@@ -0,0 +1,184 @@
+/// What a resource's bytes look like, from
+/// [`Store::content_type`](crate::Store::content_type) or [`sniff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentType {
+    pub mime_type: &'static str,
+    pub is_binary: bool,
+    /// `ascii`, `utf-8`, `utf-16le` or `utf-16be` for text; `None` for binary.
+    pub encoding_guess: Option<&'static str>,
+}
+
+/// Leading bytes of well-known binary formats.
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\0asm", "application/wasm"),
+    (b"\x7fELF", "application/x-elf"),
+    (b"wOFF", "font/woff"),
+    (b"wOF2", "font/woff2"),
+    (b"OggS", "audio/ogg"),
+    (b"ID3", "audio/mpeg"),
+    (b"SQLite format 3\0", "application/vnd.sqlite3"),
+];
+
+/// Guess the content type of resource `path` from `head`, its first bytes
+/// (one chunk is plenty): magic bytes first, then whether `head` reads as
+/// text, then the extension for the exact text or binary type.
+pub fn sniff(path: &str, head: &[u8]) -> ContentType {
+    let binary = |mime_type| ContentType {
+        mime_type,
+        is_binary: true,
+        encoding_guess: None,
+    };
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return binary(mime);
+    }
+    if head.len() >= 12 && &head[..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        return binary("image/webp");
+    }
+    if head.len() >= 8 && &head[4..8] == b"ftyp" {
+        return binary("video/mp4");
+    }
+    let extension = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    let by_extension = extension.as_deref().and_then(mime_for_extension);
+    match text_encoding(head) {
+        Some(encoding) => ContentType {
+            mime_type: by_extension
+                .filter(|m| is_textual(m))
+                .unwrap_or("text/plain"),
+            is_binary: false,
+            encoding_guess: Some(encoding),
+        },
+        None => binary(
+            by_extension
+                .filter(|m| !is_textual(m))
+                .unwrap_or("application/octet-stream"),
+        ),
+    }
+}
+
+/// The text encoding `head` is in, or `None` if it looks binary. A UTF-16
+/// byte-order mark decides by itself; otherwise the bytes must be UTF-8
+/// (allowing a character cut off at the end) without NULs or stray control
+/// characters.
+fn text_encoding(head: &[u8]) -> Option<&'static str> {
+    if head.starts_with(b"\xff\xfe") {
+        return Some("utf-16le");
+    }
+    if head.starts_with(b"\xfe\xff") {
+        return Some("utf-16be");
+    }
+    let valid = match std::str::from_utf8(head) {
+        Ok(_) => head.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => return None,
+    };
+    let control = head[..valid]
+        .iter()
+        .any(|&b| b == 0 || (b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b)));
+    if control {
+        return None;
+    }
+    Some(if head.is_ascii() { "ascii" } else { "utf-8" })
+}
+
+fn is_textual(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/json" | "application/xml" | "image/svg+xml" | "application/toml"
+        )
+}
+
+fn mime_for_extension(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" | "map" => "application/json",
+        "md" | "markdown" => "text/markdown",
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "text/yaml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magic_bytes_win_over_the_extension() {
+        let png = sniff("logo.txt", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        assert_eq!(png.mime_type, "image/png");
+        assert!(png.is_binary);
+        assert_eq!(png.encoding_guess, None);
+        assert_eq!(sniff("a", b"RIFF\0\0\0\0WEBPVP8 ").mime_type, "image/webp");
+    }
+
+    #[test]
+    fn text_is_typed_by_extension_with_an_encoding_guess() {
+        let html = sniff("site/index.HTML", b"<!doctype html><p>hi</p>\n");
+        assert_eq!(
+            html,
+            ContentType {
+                mime_type: "text/html",
+                is_binary: false,
+                encoding_guess: Some("ascii"),
+            }
+        );
+        assert_eq!(
+            sniff("notes", "caf\u{e9}".as_bytes()).encoding_guess,
+            Some("utf-8")
+        );
+        assert_eq!(
+            sniff("notes", "caf\u{e9}".as_bytes()).mime_type,
+            "text/plain"
+        );
+        // A multi-byte character cut off at the end of the sniffed chunk.
+        assert!(!sniff("a.md", &"\u{e9}".as_bytes()[..1]).is_binary);
+        assert_eq!(
+            sniff("a.txt", b"\xff\xfeh\0i\0").encoding_guess,
+            Some("utf-16le")
+        );
+    }
+
+    #[test]
+    fn binary_without_magic_falls_back_to_the_extension() {
+        assert_eq!(sniff("f.woff2", b"\0\x01\x02").mime_type, "font/woff2");
+        let blob = sniff("data.bin", b"\0\x01\x02\x03");
+        assert_eq!(blob.mime_type, "application/octet-stream");
+        assert!(blob.is_binary);
+        // A text extension does not make binary bytes text.
+        assert_eq!(
+            sniff("a.txt", b"\0\0\0").mime_type,
+            "application/octet-stream"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::{Result, StoreError};
+use crate::generation::GenerationManifest;
+use digstore_core::Bytes32;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 10] = b"DIGDELTA1\n";
+
+/// The generations a store gained after `base`, and the chunks they need that
+/// were not stored at or before `base`, from
+/// [`Store::export_since`](crate::Store::export_since) and applied by
+/// [`Store::import_delta`](crate::Store::import_delta).
+///
+/// On disk: the magic line `DIGDELTA1`, a little-endian `u32` length and a
+/// JSON header (store id, base root, manifests in history order), then each
+/// chunk as its 32-byte hash, a little-endian `u64` length and its sealed
+/// bytes, to the end of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaBundle {
+    pub store_id: Bytes32,
+    pub base: Bytes32,
+    pub generations: Vec<GenerationManifest>,
+    pub chunks: Vec<(Bytes32, Vec<u8>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    store_id: Bytes32,
+    base: Bytes32,
+    generations: Vec<GenerationManifest>,
+}
+
+impl DeltaBundle {
+    pub fn write_to(&self, mut out: impl Write) -> Result<()> {
+        let header = serde_json::to_vec(&Header {
+            store_id: self.store_id,
+            base: self.base,
+            generations: self.generations.clone(),
+        })
+        .map_err(|e| StoreError::InvalidDelta(e.to_string()))?;
+        out.write_all(MAGIC)?;
+        out.write_all(&(header.len() as u32).to_le_bytes())?;
+        out.write_all(&header)?;
+        for (hash, data) in &self.chunks {
+            out.write_all(&hash.0)?;
+            out.write_all(&(data.len() as u64).to_le_bytes())?;
+            out.write_all(data)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn read_from(mut input: impl Read) -> Result<Self> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes)?;
+        let truncated = || StoreError::InvalidDelta("truncated bundle".into());
+        let rest = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or_else(|| StoreError::InvalidDelta("not a delta bundle".into()))?;
+        let (len, rest) = rest.split_first_chunk::<4>().ok_or_else(truncated)?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        let (header, mut rest) = rest.split_at(len);
+        let header: Header =
+            serde_json::from_slice(header).map_err(|e| StoreError::InvalidDelta(e.to_string()))?;
+
+        let mut chunks = Vec::new();
+        while !rest.is_empty() {
+            let (hash, after) = rest.split_first_chunk::<32>().ok_or_else(truncated)?;
+            let (len, after) = after.split_first_chunk::<8>().ok_or_else(truncated)?;
+            let len = u64::from_le_bytes(*len);
+            if (after.len() as u64) < len {
+                return Err(truncated());
+            }
+            let (data, after) = after.split_at(len as usize);
+            chunks.push((Bytes32(*hash), data.to_vec()));
+            rest = after;
+        }
+        Ok(Self {
+            store_id: header.store_id,
+            base: header.base,
+            generations: header.generations,
+            chunks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_round_trips_and_rejects_truncation() {
+        let bundle = DeltaBundle {
+            store_id: Bytes32([1; 32]),
+            base: Bytes32([2; 32]),
+            generations: Vec::new(),
+            chunks: vec![
+                (Bytes32([3; 32]), b"sealed".to_vec()),
+                (Bytes32([4; 32]), Vec::new()),
+            ],
+        };
+        let mut bytes = Vec::new();
+        bundle.write_to(&mut bytes).unwrap();
+        assert_eq!(DeltaBundle::read_from(bytes.as_slice()).unwrap(), bundle);
+
+        bytes.truncate(bytes.len() - 3);
+        assert!(DeltaBundle::read_from(bytes.as_slice()).is_err());
+        assert!(DeltaBundle::read_from(&b"DIGSTORE"[..]).is_err());
+    }
+}
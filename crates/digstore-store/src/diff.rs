@@ -55,6 +55,63 @@ impl GenerationDiff {
     }
 }
 
+/// How one file differs between the head generation and staging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// One changed file in a [`DiffSummary`]. Sizes are plaintext bytes; `None`
+/// on the side where the file does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiffStat {
+    pub resource_key: String,
+    pub change: FileChange,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+/// What committing the staging area would change against the head, from
+/// [`Store::staged_diff_summary`](crate::Store::staged_diff_summary).
+/// `files` holds only changed files, sorted by key.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    /// Staged bytes of added and modified files.
+    pub bytes_added: u64,
+    /// Head bytes of modified and deleted files.
+    pub bytes_removed: u64,
+    pub files: Vec<FileDiffStat>,
+}
+
+impl DiffSummary {
+    /// Tally `files` (sorting them by key).
+    pub fn from_files(mut files: Vec<FileDiffStat>) -> Self {
+        files.sort_by(|a, b| a.resource_key.cmp(&b.resource_key));
+        let mut summary = DiffSummary::default();
+        for f in &files {
+            match f.change {
+                FileChange::Added => summary.added += 1,
+                FileChange::Modified => summary.modified += 1,
+                FileChange::Deleted => summary.deleted += 1,
+            }
+            summary.bytes_added += f.new_size.unwrap_or(0);
+            summary.bytes_removed += f.old_size.unwrap_or(0);
+        }
+        summary.files = files;
+        summary
+    }
+
+    /// True when committing would change no file.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +147,8 @@ mod tests {
                     total_size: 1,
                 })
                 .collect(),
+            key_epoch: 0,
+            parent: None,
         }
     }
 
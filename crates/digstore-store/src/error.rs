@@ -18,18 +18,71 @@ pub enum StoreError {
     #[error("staging area corrupt: {0}")]
     CorruptStaging(String),
 
+    #[error("store {path} is locked by {}", .pid.map_or("another process".to_string(), |pid| format!("PID {pid}")))]
+    Locked { path: String, pid: Option<u32> },
+
     #[error("generation {0} not found")]
     GenerationNotFound(String),
 
+    /// `found_in` names another generation that does hold `key`, if any.
+    #[error("resource {key} not found in generation {root}{}", .found_in.as_ref().map(|r| format!(" (found at root {r})")).unwrap_or_default())]
+    ResourceNotFound {
+        key: String,
+        root: String,
+        found_in: Option<String>,
+    },
+
+    #[error("generation {root} rebuilds to a different root {rebuilt}; its manifest or chunks were altered")]
+    RootMismatch { root: String, rebuilt: String },
+
+    #[error("resource key {0} does not map to a path under the destination")]
+    UnsafeResourceKey(String),
+
     #[error("chunk {0} not found in any generation")]
     ChunkNotFound(String),
 
     #[error("root history is not monotonic: generation id {got} follows {last}")]
     NonMonotonicHistory { last: u64, got: u64 },
 
+    #[error("store at {0} was opened read-only")]
+    ReadOnly(String),
+
+    #[error("not authorized to write to this store: {0}")]
+    Unauthorized(String),
+
+    #[error("cannot squash: {0}")]
+    InvalidSquash(String),
+
+    #[error("cannot prune: {0}")]
+    InvalidPrune(String),
+
+    #[error("branch: {0}")]
+    InvalidBranch(String),
+
+    #[error("URN does not address this store: {0}")]
+    InvalidUrn(String),
+
+    #[error("invalid delta bundle: {0}")]
+    InvalidDelta(String),
+
+    #[error("invalid byte range: {0}")]
+    InvalidRange(String),
+
+    #[error("no generation to amend; commit first")]
+    NothingToAmend,
+
     #[error("nothing staged to commit")]
     EmptyStaging,
 
+    #[error("staging area has uncommitted changes; commit or clear them first")]
+    DirtyStaging,
+
+    #[error("chunk {0} failed to decrypt under the store's current key")]
+    DecryptFailed(String),
+
+    #[error("chunk backend error: {0}")]
+    Backend(String),
+
     #[error("manifest parse error: {0}")]
     Manifest(String),
 
@@ -39,10 +92,64 @@ pub enum StoreError {
     #[error("path is not under the staging base: {0}")]
     PathEscape(PathBuf),
 
+    #[error("store is corrupted: {entry}: {detail}")]
+    Corrupted { entry: String, detail: String },
+
+    #[error("invalid tar archive: {0}")]
+    InvalidTar(String),
+
+    #[error("tar archive content is over the {limit}-byte limit")]
+    TarTooLarge { limit: u64 },
+
+    #[error("{0} is a binary file; only text files can be blamed")]
+    BinaryFile(String),
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
+impl StoreError {
+    /// Stable, machine-readable code for this error (UPPER_SNAKE), one per
+    /// variant. Callers branch on this instead of matching message text; a
+    /// code never changes meaning once released.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StoreError::AlreadyExists(_) => "STORE_EXISTS",
+            StoreError::NotFound(_) => "STORE_NOT_FOUND",
+            StoreError::InvalidConfig(_) => "INVALID_CONFIG",
+            StoreError::CorruptStaging(_) => "STAGING_CORRUPTED",
+            StoreError::Locked { .. } => "STORE_LOCKED",
+            StoreError::GenerationNotFound(_) => "GENERATION_NOT_FOUND",
+            StoreError::ResourceNotFound { .. } => "RESOURCE_NOT_FOUND",
+            StoreError::RootMismatch { .. } => "ROOT_MISMATCH",
+            StoreError::UnsafeResourceKey(_) => "UNSAFE_RESOURCE_KEY",
+            StoreError::ChunkNotFound(_) => "CHUNK_NOT_FOUND",
+            StoreError::NonMonotonicHistory { .. } => "HISTORY_NOT_MONOTONIC",
+            StoreError::ReadOnly(_) => "READ_ONLY",
+            StoreError::Unauthorized(_) => "UNAUTHORIZED",
+            StoreError::InvalidSquash(_) => "INVALID_SQUASH",
+            StoreError::InvalidPrune(_) => "INVALID_PRUNE",
+            StoreError::InvalidBranch(_) => "INVALID_BRANCH",
+            StoreError::InvalidUrn(_) => "INVALID_URN",
+            StoreError::InvalidDelta(_) => "INVALID_DELTA",
+            StoreError::InvalidRange(_) => "INVALID_BYTE_RANGE",
+            StoreError::NothingToAmend => "NOTHING_TO_AMEND",
+            StoreError::EmptyStaging => "NOTHING_STAGED",
+            StoreError::DirtyStaging => "STAGING_DIRTY",
+            StoreError::DecryptFailed(_) => "DECRYPT_FAILED",
+            StoreError::Backend(_) => "BACKEND",
+            StoreError::Manifest(_) => "MANIFEST_INVALID",
+            StoreError::Config(_) => "CONFIG_UNREADABLE",
+            StoreError::PathEscape(_) => "PATH_ESCAPE",
+            StoreError::Corrupted { .. } => "STORE_CORRUPTED",
+            StoreError::InvalidTar(_) => "INVALID_TAR",
+            StoreError::TarTooLarge { .. } => "TAR_TOO_LARGE",
+            StoreError::BinaryFile(_) => "BINARY_FILE",
+            StoreError::Io(_) => "IO",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +160,65 @@ mod tests {
         assert_eq!(e.to_string(), "store already exists at /tmp/x");
     }
 
+    #[test]
+    fn codes_are_distinct_and_upper_snake() {
+        let errs = [
+            StoreError::AlreadyExists("x".into()),
+            StoreError::NotFound("x".into()),
+            StoreError::InvalidConfig("x".into()),
+            StoreError::CorruptStaging("x".into()),
+            StoreError::Locked {
+                path: "x".into(),
+                pid: None,
+            },
+            StoreError::GenerationNotFound("x".into()),
+            StoreError::ResourceNotFound {
+                key: "x".into(),
+                root: "x".into(),
+                found_in: None,
+            },
+            StoreError::RootMismatch {
+                root: "x".into(),
+                rebuilt: "x".into(),
+            },
+            StoreError::UnsafeResourceKey("x".into()),
+            StoreError::ChunkNotFound("x".into()),
+            StoreError::NonMonotonicHistory { last: 2, got: 1 },
+            StoreError::ReadOnly("x".into()),
+            StoreError::Unauthorized("x".into()),
+            StoreError::InvalidSquash("x".into()),
+            StoreError::InvalidPrune("x".into()),
+            StoreError::InvalidBranch("x".into()),
+            StoreError::InvalidUrn("x".into()),
+            StoreError::InvalidDelta("x".into()),
+            StoreError::InvalidRange("x".into()),
+            StoreError::NothingToAmend,
+            StoreError::EmptyStaging,
+            StoreError::DirtyStaging,
+            StoreError::DecryptFailed("x".into()),
+            StoreError::Backend("x".into()),
+            StoreError::Manifest("x".into()),
+            StoreError::Config("x".into()),
+            StoreError::PathEscape("x".into()),
+            StoreError::Corrupted {
+                entry: "x".into(),
+                detail: "x".into(),
+            },
+            StoreError::InvalidTar("x".into()),
+            StoreError::TarTooLarge { limit: 1 },
+            StoreError::BinaryFile("x".into()),
+            StoreError::Io(std::io::Error::other("x")),
+        ];
+        let mut codes: Vec<&str> = errs.iter().map(StoreError::code).collect();
+        assert!(codes
+            .iter()
+            .all(|c| c.chars().all(|ch| ch.is_ascii_uppercase() || ch == '_')));
+        let n = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), n, "codes must be distinct");
+    }
+
     #[test]
     fn io_error_wraps_source() {
         let io = std::io::Error::new(std::io::ErrorKind::NotFound, "nope");
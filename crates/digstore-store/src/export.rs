@@ -0,0 +1,42 @@
+use crate::error::{Result, StoreError};
+use serde::{Deserialize, Serialize};
+
+/// A canonical, self-describing listing of one generation, from
+/// [`Store::export_manifest`](crate::Store::export_manifest).
+///
+/// Files are sorted by path and every field is fixed-order, so two exports of
+/// the same root serialize to identical bytes ([`SnapshotManifest::to_canonical_json`])
+/// and one signature over those bytes covers the whole snapshot. Each file's
+/// `leaf` is its D5 merkle leaf; together they rebuild `root`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub store_id: String,
+    /// The generation's merkle root (hex).
+    pub root: String,
+    pub generation: u64,
+    pub files: Vec<SnapshotFile>,
+}
+
+/// One resource in a [`SnapshotManifest`]. Hashes are lowercase hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub path: String,
+    /// Plaintext size in bytes.
+    pub size: u64,
+    /// URN retrieval (static) key.
+    pub retrieval_key: String,
+    /// D5 resource leaf over the resource's ciphertext.
+    pub leaf: String,
+    /// Ciphertext chunk hashes in read order.
+    pub chunks: Vec<String>,
+}
+
+impl SnapshotManifest {
+    /// The canonical encoding: pretty JSON with a trailing newline.
+    pub fn to_canonical_json(&self) -> Result<String> {
+        let mut text =
+            serde_json::to_string_pretty(self).map_err(|e| StoreError::Manifest(e.to_string()))?;
+        text.push('\n');
+        Ok(text)
+    }
+}
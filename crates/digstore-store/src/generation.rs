@@ -13,6 +13,22 @@ fn de_hash<'de, D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Bytes3
     Bytes32::from_hex(&s).map_err(|_| serde::de::Error::custom("invalid 32-byte hex"))
 }
 
+fn ser_opt_hash<S: serde::Serializer>(
+    h: &Option<Bytes32>,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match h {
+        Some(h) => ser_hash(h, s),
+        None => s.serialize_none(),
+    }
+}
+
+fn de_opt_hash<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> std::result::Result<Option<Bytes32>, D::Error> {
+    de_hash(d).map(Some)
+}
+
 /// One chunk's placement in the generation: its pool index, content hash, size.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChunkRef {
@@ -64,6 +80,27 @@ pub struct GenerationManifest {
     pub timestamp: u64,
     pub chunks: Vec<ChunkRef>,
     pub key_table: Vec<KeyTableRecord>,
+    /// Which secret-salt epoch sealed this generation's chunks. Starts at `0`
+    /// and is bumped by `Store::rotate_salt`, so a reader knows which salt of
+    /// the config's `salt_keyring` a historical generation needs. Omitted
+    /// from the JSON while it is `0`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub key_epoch: u32,
+    /// The generation this one was committed on top of with only its own
+    /// changes (`commit --changes-only`). Its key table then lists just the
+    /// files staged for it, and reads of any other file fall through to the
+    /// parent chain. Omitted from the JSON for a self-contained generation.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "ser_opt_hash",
+        deserialize_with = "de_opt_hash"
+    )]
+    pub parent: Option<Bytes32>,
+}
+
+fn is_zero(v: &u32) -> bool {
+    *v == 0
 }
 
 impl GenerationManifest {
@@ -75,8 +112,13 @@ impl GenerationManifest {
         serde_json::from_str(s).map_err(|e| StoreError::Manifest(e.to_string()))
     }
 
+    /// Write the manifest via a synced temp sibling and a rename, so a reader
+    /// never sees a half-written file.
     pub fn write_to(&self, path: impl AsRef<Path>) -> Result<()> {
-        std::fs::write(path, self.to_json()?)?;
+        let path = path.as_ref();
+        let tmp = path.with_extension("json.tmp");
+        crate::chunkstore::write_synced(&tmp, self.to_json()?.as_bytes())?;
+        std::fs::rename(&tmp, path)?;
         Ok(())
     }
 
@@ -139,6 +181,8 @@ mod tests {
                 chunk_indices: vec![0, 1],
                 total_size: 48,
             }],
+            key_epoch: 0,
+            parent: None,
         }
     }
 
@@ -150,6 +194,29 @@ mod tests {
         assert_eq!(back, m);
     }
 
+    #[test]
+    fn key_epoch_is_omitted_at_zero_and_roundtrips_otherwise() {
+        let json = sample().to_json().unwrap();
+        assert!(!json.contains("key_epoch"));
+
+        let mut m = sample();
+        m.key_epoch = 2;
+        let back = GenerationManifest::from_json(&m.to_json().unwrap()).unwrap();
+        assert_eq!(back.key_epoch, 2);
+    }
+
+    #[test]
+    fn parent_is_omitted_when_absent_and_roundtrips_as_hex() {
+        let json = sample().to_json().unwrap();
+        assert!(!json.contains("parent"));
+
+        let mut m = sample();
+        m.parent = Some(b(0x5a));
+        let json = m.to_json().unwrap();
+        assert!(json.contains(&format!("\"parent\": \"{}\"", "5a".repeat(32))));
+        assert_eq!(GenerationManifest::from_json(&json).unwrap(), m);
+    }
+
     #[test]
     fn manifest_json_uses_hex_for_hashes() {
         let json = sample().to_json().unwrap();
@@ -4,7 +4,9 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Append-only, monotonic root history backed by `roots.log` (§4.3).
-/// Line format: `{id}\t{root_hex}\t{timestamp}`.
+/// Line format: `{id}\t{root_hex}\t{timestamp}`. The only in-place edits are
+/// [`RootHistory::replace_head`] (`Store::amend_commit`) and
+/// [`RootHistory::rewrite`] (`Store::squash`).
 pub struct RootHistory {
     path: PathBuf,
 }
@@ -72,8 +74,57 @@ impl RootHistory {
         }
         let mut f = std::fs::OpenOptions::new().append(true).open(&self.path)?;
         writeln!(f, "{}\t{}\t{}", gen.id, gen.root.to_hex(), gen.timestamp)?;
+        f.sync_all()?;
         Ok(())
     }
+
+    /// Swap the latest generation for `gen`, which must carry the head's id.
+    pub fn replace_head(&mut self, gen: &GenerationState) -> Result<()> {
+        let mut entries = self.entries()?;
+        match entries.last_mut() {
+            Some(head) if head.id == gen.id => *head = gen.clone(),
+            head => {
+                return Err(StoreError::NonMonotonicHistory {
+                    last: head.map_or(0, |h| h.id),
+                    got: gen.id,
+                })
+            }
+        }
+        self.rewrite(&entries)
+    }
+
+    /// Replace the whole history with `entries`, whose ids must run `0, 1, …`.
+    /// The file is written to a temp sibling and renamed over `roots.log`.
+    pub fn rewrite(&mut self, entries: &[GenerationState]) -> Result<()> {
+        let mut text = String::new();
+        for (expected, g) in entries.iter().enumerate() {
+            if g.id != expected as u64 {
+                return Err(StoreError::NonMonotonicHistory {
+                    last: (expected as u64).saturating_sub(1),
+                    got: g.id,
+                });
+            }
+            text.push_str(&format!("{}\t{}\t{}\n", g.id, g.root.to_hex(), g.timestamp));
+        }
+        let tmp = self.path.with_extension("log.tmp");
+        crate::chunkstore::write_synced(&tmp, text.as_bytes())?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Drop a partial last line left by an append that was cut short, so the
+    /// history ends at the last complete entry. Returns whether anything was cut.
+    pub fn truncate_torn_tail(&mut self) -> Result<bool> {
+        let bytes = std::fs::read(&self.path)?;
+        if bytes.is_empty() || bytes.ends_with(b"\n") {
+            return Ok(false);
+        }
+        let keep = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        let f = std::fs::OpenOptions::new().write(true).open(&self.path)?;
+        f.set_len(keep as u64)?;
+        f.sync_all()?;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -106,6 +157,52 @@ mod tests {
         assert_eq!(all[1].timestamp, 200);
     }
 
+    #[test]
+    fn truncate_torn_tail_drops_only_the_partial_line() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("roots.log");
+        let mut h = RootHistory::open(&path).unwrap();
+        h.append(&gs(0, 0xa0, 100)).unwrap();
+        assert!(!h.truncate_torn_tail().unwrap());
+
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("1\ta1a1");
+        std::fs::write(&path, text).unwrap();
+        assert!(h.truncate_torn_tail().unwrap());
+        let all = h.entries().unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].root, Bytes32([0xa0; 32]));
+        h.append(&gs(1, 0xa1, 200)).unwrap();
+        assert_eq!(h.entries().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn replace_head_swaps_only_the_latest_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("roots.log");
+        let mut h = RootHistory::open(&path).unwrap();
+        h.append(&gs(0, 0xa0, 100)).unwrap();
+        h.append(&gs(1, 0xa1, 200)).unwrap();
+        h.replace_head(&gs(1, 0xb1, 300)).unwrap();
+
+        let all = h.entries().unwrap();
+        assert_eq!(all, vec![gs(0, 0xa0, 100), gs(1, 0xb1, 300)]);
+        assert!(h.replace_head(&gs(0, 0xcc, 1)).is_err());
+        assert!(RootHistory::open(dir.path().join("empty.log"))
+            .unwrap()
+            .replace_head(&gs(0, 0xcc, 1))
+            .is_err());
+    }
+
+    #[test]
+    fn rewrite_requires_consecutive_ids_from_zero() {
+        let dir = tempdir().unwrap();
+        let mut h = RootHistory::open(dir.path().join("roots.log")).unwrap();
+        assert!(h.rewrite(&[gs(0, 1, 1), gs(2, 2, 2)]).is_err());
+        h.rewrite(&[gs(0, 1, 1), gs(1, 2, 2)]).unwrap();
+        assert_eq!(h.entries().unwrap(), vec![gs(0, 1, 1), gs(1, 2, 2)]);
+    }
+
     #[test]
     fn first_append_must_be_id_zero() {
         let dir = tempdir().unwrap();
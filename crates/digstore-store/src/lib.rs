@@ -5,24 +5,54 @@
 //! produced here are consumed by `digstore-compiler` (which owns §8.3 pool
 //! ordering and §19.3 byte-identical compilation) and `digstore-guest`.
 
+mod access;
+mod backend;
+mod blame;
+mod cache;
 mod chunkstore;
 mod clock;
 mod config;
+mod content_type;
+mod delta;
 mod diff;
 mod error;
+mod export;
 mod generation;
 mod history;
+mod lock;
+mod mapped;
 mod paths;
+mod progress;
+mod range_proof;
+mod refs;
+mod signatures;
 mod staging;
 mod store;
 
+pub use access::AccessPolicy;
+pub use backend::{ChunkBackend, MemoryBackend};
+pub use blame::BlameLine;
+pub use cache::{CacheStats, ChunkCache, DEFAULT_CHUNK_CACHE_BYTES, DEFAULT_READ_AHEAD_BYTES};
 pub use chunkstore::ChunkStore;
 pub use clock::{Clock, FixedClock, SystemClock};
 pub use config::{load_config, save_config};
-pub use diff::GenerationDiff;
+pub use content_type::{sniff, ContentType};
+pub use delta::DeltaBundle;
+pub use diff::{DiffSummary, FileChange, FileDiffStat, GenerationDiff};
 pub use error::{Result, StoreError};
+pub use export::{SnapshotFile, SnapshotManifest};
 pub use generation::{ChunkRef, GenerationManifest, KeyTableRecord};
 pub use history::RootHistory;
+pub use lock::StoreLock;
+pub use mapped::MappedChunk;
 pub use paths::StorePaths;
-pub use staging::{StagedRecord, StagingArea};
-pub use store::Store;
+pub use progress::ProgressSink;
+pub use range_proof::ByteRangeProof;
+pub use refs::{BranchRefs, DEFAULT_BRANCH};
+pub use signatures::RootSignature;
+pub use staging::{StagedRecord, StagingArea, TarImport, TarSkipped};
+pub use store::{
+    BatchRead, ChunkSizeDistribution, CommitEstimate, CommitOptions, FileStat, FileVersion,
+    GenerationSize, GrepMatch, HashKind, HashMatch, KeyRotation, Prune, PruneBefore, SizeReport,
+    Squash, Store, VerificationResult,
+};
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+
+use fs4::FileExt;
+
+use crate::error::{Result, StoreError};
+use crate::paths::LOCK_FILE;
+
+/// Lock files this process already holds, by path. The OS lock is per open
+/// file, so a second `Store` on the same directory in this process shares the
+/// first one's lock instead of failing against itself.
+static HELD: Mutex<Option<HashMap<PathBuf, Weak<Held>>>> = Mutex::new(None);
+
+struct Held {
+    /// Closing the file releases the advisory lock.
+    _file: File,
+}
+
+/// An exclusive, advisory lock on a store directory (`store.lock`), held by
+/// writers so two processes cannot interleave updates to the staging area or
+/// the generations. Released on drop, and by the OS when the process exits,
+/// so a crash never leaves the store locked. The file records the PID of the
+/// holder for the error other processes see.
+///
+/// [`Store::open`](crate::Store::open) and [`Store::init`](crate::Store::init)
+/// take it for the life of the store; read-only opens do not. Callers that
+/// write store files directly take it with [`StoreLock::acquire`].
+#[derive(Clone)]
+pub struct StoreLock {
+    _held: Arc<Held>,
+}
+
+impl std::fmt::Debug for StoreLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreLock").finish_non_exhaustive()
+    }
+}
+
+impl StoreLock {
+    /// Lock the store at `data_dir` without waiting. Fails with `Locked`
+    /// when another process holds it; succeeds at once when this process
+    /// already does.
+    pub fn acquire(data_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = data_dir.as_ref().join(LOCK_FILE);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)?;
+        let key = path.canonicalize()?;
+        let mut held = HELD.lock().unwrap_or_else(|p| p.into_inner());
+        let held = held.get_or_insert_with(HashMap::new);
+        if let Some(existing) = held.get(&key).and_then(Weak::upgrade) {
+            return Ok(Self { _held: existing });
+        }
+        if FileExt::try_lock(&file).is_err() {
+            let mut pid = String::new();
+            let _ = file.read_to_string(&mut pid);
+            return Err(StoreError::Locked {
+                path: data_dir.as_ref().display().to_string(),
+                pid: pid.trim().parse().ok(),
+            });
+        }
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        writeln!(file, "{}", std::process::id())?;
+        let lock = Arc::new(Held { _file: file });
+        held.retain(|_, w| w.strong_count() > 0);
+        held.insert(key, Arc::downgrade(&lock));
+        Ok(Self { _held: lock })
+    }
+
+    /// Remove the lock file of the store at `data_dir`, so the next
+    /// [`StoreLock::acquire`] succeeds even while a stuck process still holds
+    /// the old one. Only for a holder known to be hung: two live writers
+    /// after this are exactly what the lock exists to prevent. Returns
+    /// whether there was a lock file.
+    pub fn force_unlock(data_dir: impl AsRef<Path>) -> Result<bool> {
+        match std::fs::remove_file(data_dir.as_ref().join(LOCK_FILE)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_process_shares_its_lock_and_releases_it_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = StoreLock::acquire(dir.path()).unwrap();
+        let second = StoreLock::acquire(dir.path()).unwrap();
+        let pid = std::fs::read_to_string(dir.path().join("store.lock")).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+
+        // Another open file on the same lock is what another process sees.
+        let other = File::open(dir.path().join("store.lock")).unwrap();
+        assert!(FileExt::try_lock(&other).is_err());
+        drop(first);
+        assert!(FileExt::try_lock(&other).is_err(), "still held by `second`");
+        drop(second);
+        assert!(FileExt::try_lock(&other).is_ok());
+        let _ = FileExt::unlock(&other);
+    }
+
+    #[test]
+    fn force_unlock_removes_the_lock_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!StoreLock::force_unlock(dir.path()).unwrap());
+        let _lock = StoreLock::acquire(dir.path()).unwrap();
+        assert!(StoreLock::force_unlock(dir.path()).unwrap());
+        assert!(!dir.path().join("store.lock").exists());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::Result;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+/// A chunk file's bytes, either memory-mapped (zero-copy, served from the page
+/// cache) or read into an owned buffer. Derefs to `[u8]` either way.
+///
+/// Chunk files are content-addressed and write-once (§8.2): nothing rewrites a
+/// chunk in place, so a live map can never observe torn bytes. On Windows an
+/// open map still pins the file, so drop every `MappedChunk` before deleting a
+/// generation dir.
+#[derive(Debug)]
+pub enum MappedChunk {
+    Mapped(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+impl MappedChunk {
+    /// Open the chunk file at `path`, mapping it when `mmap` is set. Empty files
+    /// are always read (a zero-length map is not portable).
+    pub fn open(path: &Path, mmap: bool) -> Result<Self> {
+        if !mmap {
+            return Ok(MappedChunk::Owned(std::fs::read(path)?));
+        }
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(MappedChunk::Owned(Vec::new()));
+        }
+        // SAFETY: chunk files are write-once and never truncated or rewritten
+        // while a store is open, so the mapped bytes stay valid and immutable.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(MappedChunk::Mapped(map))
+    }
+
+    /// True when the bytes are borrowed from a memory map.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, MappedChunk::Mapped(_))
+    }
+
+    /// Take the bytes as an owned buffer (copies a mapped chunk).
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            MappedChunk::Mapped(map) => map.to_vec(),
+            MappedChunk::Owned(body) => body,
+        }
+    }
+}
+
+impl Deref for MappedChunk {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            MappedChunk::Mapped(map) => map,
+            MappedChunk::Owned(body) => body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn mapped_and_owned_reads_agree() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("chunk");
+        std::fs::write(&path, b"ciphertext bytes").unwrap();
+
+        let mapped = MappedChunk::open(&path, true).unwrap();
+        let owned = MappedChunk::open(&path, false).unwrap();
+        assert!(mapped.is_mapped());
+        assert!(!owned.is_mapped());
+        assert_eq!(&*mapped, &*owned);
+        assert_eq!(mapped.into_vec(), b"ciphertext bytes");
+    }
+
+    #[test]
+    fn empty_chunk_file_is_read_not_mapped() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("empty");
+        std::fs::write(&path, b"").unwrap();
+        let chunk = MappedChunk::open(&path, true).unwrap();
+        assert!(!chunk.is_mapped());
+        assert!(chunk.is_empty());
+    }
+}
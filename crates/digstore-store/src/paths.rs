@@ -8,10 +8,18 @@ use std::path::{Path, PathBuf};
 ///   {store_id_hex}.staging.bin
 ///   config.toml
 ///   roots.log                         // append-only root history
+///   refs                              // branch -> tip root (once a branch exists)
+///   HEAD                              // current branch name
+///   commit.pending                    // root of an in-flight commit
+///   access.json                       // writers allowed (once a policy is set)
+///   store.lock                        // PID of the writer holding the lock
 ///   generations/{roothash_hex}/manifest.json
 ///   generations/{roothash_hex}/chunks/{chunk_hash_hex}   // sparse after dedup
 ///   modules/{store_id_hex}-{roothash_hex}.dig
 /// ```
+/// Name of the writer lock file in the data dir; see [`crate::StoreLock`].
+pub(crate) const LOCK_FILE: &str = "store.lock";
+
 #[derive(Debug, Clone)]
 pub struct StorePaths {
     root: PathBuf,
@@ -38,6 +46,30 @@ impl StorePaths {
         self.root.join("roots.log")
     }
 
+    pub fn refs_file(&self) -> PathBuf {
+        self.root.join("refs")
+    }
+
+    pub fn head_file(&self) -> PathBuf {
+        self.root.join("HEAD")
+    }
+
+    /// Marker naming the root of a commit that has not yet reached
+    /// `roots.log`; see [`crate::Store::recover`].
+    pub fn pending_commit_file(&self) -> PathBuf {
+        self.root.join("commit.pending")
+    }
+
+    /// The store's write [`crate::AccessPolicy`]; absent when none is set.
+    pub fn access_file(&self) -> PathBuf {
+        self.root.join("access.json")
+    }
+
+    /// The writer lock; see [`crate::StoreLock`].
+    pub fn lock_file(&self) -> PathBuf {
+        self.root.join(LOCK_FILE)
+    }
+
     pub fn staging_file(&self) -> PathBuf {
         self.root.join(format!("{}.staging.bin", self.store_id_hex))
     }
@@ -62,6 +94,14 @@ impl StorePaths {
         self.generation_dir(root_hex).join("chunks")
     }
 
+    pub fn generation_leaves(&self, root_hex: &str) -> PathBuf {
+        self.generation_dir(root_hex).join("leaves.bin")
+    }
+
+    pub fn generation_signatures(&self, root_hex: &str) -> PathBuf {
+        self.generation_dir(root_hex).join("signatures.json")
+    }
+
     pub fn chunk_file(&self, root_hex: &str, chunk_hash_hex: &str) -> PathBuf {
         self.generation_chunks_dir(root_hex).join(chunk_hash_hex)
     }
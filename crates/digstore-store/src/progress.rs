@@ -0,0 +1,83 @@
+/// Receives progress from long-running [`Store`](crate::Store) operations, so
+/// an embedder can drive its own progress display without a terminal UI
+/// dependency. Install one with [`Store::set_progress_sink`](crate::Store::set_progress_sink).
+///
+/// Both methods default to doing nothing. `done` never exceeds `total`, and
+/// each operation reports from zero upward; a new operation starts again at
+/// zero. Calls come from the thread running the operation.
+pub trait ProgressSink: Send + Sync {
+    /// `done` of `total` files (resources) have been processed.
+    fn on_file(&self, done: u64, total: u64) {
+        let _ = (done, total);
+    }
+
+    /// `done` of `total` plaintext bytes have been processed.
+    fn on_bytes(&self, done: u64, total: u64) {
+        let _ = (done, total);
+    }
+}
+
+/// Counts files and bytes for one operation and forwards them to a sink.
+pub(crate) struct Progress<'a> {
+    sink: Option<&'a dyn ProgressSink>,
+    files: (u64, u64),
+    bytes: (u64, u64),
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(sink: Option<&'a dyn ProgressSink>, files: u64, bytes: u64) -> Self {
+        let progress = Self {
+            sink,
+            files: (0, files),
+            bytes: (0, bytes),
+        };
+        if let Some(sink) = progress.sink {
+            sink.on_file(0, files);
+            sink.on_bytes(0, bytes);
+        }
+        progress
+    }
+
+    /// One more file of `len` bytes is done.
+    pub(crate) fn file_done(&mut self, len: u64) {
+        let Some(sink) = self.sink else {
+            return;
+        };
+        self.files.0 = (self.files.0 + 1).min(self.files.1);
+        self.bytes.0 = (self.bytes.0 + len).min(self.bytes.1);
+        sink.on_bytes(self.bytes.0, self.bytes.1);
+        sink.on_file(self.files.0, self.files.1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(&'static str, u64, u64)>>);
+
+    impl ProgressSink for Recorder {
+        fn on_file(&self, done: u64, total: u64) {
+            self.0.lock().unwrap().push(("file", done, total));
+        }
+        fn on_bytes(&self, done: u64, total: u64) {
+            self.0.lock().unwrap().push(("bytes", done, total));
+        }
+    }
+
+    #[test]
+    fn counts_up_from_zero_and_never_passes_the_total() {
+        let sink = Recorder::default();
+        let mut p = Progress::new(Some(&sink), 2, 10);
+        p.file_done(4);
+        p.file_done(6);
+        p.file_done(1);
+        let seen = sink.0.into_inner().unwrap();
+        assert_eq!(seen[..2], [("file", 0, 2), ("bytes", 0, 10)]);
+        assert_eq!(seen[2..4], [("bytes", 4, 10), ("file", 1, 2)]);
+        assert_eq!(seen.last(), Some(&("file", 2, 2)));
+        assert!(seen.iter().all(|&(_, done, total)| done <= total));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::store::SEAL_OVERHEAD;
+use digstore_core::serving::concat_output;
+use digstore_core::{HashAlgorithm, MerkleProof};
+use std::ops::Range;
+
+/// A proof that plaintext bytes `range` of one resource have specific content,
+/// built by [`Store::prove_byte_range`](crate::Store::prove_byte_range).
+///
+/// A resource's D5 leaf is the digest of ALL its sealed chunks concatenated,
+/// so the proof carries every sealed chunk in order; the verifier re-hashes
+/// them into the leaf and checks the leaf's inclusion proof. Only the chunks
+/// covering `range` (`chunks[first_chunk..]`, as many as the range spans) are
+/// decrypted. The rest travel sealed: they disclose nothing to a verifier
+/// without the resource key, but one holding the key could open them too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteRangeProof {
+    pub resource_key: String,
+    /// The proven plaintext byte range (half-open, within the resource).
+    pub range: Range<u64>,
+    /// Position, in the resource's chunk list, of the first chunk covering
+    /// `range`.
+    pub first_chunk: usize,
+    /// Every sealed chunk of the resource, in manifest order.
+    pub chunks: Vec<Vec<u8>>,
+    /// Inclusion proof of the resource's leaf in the generation root.
+    pub proof: MerkleProof,
+}
+
+impl ByteRangeProof {
+    /// Check the proof for a tree built with `algorithm` and return the proven
+    /// plaintext bytes, decrypting only the covering chunks under `key` (the
+    /// resource's per-URN key). `None` when the chunks do not hash into
+    /// `proof.leaf`, the leaf does not prove into `proof.root`, `first_chunk`
+    /// is not where `range` starts, or a covering chunk fails to open.
+    /// Callers compare `proof.root` with the root they trust.
+    pub fn verify_with(&self, algorithm: HashAlgorithm, key: &[u8; 32]) -> Option<Vec<u8>> {
+        let slices: Vec<&[u8]> = self.chunks.iter().map(|c| c.as_slice()).collect();
+        if digstore_core::resource_leaf_with(algorithm, &concat_output(&slices)) != self.proof.leaf
+            || !self.proof.verify_with(algorithm)
+            || self.range.start >= self.range.end
+        {
+            return None;
+        }
+        // Plaintext offsets follow from the sealed lengths alone, so the
+        // chunks before the range are never opened.
+        let mut offset = 0u64;
+        let mut out = Vec::with_capacity((self.range.end - self.range.start) as usize);
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let len = (chunk.len() as u64).checked_sub(SEAL_OVERHEAD)?;
+            let span = offset..offset + len;
+            if i == self.first_chunk && !span.contains(&self.range.start) {
+                return None;
+            }
+            if span.end > self.range.start && span.start < self.range.end {
+                if i < self.first_chunk {
+                    return None;
+                }
+                let pt = digstore_crypto::decrypt_chunk(key, chunk).ok()?;
+                if pt.len() as u64 != len {
+                    return None;
+                }
+                let lo = self.range.start.max(span.start) - span.start;
+                let hi = self.range.end.min(span.end) - span.start;
+                out.extend_from_slice(&pt[lo as usize..hi as usize]);
+            }
+            offset = span.end;
+        }
+        (out.len() as u64 == self.range.end - self.range.start).then_some(out)
+    }
+}
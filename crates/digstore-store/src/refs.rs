@@ -0,0 +1,141 @@
+use crate::error::{Result, StoreError};
+use digstore_core::Bytes32;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The branch every store starts on. Until another branch is created it has
+/// no `refs` entry and simply follows the head of `roots.log`.
+pub const DEFAULT_BRANCH: &str = "main";
+
+/// Named refs (branches) backed by `refs`, one `{name}\t{root_hex}` line per
+/// branch, and `HEAD`, which holds the name of the current branch.
+///
+/// `roots.log` stays the single append-only record of every generation; a
+/// branch only names the generation its next commit builds on. A store that
+/// never created a branch has neither file.
+pub struct BranchRefs {
+    refs: PathBuf,
+    head: PathBuf,
+}
+
+impl BranchRefs {
+    pub fn open(refs: impl AsRef<Path>, head: impl AsRef<Path>) -> Self {
+        Self {
+            refs: refs.as_ref().to_path_buf(),
+            head: head.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Whether any branch has been created (the `refs` file exists).
+    pub fn exists(&self) -> bool {
+        self.refs.exists()
+    }
+
+    /// Every branch and its tip, by name. Empty when no branch was created.
+    pub fn entries(&self) -> Result<BTreeMap<String, Bytes32>> {
+        let mut out = BTreeMap::new();
+        if !self.refs.exists() {
+            return Ok(out);
+        }
+        for line in std::fs::read_to_string(&self.refs)?.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (name, root_hex) = line
+                .split_once('\t')
+                .ok_or_else(|| StoreError::CorruptStaging("refs: missing root".into()))?;
+            let root = Bytes32::from_hex(root_hex)
+                .map_err(|_| StoreError::CorruptStaging("refs: bad root hex".into()))?;
+            out.insert(name.to_string(), root);
+        }
+        Ok(out)
+    }
+
+    /// Replace every ref with `entries`, via a temp sibling renamed over `refs`.
+    pub fn write(&self, entries: &BTreeMap<String, Bytes32>) -> Result<()> {
+        let text: String = entries
+            .iter()
+            .map(|(name, root)| format!("{name}\t{}\n", root.to_hex()))
+            .collect();
+        let tmp = self.refs.with_extension("tmp");
+        crate::chunkstore::write_synced(&tmp, text.as_bytes())?;
+        std::fs::rename(&tmp, &self.refs)?;
+        Ok(())
+    }
+
+    /// Point `name` at `root`, creating the branch if needed.
+    pub fn set(&self, name: &str, root: Bytes32) -> Result<()> {
+        let mut entries = self.entries()?;
+        entries.insert(name.to_string(), root);
+        self.write(&entries)
+    }
+
+    /// The current branch: the name in `HEAD`, or [`DEFAULT_BRANCH`].
+    pub fn current(&self) -> Result<String> {
+        if !self.head.exists() {
+            return Ok(DEFAULT_BRANCH.to_string());
+        }
+        Ok(std::fs::read_to_string(&self.head)?.trim().to_string())
+    }
+
+    /// Make `name` the current branch.
+    pub fn set_current(&self, name: &str) -> Result<()> {
+        crate::chunkstore::write_synced(&self.head, format!("{name}\n").as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Check a branch name: non-empty, made of ASCII letters, digits, `-`, `_`,
+/// `.` and `/`, not starting with `-` or `/`, and not a 64-char hex string
+/// (which would read as a root).
+pub fn validate_branch_name(name: &str) -> Result<()> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    if name.is_empty()
+        || !valid_chars
+        || name.starts_with(['-', '/'])
+        || (name.len() == 64 && name.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return Err(StoreError::InvalidBranch(format!(
+            "{name:?} is not a valid branch name"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn refs_round_trip_and_head_defaults_to_main() {
+        let dir = tempdir().unwrap();
+        let refs = BranchRefs::open(dir.path().join("refs"), dir.path().join("HEAD"));
+        assert!(!refs.exists());
+        assert!(refs.entries().unwrap().is_empty());
+        assert_eq!(refs.current().unwrap(), DEFAULT_BRANCH);
+
+        refs.set("main", Bytes32([1; 32])).unwrap();
+        refs.set("feature/x", Bytes32([2; 32])).unwrap();
+        refs.set("main", Bytes32([3; 32])).unwrap();
+        refs.set_current("feature/x").unwrap();
+
+        let entries = refs.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries["main"], Bytes32([3; 32]));
+        assert_eq!(entries["feature/x"], Bytes32([2; 32]));
+        assert_eq!(refs.current().unwrap(), "feature/x");
+    }
+
+    #[test]
+    fn branch_names_are_checked() {
+        for ok in ["main", "feature/x", "release-1.2", "a_b"] {
+            assert!(validate_branch_name(ok).is_ok(), "{ok}");
+        }
+        for bad in ["", "-x", "/x", "a b", "a\tb", &"ab".repeat(32)] {
+            assert!(validate_branch_name(bad).is_err(), "{bad}");
+        }
+    }
+}
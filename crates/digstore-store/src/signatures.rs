@@ -0,0 +1,32 @@
+use crate::error::{Result, StoreError};
+use digstore_core::{Bytes48, Bytes96};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A publisher's BLS signature over a generation root (see
+/// [`digstore_crypto::root_signing_message`]), kept in the generation's
+/// `signatures.json`. A generation may carry one signature per public key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootSignature {
+    pub public_key: Bytes48,
+    pub signature: Bytes96,
+}
+
+/// Read a `signatures.json`; a missing file means no signatures.
+pub(crate) fn read_signatures(path: &Path) -> Result<Vec<RootSignature>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|e| StoreError::Manifest(e.to_string()))
+}
+
+/// Write `sigs` to `path` via a temp sibling and a rename.
+pub(crate) fn write_signatures(path: &Path, sigs: &[RootSignature]) -> Result<()> {
+    let text =
+        serde_json::to_string_pretty(sigs).map_err(|e| StoreError::Manifest(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::error::{Result, StoreError};
 use std::collections::BTreeMap;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
 /// One staged resource: its key and the latest bytes staged for it.
@@ -10,6 +10,24 @@ pub struct StagedRecord {
     pub content: Vec<u8>,
 }
 
+/// What [`StagingArea::append_tar`] did with an archive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TarImport {
+    /// `(resource key, size)` per regular file staged, in archive order.
+    pub staged: Vec<(String, u64)>,
+    /// Entries that are not regular files or directories, left out.
+    pub skipped: Vec<TarSkipped>,
+}
+
+/// An archive entry [`StagingArea::append_tar`] did not stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarSkipped {
+    /// The entry's path as written in the archive.
+    pub path: String,
+    /// What it is: `symlink`, `hardlink`, `fifo`, ...
+    pub kind: &'static str,
+}
+
 /// Append-only binary staging file. Frame (Chia big-endian conventions):
 /// `u32 BE key_len | key utf8 | u64 BE content_len | content`.
 /// Re-staging a key appends a new frame; read-back is last-write-wins,
@@ -39,6 +57,101 @@ impl StagingArea {
         Ok(())
     }
 
+    /// Append a frame whose content is streamed from `reader`, never held in
+    /// memory whole; the length field is patched once the copy ends. If
+    /// reading or writing fails, the file is cut back to its previous length so
+    /// no partial frame is left. Returns the content length.
+    pub fn append_reader(&mut self, resource_key: &str, reader: &mut dyn Read) -> Result<u64> {
+        let mut f = std::fs::OpenOptions::new().write(true).open(&self.path)?;
+        let start = f.seek(SeekFrom::End(0))?;
+        let key_bytes = resource_key.as_bytes();
+        let written = (|| {
+            f.write_all(&(key_bytes.len() as u32).to_be_bytes())?;
+            f.write_all(key_bytes)?;
+            f.write_all(&0u64.to_be_bytes())?;
+            let len = std::io::copy(reader, &mut f)?;
+            f.seek(SeekFrom::Start(start + 4 + key_bytes.len() as u64))?;
+            f.write_all(&len.to_be_bytes())?;
+            Ok::<_, std::io::Error>(len)
+        })();
+        match written {
+            Ok(len) => Ok(len),
+            Err(e) => {
+                f.set_len(start)?;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Stage every regular file in a tar stream (plain or gzip-compressed,
+    /// detected from the first bytes) under its in-archive path, streaming each
+    /// one into the staging file without extracting to disk. Directories are
+    /// implied by the file keys; symlinks, hardlinks and device entries are
+    /// reported in [`TarImport::skipped`]. File modes and mtimes are not kept:
+    /// a resource is its key and bytes.
+    ///
+    /// All-or-nothing: a malformed archive, a path that escapes the root, or
+    /// file content totalling more than `limit` bytes cuts the staging file
+    /// back to where it was.
+    pub fn append_tar(&mut self, tar: impl Read, limit: u64) -> Result<TarImport> {
+        let start = std::fs::metadata(&self.path)?.len();
+        let imported = self.append_tar_entries(tar, limit);
+        if imported.is_err() {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(&self.path)?
+                .set_len(start)?;
+        }
+        imported
+    }
+
+    fn append_tar_entries(&mut self, tar: impl Read, limit: u64) -> Result<TarImport> {
+        let invalid = |e: std::io::Error| StoreError::InvalidTar(e.to_string());
+        let mut buffered = BufReader::new(tar);
+        let gzip = buffered.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+        let reader: Box<dyn Read + '_> = if gzip {
+            Box::new(flate2::read::GzDecoder::new(buffered))
+        } else {
+            Box::new(buffered)
+        };
+        let mut archive = tar::Archive::new(reader);
+        let mut import = TarImport::default();
+        let mut left = limit;
+        for entry in archive.entries().map_err(invalid)? {
+            let mut entry = entry.map_err(invalid)?;
+            let path = entry.path().map_err(invalid)?.into_owned();
+            let kind = match entry.header().entry_type() {
+                tar::EntryType::Regular | tar::EntryType::Continuous => None,
+                tar::EntryType::Directory
+                | tar::EntryType::XGlobalHeader
+                | tar::EntryType::XHeader
+                | tar::EntryType::GNULongName
+                | tar::EntryType::GNULongLink => continue,
+                tar::EntryType::Symlink => Some("symlink"),
+                tar::EntryType::Link => Some("hardlink"),
+                tar::EntryType::Char => Some("character device"),
+                tar::EntryType::Block => Some("block device"),
+                tar::EntryType::Fifo => Some("fifo"),
+                _ => Some("special entry"),
+            };
+            if let Some(kind) = kind {
+                import.skipped.push(TarSkipped {
+                    path: path.to_string_lossy().into_owned(),
+                    kind,
+                });
+                continue;
+            }
+            let key = crate::store::relative_resource_key(&path)
+                .ok_or_else(|| StoreError::PathEscape(path.clone()))?;
+            left = left
+                .checked_sub(entry.size())
+                .ok_or(StoreError::TarTooLarge { limit })?;
+            let size = self.append_reader(&key, &mut entry)?;
+            import.staged.push((key, size));
+        }
+        Ok(import)
+    }
+
     /// Read all frames, collapsing to last-write-wins per key in first-seen order.
     pub fn records(&self) -> Result<Vec<StagedRecord>> {
         let raw = std::fs::read(&self.path)?;
@@ -126,6 +239,38 @@ mod tests {
         assert_eq!(records[0].content, b"<html>");
     }
 
+    #[test]
+    fn append_reader_streams_a_frame_and_rolls_back_on_error() {
+        struct Failing(usize);
+        impl Read for Failing {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(std::io::Error::other("source broke"));
+                }
+                self.0 -= 1;
+                buf[0] = b'x';
+                Ok(1)
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("s.staging.bin");
+        let mut area = StagingArea::open(&path).unwrap();
+        area.append("a.txt", b"first").unwrap();
+        let n = area
+            .append_reader("piped.log", &mut &b"from a pipe"[..])
+            .unwrap();
+        assert_eq!(n, 11);
+        let before = std::fs::metadata(&path).unwrap().len();
+        assert!(area.append_reader("broken", &mut Failing(3)).is_err());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), before);
+
+        let records = area.records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].resource_key, "piped.log");
+        assert_eq!(records[1].content, b"from a pipe");
+    }
+
     #[test]
     fn last_write_wins_per_key_in_first_seen_order() {
         let dir = tempdir().unwrap();
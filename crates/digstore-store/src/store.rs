@@ -1,18 +1,322 @@
+use crate::access::{read_policy, write_policy, AccessPolicy};
+use crate::backend::ChunkBackend;
+use crate::blame::{matched_lines, BlameLine};
+use crate::cache::{CacheStats, ChunkCache, DEFAULT_CHUNK_CACHE_BYTES, DEFAULT_READ_AHEAD_BYTES};
 use crate::chunkstore::ChunkStore;
 use crate::clock::Clock;
 use crate::config::{load_config, save_config};
+use crate::content_type::{sniff, ContentType};
+use crate::delta::DeltaBundle;
+use crate::diff::{DiffSummary, FileChange, FileDiffStat};
 use crate::error::{Result, StoreError};
+use crate::export::{SnapshotFile, SnapshotManifest};
 use crate::generation::{ChunkRef, GenerationManifest, KeyTableRecord};
 use crate::history::RootHistory;
+use crate::lock::StoreLock;
+use crate::mapped::MappedChunk;
 use crate::paths::StorePaths;
-use crate::staging::StagingArea;
-use digstore_chunker::chunk_slice;
+use crate::progress::{Progress, ProgressSink};
+use crate::range_proof::ByteRangeProof;
+use crate::refs::{validate_branch_name, BranchRefs};
+use crate::signatures::{read_signatures, write_signatures, RootSignature};
+use crate::staging::{StagedRecord, StagingArea, TarImport};
+use digstore_chunker::chunk_slice_with;
 use digstore_core::serving::concat_output;
 use digstore_core::{
-    Bytes32, ChunkerConfig, GenerationState, MerkleTree, SecretSalt, StoreConfig, Urn, Visibility,
-    CHAIN,
+    Bytes32, Bytes48, ChunkerConfig, GenerationState, HashAlgorithm, MerkleProof, MerkleTree,
+    SecretSalt, StoreConfig, Urn, Visibility, CHAIN, MAX_STORE_BYTES,
 };
-use std::path::Path;
+use digstore_crypto::UrnSealer;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Outcome of [`Store::rotate_salt`]: what was (or, for a dry run, would be)
+/// re-encrypted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRotation {
+    /// Resources the head serves, including any read through its parent chain.
+    pub resources: usize,
+    /// Distinct chunks of those resources, re-sealed under the new salt.
+    pub chunks: usize,
+    /// The key epoch the re-sealed generation carries.
+    pub key_epoch: u32,
+    /// The new head root, or `None` for a dry run / a store with no generations.
+    pub root: Option<Bytes32>,
+}
+
+/// One matching line found by [`Store::grep`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    pub resource_key: String,
+    /// 1-based line number within the resource.
+    pub line_number: usize,
+    /// The line, without its line ending (invalid UTF-8 replaced).
+    pub line: String,
+}
+
+/// One resource's outcome from [`Store::verify_all_proofs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationResult {
+    pub resource_key: String,
+    pub static_key: Bytes32,
+    /// Whether the resource's content proved into the generation root.
+    pub verified: bool,
+    /// Why it did not, when `verified` is false.
+    pub error: Option<String>,
+}
+
+/// Outcome of [`Store::get_files`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchRead {
+    /// Resources written.
+    pub files: usize,
+    /// Plaintext bytes written across all of them.
+    pub bytes: u64,
+}
+
+/// Options for [`Store::commit_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommitOptions {
+    /// With nothing staged, record a checkpoint instead of failing with
+    /// `EmptyStaging`: a new history entry (new id and timestamp) for the
+    /// head's root. The head generation itself is not rewritten. There is
+    /// still nothing to commit before the first generation.
+    pub allow_empty: bool,
+    /// Record the head as the new generation's parent. The generation (and
+    /// its root) still covers only the staged files, but reads at its root of
+    /// any file it does not hold fall through to the parent chain (see
+    /// [`Store::generation_holding`]), so it is a layer of just this commit's
+    /// changes rather than a snapshot missing everything unstaged.
+    pub changes_only: bool,
+}
+
+/// Staged records chunked and encrypted by `Store::seal_records`.
+struct SealedRecords {
+    /// `(SHA-256(ciphertext), ciphertext)` per chunk, in pool order.
+    pool: Vec<(Bytes32, Vec<u8>)>,
+    key_table: Vec<KeyTableRecord>,
+    /// `(static_key, D5 leaf)` per resource, in record order.
+    leaves: Vec<([u8; 32], Bytes32)>,
+}
+
+/// One generation of a parent chain, from `Store::resolved_layers`, with the
+/// key-table records the chain's top serves from it.
+struct ResolvedLayer {
+    root: Bytes32,
+    manifest: GenerationManifest,
+    /// Indices into `manifest.key_table` that no newer layer shadows.
+    served: Vec<usize>,
+}
+
+impl ResolvedLayer {
+    fn records(&self) -> impl Iterator<Item = &KeyTableRecord> {
+        self.served.iter().map(|&i| &self.manifest.key_table[i])
+    }
+}
+
+/// A committed resource's layout, from [`Store::stat_file`]. Built from the
+/// generation manifest alone; no chunk is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStat {
+    pub resource_key: String,
+    /// The generation it was read from.
+    pub root: Bytes32,
+    /// The retrieval key (URN static key).
+    pub static_key: Bytes32,
+    /// Plaintext size in bytes.
+    pub size: u64,
+    /// The resource's chunks in read order (hash and stored size).
+    pub chunks: Vec<ChunkRef>,
+}
+
+impl FileStat {
+    /// Chunk counts by stored size, as `(bound, count)` pairs in ascending
+    /// order: each chunk counts toward the smallest power of two `>=` its
+    /// size. Empty buckets are left out.
+    pub fn size_histogram(&self) -> Vec<(u64, usize)> {
+        let mut buckets = std::collections::BTreeMap::new();
+        for c in &self.chunks {
+            *buckets.entry(c.size.next_power_of_two()).or_insert(0) += 1;
+        }
+        buckets.into_iter().collect()
+    }
+}
+
+/// What a hash given to [`Store::find_files_by_hash`] turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+    /// The content hash of one sealed chunk.
+    Chunk,
+    /// A file's D5 leaf: the hash of all its sealed chunks in order.
+    File,
+    /// A file's retrieval (static) key.
+    RetrievalKey,
+}
+
+/// A file referencing a hash, from [`Store::find_files_by_hash`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashMatch {
+    pub root: Bytes32,
+    pub resource_key: String,
+    pub kind: HashKind,
+}
+
+/// A version of one file, from [`Store::get_file_versions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileVersion {
+    pub root: Bytes32,
+    pub generation_id: u64,
+    /// The file's D5 leaf in this generation, which changes exactly when its
+    /// content does.
+    pub hash: Bytes32,
+    pub timestamp: u64,
+}
+
+/// One generation's footprint, from [`Store::size_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationSize {
+    pub root: Bytes32,
+    pub generation_id: u64,
+    /// Plaintext bytes of its resources.
+    pub logical_bytes: u64,
+    /// Sealed bytes of its chunk references, repeats included: what the
+    /// generation would take on disk alone, before deduplication.
+    pub stored_bytes: u64,
+    /// Chunk references in its pool.
+    pub chunks: usize,
+    /// Distinct chunk hashes among them.
+    pub unique_chunks: usize,
+    /// Stored bytes saved within this generation: repeat references to a
+    /// chunk it already holds.
+    pub intra_dedup_bytes: u64,
+    /// Stored bytes saved across generations: its distinct chunks that an
+    /// earlier generation introduced first.
+    pub inter_dedup_bytes: u64,
+}
+
+/// Storage use of every generation still on disk, from [`Store::size_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SizeReport {
+    /// Oldest first.
+    pub generations: Vec<GenerationSize>,
+    /// Distinct chunks across all of them: the chunk files on disk.
+    pub unique_chunks: usize,
+    /// Bytes those chunk files hold.
+    pub physical_bytes: u64,
+}
+
+impl SizeReport {
+    /// Sum of every generation's [`GenerationSize::stored_bytes`]: the disk
+    /// use with no deduplication at all.
+    pub fn stored_bytes(&self) -> u64 {
+        self.generations.iter().map(|g| g.stored_bytes).sum()
+    }
+
+    /// Bytes saved by repeats within a generation.
+    pub fn intra_dedup_bytes(&self) -> u64 {
+        self.generations.iter().map(|g| g.intra_dedup_bytes).sum()
+    }
+
+    /// Bytes saved by chunks shared with an earlier generation.
+    /// `stored_bytes() == physical_bytes + intra_dedup_bytes() + inter_dedup_bytes()`.
+    pub fn inter_dedup_bytes(&self) -> u64 {
+        self.generations.iter().map(|g| g.inter_dedup_bytes).sum()
+    }
+}
+
+/// What committing the staging area would add to disk, from
+/// [`Store::estimate_commit_size`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CommitEstimate {
+    /// Staged resources.
+    pub files: usize,
+    /// Their plaintext bytes.
+    pub logical_bytes: u64,
+    /// Chunk references the generation would hold, repeats included.
+    pub chunks: usize,
+    /// Distinct chunks no generation stores yet: the chunk files the commit
+    /// writes.
+    pub new_chunks: usize,
+    /// Sealed bytes of those chunk files.
+    pub new_bytes: u64,
+    /// Sealed bytes deduplication saves: references to a chunk already on
+    /// disk or repeated within the staged files.
+    pub reused_bytes: u64,
+}
+
+/// Sizes of distinct sealed chunks, from [`Store::chunk_size_distribution`].
+/// All zero when there are no chunks.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChunkSizeDistribution {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    /// The lower middle value when `count` is even.
+    pub median: u64,
+    /// Chunk counts as `(bound, count)` pairs in ascending order, bucketed as
+    /// in [`FileStat::size_histogram`]. Empty buckets are left out.
+    pub buckets: Vec<(u64, usize)>,
+}
+
+impl ChunkSizeDistribution {
+    fn from_sizes(mut sizes: Vec<u64>) -> Self {
+        if sizes.is_empty() {
+            return Self::default();
+        }
+        sizes.sort_unstable();
+        let mut buckets = std::collections::BTreeMap::new();
+        for size in &sizes {
+            *buckets.entry(size.next_power_of_two()).or_insert(0) += 1;
+        }
+        Self {
+            count: sizes.len(),
+            min: sizes[0],
+            max: sizes[sizes.len() - 1],
+            mean: sizes.iter().sum::<u64>() as f64 / sizes.len() as f64,
+            median: sizes[(sizes.len() - 1) / 2],
+            buckets: buckets.into_iter().collect(),
+        }
+    }
+}
+
+/// Outcome of [`Store::squash`]: what was (or, for a dry run, would be) removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Squash {
+    /// The generation the range collapsed into (`to`).
+    pub kept: Bytes32,
+    /// Roots dropped from the history, oldest first.
+    pub dropped: Vec<Bytes32>,
+    /// Chunk files deleted because no remaining generation references them.
+    pub chunks_freed: usize,
+    /// Bytes those chunk files held.
+    pub bytes_freed: u64,
+}
+
+/// Outcome of [`Store::remove_root`] / [`Store::prune`]: what was (or, for a
+/// dry run, would be) removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prune {
+    /// Roots dropped from the history, oldest first.
+    pub dropped: Vec<Bytes32>,
+    /// Chunk files deleted because no remaining generation references them.
+    pub chunks_freed: usize,
+    /// Bytes those chunk files held.
+    pub bytes_freed: u64,
+}
+
+/// Which generations [`Store::prune`] may drop: those committed before a
+/// Unix time, or with an id below a generation id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneBefore {
+    Timestamp(u64),
+    Generation(u64),
+}
 
 /// The host-side Store entity (§4). Owns the on-disk layout, staging, and
 /// generations. Generic over a `Clock` so commit timestamps are injectable.
@@ -20,6 +324,39 @@ pub struct Store<C: Clock> {
     config: StoreConfig,
     paths: StorePaths,
     clock: C,
+    /// Decoded chunk bodies by hash, shared by every read path.
+    chunk_cache: Mutex<ChunkCache>,
+    /// Decrypted plaintext of recently read chunks, by chunk hash: the
+    /// read-ahead window that [`Store::read_resource_range`] serves
+    /// overlapping and sequential windows from.
+    read_ahead: Mutex<ReadAhead>,
+    /// Chunk hash -> root hex of the generation dir holding its file. Built by
+    /// one scan on first use, then kept current by `commit`.
+    chunk_index: Mutex<Option<HashMap<[u8; 32], String>>>,
+    /// Threads used to read + decrypt a resource's chunks (`1` = sequential).
+    read_parallelism: usize,
+    /// Memory-map chunk files on the `read_resource` path instead of copying
+    /// them into owned buffers.
+    mmap_reads: bool,
+    /// Set by [`Store::open_read_only`]: every mutating method fails early.
+    read_only: bool,
+    /// Public key of the wallet this store was opened by, from
+    /// [`Store::open_as`]; checked against the store's [`AccessPolicy`].
+    writer: Option<Bytes48>,
+    /// Set by [`Store::init_ephemeral`]: the data dir is removed on drop.
+    ephemeral: bool,
+    /// Told about files and bytes as commits, batch adds and restores run.
+    progress: Option<Box<dyn ProgressSink>>,
+    /// The writer lock, held for the life of a writable store.
+    lock: Option<StoreLock>,
+}
+
+impl<C: Clock> Drop for Store<C> {
+    fn drop(&mut self) {
+        if self.ephemeral {
+            let _ = std::fs::remove_dir_all(self.paths.root());
+        }
+    }
 }
 
 impl<C: Clock> std::fmt::Debug for Store<C> {
@@ -42,6 +379,7 @@ impl<C: Clock> Store<C> {
             ));
         }
         std::fs::create_dir_all(paths.root())?;
+        let lock = StoreLock::acquire(paths.root())?;
         std::fs::create_dir_all(paths.generations_dir())?;
         std::fs::create_dir_all(paths.modules_dir())?;
         save_config(paths.config_file(), &config)?;
@@ -51,12 +389,83 @@ impl<C: Clock> Store<C> {
             config,
             paths,
             clock,
+            chunk_cache: Mutex::new(ChunkCache::new(DEFAULT_CHUNK_CACHE_BYTES)),
+            read_ahead: Mutex::new(ReadAhead::new(DEFAULT_READ_AHEAD_BYTES)),
+            chunk_index: Mutex::new(None),
+            read_parallelism: default_read_parallelism(),
+            mmap_reads: true,
+            read_only: false,
+            writer: None,
+            ephemeral: false,
+            progress: None,
+            lock: Some(lock),
         })
     }
 
-    /// Open an existing store rooted at `data_dir`.
+    /// Create a throwaway store in a fresh scratch directory under the
+    /// system temp dir, removed again when the store is dropped. For tests and
+    /// for embedders that need a transient content store: the whole `Store`
+    /// API works as on a persistent one. Copy a generation's chunks into a
+    /// [`MemoryBackend`](crate::MemoryBackend) with [`Store::copy_chunks_to`]
+    /// to keep serving it from memory.
+    pub fn init_ephemeral(store_id: Bytes32, visibility: Visibility, clock: C) -> Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "digstore-{}-{}-{nanos}-{}",
+            &store_id.to_hex()[..8],
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir(&dir)?;
+        let config = StoreConfig {
+            store_id,
+            data_dir: dir.display().to_string(),
+            max_size: MAX_STORE_BYTES,
+            visibility,
+            label: None,
+            description: None,
+            hash_algorithm: HashAlgorithm::default(),
+            salt_keyring: Default::default(),
+        };
+        match Self::init(config, clock) {
+            Ok(mut store) => {
+                store.ephemeral = true;
+                Ok(store)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                Err(e)
+            }
+        }
+    }
+
+    /// Open an existing store rooted at `data_dir`, first running
+    /// [`Store::recover`] to undo a commit that was interrupted. Takes the
+    /// writer [`StoreLock`] and fails with `Locked` while another process
+    /// holds it.
     pub fn open(data_dir: impl AsRef<Path>, clock: C) -> Result<Self> {
-        let data_dir = data_dir.as_ref();
+        let mut store = Self::open_unchecked(data_dir.as_ref(), clock)?;
+        store.lock = Some(StoreLock::acquire(data_dir)?);
+        store.recover()?;
+        store.check_head_index()?;
+        Ok(store)
+    }
+
+    /// Take the writer [`StoreLock`] on the store at `data_dir` and run
+    /// [`Store::recover`], without the head check [`Store::open`] also makes.
+    /// For a writer that lays generations down itself (the CLI's stage
+    /// engine) under the same `commit.pending` protocol. Returns the
+    /// discarded root, if any.
+    pub fn recover_at(data_dir: impl AsRef<Path>, clock: C) -> Result<Option<Bytes32>> {
+        let mut store = Self::open_unchecked(data_dir.as_ref(), clock)?;
+        store.lock = Some(StoreLock::acquire(data_dir)?);
+        store.recover()
+    }
+
+    fn open_unchecked(data_dir: &Path, clock: C) -> Result<Self> {
         let config_file = data_dir.join("config.toml");
         if !config_file.exists() {
             return Err(StoreError::NotFound(data_dir.display().to_string()));
@@ -67,9 +476,300 @@ impl<C: Clock> Store<C> {
             config,
             paths,
             clock,
+            chunk_cache: Mutex::new(ChunkCache::new(DEFAULT_CHUNK_CACHE_BYTES)),
+            read_ahead: Mutex::new(ReadAhead::new(DEFAULT_READ_AHEAD_BYTES)),
+            chunk_index: Mutex::new(None),
+            read_parallelism: default_read_parallelism(),
+            mmap_reads: true,
+            read_only: false,
+            writer: None,
+            ephemeral: false,
+            progress: None,
+            lock: None,
         })
     }
 
+    /// Open an existing store as the wallet holding `sk`. When the store has
+    /// an [`AccessPolicy`] that does not list `sk`'s public key this fails
+    /// with `Unauthorized`; a store opened with plain [`Store::open`] can
+    /// still be read, but every write to a store with a policy fails the
+    /// same way.
+    pub fn open_as(
+        data_dir: impl AsRef<Path>,
+        clock: C,
+        sk: &digstore_crypto::bls::SecretKey,
+    ) -> Result<Self> {
+        let mut store = Self::open_unchecked(data_dir.as_ref(), clock)?;
+        store.writer = Some(sk.public_key().to_bytes());
+        store.ensure_authorized()?;
+        store.lock = Some(StoreLock::acquire(data_dir)?);
+        store.recover()?;
+        store.check_head_index()?;
+        Ok(store)
+    }
+
+    /// Sanity-check the head generation's index so a damaged store fails on
+    /// open with the bad entry named, not later inside a read: every key-table
+    /// record must reference chunk indices the manifest lists, indices must be
+    /// unique, and the leaf cache (`leaves.bin`), when present, must hold one
+    /// entry per resource. Only the manifest is read and one file stat'ed, so
+    /// the check costs the same however large the generation; chunk contents
+    /// are checked by [`Store::verify_all_proofs`].
+    fn check_head_index(&self) -> Result<()> {
+        let Some(root) = self.current_root()? else {
+            return Ok(());
+        };
+        let root_hex = root.to_hex();
+        let manifest = match self.generation_manifest(root) {
+            Ok(m) => m,
+            Err(StoreError::GenerationNotFound(_)) => {
+                return Err(StoreError::Corrupted {
+                    entry: format!("generation {root_hex}"),
+                    detail: "the head generation's manifest is missing".into(),
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        let corrupted = |entry: String, detail: String| StoreError::Corrupted {
+            entry: format!("generation {root_hex}, {entry}"),
+            detail,
+        };
+        let mut indices: HashSet<u32> = HashSet::with_capacity(manifest.chunks.len());
+        for chunk in &manifest.chunks {
+            if !indices.insert(chunk.index) {
+                return Err(corrupted(
+                    format!("chunk index {}", chunk.index),
+                    "listed more than once".into(),
+                ));
+            }
+        }
+        for rec in &manifest.key_table {
+            if let Some(i) = rec.chunk_indices.iter().find(|i| !indices.contains(i)) {
+                return Err(corrupted(
+                    format!("resource {}", rec.resource_key),
+                    format!("references chunk index {i}, which the manifest does not list"),
+                ));
+            }
+        }
+        if let Ok(meta) = std::fs::metadata(self.paths.generation_leaves(&root_hex)) {
+            let expected = manifest.key_table.len() as u64 * 64;
+            if meta.len() != expected {
+                return Err(corrupted(
+                    "leaves.bin".into(),
+                    format!(
+                        "file is {} bytes, the manifest's {} resource(s) need {expected}",
+                        meta.len(),
+                        manifest.key_table.len()
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Open an existing store for reading only, e.g. on read-only media or for
+    /// CI verification. Nothing under `data_dir` is created or written: the
+    /// staging file and root history are never initialized, a store without a
+    /// history file reads as having no generations, and `stage_file`, `add`,
+    /// `commit` and `rotate_salt` fail with `ReadOnly` before touching disk.
+    /// No [`StoreLock`] is taken, so this works while a writer holds it.
+    pub fn open_read_only(data_dir: impl AsRef<Path>, clock: C) -> Result<Self> {
+        let mut store = Self::open_unchecked(data_dir.as_ref(), clock)?;
+        store.read_only = true;
+        Ok(store)
+    }
+
+    /// Open, read-only, the store at `data_dir` that `urn` names, so a URN
+    /// string is all a reader needs alongside the store's location. Fails
+    /// with `InvalidUrn` when the store there has another id, and with
+    /// `GenerationNotFound` when the URN pins a root the store does not hold.
+    /// Read the addressed resource with [`Store::read_urn`].
+    pub fn from_urn(data_dir: impl AsRef<Path>, urn: &Urn, clock: C) -> Result<Self> {
+        let store = Self::open_read_only(data_dir, clock)?;
+        store.urn_root(urn)?;
+        Ok(store)
+    }
+
+    /// The root `urn` addresses in this store: its pinned root hash, or the
+    /// current root for a rootless URN.
+    pub fn urn_root(&self, urn: &Urn) -> Result<Bytes32> {
+        if urn.store_id != self.config.store_id {
+            return Err(StoreError::InvalidUrn(format!(
+                "it names store {}, not {}",
+                urn.store_id.to_hex(),
+                self.config.store_id.to_hex()
+            )));
+        }
+        let root = match urn.root_hash {
+            Some(root) => root,
+            None => self
+                .current_root()?
+                .ok_or_else(|| StoreError::InvalidUrn("the store has no version yet".into()))?,
+        };
+        if !self.paths.generation_manifest(&root.to_hex()).exists() {
+            return Err(StoreError::GenerationNotFound(root.to_hex()));
+        }
+        Ok(root)
+    }
+
+    /// Read the resource `urn` names, at its pinned root or the current one
+    /// ([`Store::read_resource`] after [`Store::urn_root`]).
+    pub fn read_urn(&self, urn: &Urn) -> Result<Vec<u8>> {
+        let root = self.urn_root(urn)?;
+        let key = urn
+            .resource_key
+            .as_deref()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| StoreError::InvalidUrn("it names no resource".into()))?;
+        self.read_resource(root, key)
+    }
+
+    /// Roll back a commit that was cut short. A commit writes
+    /// `commit.pending` naming its root and history id, then the generation
+    /// dir, and only then its `roots.log` entry, so a commit is done exactly
+    /// when the history lists that entry. This drops a torn last line from
+    /// `roots.log` and, if the pending entry never made it into the history,
+    /// deletes its partial generation dir and any module compiled from it
+    /// (unless an earlier entry lists the same root and so owns them). Returns the
+    /// discarded root, if any. Runs on
+    /// every [`Store::open`] once the writer lock is held; a no-op when the
+    /// last commit finished.
+    ///
+    /// Only the lock holder may roll back: to anyone else a live writer's
+    /// commit in progress looks exactly like an interrupted one. A read-only
+    /// store never recovers and fails here with `ReadOnly`.
+    pub fn recover(&mut self) -> Result<Option<Bytes32>> {
+        self.ensure_not_read_only()?;
+        if self.lock.is_none() {
+            return Err(StoreError::ReadOnly(
+                self.paths.root().display().to_string(),
+            ));
+        }
+        let mut history = RootHistory::open(self.paths.history_file())?;
+        history.truncate_torn_tail()?;
+
+        let marker = self.paths.pending_commit_file();
+        if !marker.exists() {
+            return Ok(None);
+        }
+        // `<root> <id>`: the pending history entry. A marker without the id
+        // names the root alone.
+        let text = std::fs::read_to_string(&marker)?;
+        let mut fields = text.split_whitespace();
+        let pending = fields.next().and_then(|hex| Bytes32::from_hex(hex).ok());
+        let id: Option<u64> = fields.next().and_then(|id| id.parse().ok());
+        let entries = history.entries()?;
+        let committed = |root: Bytes32| {
+            entries
+                .iter()
+                .any(|g| g.root == root && id.is_none_or(|id| g.id == id))
+        };
+        let discarded = match pending {
+            Some(root) if !committed(root) => {
+                // An earlier entry for the same root owns the generation dir.
+                if !entries.iter().any(|g| g.root == root) {
+                    let dir = self.paths.generation_dir(&root.to_hex());
+                    if dir.exists() {
+                        std::fs::remove_dir_all(dir)?;
+                    }
+                    let module = self.paths.module_file(&root.to_hex());
+                    if module.exists() {
+                        std::fs::remove_file(module)?;
+                    }
+                    *self.chunk_index.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                }
+                Some(root)
+            }
+            Some(root) => {
+                // Listed but cut short before the branch moved: finish the job.
+                let refs = self.refs();
+                if refs.exists() {
+                    refs.set(&refs.current()?, root)?;
+                }
+                None
+            }
+            None => None,
+        };
+        std::fs::remove_file(marker)?;
+        Ok(discarded)
+    }
+
+    /// True when opened with [`Store::open_read_only`].
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        self.ensure_not_read_only()?;
+        self.ensure_authorized()
+    }
+
+    fn ensure_not_read_only(&self) -> Result<()> {
+        if self.read_only {
+            return Err(StoreError::ReadOnly(
+                self.paths.root().display().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fail with `Unauthorized` unless the store has no [`AccessPolicy`] or
+    /// its policy lists the key this store was opened as.
+    fn ensure_authorized(&self) -> Result<()> {
+        let Some(policy) = self.access_policy()? else {
+            return Ok(());
+        };
+        match &self.writer {
+            Some(key) if policy.allows(key) => Ok(()),
+            Some(key) => Err(StoreError::Unauthorized(format!(
+                "key {} is not a listed writer",
+                key.to_hex()
+            ))),
+            None => Err(StoreError::Unauthorized(
+                "the store has an access policy; open it as a listed writer".into(),
+            )),
+        }
+    }
+
+    /// The store's write policy, or `None` when any writer is allowed.
+    pub fn access_policy(&self) -> Result<Option<AccessPolicy>> {
+        read_policy(&self.paths.access_file())
+    }
+
+    /// Replace the store's write policy, or drop it with `None` so any writer
+    /// is allowed again. Only a writer the current policy admits may change
+    /// it, and a new policy must list the key the store was opened as (see
+    /// [`Store::open_as`]), so a writer cannot lock itself out.
+    pub fn set_access_policy(&mut self, policy: Option<&AccessPolicy>) -> Result<()> {
+        self.ensure_writable()?;
+        if let Some(policy) = policy {
+            if !self.writer.is_some_and(|key| policy.allows(&key)) {
+                return Err(StoreError::Unauthorized(
+                    "a new policy must list the key the store was opened as".into(),
+                ));
+            }
+        }
+        write_policy(&self.paths.access_file(), policy)
+    }
+
+    /// The public key this store was opened as, from [`Store::open_as`].
+    pub fn writer(&self) -> Option<Bytes48> {
+        self.writer
+    }
+
+    /// The root history, or `None` when a read-only store has no history file.
+    fn history(&self) -> Result<Option<RootHistory>> {
+        let path = self.paths.history_file();
+        if self.read_only && !path.exists() {
+            return Ok(None);
+        }
+        RootHistory::open(path).map(Some)
+    }
+
+    fn refs(&self) -> BranchRefs {
+        BranchRefs::open(self.paths.refs_file(), self.paths.head_file())
+    }
+
     pub fn store_id(&self) -> Bytes32 {
         self.config.store_id
     }
@@ -78,34 +778,199 @@ impl<C: Clock> Store<C> {
         &self.config
     }
 
+    /// The content-defined chunking every commit uses: the catalog defaults
+    /// (min 16 KiB, target 64 KiB, max 256 KiB), where `mask` (1<<16)-1
+    /// selects the ~64 KiB average chunk size.
+    pub fn chunker_config(&self) -> ChunkerConfig {
+        ChunkerConfig {
+            min_size: 16 * 1024,
+            target_size: 64 * 1024,
+            max_size: 256 * 1024,
+            mask: (1u64 << 16) - 1,
+        }
+    }
+
     pub fn paths(&self) -> &StorePaths {
         &self.paths
     }
 
+    /// Set the chunk cache's byte budget (`0` disables it). Defaults to
+    /// [`DEFAULT_CHUNK_CACHE_BYTES`].
+    pub fn set_chunk_cache_capacity(&mut self, bytes: usize) {
+        self.cache().set_capacity(bytes);
+    }
+
+    /// Set the byte budget of the read-ahead window of decrypted chunks kept
+    /// for [`Store::read_resource_range`] (`0` disables it). Defaults to
+    /// [`DEFAULT_READ_AHEAD_BYTES`].
+    pub fn set_read_ahead_capacity(&mut self, bytes: usize) {
+        self.read_ahead().plaintext.set_capacity(bytes);
+    }
+
+    /// Read-ahead window hit/miss counters and occupancy.
+    pub fn read_ahead_stats(&self) -> CacheStats {
+        self.read_ahead().plaintext.stats()
+    }
+
+    /// Set how many threads [`Store::read_resource`] uses to read and decrypt a
+    /// resource's chunks. Defaults to the machine's available parallelism;
+    /// `0` and `1` both read sequentially.
+    pub fn set_read_parallelism(&mut self, threads: usize) {
+        self.read_parallelism = threads.max(1);
+    }
+
+    /// Turn memory-mapped chunk reads on or off (on by default). Mapped chunks
+    /// are decrypted straight from the page cache and bypass the chunk cache;
+    /// turn this off on memory-constrained hosts to read into owned buffers.
+    pub fn set_mmap_reads(&mut self, enabled: bool) {
+        self.mmap_reads = enabled;
+    }
+
+    /// Report the progress of [`Store::add_files`], the commit methods,
+    /// [`Store::get_files`] and [`Store::export_tar`] to `sink` (`None` stops
+    /// reporting).
+    pub fn set_progress_sink(&mut self, sink: Option<Box<dyn ProgressSink>>) {
+        self.progress = sink;
+    }
+
+    /// Catch up with changes another process (or another `Store` on the same
+    /// directory) made since this one was opened, and return the current
+    /// root. The root history, branch refs and staging area are read from
+    /// disk on every call, so commits made elsewhere already show; what a
+    /// long-lived store holds in memory does not. This re-reads
+    /// `config.toml` (a salt rotated elsewhere changes how generations
+    /// decrypt) and invalidates every cached chunk, decrypted read-ahead
+    /// window, generation already verified against its root and the chunk
+    /// location index, so generations pruned or squashed away elsewhere are
+    /// not served from memory. Cache hit/miss counters are kept.
+    pub fn refresh(&mut self) -> Result<Option<Bytes32>> {
+        self.config = load_config(self.paths.config_file())?;
+        self.cache().clear();
+        {
+            let mut read_ahead = self.read_ahead();
+            read_ahead.plaintext.clear();
+            read_ahead.last = None;
+            read_ahead.verified.clear();
+        }
+        *self
+            .chunk_index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        self.current_root()
+    }
+
+    /// Chunk cache hit/miss counters and occupancy.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache().stats()
+    }
+
+    fn read_ahead(&self) -> std::sync::MutexGuard<'_, ReadAhead> {
+        self.read_ahead
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, ChunkCache> {
+        // The cache holds no invariants a panicking reader could break.
+        self.chunk_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// All generation states, oldest first (§4.3 root history).
     pub fn root_history(&self) -> Result<Vec<GenerationState>> {
-        RootHistory::open(self.paths.history_file())?.entries()
+        match self.history()? {
+            Some(history) => history.entries(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Every generation in the root history, oldest first, with its manifest.
+    /// Only the history is read up front; each manifest is loaded as the
+    /// iterator reaches it, so walking a long history holds one manifest at a
+    /// time. A generation whose manifest is missing yields
+    /// `GenerationNotFound` and iteration carries on with the next.
+    pub fn iter_generations(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(GenerationState, GenerationManifest)>> + '_> {
+        Ok(self.root_history()?.into_iter().map(|g| {
+            let manifest = self.generation_manifest(g.root)?;
+            Ok((g, manifest))
+        }))
     }
 
     /// True if a chunk with this hash is already stored under some generation
     /// directory (global dedup index, §8.2).
     fn chunk_exists_anywhere(&self, hash: Bytes32) -> Result<bool> {
+        Ok(self.chunk_location(hash, false)?.is_some())
+    }
+
+    /// The on-disk file of chunk `hash`, via the chunk index (an O(1) lookup
+    /// instead of probing every generation dir). With `refresh_on_miss`, an
+    /// unknown or stale entry triggers one rescan, picking up generations
+    /// written by another process since the index was built.
+    fn chunk_location(&self, hash: Bytes32, refresh_on_miss: bool) -> Result<Option<PathBuf>> {
+        let name = hash.to_hex();
+        let lookup = |map: &HashMap<[u8; 32], String>| {
+            map.get(&hash.0)
+                .map(|root_hex| self.paths.chunk_file(root_hex, &name))
+                .filter(|p| p.exists())
+        };
+        let mut index = self
+            .chunk_index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(found) = index.as_ref().and_then(lookup) {
+            return Ok(Some(found));
+        }
+        if index.is_some() && !refresh_on_miss {
+            return Ok(None);
+        }
+        let map = self.scan_chunk_index()?;
+        let found = lookup(&map);
+        *index = Some(map);
+        Ok(found)
+    }
+
+    /// Record a chunk file written under generation `root_hex` in the index.
+    fn index_chunk(&self, hash: Bytes32, root_hex: &str) {
+        let mut index = self
+            .chunk_index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(map) = index.as_mut() {
+            map.insert(hash.0, root_hex.to_string());
+        }
+    }
+
+    /// Scan every generation's `chunks/` dir once into a hash -> root map.
+    fn scan_chunk_index(&self) -> Result<HashMap<[u8; 32], String>> {
+        let mut map = HashMap::new();
         let gens = self.paths.generations_dir();
         if !gens.exists() {
-            return Ok(false);
+            return Ok(map);
         }
-        let name = hash.to_hex();
-        for entry in std::fs::read_dir(&gens)? {
-            let chunks = entry?.path().join("chunks");
-            if chunks.join(&name).exists() {
-                return Ok(true);
+        for gen in std::fs::read_dir(&gens)? {
+            let gen = gen?;
+            let chunks = gen.path().join("chunks");
+            if !chunks.is_dir() {
+                continue;
+            }
+            let root_hex = gen.file_name().to_string_lossy().into_owned();
+            for entry in std::fs::read_dir(&chunks)? {
+                let file_name = entry?.file_name();
+                // In-flight `.tmp` writes and stray files fail to parse; skip them.
+                if let Ok(hash) = Bytes32::from_hex(&file_name.to_string_lossy()) {
+                    map.entry(hash.0).or_insert_with(|| root_hex.clone());
+                }
             }
         }
-        Ok(false)
+        Ok(map)
     }
 
     /// Stage raw bytes under an explicit resource key (§20.2).
     pub fn stage_file(&mut self, resource_key: &str, bytes: &[u8]) -> Result<()> {
+        self.ensure_writable()?;
         let mut staging = StagingArea::open(self.paths.staging_file())?;
         staging.append(resource_key, bytes)?;
         Ok(())
@@ -114,20 +979,79 @@ impl<C: Clock> Store<C> {
     /// Stage a file from disk. The path relative to `base` becomes the resource
     /// key (forward-slash normalized); the file bytes are staged verbatim.
     pub fn add(&mut self, file: impl AsRef<Path>, base: impl AsRef<Path>) -> Result<()> {
+        self.ensure_writable()?;
         let file = file.as_ref();
         let base = base.as_ref();
         let rel = file
             .strip_prefix(base)
             .map_err(|_| StoreError::PathEscape(file.to_path_buf()))?;
-        let resource_key = rel
-            .components()
-            .map(|c| c.as_os_str().to_string_lossy())
-            .collect::<Vec<_>>()
-            .join("/");
+        let resource_key =
+            relative_resource_key(rel).ok_or_else(|| StoreError::PathEscape(file.to_path_buf()))?;
         let bytes = std::fs::read(file)?;
         self.stage_file(&resource_key, &bytes)
     }
 
+    /// Stage many files from disk, each keyed as in [`Store::add`]. Every path
+    /// is checked before anything is staged, so a file outside `base` stages
+    /// nothing; progress goes to the [`ProgressSink`], if any.
+    pub fn add_files(&mut self, files: &[PathBuf], base: impl AsRef<Path>) -> Result<()> {
+        self.ensure_writable()?;
+        let base = base.as_ref();
+        let mut keyed = Vec::with_capacity(files.len());
+        let mut total = 0;
+        for file in files {
+            let resource_key = file
+                .strip_prefix(base)
+                .ok()
+                .and_then(relative_resource_key)
+                .ok_or_else(|| StoreError::PathEscape(file.clone()))?;
+            total += std::fs::metadata(file)?.len();
+            keyed.push((file, resource_key));
+        }
+        let mut progress = Progress::new(self.progress.as_deref(), files.len() as u64, total);
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        for (file, resource_key) in keyed {
+            let bytes = std::fs::read(file)?;
+            staging.append(&resource_key, &bytes)?;
+            progress.file_done(bytes.len() as u64);
+        }
+        Ok(())
+    }
+
+    /// Stage in-memory `data` as if it were a file at `path`, with no file on
+    /// disk: `path` (relative, no `..`) becomes the resource key exactly as
+    /// [`Store::add`] derives it. Staging holds the bytes themselves, so
+    /// `commit` needs nothing else.
+    pub fn add_bytes(&mut self, path: impl AsRef<Path>, data: &[u8]) -> Result<()> {
+        self.ensure_writable()?;
+        let path = path.as_ref();
+        let resource_key = relative_resource_key(path)
+            .ok_or_else(|| StoreError::PathEscape(path.to_path_buf()))?;
+        self.stage_file(&resource_key, data)
+    }
+
+    /// Stage everything `reader` yields as a file at `path` (keyed as in
+    /// [`Store::add_bytes`]), e.g. a command's piped output. The bytes stream
+    /// straight into the staging file without being buffered whole or written
+    /// to a temp file. Returns the number of bytes staged.
+    pub fn add_reader(&mut self, path: impl AsRef<Path>, mut reader: impl Read) -> Result<u64> {
+        self.ensure_writable()?;
+        let path = path.as_ref();
+        let resource_key = relative_resource_key(path)
+            .ok_or_else(|| StoreError::PathEscape(path.to_path_buf()))?;
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        staging.append_reader(&resource_key, &mut reader)
+    }
+
+    /// Stage every regular file in a tar (or `.tar.gz`) stream under its
+    /// in-archive path, without extracting it; see [`StagingArea::append_tar`].
+    /// A bad archive stages nothing.
+    pub fn add_tar(&mut self, tar: impl Read) -> Result<TarImport> {
+        self.ensure_writable()?;
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        staging.append_tar(tar, u64::MAX)
+    }
+
     /// Finalize a generation (§20.3, §8.2): chunk staged content, AES-256-GCM
     /// seal each chunk under its resource's per-URN key (chunks are stored as
     /// CIPHERTEXT, content-addressed by `SHA-256(ciphertext)`), build the
@@ -137,66 +1061,337 @@ impl<C: Clock> Store<C> {
     /// generation directory. Returns the new root hash. Does NOT compile the
     /// module (that is `digstore-compiler`'s job over this generation dir).
     pub fn commit(&mut self) -> Result<Bytes32> {
+        let key_epoch = self.current_key_epoch()?;
+        self.commit_with_epoch(key_epoch, false, None)
+    }
+
+    /// [`Store::commit`] with `options`. An allowed empty commit only appends
+    /// a history entry, so nothing is sealed or written under `generations/`.
+    pub fn commit_with_options(&mut self, options: CommitOptions) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        let staged = StagingArea::open(self.paths.staging_file())?.records()?;
+        if options.allow_empty && staged.is_empty() {
+            if let Some(head) = self.current_root()? {
+                let mut history = RootHistory::open(self.paths.history_file())?;
+                history.append(&GenerationState {
+                    id: history.next_id()?,
+                    root: head,
+                    timestamp: self.clock.unix_seconds(),
+                })?;
+                return Ok(head);
+            }
+        }
+        if options.changes_only {
+            let key_epoch = self.current_key_epoch()?;
+            let parent = self.current_root()?;
+            return self.commit_with_epoch(key_epoch, false, parent);
+        }
+        self.commit()
+    }
+
+    /// Fold the staged changes into the head generation instead of stacking a
+    /// new one (the `commit --amend` of §20.3). The amended generation holds
+    /// every resource of the head, with staged keys overriding, and REPLACES
+    /// the head in the root history under the same generation id: the history
+    /// length is unchanged and the old head root is no longer listed (its
+    /// generation dir stays on disk). The head is validated against its root
+    /// before anything is re-sealed. Fails with `NothingToAmend` when there is
+    /// no generation yet; with nothing staged it reproduces the head's root.
+    pub fn amend_commit(&mut self) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        let head = self.current_root()?.ok_or(StoreError::NothingToAmend)?;
+        let latest = self.history()?.map(|h| h.head()).transpose()?.flatten();
+        if latest.is_some_and(|g| g.root != head) {
+            return Err(StoreError::InvalidBranch(format!(
+                "{} is not the latest version; only the latest can be amended",
+                self.current_branch()?
+            )));
+        }
+        let manifest = self.generation_manifest(head)?;
+        self.rebuild_generation_tree(&manifest, head)?;
+
         let mut staging = StagingArea::open(self.paths.staging_file())?;
-        let records = staging.records()?;
-        if records.is_empty() {
-            return Err(StoreError::EmptyStaging);
+        let staged = staging.records()?;
+        let mut carried = Vec::new();
+        for rec in &manifest.key_table {
+            if !staged.iter().any(|s| s.resource_key == rec.resource_key) {
+                carried.push((
+                    rec.resource_key.clone(),
+                    self.decrypt_record(&manifest, rec)?,
+                ));
+            }
         }
 
-        // Catalog chunker defaults (min 16 KiB, target 64 KiB, max 256 KiB).
-        // `mask` selects the average chunk size; (1<<16)-1 targets ~64 KiB.
-        let chunker = ChunkerConfig {
-            min_size: 16 * 1024,
-            target_size: 64 * 1024,
-            max_size: 256 * 1024,
-            mask: (1u64 << 16) - 1,
+        // Staging is last-write-wins, so the head's resources go in first.
+        staging.clear()?;
+        for (key, content) in &carried {
+            staging.append(key, content)?;
+        }
+        for rec in &staged {
+            staging.append(&rec.resource_key, &rec.content)?;
+        }
+        self.commit_with_epoch(manifest.key_epoch, true, manifest.parent)
+            .inspect_err(|_| {
+                // Put back exactly what the caller had staged.
+                let _ = staging.clear();
+                for rec in &staged {
+                    let _ = staging.append(&rec.resource_key, &rec.content);
+                }
+            })
+    }
+
+    /// The key epoch a freshly sealed generation takes: the config's newest
+    /// ([`StoreConfig::key_epoch`]), or the head's when that is newer (a store
+    /// rotated before the keyring existed; its epochs all fall back to the
+    /// current salt).
+    fn current_key_epoch(&self) -> Result<u32> {
+        let head = match self.current_root()? {
+            Some(root) => self.generation_manifest(root)?.key_epoch,
+            None => 0,
         };
+        Ok(head.max(self.config.key_epoch()))
+    }
 
-        // Per-store secret salt (private stores mix it into the per-URN key, §11.4).
-        let salt: Option<SecretSalt> = match &self.config.visibility {
-            Visibility::Private(s) => Some(*s),
-            Visibility::Public => None,
+    /// Commit only what changed since the head (the incremental form of
+    /// [`Store::commit`]).
+    ///
+    /// The new generation holds every resource of the head with staged keys
+    /// overriding, like [`Store::amend_commit`], but is appended as a new
+    /// generation. Carried resources are NOT re-chunked or re-encrypted: their
+    /// key-table records and chunk refs are reused as is, and their D5 leaves
+    /// come from the head's leaf cache (`leaves.bin`, recomputed from the
+    /// stored ciphertext if absent). When no resource is added, only the
+    /// changed leaves' paths are rehashed ([`MerkleTree::update_leaf`]).
+    /// Encryption is deterministic, so the root equals what a full commit of
+    /// the same content produces. With no head it is a plain `commit`.
+    pub fn commit_incremental(&mut self) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        let Some(head) = self.current_root()? else {
+            return self.commit();
         };
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        let records = staging.records()?;
+        if records.is_empty() {
+            return Err(StoreError::EmptyStaging);
+        }
+        let manifest = self.generation_manifest(head)?;
+        let (mut keys, mut tree) = self.generation_leaves(&manifest, head)?;
 
-        // Build the chunk pool in staged-record order (the §8.3 source consumed
-        // by the compiler) and the key table mapping each resource to its
-        // ordered pool indices. Chunks are stored as CIPHERTEXT: each resource's
-        // chunks are AES-256-GCM-sealed under its per-URN key, and the merkle
-        // leaf is committed over those SAME ciphertext bytes (D4/D5), so the
-        // committed root matches what the compiler injects.
-        let mut pool: Vec<(Bytes32, Vec<u8>)> = Vec::new();
-        let mut key_table: Vec<KeyTableRecord> = Vec::new();
-        // (static_key raw, resource ciphertext leaf) per resource, for the D5 tree.
-        let mut resource_leaves: Vec<([u8; 32], Bytes32)> = Vec::new();
+        // Carried resources keep their chunks; only their pool indices move.
+        let by_index: HashMap<u32, &ChunkRef> =
+            manifest.chunks.iter().map(|c| (c.index, c)).collect();
+        let mut carried_refs: Vec<ChunkRef> = Vec::new();
+        let mut carried: Vec<KeyTableRecord> = Vec::new();
+        for rec in &manifest.key_table {
+            if records.iter().any(|r| r.resource_key == rec.resource_key) {
+                continue;
+            }
+            let mut indices = Vec::with_capacity(rec.chunk_indices.len());
+            for i in &rec.chunk_indices {
+                let chunk = by_index.get(i).ok_or_else(|| {
+                    StoreError::Manifest(format!("key table references missing chunk index {i}"))
+                })?;
+                indices.push(carried_refs.len() as u32);
+                carried_refs.push(ChunkRef {
+                    index: carried_refs.len() as u32,
+                    hash: chunk.hash,
+                    size: chunk.size,
+                });
+            }
+            carried.push(KeyTableRecord {
+                chunk_indices: indices,
+                ..rec.clone()
+            });
+        }
 
-        for rec in &records {
-            // root_hash: None -> retrieval key is root-independent (documented).
-            let urn = Urn {
-                chain: CHAIN.to_string(),
-                store_id: self.config.store_id,
-                root_hash: None,
-                resource_key: Some(rec.resource_key.clone()),
-            };
-            let static_key = urn.retrieval_key();
-            // Per-URN AES-256 key (§11.1): public store uses the fixed salt domain,
-            // private store mixes the secret salt.
-            let aes_key = digstore_crypto::derive_decryption_key(&urn.canonical(), salt.as_ref());
+        let sealed = self.seal_records(&records, carried_refs.len() as u32, manifest.key_epoch);
+        let positions: Option<Vec<usize>> = sealed
+            .leaves
+            .iter()
+            .map(|(key, _)| keys.binary_search(key).ok())
+            .collect();
+        match positions {
+            // Same resource set: rehash just the changed paths.
+            Some(positions) => {
+                for (pos, (_, leaf)) in positions.into_iter().zip(&sealed.leaves) {
+                    tree.update_leaf(pos, *leaf);
+                }
+            }
+            // New resources shift leaf positions; rebuild from the merged leaves.
+            None => {
+                let mut merged: BTreeMap<[u8; 32], Bytes32> = keys
+                    .into_iter()
+                    .zip(tree.leaves().iter().copied())
+                    .collect();
+                merged.extend(sealed.leaves.iter().copied());
+                let (merged_keys, leaves): (Vec<[u8; 32]>, Vec<Bytes32>) =
+                    merged.into_iter().unzip();
+                keys = merged_keys;
+                tree = MerkleTree::from_leaves_with(self.config.hash_algorithm, leaves);
+            }
+        }
 
-            let chunks = chunk_slice(&rec.content, &chunker);
-            let mut indices = Vec::with_capacity(chunks.len());
-            let mut total: u64 = 0;
+        carried.extend(sealed.key_table);
+        let root = self.write_generation(
+            carried_refs,
+            sealed.pool,
+            carried,
+            (&keys, &tree),
+            manifest.key_epoch,
+            manifest.parent,
+            false,
+        )?;
+        staging.clear()?;
+        Ok(root)
+    }
+
+    /// Generation `root`'s static keys in leaf order and its D5 tree, from the
+    /// `leaves.bin` cache when it matches the manifest's keys and rebuilds
+    /// `root`, else from the stored ciphertext (and the cache rewritten).
+    fn generation_leaves(
+        &self,
+        manifest: &GenerationManifest,
+        root: Bytes32,
+    ) -> Result<(Vec<[u8; 32]>, MerkleTree)> {
+        if let Some(cached) = self.cached_leaf_tree(manifest, root) {
+            return Ok(cached);
+        }
+        let (keys, tree) = self.rebuild_generation_tree(manifest, root)?;
+        write_leaf_cache(
+            &self.paths.generation_leaves(&root.to_hex()),
+            &keys,
+            tree.leaves(),
+        )?;
+        Ok((keys, tree))
+    }
+
+    /// Generation `root`'s keys and tree from its `leaves.bin`, when the file
+    /// matches the manifest's keys and folds to `root`.
+    fn cached_leaf_tree(
+        &self,
+        manifest: &GenerationManifest,
+        root: Bytes32,
+    ) -> Option<(Vec<[u8; 32]>, MerkleTree)> {
+        let bytes = std::fs::read(self.paths.generation_leaves(&root.to_hex())).ok()?;
+        if bytes.len() != manifest.key_table.len() * 64 {
+            return None;
+        }
+        let (keys, leaves): (Vec<[u8; 32]>, Vec<Bytes32>) = bytes
+            .chunks_exact(64)
+            .map(|r| {
+                let (key, leaf) = r.split_at(32);
+                (
+                    <[u8; 32]>::try_from(key).expect("32 bytes"),
+                    Bytes32(leaf.try_into().expect("32 bytes")),
+                )
+            })
+            .unzip();
+        let mut expected: Vec<[u8; 32]> =
+            manifest.key_table.iter().map(|r| r.static_key.0).collect();
+        expected.sort();
+        let tree = MerkleTree::from_leaves_with(self.config.hash_algorithm, leaves);
+        (keys == expected && tree.root() == root).then_some((keys, tree))
+    }
+
+    /// Seal the staging area as a generation on top of `parent` (`None` for a
+    /// self-contained one). With `replace_head` it takes the head's generation
+    /// id and replaces the head in the root history.
+    fn commit_with_epoch(
+        &mut self,
+        key_epoch: u32,
+        replace_head: bool,
+        parent: Option<Bytes32>,
+    ) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        let records = staging.records()?;
+        if records.is_empty() {
+            return Err(StoreError::EmptyStaging);
+        }
+
+        // D5 merkle tree: ONE leaf per resource, ascending by static_key (the
+        // exact leaf order the compiler injects and the guest ranks against).
+        // §9.4 invariant `state.root == tree.root()` still holds.
+        let mut sealed = self.seal_records(&records, 0, key_epoch);
+        sealed.leaves.sort_by_key(|r| r.0);
+        let (keys, leaves): (Vec<[u8; 32]>, Vec<Bytes32>) = sealed.leaves.into_iter().unzip();
+        let tree = MerkleTree::from_leaves_with(self.config.hash_algorithm, leaves);
+
+        let root = self.write_generation(
+            Vec::new(),
+            sealed.pool,
+            sealed.key_table,
+            (&keys, &tree),
+            key_epoch,
+            parent,
+            replace_head,
+        )?;
+        staging.clear()?;
+        Ok(root)
+    }
+
+    /// Chunk and encrypt `records` under the salt of `key_epoch` into a chunk
+    /// pool numbered from `first_index`, with their key-table records
+    /// (generation left as a placeholder) and `(static_key, D5 leaf)` per
+    /// resource.
+    fn seal_records(
+        &self,
+        records: &[StagedRecord],
+        first_index: u32,
+        key_epoch: u32,
+    ) -> SealedRecords {
+        let chunker = self.chunker_config();
+
+        // Per-store secret salt (private stores mix it into the per-URN key, §11.4).
+        let sealer = UrnSealer::for_visibility(&self.config.epoch_visibility(key_epoch));
+        let algorithm = self.config.hash_algorithm;
+
+        // Build the chunk pool in staged-record order (the §8.3 source consumed
+        // by the compiler) and the key table mapping each resource to its
+        // ordered pool indices. Chunks are stored as CIPHERTEXT: each resource's
+        // chunks are AES-256-GCM-sealed under its per-URN key, and the merkle
+        // leaf is committed over those SAME ciphertext bytes (D4/D5), so the
+        // committed root matches what the compiler injects.
+        let mut sealed = SealedRecords {
+            pool: Vec::new(),
+            key_table: Vec::new(),
+            leaves: Vec::new(),
+        };
+        let mut progress = Progress::new(
+            self.progress.as_deref(),
+            records.len() as u64,
+            records.iter().map(|r| r.content.len() as u64).sum(),
+        );
+
+        for rec in records {
+            // root_hash: None -> retrieval key is root-independent (documented).
+            let urn = Urn {
+                chain: CHAIN.to_string(),
+                store_id: self.config.store_id,
+                root_hash: None,
+                resource_key: Some(rec.resource_key.clone()),
+            };
+            let static_key = urn.retrieval_key();
+            // Per-URN AES-256 key (§11.1): public store uses the fixed salt domain,
+            // private store mixes the secret salt.
+            let aes_key = sealer.key_for(&urn);
+
+            let chunks = chunk_slice_with(&rec.content, &chunker, algorithm);
+            let mut indices = Vec::with_capacity(chunks.len());
+            let mut total: u64 = 0;
             // Ciphertext bodies of this resource's chunks, in resource order.
             let mut ct_bodies: Vec<Vec<u8>> = Vec::with_capacity(chunks.len());
             for chunk in &chunks {
                 // Encrypt the plaintext chunk; the pool/merkle commit over ciphertext.
                 let ct = digstore_crypto::encrypt_chunk(&aes_key, &chunk.data);
-                // Content-addressed by SHA-256(ciphertext) — dedup key is the
-                // ciphertext hash (still per-chunk, unchanged storage model).
-                let hash = digstore_crypto::sha256(&ct);
-                let index = pool.len() as u32;
+                // Content-addressed by SHA-256(ciphertext) (or the store's hash
+                // algorithm) — dedup key is the ciphertext hash (still per-chunk,
+                // unchanged storage model).
+                let hash = algorithm.digest(&ct);
+                let index = first_index + sealed.pool.len() as u32;
                 total += chunk.data.len() as u64;
                 ct_bodies.push(ct.clone());
-                pool.push((hash, ct));
+                sealed.pool.push((hash, ct));
                 indices.push(index);
             }
 
@@ -206,24 +1401,38 @@ impl<C: Clock> Store<C> {
             // against (`dig-client-wasm`), so the content→leaf contract is shared.
             let slices: Vec<&[u8]> = ct_bodies.iter().map(|b| b.as_slice()).collect();
             let blob = concat_output(&slices);
-            let leaf = digstore_core::resource_leaf(&blob);
-            resource_leaves.push((static_key.0, leaf));
+            let leaf = digstore_core::resource_leaf_with(algorithm, &blob);
+            sealed.leaves.push((static_key.0, leaf));
 
-            key_table.push(KeyTableRecord {
+            sealed.key_table.push(KeyTableRecord {
                 resource_key: rec.resource_key.clone(),
                 static_key,
                 generation: Bytes32([0u8; 32]), // placeholder set after root
                 chunk_indices: indices,
                 total_size: total,
             });
+            progress.file_done(rec.content.len() as u64);
         }
+        sealed
+    }
 
-        // D5 merkle tree: ONE leaf per resource, ascending by static_key (the
-        // exact leaf order the compiler injects and the guest ranks against).
-        // §9.4 invariant `state.root == tree.root()` still holds.
-        resource_leaves.sort_by_key(|r| r.0);
-        let leaves: Vec<Bytes32> = resource_leaves.iter().map(|(_, leaf)| *leaf).collect();
-        let tree = MerkleTree::from_leaves(leaves);
+    /// Write the generation whose D5 `tree` has static keys `keys` (leaf
+    /// order): store the new `pool` chunks (indexed after `carried_refs`,
+    /// which are already on disk), the manifest and leaf cache, and append (or
+    /// with `replace_head`, swap in) its history entry. A root the history
+    /// already lists keeps the generation its first entry wrote: only the new
+    /// entry is recorded. Returns the root.
+    #[allow(clippy::too_many_arguments)]
+    fn write_generation(
+        &mut self,
+        carried_refs: Vec<ChunkRef>,
+        pool: Vec<(Bytes32, Vec<u8>)>,
+        mut key_table: Vec<KeyTableRecord>,
+        (keys, tree): (&[[u8; 32]], &MerkleTree),
+        key_epoch: u32,
+        parent: Option<Bytes32>,
+        replace_head: bool,
+    ) -> Result<Bytes32> {
         let root = tree.root();
         let root_hex = root.to_hex();
 
@@ -232,49 +1441,998 @@ impl<C: Clock> Store<C> {
             rec.generation = root;
         }
 
+        let mut history = RootHistory::open(self.paths.history_file())?;
+        let next_id = match history.head()? {
+            Some(head) if replace_head => head.id,
+            _ => history.next_id()?,
+        };
+        let timestamp = self.clock.unix_seconds();
+        let written = history.entries()?.iter().any(|g| g.root == root);
+
+        // Until the history entry lands, `recover` treats this generation as
+        // partial and removes it.
+        let marker = self.paths.pending_commit_file();
+        crate::chunkstore::write_synced(&marker, format!("{root_hex} {next_id}").as_bytes())?;
+
         // Write generation dir (per-directory dedup; global dedup added in
         // Task 13 over `chunk_exists_anywhere`).
         let chunks_dir = self.paths.generation_chunks_dir(&root_hex);
         std::fs::create_dir_all(&chunks_dir)?;
         let chunkstore = ChunkStore::new(&chunks_dir);
-        let mut chunk_refs = Vec::with_capacity(pool.len());
-        for (i, (hash, data)) in pool.iter().enumerate() {
+        let mut chunk_refs = carried_refs;
+        for (hash, data) in &pool {
             // §8.2: only store the chunk if it is not already present in this or
             // any prior generation. `chunk_refs` still records every chunk's
             // index so reassembly is complete regardless of where the bytes live
             // (resolved globally by `Store::resolve_chunk`, Task 14).
             if !self.chunk_exists_anywhere(*hash)? {
                 chunkstore.put(*hash, data)?;
+                self.index_chunk(*hash, &root_hex);
             }
             chunk_refs.push(ChunkRef {
-                index: i as u32,
+                index: chunk_refs.len() as u32,
                 hash: *hash,
                 size: data.len() as u64,
             });
         }
 
-        let next_id = RootHistory::open(self.paths.history_file())?.next_id()?;
-        let timestamp = self.clock.unix_seconds();
+        if !written {
+            let manifest = GenerationManifest {
+                schema_version: 1,
+                generation_id: next_id,
+                root,
+                timestamp,
+                chunks: chunk_refs,
+                key_table,
+                key_epoch,
+                parent,
+            };
+            manifest.write_to(self.paths.generation_manifest(&root_hex))?;
+            write_leaf_cache(
+                &self.paths.generation_leaves(&root_hex),
+                keys,
+                tree.leaves(),
+            )?;
+        }
 
-        let manifest = GenerationManifest {
-            schema_version: 1,
-            generation_id: next_id,
+        let state = GenerationState {
+            id: next_id,
             root,
             timestamp,
-            chunks: chunk_refs,
-            key_table,
         };
-        manifest.write_to(self.paths.generation_manifest(&root_hex))?;
+        if replace_head {
+            history.replace_head(&state)?;
+        } else {
+            history.append(&state)?;
+        }
+        let refs = self.refs();
+        if refs.exists() {
+            refs.set(&refs.current()?, root)?;
+        }
+        std::fs::remove_file(marker)?;
+        Ok(root)
+    }
+
+    /// Everything committed after `base`, for an incremental backup: the
+    /// manifest of every later generation in the root history, and the
+    /// sealed chunks they reference that no generation up to and including
+    /// `base` stores. Apply it with [`Store::import_delta`] on a store whose
+    /// head is `base`.
+    pub fn export_since(&self, base: Bytes32) -> Result<DeltaBundle> {
+        let history = self.root_history()?;
+        let position = history
+            .iter()
+            .position(|g| g.root == base)
+            .ok_or_else(|| StoreError::GenerationNotFound(base.to_hex()))?;
+        let mut known: HashSet<[u8; 32]> = HashSet::new();
+        for g in &history[..=position] {
+            match self.generation_manifest(g.root) {
+                Ok(manifest) => known.extend(manifest.chunks.iter().map(|c| c.hash.0)),
+                Err(StoreError::GenerationNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut bundle = DeltaBundle {
+            store_id: self.config.store_id,
+            base,
+            generations: Vec::new(),
+            chunks: Vec::new(),
+        };
+        for g in &history[position + 1..] {
+            let manifest = self.generation_manifest(g.root)?;
+            for c in &manifest.chunks {
+                if known.insert(c.hash.0) {
+                    bundle.chunks.push((c.hash, self.resolve_chunk(c.hash)?));
+                }
+            }
+            bundle.generations.push(manifest);
+        }
+        Ok(bundle)
+    }
+
+    /// Apply a bundle from [`Store::export_since`]. The store must belong to
+    /// the same store id and be at the bundle's base: `base` is both the
+    /// latest generation and the current root. Each generation's chunks are
+    /// checked against their hashes and its root rebuilt from them before
+    /// its history entry is appended, so a generation is either imported
+    /// whole or not at all. Returns the imported roots, oldest first.
+    pub fn import_delta(&mut self, bundle: &DeltaBundle) -> Result<Vec<Bytes32>> {
+        self.ensure_writable()?;
+        if bundle.store_id != self.config.store_id {
+            return Err(StoreError::InvalidDelta(format!(
+                "bundle is for store {}, not {}",
+                bundle.store_id.to_hex(),
+                self.config.store_id.to_hex()
+            )));
+        }
+        let history = RootHistory::open(self.paths.history_file())?;
+        if !history.entries()?.iter().any(|g| g.root == bundle.base) {
+            return Err(StoreError::GenerationNotFound(bundle.base.to_hex()));
+        }
+        let latest = history.head()?.map(|g| g.root);
+        let current = self.current_root()?;
+        if latest != Some(bundle.base) || current != Some(bundle.base) {
+            return Err(StoreError::InvalidDelta(format!(
+                "store is at {}, not at the bundle's base {}",
+                current.map_or_else(|| "no version".to_string(), |r| r.to_hex()),
+                bundle.base.to_hex()
+            )));
+        }
+
+        let algorithm = self.config.hash_algorithm;
+        let mut chunks: HashMap<[u8; 32], &[u8]> = HashMap::new();
+        for (hash, data) in &bundle.chunks {
+            if algorithm.digest(data) != *hash {
+                return Err(StoreError::InvalidDelta(format!(
+                    "chunk {} does not match its hash",
+                    hash.to_hex()
+                )));
+            }
+            chunks.insert(hash.0, data);
+        }
+
+        let mut imported = Vec::with_capacity(bundle.generations.len());
+        for manifest in &bundle.generations {
+            self.import_generation(manifest, &chunks).inspect_err(|_| {
+                // Drops the partial generation dir and the stale chunk index.
+                let _ = self.recover();
+            })?;
+            imported.push(manifest.root);
+        }
+        Ok(imported)
+    }
 
+    /// Write one imported generation the way [`Store::commit`] does: marker,
+    /// chunks not already stored, manifest, leaf cache, then history entry.
+    fn import_generation(
+        &mut self,
+        manifest: &GenerationManifest,
+        chunks: &HashMap<[u8; 32], &[u8]>,
+    ) -> Result<()> {
         let mut history = RootHistory::open(self.paths.history_file())?;
+        let next_id = history.next_id()?;
+        if manifest.generation_id != next_id {
+            return Err(StoreError::InvalidDelta(format!(
+                "generation {} does not follow {}",
+                manifest.generation_id,
+                next_id.saturating_sub(1)
+            )));
+        }
+        let root = manifest.root;
+        let root_hex = root.to_hex();
+        let marker = self.paths.pending_commit_file();
+        crate::chunkstore::write_synced(&marker, root_hex.as_bytes())?;
+
+        let chunkstore = ChunkStore::new(self.paths.generation_chunks_dir(&root_hex));
+        for c in &manifest.chunks {
+            if self.chunk_exists_anywhere(c.hash)? {
+                continue;
+            }
+            let data = chunks
+                .get(&c.hash.0)
+                .ok_or_else(|| StoreError::ChunkNotFound(c.hash.to_hex()))?;
+            chunkstore.put(c.hash, data)?;
+            self.index_chunk(c.hash, &root_hex);
+        }
+        manifest.write_to(self.paths.generation_manifest(&root_hex))?;
+        let (keys, tree) = self.rebuild_generation_tree(manifest, root)?;
+        write_leaf_cache(
+            &self.paths.generation_leaves(&root_hex),
+            &keys,
+            tree.leaves(),
+        )?;
+
         history.append(&GenerationState {
-            id: next_id,
+            id: manifest.generation_id,
             root,
-            timestamp,
+            timestamp: manifest.timestamp,
         })?;
-        staging.clear()?;
+        let refs = self.refs();
+        if refs.exists() {
+            refs.set(&refs.current()?, root)?;
+        }
+        std::fs::remove_file(marker)?;
+        Ok(())
+    }
 
-        Ok(root)
+    /// Collapse the history range `from..=to` into the single generation `to`.
+    ///
+    /// `to` is the squashed result as is: its root, manifest and content are
+    /// untouched. A changes-only generation (see
+    /// [`CommitOptions::changes_only`]) reads its unstaged files through its
+    /// parent chain, so a range holding a generation that `to` or any later
+    /// one links to is refused with `InvalidSquash`; commit a full snapshot
+    /// on top first. Every generation from `from` up to (not including) `to` leaves the root
+    /// history, later ids are renumbered to stay consecutive, and the dropped
+    /// generation dirs are removed. Chunks are stored once under the
+    /// generation that introduced them (§8.2), so a chunk a remaining
+    /// generation still references is moved into the first such
+    /// generation's dir; only unreferenced chunks are freed. A root that also
+    /// appears outside the range keeps its dir. With `dry_run` nothing is
+    /// changed.
+    pub fn squash(&mut self, from: Bytes32, to: Bytes32, dry_run: bool) -> Result<Squash> {
+        self.ensure_writable()?;
+        let mut history = RootHistory::open(self.paths.history_file())?;
+        let entries = history.entries()?;
+        let position = |root: Bytes32| {
+            entries
+                .iter()
+                .position(|g| g.root == root)
+                .ok_or_else(|| StoreError::GenerationNotFound(root.to_hex()))
+        };
+        let (start, end) = (position(from)?, position(to)?);
+        if start > end {
+            return Err(StoreError::InvalidSquash(format!(
+                "{} comes after {} in the history",
+                from.to_hex(),
+                to.to_hex()
+            )));
+        }
+
+        let positions: Vec<usize> = (start..end).collect();
+        let linked = self.linked_parents(&entries, &positions)?;
+        if let Some(g) = positions
+            .iter()
+            .find(|&&i| linked.contains_key(&entries[i].root.0))
+        {
+            let root = entries[*g].root;
+            return Err(StoreError::InvalidSquash(format!(
+                "{} is in the parent chain of {}, which reads unstaged files through it",
+                root.to_hex(),
+                linked[&root.0].to_hex()
+            )));
+        }
+        let removed = self.drop_generations(&mut history, entries, &positions, dry_run)?;
+        Ok(Squash {
+            kept: to,
+            dropped: removed.dropped,
+            chunks_freed: removed.chunks_freed,
+            bytes_freed: removed.bytes_freed,
+        })
+    }
+
+    /// Remove every history entry for generation `root` and free the chunks
+    /// no remaining generation references. The head is refused because it is
+    /// the store's current content, and so is a generation in the parent
+    /// chain of a kept changes-only generation (see
+    /// [`CommitOptions::changes_only`]), which reads its unstaged files
+    /// through it.
+    pub fn remove_root(&mut self, root: Bytes32) -> Result<Prune> {
+        self.ensure_writable()?;
+        let mut history = RootHistory::open(self.paths.history_file())?;
+        let entries = history.entries()?;
+        if entries.last().is_some_and(|head| head.root == root) {
+            return Err(StoreError::InvalidPrune(format!(
+                "{} is the current head",
+                root.to_hex()
+            )));
+        }
+        let positions: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.root == root)
+            .map(|(i, _)| i)
+            .collect();
+        if positions.is_empty() {
+            return Err(StoreError::GenerationNotFound(root.to_hex()));
+        }
+        self.drop_generations(&mut history, entries, &positions, false)
+    }
+
+    /// Bulk-remove old generations: those older than `before` (when given)
+    /// and not among the newest `keep_last` (when given). At least one
+    /// cutoff is required, and the head and every branch tip are always kept,
+    /// as is every generation a kept changes-only generation reads through its
+    /// parent chain (see [`CommitOptions::changes_only`]). With `dry_run`
+    /// nothing is changed.
+    pub fn prune(
+        &mut self,
+        before: Option<PruneBefore>,
+        keep_last: Option<usize>,
+        dry_run: bool,
+    ) -> Result<Prune> {
+        self.ensure_writable()?;
+        if before.is_none() && keep_last.is_none() {
+            return Err(StoreError::InvalidPrune(
+                "give a cutoff or a number of generations to keep".into(),
+            ));
+        }
+        let mut history = RootHistory::open(self.paths.history_file())?;
+        let entries = history.entries()?;
+        let protected = keep_last.unwrap_or(1).max(1);
+        let candidates = entries.len().saturating_sub(protected);
+        let tips: Vec<Bytes32> = self.refs().entries()?.into_values().collect();
+        let positions: Vec<usize> = (0..candidates)
+            .filter(|&i| !tips.contains(&entries[i].root))
+            .filter(|&i| match before {
+                Some(PruneBefore::Timestamp(t)) => entries[i].timestamp < t,
+                Some(PruneBefore::Generation(id)) => entries[i].id < id,
+                None => true,
+            })
+            .collect();
+        // The chains are walked from every kept generation, so a parent kept
+        // here needs nothing further kept for its own sake.
+        let linked = self.linked_parents(&entries, &positions)?;
+        let positions: Vec<usize> = positions
+            .into_iter()
+            .filter(|&i| !linked.contains_key(&entries[i].root.0))
+            .collect();
+        self.drop_generations(&mut history, entries, &positions, dry_run)
+    }
+
+    /// Every root that a generation of `entries` outside `positions` reaches
+    /// through its `parent` links (see [`CommitOptions::changes_only`]),
+    /// mapped to a generation that reaches it. Dropping one would leave that
+    /// generation's unstaged files unreadable.
+    fn linked_parents(
+        &self,
+        entries: &[GenerationState],
+        positions: &[usize],
+    ) -> Result<HashMap<[u8; 32], Bytes32>> {
+        let mut linked = HashMap::new();
+        for (i, g) in entries.iter().enumerate() {
+            if positions.contains(&i) {
+                continue;
+            }
+            let mut at = self.generation_manifest(g.root)?.parent;
+            while let Some(parent) = at {
+                // The rest of the chain was walked when `parent` was first seen.
+                if linked.insert(parent.0, g.root).is_some() {
+                    break;
+                }
+                at = self.generation_manifest(parent)?.parent;
+            }
+        }
+        Ok(linked)
+    }
+
+    /// Drop the history `entries` at `positions` (ascending) and renumber the
+    /// rest. A chunk is stored once under the generation that introduced it
+    /// (§8.2), so one a remaining generation still references moves into the
+    /// first such generation's dir; only unreferenced chunks are freed. A
+    /// root that also appears at a kept position keeps its dir. Dropping a
+    /// branch tip, or a generation a kept one links to through its parent
+    /// chain, is refused.
+    fn drop_generations(
+        &mut self,
+        history: &mut RootHistory,
+        entries: Vec<GenerationState>,
+        positions: &[usize],
+        dry_run: bool,
+    ) -> Result<Prune> {
+        let dropped: Vec<Bytes32> = positions.iter().map(|&i| entries[i].root).collect();
+        let kept: Vec<GenerationState> = entries
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !positions.contains(i))
+            .map(|(_, g)| g)
+            .collect();
+        // Chunk hash -> the first remaining generation that references it.
+        let mut referenced: HashMap<[u8; 32], Bytes32> = HashMap::new();
+        for (i, generation) in self.iter_generations()?.enumerate() {
+            if positions.contains(&i) {
+                continue;
+            }
+            let (g, manifest) = generation?;
+            for c in manifest.chunks {
+                referenced.entry(c.hash.0).or_insert(g.root);
+            }
+        }
+        let doomed: Vec<Bytes32> = {
+            let mut roots: Vec<Bytes32> = dropped
+                .iter()
+                .copied()
+                .filter(|r| !kept.iter().any(|g| g.root == *r))
+                .collect();
+            roots.dedup();
+            roots
+        };
+        for (name, tip) in self.refs().entries()? {
+            if doomed.contains(&tip) {
+                return Err(StoreError::InvalidBranch(format!(
+                    "{} is the tip of branch {name}",
+                    tip.to_hex()
+                )));
+            }
+        }
+        let linked = self.linked_parents(&kept, &[])?;
+        if let Some(root) = doomed.iter().find(|r| linked.contains_key(&r.0)) {
+            return Err(StoreError::InvalidPrune(format!(
+                "{} is in the parent chain of {}, which reads unstaged files through it",
+                root.to_hex(),
+                linked[&root.0].to_hex()
+            )));
+        }
+
+        let mut report = Prune {
+            dropped,
+            chunks_freed: 0,
+            bytes_freed: 0,
+        };
+        for root in &doomed {
+            let chunks_dir = self.paths.generation_chunks_dir(&root.to_hex());
+            let listing = match std::fs::read_dir(&chunks_dir) {
+                Ok(listing) => listing,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for entry in listing {
+                let entry = entry?;
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let user = Bytes32::from_hex(&name)
+                    .ok()
+                    .and_then(|h| referenced.get(&h.0));
+                if let Some(user) = user {
+                    let keep_dir = self.paths.generation_chunks_dir(&user.to_hex());
+                    if !dry_run && !keep_dir.join(&name).exists() {
+                        std::fs::create_dir_all(&keep_dir)?;
+                        std::fs::rename(&path, keep_dir.join(&name))?;
+                    }
+                } else {
+                    report.chunks_freed += 1;
+                    report.bytes_freed += entry.metadata()?.len();
+                }
+            }
+        }
+        if dry_run || report.dropped.is_empty() {
+            return Ok(report);
+        }
+
+        // Renumber, rewriting each moved generation's manifest id to match the
+        // first entry that still lists its root.
+        let mut renumbered: Vec<GenerationState> = Vec::with_capacity(kept.len());
+        for (id, g) in kept.into_iter().enumerate() {
+            let id = id as u64;
+            let first = !renumbered.iter().any(|r| r.root == g.root);
+            if first && g.id != id {
+                let path = self.paths.generation_manifest(&g.root.to_hex());
+                let mut manifest = GenerationManifest::read_from(&path)?;
+                manifest.generation_id = id;
+                manifest.write_to(&path)?;
+            }
+            renumbered.push(GenerationState { id, ..g });
+        }
+        history.rewrite(&renumbered)?;
+        for root in &doomed {
+            std::fs::remove_dir_all(self.paths.generation_dir(&root.to_hex()))?;
+        }
+        // Chunk files moved between generation dirs.
+        *self
+            .chunk_index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        Ok(report)
+    }
+
+    /// Sign generation `root` with `sk` over
+    /// [`digstore_crypto::root_signing_message`] (root and store id) and record
+    /// it in the generation's `signatures.json`, replacing any earlier
+    /// signature by the same key.
+    pub fn sign_root(
+        &self,
+        root: Bytes32,
+        sk: &digstore_crypto::bls::SecretKey,
+    ) -> Result<RootSignature> {
+        self.ensure_writable()?;
+        self.generation_manifest(root)?;
+        let signed = RootSignature {
+            public_key: sk.public_key().to_bytes(),
+            signature: digstore_crypto::sign_root(sk, &root, &self.config.store_id),
+        };
+        let path = self.paths.generation_signatures(&root.to_hex());
+        let mut sigs = read_signatures(&path)?;
+        sigs.retain(|s| s.public_key != signed.public_key);
+        sigs.push(signed.clone());
+        write_signatures(&path, &sigs)?;
+        Ok(signed)
+    }
+
+    /// Every signature recorded for generation `root`, valid or not.
+    pub fn root_signatures(&self, root: Bytes32) -> Result<Vec<RootSignature>> {
+        self.generation_manifest(root)?;
+        read_signatures(&self.paths.generation_signatures(&root.to_hex()))
+    }
+
+    /// Whether generation `root` carries a valid signature by `public_key`.
+    /// `false` when that key never signed it or its signature does not verify.
+    pub fn verify_root_signature(&self, root: Bytes32, public_key: &Bytes48) -> Result<bool> {
+        Ok(self.root_signatures(root)?.iter().any(|s| {
+            s.public_key == *public_key
+                && digstore_crypto::verify_root(
+                    public_key,
+                    &root,
+                    &self.config.store_id,
+                    &s.signature,
+                )
+        }))
+    }
+
+    /// Describe `resource_key` as committed in generation `root`: size,
+    /// retrieval key and chunk layout. Only the manifest is loaded.
+    pub fn stat_file(&self, root: Bytes32, resource_key: &str) -> Result<FileStat> {
+        let root = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(root)?;
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        let by_index: HashMap<u32, &ChunkRef> =
+            manifest.chunks.iter().map(|c| (c.index, c)).collect();
+        let chunks = rec
+            .chunk_indices
+            .iter()
+            .map(|i| {
+                by_index.get(i).map(|c| (*c).clone()).ok_or_else(|| {
+                    StoreError::Manifest(format!("key table references missing chunk index {i}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(FileStat {
+            resource_key: rec.resource_key.clone(),
+            root,
+            static_key: rec.static_key,
+            size: rec.total_size,
+            chunks,
+        })
+    }
+
+    /// Detect a committed resource's content type ([`sniff`]) from its path
+    /// and its first chunk, the only chunk decrypted. An empty resource is
+    /// text.
+    pub fn content_type(&self, root: Bytes32, resource_key: &str) -> Result<ContentType> {
+        let root = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(root)?;
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        let head = match record_chunk_hashes(&manifest, rec)?.first() {
+            Some(&hash) => {
+                let ct = self.resolve_chunk(hash)?;
+                digstore_crypto::decrypt_chunk(
+                    &self.record_key(manifest.key_epoch, &rec.resource_key),
+                    &ct,
+                )
+                .map_err(|_| StoreError::DecryptFailed(hash.to_hex()))?
+            }
+            None => Vec::new(),
+        };
+        Ok(sniff(&rec.resource_key, &head))
+    }
+
+    /// Read a resource's plaintext as committed in generation `root`.
+    ///
+    /// Chunks are sealed independently under the per-URN key, so decryption
+    /// alone cannot tell if they were reordered, dropped or swapped. The
+    /// committed D5 leaf is taken over the ORDERED chunk ciphertexts, so the
+    /// sequence is validated first: the resource's leaf is recomputed from
+    /// the manifest's chunk order and must fold to `root` along its path in
+    /// the committed tree (see `Store::verify_record`), so only this
+    /// resource's chunks are read. Only then are the chunks decrypted.
+    pub fn read_resource(&self, root: Bytes32, resource_key: &str) -> Result<Vec<u8>> {
+        let root = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(root)?;
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        self.verify_record(&manifest, root, rec)?;
+        self.decrypt_record(&manifest, rec)
+    }
+
+    /// Read plaintext bytes `range` of a resource at generation `root`,
+    /// decrypting only the chunks that overlap it. The range is half-open and
+    /// clamped to the resource. The generation is checked against `root` on
+    /// its first range read through this handle, as [`Store::read_resource`]
+    /// does on every read.
+    ///
+    /// Decrypted chunks stay in a read-ahead window (see
+    /// [`Store::set_read_ahead_capacity`]), so windows that share a chunk
+    /// decrypt it once. When a read starts where the previous one on the same
+    /// resource ended, the chunk after the range is decrypted too, so the
+    /// next window of a progressive (streaming) read starts from memory.
+    pub fn read_resource_range(
+        &self,
+        root: Bytes32,
+        resource_key: &str,
+        range: Range<u64>,
+    ) -> Result<Vec<u8>> {
+        let root = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(root)?;
+        if !self.read_ahead().verified.contains(&root.0) {
+            self.rebuild_generation_tree(&manifest, root)?;
+            self.read_ahead().verified.insert(root.0);
+        }
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        let by_index: HashMap<u32, &ChunkRef> =
+            manifest.chunks.iter().map(|c| (c.index, c)).collect();
+        let end = range.end.min(rec.total_size);
+        let start = range.start.min(end);
+        let sequential = matches!(
+            &self.read_ahead().last,
+            Some((r, k, e)) if *r == root && k == resource_key && *e == start
+        );
+        let aes_key = self.record_key(manifest.key_epoch, &rec.resource_key);
+
+        let mut out = Vec::with_capacity((end - start) as usize);
+        let mut lo = 0u64;
+        for i in &rec.chunk_indices {
+            let chunk = by_index.get(i).ok_or_else(|| {
+                StoreError::Manifest(format!("key table references missing chunk index {i}"))
+            })?;
+            let hi = lo + chunk.size.saturating_sub(SEAL_OVERHEAD);
+            if lo >= end && hi > lo {
+                if sequential {
+                    self.decrypted_chunk(chunk.hash, &aes_key)?;
+                }
+                break;
+            }
+            if hi > start {
+                let pt = self.decrypted_chunk(chunk.hash, &aes_key)?;
+                if pt.len() as u64 != hi - lo {
+                    return Err(StoreError::Manifest(format!(
+                        "chunk {} decrypts to {} bytes, manifest implies {}",
+                        chunk.hash.to_hex(),
+                        pt.len(),
+                        hi - lo
+                    )));
+                }
+                out.extend_from_slice(
+                    &pt[(start.max(lo) - lo) as usize..(end.min(hi) - lo) as usize],
+                );
+            }
+            lo = hi;
+        }
+        self.read_ahead().last = Some((root, resource_key.to_string(), end));
+        Ok(out)
+    }
+
+    /// Chunk `hash` decrypted under `aes_key`, from the read-ahead window when
+    /// it is there.
+    fn decrypted_chunk(&self, hash: Bytes32, aes_key: &[u8; 32]) -> Result<Vec<u8>> {
+        if let Some(pt) = self.read_ahead().plaintext.get(hash) {
+            return Ok(pt);
+        }
+        let ct = self.resolve_chunk(hash)?;
+        let pt = digstore_crypto::decrypt_chunk(aes_key, &ct)
+            .map_err(|_| StoreError::DecryptFailed(hash.to_hex()))?;
+        self.read_ahead().plaintext.insert(hash, pt.clone());
+        Ok(pt)
+    }
+
+    /// Write every chunk generation `root` references into `backend`, so
+    /// [`Store::read_resource_from`] can serve the generation from it (for a
+    /// [`MemoryBackend`](crate::MemoryBackend), without the chunk files).
+    /// Returns how many chunks were new to `backend`.
+    pub fn copy_chunks_to(&self, root: Bytes32, backend: &dyn ChunkBackend) -> Result<usize> {
+        let manifest = self.generation_manifest(root)?;
+        let mut written = 0;
+        let mut seen = HashSet::new();
+        for chunk in &manifest.chunks {
+            if seen.insert(chunk.hash)
+                && backend.write(chunk.hash, &self.resolve_chunk(chunk.hash)?)?
+            {
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Read a resource's plaintext at generation `root`, fetching its chunk
+    /// bodies from `backend` instead of the local generation dirs. Only the
+    /// manifest is read locally, so a store whose chunks live on a remote
+    /// server can serve a single resource without downloading the rest.
+    ///
+    /// Unlike [`Store::read_resource`] this cannot rebuild the whole tree
+    /// (that would fetch every chunk); it trusts the local manifest and checks
+    /// each fetched body against the content hash the manifest records for it.
+    pub fn read_resource_from(
+        &self,
+        backend: &dyn ChunkBackend,
+        root: Bytes32,
+        resource_key: &str,
+    ) -> Result<Vec<u8>> {
+        let root = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(root)?;
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        let aes_key = self.record_key(manifest.key_epoch, &rec.resource_key);
+        let mut out = Vec::with_capacity(rec.total_size as usize);
+        for hash in record_chunk_hashes(&manifest, rec)? {
+            let body = backend.read(hash)?;
+            let fetched = self.config.hash_algorithm.digest(&body);
+            if fetched != hash {
+                return Err(StoreError::Backend(format!(
+                    "chunk {} arrived with content hash {}",
+                    hash.to_hex(),
+                    fetched.to_hex()
+                )));
+            }
+            let pt = digstore_crypto::decrypt_chunk(&aes_key, &body)
+                .map_err(|_| StoreError::DecryptFailed(hash.to_hex()))?;
+            out.extend_from_slice(&pt);
+        }
+        Ok(out)
+    }
+
+    /// Restore many resources of generation `root` into `dest`, each written at
+    /// its resource key, reading unstaged files of a changes-only generation
+    /// through its parent chain. The tree of each generation serving the batch
+    /// is rebuilt and checked against its root ONCE (see
+    /// [`Store::read_resource`] for why the check is needed), then each
+    /// resource is decrypted and written. Keys that would land outside `dest`
+    /// are refused before anything is written.
+    pub fn get_files(
+        &self,
+        resource_keys: &[String],
+        root: Bytes32,
+        dest: &Path,
+    ) -> Result<BatchRead> {
+        let layers = self.resolved_layers(root)?;
+        let layer_of: HashMap<&str, usize> = layers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, l)| l.records().map(move |r| (r.resource_key.as_str(), i)))
+            .collect();
+        let mut targets = Vec::with_capacity(resource_keys.len());
+        let mut used = BTreeSet::new();
+        for key in resource_keys {
+            // A key no layer serves is reported missing at `root` itself.
+            let at = layer_of.get(key.as_str()).copied().unwrap_or(0);
+            let rec = self.find_record(&layers[at].manifest, root, key)?;
+            let path = resource_dest(dest, key)
+                .ok_or_else(|| StoreError::UnsafeResourceKey(key.clone()))?;
+            used.insert(at);
+            targets.push((&layers[at].manifest, rec, path));
+        }
+        for at in used {
+            self.rebuild_generation_tree(&layers[at].manifest, layers[at].root)?;
+        }
+
+        let mut progress = Progress::new(
+            self.progress.as_deref(),
+            targets.len() as u64,
+            targets.iter().map(|(_, rec, _)| rec.total_size).sum(),
+        );
+        let mut report = BatchRead::default();
+        for (manifest, rec, path) in targets {
+            let plaintext = self.decrypt_record(manifest, rec)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &plaintext)?;
+            report.files += 1;
+            report.bytes += plaintext.len() as u64;
+            progress.file_done(plaintext.len() as u64);
+        }
+        Ok(report)
+    }
+
+    /// Decrypt one key-table record's chunks, in manifest order, under the
+    /// salt of the manifest's key epoch. Callers validate the chunk order against the root first.
+    fn decrypt_record(
+        &self,
+        manifest: &GenerationManifest,
+        rec: &KeyTableRecord,
+    ) -> Result<Vec<u8>> {
+        let aes_key = self.record_key(manifest.key_epoch, &rec.resource_key);
+        // Locate every chunk up front (cache or file), then read + decrypt them
+        // across `read_parallelism` threads; output order is the manifest order.
+        let mut sources: Vec<(Bytes32, Option<Vec<u8>>, Option<PathBuf>)> =
+            Vec::with_capacity(rec.chunk_indices.len());
+        for hash in record_chunk_hashes(manifest, rec)? {
+            let cached = self.cache().get(hash);
+            let path = match cached {
+                Some(_) => None,
+                None => Some(
+                    self.chunk_location(hash, true)?
+                        .ok_or_else(|| StoreError::ChunkNotFound(hash.to_hex()))?,
+                ),
+            };
+            sources.push((hash, cached, path));
+        }
+        let mmap = self.mmap_reads;
+        let opened = parallel_map(&sources, self.read_parallelism, |(hash, cached, path)| {
+            let fresh = match path {
+                Some(p) => Some(MappedChunk::open(p, mmap)?),
+                None => None,
+            };
+            let ct: &[u8] = match (cached, &fresh) {
+                (Some(body), _) => body,
+                (None, Some(chunk)) => chunk,
+                (None, None) => unreachable!("every source is cached or located"),
+            };
+            let pt = digstore_crypto::decrypt_chunk(&aes_key, ct)
+                .map_err(|_| StoreError::DecryptFailed(hash.to_hex()))?;
+            Ok::<_, StoreError>((fresh, pt))
+        });
+        let mut out = Vec::with_capacity(rec.total_size as usize);
+        for ((hash, _, _), result) in sources.iter().zip(opened) {
+            let (fresh, pt) = result?;
+            if let Some(chunk) = fresh.filter(|c| !c.is_mapped()) {
+                self.cache().insert(*hash, chunk.into_vec());
+            }
+            out.extend_from_slice(&pt);
+        }
+        Ok(out)
+    }
+
+    /// The per-URN AES key a resource's chunks are sealed under in a
+    /// generation of `key_epoch`.
+    fn record_key(&self, key_epoch: u32, resource_key: &str) -> [u8; 32] {
+        let urn = Urn {
+            chain: CHAIN.to_string(),
+            store_id: self.config.store_id,
+            root_hash: None,
+            resource_key: Some(resource_key.to_string()),
+        };
+        UrnSealer::for_visibility(&self.config.epoch_visibility(key_epoch)).key_for(&urn)
+    }
+
+    /// Search the text resources of generation `root` for `pattern`, line by
+    /// line, calling `on_match` for each matching line as it is found. A
+    /// resource is decrypted one chunk at a time, so memory stays bounded by
+    /// the chunk size (plus the longest line) however large the file.
+    /// Resources with a NUL byte in their first 8000 bytes are treated as
+    /// binary and skipped unless `text` is set. `on_match` returns whether to
+    /// keep searching the same resource (`false` moves on to the next, e.g.
+    /// when only file names are wanted). A changes-only generation is searched
+    /// with the files it reads through its parent chain, and each serving
+    /// generation's tree is checked once up front, as in [`Store::get_files`].
+    pub fn grep(
+        &self,
+        pattern: &Regex,
+        root: Bytes32,
+        text: bool,
+        mut on_match: impl FnMut(GrepMatch) -> bool,
+    ) -> Result<()> {
+        let layers = self.resolved_layers(root)?;
+        self.check_layers(&layers)?;
+
+        for (at, rec) in served_records(&layers) {
+            let manifest = &layers[at].manifest;
+            let aes_key = self.record_key(manifest.key_epoch, &rec.resource_key);
+            let mut line: Vec<u8> = Vec::new();
+            let mut line_number = 0;
+            let mut searching = true;
+            let mut check = |line: &[u8], line_number: usize| {
+                let line = String::from_utf8_lossy(line);
+                let line = line.strip_suffix('\r').unwrap_or(&line);
+                if pattern.is_match(line) {
+                    return on_match(GrepMatch {
+                        resource_key: rec.resource_key.clone(),
+                        line_number,
+                        line: line.to_string(),
+                    });
+                }
+                true
+            };
+            for (n, hash) in record_chunk_hashes(manifest, rec)?.into_iter().enumerate() {
+                let pt = digstore_crypto::decrypt_chunk(&aes_key, &self.resolve_chunk(hash)?)
+                    .map_err(|_| StoreError::DecryptFailed(hash.to_hex()))?;
+                if n == 0 && !text && pt[..pt.len().min(8000)].contains(&0) {
+                    searching = false;
+                    break;
+                }
+                let mut rest = pt.as_slice();
+                while let Some(end) = rest.iter().position(|&b| b == b'\n') {
+                    line.extend_from_slice(&rest[..end]);
+                    rest = &rest[end + 1..];
+                    line_number += 1;
+                    if !check(&line, line_number) {
+                        searching = false;
+                        break;
+                    }
+                    line.clear();
+                }
+                if !searching {
+                    break;
+                }
+                line.extend_from_slice(rest);
+            }
+            if searching && !line.is_empty() {
+                check(&line, line_number + 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rotate the store's secret salt (the private-store key input, §11.4).
+    ///
+    /// Every resource the head serves, including any it reads through a
+    /// changes-only parent chain, is decrypted under the key of the generation
+    /// holding it and re-sealed under keys derived with `new_salt`. The result
+    /// is a self-contained head generation (no parent) whose manifest carries
+    /// the next `key_epoch`. Earlier generations are immutable (their roots
+    /// are committed over their ciphertext) and stay sealed under the salt of
+    /// their own epoch, which the config's salt keyring keeps.
+    ///
+    /// The keyring and `Visibility::Private(new_salt)` are saved BEFORE the
+    /// generation is written: an interrupted rotation leaves an epoch no
+    /// generation uses yet, never a generation no saved salt opens. If the
+    /// write fails the previous config is restored. Refuses to run over
+    /// uncommitted staged changes.
+    ///
+    /// With `dry_run` nothing is decrypted or written; the report only counts
+    /// what would be re-encrypted.
+    pub fn rotate_salt(&mut self, new_salt: SecretSalt, dry_run: bool) -> Result<KeyRotation> {
+        self.ensure_writable()?;
+        let mut staging = StagingArea::open(self.paths.staging_file())?;
+        if !staging.is_empty()? {
+            return Err(StoreError::DirtyStaging);
+        }
+        let layers = match self.current_root()? {
+            Some(root) => self.resolved_layers(root)?,
+            None => Vec::new(),
+        };
+        let records = served_records(&layers);
+        let mut chunks = HashSet::new();
+        for (at, rec) in &records {
+            chunks.extend(record_chunk_hashes(&layers[*at].manifest, rec)?);
+        }
+        let old_epoch = self.current_key_epoch()?;
+        let key_epoch = old_epoch + 1;
+        let mut report = KeyRotation {
+            resources: records.len(),
+            chunks: chunks.len(),
+            key_epoch,
+            root: None,
+        };
+        if dry_run {
+            return Ok(report);
+        }
+
+        // Decrypt everything up front so a bad chunk aborts before any write.
+        let plaintexts = records
+            .iter()
+            .map(|(at, rec)| {
+                let content = self.decrypt_record(&layers[*at].manifest, rec)?;
+                Ok((rec.resource_key.clone(), content))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let previous = self.config.clone();
+        let old_salt = match self.config.visibility {
+            Visibility::Private(salt) => Some(salt),
+            Visibility::Public => None,
+        };
+        for epoch in 0..=old_epoch {
+            self.config.salt_keyring.entry(epoch).or_insert(old_salt);
+        }
+        self.config.salt_keyring.insert(key_epoch, Some(new_salt));
+        self.config.visibility = Visibility::Private(new_salt);
+        if let Err(e) = save_config(self.paths.config_file(), &self.config) {
+            self.config = previous;
+            return Err(e);
+        }
+
+        if !plaintexts.is_empty() {
+            for (key, content) in &plaintexts {
+                staging.append(key, content)?;
+            }
+            match self.commit_with_epoch(key_epoch, false, None) {
+                Ok(root) => report.root = Some(root),
+                Err(e) => {
+                    self.config = previous;
+                    save_config(self.paths.config_file(), &self.config)?;
+                    staging.clear()?;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(report)
     }
 
     /// Resolve a chunk's bytes by content hash across ALL generation chunk dirs.
@@ -282,18 +2440,29 @@ impl<C: Clock> Store<C> {
     /// chunk introduced by an earlier generation lives only under that
     /// generation's `chunks/` dir; later generations referencing it have a
     /// sparse `chunks/`. Returns `ChunkNotFound` if no generation holds it.
+    /// Repeated lookups are served from the in-process chunk cache.
     pub fn resolve_chunk(&self, hash: Bytes32) -> Result<Vec<u8>> {
-        let gens = self.paths.generations_dir();
-        if gens.exists() {
-            let name = hash.to_hex();
-            for entry in std::fs::read_dir(&gens)? {
-                let candidate = entry?.path().join("chunks").join(&name);
-                if candidate.exists() {
-                    return Ok(std::fs::read(&candidate)?);
-                }
+        if let Some(body) = self.cache().get(hash) {
+            return Ok(body);
+        }
+        match self.chunk_location(hash, true)? {
+            Some(path) => {
+                let body = std::fs::read(&path)?;
+                self.cache().insert(hash, body.clone());
+                Ok(body)
             }
+            None => Err(StoreError::ChunkNotFound(hash.to_hex())),
         }
-        Err(StoreError::ChunkNotFound(hash.to_hex()))
+    }
+
+    /// Open a chunk by content hash without copying it: a memory map over the
+    /// chunk file (or an owned read when mmap reads are off). Unlike
+    /// [`Store::resolve_chunk`] this never touches the chunk cache.
+    pub fn map_chunk(&self, hash: Bytes32) -> Result<MappedChunk> {
+        let path = self
+            .chunk_location(hash, true)?
+            .ok_or_else(|| StoreError::ChunkNotFound(hash.to_hex()))?;
+        MappedChunk::open(&path, self.mmap_reads)
     }
 
     /// Generations in chronological order (§20.4 `log`). Alias of root history.
@@ -301,6 +2470,377 @@ impl<C: Clock> Store<C> {
         self.root_history()
     }
 
+    /// Every file, in every generation of the history, that `hash` identifies:
+    /// as one of its chunks, as its D5 leaf, or as its retrieval key. Matches
+    /// come oldest generation first, then by resource key; a changes-only
+    /// generation matches the files it reads through its parent chain too.
+    /// File leaves come from each generation's leaf cache, or are recomputed
+    /// from the chunks that are still readable, so a damaged store can still
+    /// be searched.
+    pub fn find_files_by_hash(&self, hash: Bytes32) -> Result<Vec<HashMatch>> {
+        let mut matches = Vec::new();
+        // Leaves by static key, per generation root: layers recur down chains.
+        let mut leaf_maps: HashMap<[u8; 32], HashMap<[u8; 32], Bytes32>> = HashMap::new();
+        for state in self.root_history()? {
+            let layers = match self.resolved_layers(state.root) {
+                Ok(layers) => layers,
+                Err(StoreError::GenerationNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let mut found = Vec::new();
+            for (at, rec) in served_records(&layers) {
+                let layer = &layers[at];
+                let leaves = leaf_maps
+                    .entry(layer.root.0)
+                    .or_insert_with(|| self.leaf_map(&layer.manifest, layer.root));
+                let kind = if rec.static_key == hash {
+                    HashKind::RetrievalKey
+                } else if leaves.get(&rec.static_key.0) == Some(&hash) {
+                    HashKind::File
+                } else if record_chunk_hashes(&layer.manifest, rec)?.contains(&hash) {
+                    HashKind::Chunk
+                } else {
+                    continue;
+                };
+                found.push(HashMatch {
+                    root: state.root,
+                    resource_key: rec.resource_key.clone(),
+                    kind,
+                });
+            }
+            matches.extend(found);
+        }
+        Ok(matches)
+    }
+
+    /// Generation `root`'s D5 leaves by static key, from its leaf cache or
+    /// recomputed from whichever chunks are still readable.
+    fn leaf_map(&self, manifest: &GenerationManifest, root: Bytes32) -> HashMap<[u8; 32], Bytes32> {
+        match self.cached_leaf_tree(manifest, root) {
+            Some((keys, tree)) => keys
+                .into_iter()
+                .zip(tree.leaves().iter().copied())
+                .collect(),
+            None => manifest
+                .key_table
+                .iter()
+                .filter_map(|rec| {
+                    let leaf = self.record_leaf(manifest, rec).ok()?;
+                    Some((rec.static_key.0, leaf))
+                })
+                .collect(),
+        }
+    }
+
+    /// The versions of file `resource_key` through the root history, oldest
+    /// first: each generation where its content hash differs from the
+    /// previous generation's, including where it first appears and where it
+    /// reappears after being removed. A changes-only generation holds the
+    /// file its parent chain serves, so reading it through unchanged is not a
+    /// new version. Empty when no generation holds it. Generations whose
+    /// manifest (or a parent's) is missing are skipped.
+    pub fn get_file_versions(&self, resource_key: &str) -> Result<Vec<FileVersion>> {
+        let mut versions = Vec::new();
+        let mut previous: Option<Bytes32> = None;
+        for state in self.root_history()? {
+            let (holding, manifest) = match self.holding_manifest(state.root, resource_key) {
+                Ok(Some(found)) => found,
+                Ok(None) => {
+                    previous = None;
+                    continue;
+                }
+                Err(StoreError::GenerationNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let rec = find_record(&manifest, holding, resource_key)?;
+            let cached = self
+                .cached_leaf_tree(&manifest, holding)
+                .and_then(|(keys, tree)| {
+                    let pos = keys.binary_search(&rec.static_key.0).ok()?;
+                    tree.leaves().get(pos).copied()
+                });
+            let hash = match cached {
+                Some(leaf) => leaf,
+                None => self.record_leaf(&manifest, rec)?,
+            };
+            if previous != Some(hash) {
+                versions.push(FileVersion {
+                    root: state.root,
+                    generation_id: state.id,
+                    hash,
+                    timestamp: state.timestamp,
+                });
+            }
+            previous = Some(hash);
+        }
+        Ok(versions)
+    }
+
+    /// Each line of the newest version of text file `resource_key`, with the
+    /// version (see [`Store::get_file_versions`]) that introduced it: every
+    /// consecutive pair of versions is diffed line by line, and a line kept
+    /// from the previous version keeps that version's attribution. Empty when
+    /// no generation holds the file. Fails with `BinaryFile` when the newest
+    /// version is binary; an older binary version counts as having no lines.
+    pub fn blame(&self, resource_key: &str) -> Result<Vec<BlameLine>> {
+        let versions = self.read_file_versions(resource_key)?;
+        let is_binary =
+            |content: &[u8]| sniff(resource_key, &content[..content.len().min(8192)]).is_binary;
+        if versions
+            .last()
+            .is_some_and(|(_, content)| is_binary(content))
+        {
+            return Err(StoreError::BinaryFile(resource_key.to_string()));
+        }
+
+        // The text of the version so far, and the version index behind each line.
+        let mut text = String::new();
+        let mut owners: Vec<usize> = Vec::new();
+        for (at, (_, content)) in versions.iter().enumerate() {
+            let next = if is_binary(content) {
+                String::new()
+            } else {
+                String::from_utf8_lossy(content).into_owned()
+            };
+            let old: Vec<&str> = text.lines().collect();
+            let new: Vec<&str> = next.lines().collect();
+            owners = matched_lines(&old, &new)
+                .into_iter()
+                .map(|kept| kept.map_or(at, |i| owners[i]))
+                .collect();
+            text = next;
+        }
+        Ok(text
+            .lines()
+            .zip(owners)
+            .enumerate()
+            .map(|(i, (line, owner))| {
+                let v = &versions[owner].0;
+                BlameLine {
+                    line: i + 1,
+                    text: line.to_string(),
+                    root: v.root,
+                    generation_id: v.generation_id,
+                    timestamp: v.timestamp,
+                }
+            })
+            .collect())
+    }
+
+    /// Roots of the generations that serve `resource_key`, from their own
+    /// key table or (changes-only generations) through their parent chain,
+    /// oldest first, whether or not its content changed between them.
+    /// Generations whose manifest (or a parent's) is missing are skipped.
+    pub fn generations_containing(&self, resource_key: &str) -> Result<Vec<Bytes32>> {
+        let mut roots = Vec::new();
+        for state in self.root_history()? {
+            match self.holding_manifest(state.root, resource_key) {
+                Ok(Some(_)) => roots.push(state.root),
+                Ok(None) | Err(StoreError::GenerationNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(roots)
+    }
+
+    /// Every distinct version of file `resource_key` (see
+    /// [`Store::get_file_versions`]) with its content, read and verified from
+    /// the generation that introduced it. Oldest first; empty when no
+    /// generation holds the file.
+    pub fn read_file_versions(&self, resource_key: &str) -> Result<Vec<(FileVersion, Vec<u8>)>> {
+        self.get_file_versions(resource_key)?
+            .into_iter()
+            .map(|v| {
+                let content = self.read_resource(v.root, resource_key)?;
+                Ok((v, content))
+            })
+            .collect()
+    }
+
+    /// Logical and stored size of every generation in the history, and the
+    /// deduplicated total on disk. Deduplication is attributed by walking the
+    /// history oldest first: a chunk's bytes belong to the generation that
+    /// introduced it, and every later reference counts as a saving — within
+    /// a generation or across generations. Read from the manifests alone;
+    /// generations whose directory is gone (e.g. pruned) are skipped.
+    pub fn size_report(&self) -> Result<SizeReport> {
+        let mut report = SizeReport::default();
+        let mut seen: HashSet<[u8; 32]> = HashSet::new();
+        for state in self.root_history()? {
+            let manifest = match self.generation_manifest(state.root) {
+                Ok(m) => m,
+                Err(StoreError::GenerationNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let mut unique: HashSet<[u8; 32]> = HashSet::new();
+            let (mut intra, mut inter) = (0u64, 0u64);
+            for c in &manifest.chunks {
+                if !unique.insert(c.hash.0) {
+                    intra += c.size;
+                } else if seen.insert(c.hash.0) {
+                    report.physical_bytes += c.size;
+                } else {
+                    inter += c.size;
+                }
+            }
+            report.generations.push(GenerationSize {
+                root: state.root,
+                generation_id: state.id,
+                logical_bytes: manifest.key_table.iter().map(|r| r.total_size).sum(),
+                stored_bytes: manifest.chunks.iter().map(|c| c.size).sum(),
+                chunks: manifest.chunks.len(),
+                unique_chunks: unique.len(),
+                intra_dedup_bytes: intra,
+                inter_dedup_bytes: inter,
+            });
+        }
+        report.unique_chunks = seen.len();
+        Ok(report)
+    }
+
+    /// Distribution of sealed chunk sizes: over the distinct chunks of the
+    /// generation `root`, or with `None` over every distinct chunk of the
+    /// generations still on disk. A chunk referenced several times counts
+    /// once, so this describes what the chunker produced rather than how
+    /// often it was reused. Read from the manifests alone.
+    pub fn chunk_size_distribution(&self, root: Option<Bytes32>) -> Result<ChunkSizeDistribution> {
+        let manifests = match root {
+            Some(root) => vec![self.generation_manifest(root)?],
+            None => {
+                let mut manifests = Vec::new();
+                for state in self.root_history()? {
+                    match self.generation_manifest(state.root) {
+                        Ok(m) => manifests.push(m),
+                        Err(StoreError::GenerationNotFound(_)) => continue,
+                        Err(e) => return Err(e),
+                    }
+                }
+                manifests
+            }
+        };
+        let mut seen: HashSet<[u8; 32]> = HashSet::new();
+        let sizes = manifests
+            .iter()
+            .flat_map(|m| &m.chunks)
+            .filter(|c| seen.insert(c.hash.0))
+            .map(|c| c.size)
+            .collect();
+        Ok(ChunkSizeDistribution::from_sizes(sizes))
+    }
+
+    /// [`find_record`], but a miss also names the newest other generation in
+    /// the root history that holds `resource_key`, so the error points at a
+    /// version to read instead of dead-ending. The search is best effort: a
+    /// failure while looking leaves `found_in` empty.
+    fn find_record<'m>(
+        &self,
+        manifest: &'m GenerationManifest,
+        root: Bytes32,
+        resource_key: &str,
+    ) -> Result<&'m KeyTableRecord> {
+        find_record(manifest, root, resource_key).map_err(|e| match e {
+            StoreError::ResourceNotFound { key, root: hex, .. } => StoreError::ResourceNotFound {
+                found_in: self.newest_root_with(&key, root).map(|r| r.to_hex()),
+                key,
+                root: hex,
+            },
+            e => e,
+        })
+    }
+
+    /// The newest generation other than `except` whose manifest lists
+    /// `resource_key`. Generations whose manifest is missing are skipped.
+    fn newest_root_with(&self, resource_key: &str, except: Bytes32) -> Option<Bytes32> {
+        let history = self.root_history().ok()?;
+        history
+            .iter()
+            .rev()
+            .filter(|state| state.root != except)
+            .find(|state| {
+                self.generation_manifest(state.root)
+                    .is_ok_and(|m| m.key_table.iter().any(|r| r.resource_key == resource_key))
+            })
+            .map(|state| state.root)
+    }
+
+    /// The generation that serves `resource_key` at `root`: `root` itself when
+    /// its key table lists the key, else the nearest generation up its parent
+    /// chain (see [`CommitOptions::changes_only`]) that does. `root` when none
+    /// does, so a lookup there reports the key missing at the root asked for.
+    pub fn generation_holding(&self, root: Bytes32, resource_key: &str) -> Result<Bytes32> {
+        let mut at = root;
+        let mut seen = HashSet::new();
+        while seen.insert(at.0) {
+            let manifest = self.generation_manifest(at)?;
+            if manifest
+                .key_table
+                .iter()
+                .any(|r| r.resource_key == resource_key)
+            {
+                return Ok(at);
+            }
+            match manifest.parent {
+                Some(parent) => at = parent,
+                None => break,
+            }
+        }
+        Ok(root)
+    }
+
+    /// The generation serving `resource_key` at `root` (see
+    /// [`Store::generation_holding`]) and its manifest, or `None` when no
+    /// generation of the chain holds the key.
+    fn holding_manifest(
+        &self,
+        root: Bytes32,
+        resource_key: &str,
+    ) -> Result<Option<(Bytes32, GenerationManifest)>> {
+        let holding = self.generation_holding(root, resource_key)?;
+        let manifest = self.generation_manifest(holding)?;
+        Ok(manifest
+            .key_table
+            .iter()
+            .any(|r| r.resource_key == resource_key)
+            .then_some((holding, manifest)))
+    }
+
+    /// The merged key view of generation `root`: `root` first, then each
+    /// generation up its parent chain (see [`CommitOptions::changes_only`]),
+    /// each with the records no newer layer already serves. A self-contained
+    /// generation is one layer serving its whole key table.
+    fn resolved_layers(&self, root: Bytes32) -> Result<Vec<ResolvedLayer>> {
+        let mut layers = Vec::new();
+        let mut keys: HashSet<String> = HashSet::new();
+        let mut roots = HashSet::new();
+        let mut at = Some(root);
+        while let Some(root) = at.filter(|r| roots.insert(r.0)) {
+            let manifest = self.generation_manifest(root)?;
+            let served = manifest
+                .key_table
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| keys.insert(r.resource_key.clone()))
+                .map(|(i, _)| i)
+                .collect();
+            at = manifest.parent;
+            layers.push(ResolvedLayer {
+                root,
+                manifest,
+                served,
+            });
+        }
+        Ok(layers)
+    }
+
+    /// Rebuild the tree of every layer that serves a record and check it
+    /// against that layer's root.
+    fn check_layers(&self, layers: &[ResolvedLayer]) -> Result<()> {
+        for layer in layers.iter().filter(|l| !l.served.is_empty()) {
+            self.rebuild_generation_tree(&layer.manifest, layer.root)?;
+        }
+        Ok(())
+    }
+
     /// Load a generation manifest by its root hash.
     pub fn generation_manifest(&self, root: Bytes32) -> Result<GenerationManifest> {
         let path = self.paths.generation_manifest(&root.to_hex());
@@ -310,6 +2850,353 @@ impl<C: Clock> Store<C> {
         GenerationManifest::read_from(path)
     }
 
+    /// Build a merkle inclusion proof for `resource_key` against the generation
+    /// `root` — ANY generation in the history, not just the head. The D5
+    /// per-resource tree is rebuilt from that generation's persisted manifest and
+    /// ciphertext chunks (resolved globally), so a resource that was later
+    /// changed or dropped can still be proven as it stood at `root`. The proof
+    /// verifies against `root` and no other generation's root; in a store whose
+    /// [`StoreConfig::hash_algorithm`] is not SHA-256, check it with
+    /// [`MerkleProof::verify_with`].
+    pub fn prove_resource(&self, root: Bytes32, resource_key: &str) -> Result<MerkleProof> {
+        let manifest = self.generation_manifest(root)?;
+        let target = self
+            .find_record(&manifest, root, resource_key)?
+            .static_key
+            .0;
+        let (keys, tree) = self.rebuild_generation_tree(&manifest, root)?;
+        let index = keys
+            .iter()
+            .position(|k| *k == target)
+            .expect("target key is in the key table");
+        Ok(tree.prove(index).expect("index is within the leaf count"))
+    }
+
+    /// Prove plaintext bytes `range` of `resource_key` at generation `root`
+    /// (any generation, as with [`Store::prove_resource`]). The range is
+    /// clamped to the resource; one that selects nothing is rejected. The
+    /// proof carries the resource's sealed chunks and its leaf's inclusion
+    /// proof; see [`ByteRangeProof::verify_with`].
+    pub fn prove_byte_range(
+        &self,
+        root: Bytes32,
+        resource_key: &str,
+        range: Range<u64>,
+    ) -> Result<ByteRangeProof> {
+        let manifest = self.generation_manifest(root)?;
+        let rec = self.find_record(&manifest, root, resource_key)?;
+        let end = range.end.min(rec.total_size);
+        if range.start >= end {
+            return Err(StoreError::InvalidRange(format!(
+                "{}..{} selects nothing of {resource_key} ({} bytes)",
+                range.start, range.end, rec.total_size
+            )));
+        }
+        let target = rec.static_key.0;
+        let mut chunks = Vec::with_capacity(rec.chunk_indices.len());
+        for hash in record_chunk_hashes(&manifest, rec)? {
+            chunks.push(self.resolve_chunk(hash)?);
+        }
+        let mut offset = 0u64;
+        let first_chunk = chunks
+            .iter()
+            .position(|c| {
+                offset += (c.len() as u64).saturating_sub(SEAL_OVERHEAD);
+                offset > range.start
+            })
+            .ok_or_else(|| {
+                StoreError::Manifest(format!("{resource_key}: chunks shorter than total_size"))
+            })?;
+        let (keys, tree) = self.rebuild_generation_tree(&manifest, root)?;
+        let index = keys
+            .iter()
+            .position(|k| *k == target)
+            .expect("target key is in the key table");
+        Ok(ByteRangeProof {
+            resource_key: resource_key.to_string(),
+            range: range.start..end,
+            first_chunk,
+            chunks,
+            proof: tree.prove(index).expect("index is within the leaf count"),
+        })
+    }
+
+    /// Rebuild generation `root`'s D5 tree from its manifest and ciphertext
+    /// chunks. Returns the static keys in leaf order alongside the tree, or
+    /// `RootMismatch` when the rebuilt root is not `root`.
+    fn rebuild_generation_tree(
+        &self,
+        manifest: &GenerationManifest,
+        root: Bytes32,
+    ) -> Result<(Vec<[u8; 32]>, MerkleTree)> {
+        verify_leaves(
+            self.config.hash_algorithm,
+            manifest,
+            self.resource_leaves(manifest)?,
+            root,
+        )
+    }
+
+    /// Check one record's chunks, in manifest order, against generation
+    /// `root` without reading any other resource: its leaf is recomputed from
+    /// the stored ciphertext and swapped into the committed tree
+    /// (`leaves.bin`), which must still fold to `root`. Only the leaf's path
+    /// is rehashed. When the leaf cache is missing or does not match the
+    /// manifest, the whole tree is rebuilt from the chunks instead.
+    fn verify_record(
+        &self,
+        manifest: &GenerationManifest,
+        root: Bytes32,
+        rec: &KeyTableRecord,
+    ) -> Result<()> {
+        let Some((keys, mut tree)) = self.cached_leaf_tree(manifest, root) else {
+            return self.rebuild_generation_tree(manifest, root).map(drop);
+        };
+        let index = keys
+            .binary_search(&rec.static_key.0)
+            .expect("the leaf cache holds every key-table key");
+        tree.update_leaf(index, self.record_leaf(manifest, rec)?);
+        if tree.root() != root {
+            return Err(StoreError::RootMismatch {
+                root: root.to_hex(),
+                rebuilt: tree.root().to_hex(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The D5 leaf of every key-table record, in key-table order, computed
+    /// from the stored ciphertext chunks.
+    fn resource_leaves(&self, manifest: &GenerationManifest) -> Result<Vec<Bytes32>> {
+        manifest
+            .key_table
+            .iter()
+            .map(|rec| self.record_leaf(manifest, rec))
+            .collect()
+    }
+
+    /// One record's D5 leaf, computed from its stored ciphertext chunks.
+    fn record_leaf(&self, manifest: &GenerationManifest, rec: &KeyTableRecord) -> Result<Bytes32> {
+        let mut cts = Vec::with_capacity(rec.chunk_indices.len());
+        for hash in record_chunk_hashes(manifest, rec)? {
+            cts.push(self.resolve_chunk(hash)?);
+        }
+        let slices: Vec<&[u8]> = cts.iter().map(|b| b.as_slice()).collect();
+        Ok(digstore_core::resource_leaf_with(
+            self.config.hash_algorithm,
+            &concat_output(&slices),
+        ))
+    }
+
+    /// Prove and verify every resource of generation `root`, one
+    /// [`VerificationResult`] per resource, sorted by resource key. A
+    /// changes-only generation's files read through its parent chain are
+    /// proven into the root of the generation holding them.
+    ///
+    /// Each resource's leaf is recomputed from its stored chunks and checked
+    /// with an inclusion proof against its generation's root, through the same
+    /// [`MerkleTree::prove`] / [`MerkleProof::verify_with`] paths that serve
+    /// clients. Sibling hashes come from the tree recorded at commit
+    /// (`leaves.bin`) when it still folds to that root, so a damaged resource
+    /// fails alone instead of taking every proof down with it; without that
+    /// record the tree is rebuilt from the chunks as they are now.
+    pub fn verify_all_proofs(&self, root: Bytes32) -> Result<Vec<VerificationResult>> {
+        let mut results = Vec::new();
+        for layer in self.resolved_layers(root)? {
+            results.extend(self.verify_layer_proofs(&layer));
+        }
+        results.sort_by(|a, b| a.resource_key.cmp(&b.resource_key));
+        Ok(results)
+    }
+
+    /// [`Store::verify_all_proofs`] for the records `layer` serves.
+    fn verify_layer_proofs(&self, layer: &ResolvedLayer) -> Vec<VerificationResult> {
+        let (manifest, root) = (&layer.manifest, layer.root);
+        let current: HashMap<[u8; 32], Result<Bytes32>> = layer
+            .records()
+            .map(|rec| (rec.static_key.0, self.record_leaf(manifest, rec)))
+            .collect();
+        let (keys, tree) = match self.cached_leaf_tree(manifest, root) {
+            Some(committed) => committed,
+            None => {
+                let mut keyed: Vec<([u8; 32], Bytes32)> = manifest
+                    .key_table
+                    .iter()
+                    .map(|rec| {
+                        let leaf = match current.get(&rec.static_key.0) {
+                            Some(leaf) => leaf.as_ref().ok().copied(),
+                            // Shadowed by a newer layer, but still in the tree.
+                            None => self.record_leaf(manifest, rec).ok(),
+                        };
+                        (rec.static_key.0, leaf.unwrap_or(Bytes32([0; 32])))
+                    })
+                    .collect();
+                keyed.sort_by_key(|r| r.0);
+                let (keys, leaves): (Vec<[u8; 32]>, Vec<Bytes32>) = keyed.into_iter().unzip();
+                (
+                    keys,
+                    MerkleTree::from_leaves_with(self.config.hash_algorithm, leaves),
+                )
+            }
+        };
+
+        layer
+            .records()
+            .map(|rec| {
+                let index = keys
+                    .binary_search(&rec.static_key.0)
+                    .expect("every record's key is in the tree");
+                let error = match &current[&rec.static_key.0] {
+                    Err(e) => Some(e.to_string()),
+                    Ok(leaf) => {
+                        let mut proof = tree.prove(index).expect("index is within the leaf count");
+                        proof.leaf = *leaf;
+                        proof.root = root;
+                        (!proof.verify_with(self.config.hash_algorithm))
+                            .then(|| "content does not hash into the generation root".to_string())
+                    }
+                };
+                VerificationResult {
+                    resource_key: rec.resource_key.clone(),
+                    static_key: rec.static_key,
+                    verified: error.is_none(),
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// Export generation `root` as a canonical [`SnapshotManifest`]: every
+    /// file's path, size, retrieval key, merkle leaf and chunk hashes, sorted
+    /// by path, including the files a changes-only generation reads through
+    /// its parent chain. The leaves are recomputed from the stored chunks and
+    /// must rebuild the root of the generation holding them, so a successful
+    /// export is also a full verification.
+    pub fn export_manifest(&self, root: Bytes32) -> Result<SnapshotManifest> {
+        let layers = self.resolved_layers(root)?;
+        let mut files: Vec<SnapshotFile> = Vec::new();
+        for layer in layers.iter().filter(|l| !l.served.is_empty()) {
+            let manifest = &layer.manifest;
+            let leaves = self.resource_leaves(manifest)?;
+            verify_leaves(
+                self.config.hash_algorithm,
+                manifest,
+                leaves.clone(),
+                layer.root,
+            )?;
+            let hash_of: HashMap<u32, Bytes32> =
+                manifest.chunks.iter().map(|c| (c.index, c.hash)).collect();
+            files.extend(layer.served.iter().map(|&i| {
+                let rec = &manifest.key_table[i];
+                SnapshotFile {
+                    path: rec.resource_key.clone(),
+                    size: rec.total_size,
+                    retrieval_key: rec.static_key.to_hex(),
+                    leaf: leaves[i].to_hex(),
+                    chunks: rec
+                        .chunk_indices
+                        .iter()
+                        .filter_map(|i| hash_of.get(i).map(|h| h.to_hex()))
+                        .collect(),
+                }
+            }));
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(SnapshotManifest {
+            store_id: self.config.store_id.to_hex(),
+            root: root.to_hex(),
+            generation: layers[0].manifest.generation_id,
+            files,
+        })
+    }
+
+    /// Write generation `root` to `writer` as a tar stream: one regular-file
+    /// entry per resource, sorted by path, mode `0644` and the commit time of
+    /// the generation holding it as mtime (resources carry no per-file
+    /// metadata). A changes-only generation is written with the files it reads
+    /// through its parent chain. Chunks are decrypted one at a time as the
+    /// entry is written, so memory stays at one chunk whatever the tree's
+    /// size. Each serving generation is checked against its root first.
+    /// Returns the number of files written.
+    pub fn export_tar(&self, root: Bytes32, writer: impl Write) -> Result<usize> {
+        let layers = self.resolved_layers(root)?;
+        self.check_layers(&layers)?;
+        let by_index: Vec<HashMap<u32, &ChunkRef>> = layers
+            .iter()
+            .map(|l| l.manifest.chunks.iter().map(|c| (c.index, c)).collect())
+            .collect();
+        let records = served_records(&layers);
+
+        let mut progress = Progress::new(
+            self.progress.as_deref(),
+            records.len() as u64,
+            records.iter().map(|(_, r)| r.total_size).sum(),
+        );
+        let mut builder = tar::Builder::new(writer);
+        for &(at, rec) in &records {
+            let chunks = rec
+                .chunk_indices
+                .iter()
+                .map(|i| {
+                    by_index[at].get(i).map(|c| (*c).clone()).ok_or_else(|| {
+                        StoreError::Manifest(format!(
+                            "key table references missing chunk index {i}"
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<ChunkRef>>>()?;
+            if relative_resource_key(Path::new(&rec.resource_key)).as_deref()
+                != Some(rec.resource_key.as_str())
+            {
+                return Err(StoreError::UnsafeResourceKey(rec.resource_key.clone()));
+            }
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_mtime(layers[at].manifest.timestamp);
+            header.set_size(rec.total_size);
+            let body = ChunkStream {
+                store: self,
+                aes_key: self.record_key(layers[at].manifest.key_epoch, &rec.resource_key),
+                chunks: chunks.into_iter(),
+                buf: Vec::new(),
+                pos: 0,
+            };
+            builder
+                .append_data(&mut header, &rec.resource_key, body)
+                .map_err(|e| {
+                    // A chunk that failed inside the stream keeps its own error.
+                    if e.get_ref().is_some_and(|inner| inner.is::<StoreError>()) {
+                        *e.into_inner().unwrap().downcast::<StoreError>().unwrap()
+                    } else {
+                        StoreError::Io(e)
+                    }
+                })?;
+            progress.file_done(rec.total_size);
+        }
+        builder.into_inner()?.flush()?;
+        Ok(records.len())
+    }
+
+    /// The resources served at generation `root` (the head when `None`),
+    /// sorted by resource key: for a changes-only generation, with the files
+    /// it reads through its parent chain, each record's `generation` naming
+    /// the generation holding it. A store with no generations lists nothing.
+    pub fn list_files(&self, root: Option<Bytes32>) -> Result<Vec<KeyTableRecord>> {
+        let root = match root {
+            Some(r) => r,
+            None => match self.current_root()? {
+                Some(r) => r,
+                None => return Ok(Vec::new()),
+            },
+        };
+        let layers = self.resolved_layers(root)?;
+        Ok(served_records(&layers)
+            .into_iter()
+            .map(|(_, rec)| rec.clone())
+            .collect())
+    }
+
     /// Diff two generations by root hash (§20.4 `diff`).
     pub fn diff(&self, a: Bytes32, b: Bytes32) -> Result<crate::diff::GenerationDiff> {
         let ma = self.generation_manifest(a)?;
@@ -317,11 +3204,161 @@ impl<C: Clock> Store<C> {
         Ok(crate::diff::GenerationDiff::between(&ma, &mb))
     }
 
-    /// The current head root hash, or `None` if no generation has been committed.
+    /// How much a [`Store::commit`] of the staging area would write: the
+    /// staged files are chunked and sealed in memory exactly as a commit
+    /// seals them, and every distinct chunk no generation already stores is
+    /// counted as new. Sizes are sealed chunk bytes, what lands on disk
+    /// including the AEAD overhead; the manifest and leaf cache are not
+    /// counted. Nothing is written.
+    pub fn estimate_commit_size(&self) -> Result<CommitEstimate> {
+        let records = StagingArea::open(self.paths.staging_file())?.records()?;
+        let sealed = self.seal_records(&records, 0, self.current_key_epoch()?);
+        let mut estimate = CommitEstimate {
+            files: records.len(),
+            logical_bytes: records.iter().map(|r| r.content.len() as u64).sum(),
+            chunks: sealed.pool.len(),
+            ..CommitEstimate::default()
+        };
+        let mut seen: HashSet<[u8; 32]> = HashSet::new();
+        for (hash, ct) in &sealed.pool {
+            if seen.insert(hash.0) && !self.chunk_exists_anywhere(*hash)? {
+                estimate.new_chunks += 1;
+                estimate.new_bytes += ct.len() as u64;
+            } else {
+                estimate.reused_bytes += ct.len() as u64;
+            }
+        }
+        Ok(estimate)
+    }
+
+    /// What committing the staging area would change against the head, as the
+    /// head reads (with the files a changes-only head reads through its parent
+    /// chain). A commit is a full snapshot of what is staged, so a head file
+    /// that is not staged counts as deleted, and a staged file whose bytes
+    /// equal the head's is not listed. With no head every staged file is added; with nothing
+    /// staged there is no commit to make and the summary is empty.
+    pub fn staged_diff_summary(&self) -> Result<DiffSummary> {
+        let staged = StagingArea::open(self.paths.staging_file())?.records()?;
+        if staged.is_empty() {
+            return Ok(DiffSummary::default());
+        }
+        let layers = match self.current_root()? {
+            Some(root) => self.resolved_layers(root)?,
+            None => Vec::new(),
+        };
+        let head_records: HashMap<&str, (usize, &KeyTableRecord)> = served_records(&layers)
+            .into_iter()
+            .map(|(at, r)| (r.resource_key.as_str(), (at, r)))
+            .collect();
+        let unchanged = |at: usize, old: &KeyTableRecord, content: &[u8]| -> Result<bool> {
+            Ok(old.total_size == content.len() as u64
+                && self.decrypt_record(&layers[at].manifest, old)? == content)
+        };
+        let mut files = Vec::new();
+        for rec in &staged {
+            let (change, old_size) = match head_records.get(rec.resource_key.as_str()) {
+                None => (FileChange::Added, None),
+                Some(&(at, old)) if unchanged(at, old, &rec.content)? => continue,
+                Some((_, old)) => (FileChange::Modified, Some(old.total_size)),
+            };
+            files.push(FileDiffStat {
+                resource_key: rec.resource_key.clone(),
+                change,
+                old_size,
+                new_size: Some(rec.content.len() as u64),
+            });
+        }
+        for (key, (_, old)) in &head_records {
+            if !staged.iter().any(|s| s.resource_key == *key) {
+                files.push(FileDiffStat {
+                    resource_key: key.to_string(),
+                    change: FileChange::Deleted,
+                    old_size: Some(old.total_size),
+                    new_size: None,
+                });
+            }
+        }
+        Ok(DiffSummary::from_files(files))
+    }
+
+    /// The current head root hash, or `None` if no generation has been
+    /// committed: the tip of the current branch once a branch exists,
+    /// otherwise the latest generation.
     pub fn current_root(&self) -> Result<Option<Bytes32>> {
-        Ok(RootHistory::open(self.paths.history_file())?
-            .head()?
-            .map(|g| g.root))
+        let Some(history) = self.history()? else {
+            return Ok(None);
+        };
+        let refs = self.refs();
+        if refs.exists() {
+            return Ok(refs.entries()?.get(&refs.current()?).copied());
+        }
+        Ok(history.head()?.map(|g| g.root))
+    }
+
+    /// The branch commits advance (`main` until another is switched to).
+    pub fn current_branch(&self) -> Result<String> {
+        self.refs().current()
+    }
+
+    /// Every branch and its tip, by name. Before any branch is created this
+    /// is just `main` at the latest generation (absent with no generation).
+    pub fn branches(&self) -> Result<BTreeMap<String, Bytes32>> {
+        let refs = self.refs();
+        if refs.exists() {
+            return refs.entries();
+        }
+        let mut out = BTreeMap::new();
+        if let Some(root) = self.current_root()? {
+            out.insert(refs.current()?, root);
+        }
+        Ok(out)
+    }
+
+    /// Create branch `name` at `at` (default: the current root), which must
+    /// be a generation in the root history. The current branch is unchanged;
+    /// see [`Store::switch_branch`]. The first branch created also records
+    /// where `main` stands, after which each commit advances only the
+    /// current branch's tip (a fast-forward); `roots.log` keeps every
+    /// generation of every branch.
+    pub fn create_branch(&mut self, name: &str, at: Option<Bytes32>) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        validate_branch_name(name)?;
+        let mut entries = self.branches()?;
+        if entries.contains_key(name) {
+            return Err(StoreError::InvalidBranch(format!("{name} already exists")));
+        }
+        let root = match at {
+            Some(root) => {
+                if !self.roothash_history()?.contains(&root) {
+                    return Err(StoreError::GenerationNotFound(root.to_hex()));
+                }
+                root
+            }
+            None => self.current_root()?.ok_or_else(|| {
+                StoreError::InvalidBranch("no version to branch from; commit first".into())
+            })?,
+        };
+        entries.insert(name.to_string(), root);
+        self.refs().write(&entries)?;
+        Ok(root)
+    }
+
+    /// Make `name`, an existing branch, the current one; later commits build
+    /// on and advance its tip. Returns the branch's tip. Staged changes are
+    /// left as they are.
+    pub fn switch_branch(&mut self, name: &str) -> Result<Bytes32> {
+        self.ensure_writable()?;
+        let tip = self
+            .branches()?
+            .get(name)
+            .copied()
+            .ok_or_else(|| StoreError::InvalidBranch(format!("no branch named {name}")))?;
+        let refs = self.refs();
+        if !refs.exists() {
+            refs.write(&self.branches()?)?;
+        }
+        refs.set_current(name)?;
+        Ok(tip)
     }
 
     /// All root hashes in chronological order — the source for the guest's
@@ -336,3 +3373,211 @@ impl<C: Clock> Store<C> {
         self.paths.module_file(&root.to_hex())
     }
 }
+
+/// Build the D5 tree from `leaves` (in key-table order), sorted by static key,
+/// and check it against `root`. Returns the static keys in leaf order.
+fn verify_leaves(
+    algorithm: HashAlgorithm,
+    manifest: &GenerationManifest,
+    leaves: Vec<Bytes32>,
+    root: Bytes32,
+) -> Result<(Vec<[u8; 32]>, MerkleTree)> {
+    let mut keyed: Vec<([u8; 32], Bytes32)> = manifest
+        .key_table
+        .iter()
+        .zip(leaves)
+        .map(|(rec, leaf)| (rec.static_key.0, leaf))
+        .collect();
+    keyed.sort_by_key(|r| r.0);
+
+    let (keys, leaves): (Vec<[u8; 32]>, Vec<Bytes32>) = keyed.into_iter().unzip();
+    let tree = MerkleTree::from_leaves_with(algorithm, leaves);
+    if tree.root() != root {
+        return Err(StoreError::RootMismatch {
+            root: root.to_hex(),
+            rebuilt: tree.root().to_hex(),
+        });
+    }
+    Ok((keys, tree))
+}
+
+/// Write a generation's `leaves.bin`: `static_key || leaf` (64 bytes) per
+/// resource, in leaf order.
+fn write_leaf_cache(path: &Path, keys: &[[u8; 32]], leaves: &[Bytes32]) -> Result<()> {
+    let mut bytes = Vec::with_capacity(keys.len() * 64);
+    for (key, leaf) in keys.iter().zip(leaves) {
+        bytes.extend_from_slice(key);
+        bytes.extend_from_slice(&leaf.0);
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// A resource's plaintext, decrypted one chunk at a time as it is read
+/// ([`Store::export_tar`]).
+struct ChunkStream<'s, C: Clock> {
+    store: &'s Store<C>,
+    aes_key: [u8; 32],
+    chunks: std::vec::IntoIter<ChunkRef>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<C: Clock> Read for ChunkStream<'_, C> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.buf.len() {
+            let Some(chunk) = self.chunks.next() else {
+                return Ok(0);
+            };
+            let ct = self
+                .store
+                .resolve_chunk(chunk.hash)
+                .map_err(std::io::Error::other)?;
+            let pt = digstore_crypto::decrypt_chunk(&self.aes_key, &ct).map_err(|_| {
+                std::io::Error::other(StoreError::DecryptFailed(chunk.hash.to_hex()))
+            })?;
+            if pt.len() as u64 != chunk.size.saturating_sub(SEAL_OVERHEAD) {
+                return Err(std::io::Error::other(StoreError::Manifest(format!(
+                    "chunk {} decrypts to {} bytes, manifest implies {}",
+                    chunk.hash.to_hex(),
+                    pt.len(),
+                    chunk.size.saturating_sub(SEAL_OVERHEAD)
+                ))));
+            }
+            self.buf = pt;
+            self.pos = 0;
+        }
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// The forward-slash resource key for a relative path, or `None` when the
+/// path is empty or not plainly relative (absolute, or with a `..`).
+pub(crate) fn relative_resource_key(rel: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for c in rel.components() {
+        match c {
+            std::path::Component::Normal(part) => parts.push(part.to_string_lossy()),
+            std::path::Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// The chunk hashes of `rec`, in read order.
+fn record_chunk_hashes(
+    manifest: &GenerationManifest,
+    rec: &KeyTableRecord,
+) -> Result<Vec<Bytes32>> {
+    let by_index: HashMap<u32, Bytes32> =
+        manifest.chunks.iter().map(|c| (c.index, c.hash)).collect();
+    rec.chunk_indices
+        .iter()
+        .map(|i| {
+            by_index.get(i).copied().ok_or_else(|| {
+                StoreError::Manifest(format!("key table references missing chunk index {i}"))
+            })
+        })
+        .collect()
+}
+
+/// Every record `layers` serves, with the index of its layer, sorted by
+/// resource key.
+fn served_records(layers: &[ResolvedLayer]) -> Vec<(usize, &KeyTableRecord)> {
+    let mut records: Vec<(usize, &KeyTableRecord)> = layers
+        .iter()
+        .enumerate()
+        .flat_map(|(at, l)| l.records().map(move |r| (at, r)))
+        .collect();
+    records.sort_by(|a, b| a.1.resource_key.cmp(&b.1.resource_key));
+    records
+}
+
+/// The key-table record for `resource_key` in `manifest` (generation `root`).
+/// Prefer [`Store::find_record`], whose error also says where the resource
+/// can be found instead.
+fn find_record<'m>(
+    manifest: &'m GenerationManifest,
+    root: Bytes32,
+    resource_key: &str,
+) -> Result<&'m KeyTableRecord> {
+    manifest
+        .key_table
+        .iter()
+        .find(|r| r.resource_key == resource_key)
+        .ok_or_else(|| StoreError::ResourceNotFound {
+            key: resource_key.to_string(),
+            root: root.to_hex(),
+            found_in: None,
+        })
+}
+
+/// Where resource `key` lands under `dest`, or `None` if any segment is empty,
+/// `.` or `..` (which could climb out of `dest`).
+fn resource_dest(dest: &Path, key: &str) -> Option<PathBuf> {
+    let mut path = dest.to_path_buf();
+    for seg in key.split('/') {
+        if seg.is_empty() || seg == "." || seg == ".." || seg.contains('\\') {
+            return None;
+        }
+        path.push(seg);
+    }
+    Some(path)
+}
+
+/// Bytes a sealed chunk adds to its plaintext: the AES-GCM-SIV tag.
+pub(crate) const SEAL_OVERHEAD: u64 = 16;
+
+/// State behind [`Store::read_resource_range`].
+struct ReadAhead {
+    /// Decrypted chunk plaintexts by chunk hash.
+    plaintext: ChunkCache,
+    /// Root, resource key and end offset of the previous range read, to
+    /// recognise a sequential one.
+    last: Option<(Bytes32, String, u64)>,
+    /// Generations already checked against their roots.
+    verified: HashSet<[u8; 32]>,
+}
+
+impl ReadAhead {
+    fn new(capacity_bytes: usize) -> Self {
+        Self {
+            plaintext: ChunkCache::new(capacity_bytes),
+            last: None,
+            verified: HashSet::new(),
+        }
+    }
+}
+
+fn default_read_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Map `f` over `items` on up to `threads` scoped threads, each taking one
+/// contiguous run, and return the results in input order.
+fn parallel_map<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.iter().map(f).collect();
+    }
+    let per_thread = items.len().div_ceil(threads);
+    let f = &f;
+    std::thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(per_thread)
+            .map(|run| s.spawn(move || run.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("chunk reader thread panicked"))
+            .collect()
+    })
+}
@@ -1,5 +1,5 @@
-use digstore_core::{Bytes32, StoreConfig, Visibility};
-use digstore_store::{FixedClock, StagingArea, Store};
+use digstore_core::{Bytes32, HashAlgorithm, StoreConfig, Visibility};
+use digstore_store::{FixedClock, PruneBefore, StagingArea, Store};
 use std::io::Write;
 use tempfile::tempdir;
 
@@ -58,6 +58,8 @@ fn config(dir: &std::path::Path) -> StoreConfig {
         visibility: Visibility::Public,
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     }
 }
 
@@ -477,3 +479,2554 @@ fn current_root_and_history_accessors() {
         .join(format!("{sid_hex}-{}.dig", r1.to_hex()));
     assert_eq!(store.module_path(r1), expected);
 }
+
+#[test]
+fn prove_resource_at_historical_root_after_content_changes() {
+    // A proof generated against an OLD generation must verify against that
+    // generation's root even after the resource changed (and was dropped) in
+    // later generations — and must NOT verify against the current head root.
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("notes.txt", b"original content").unwrap();
+    store.stage_file("other.txt", b"sibling").unwrap();
+    let original = store.commit().unwrap();
+
+    store.stage_file("other.txt", b"sibling v2").unwrap();
+    let current = store.commit().unwrap();
+    assert_ne!(original, current);
+
+    let proof = store.prove_resource(original, "notes.txt").unwrap();
+    assert_eq!(proof.root, original);
+    assert!(proof.verify(), "proof verifies against the original root");
+
+    let mut against_current = proof.clone();
+    against_current.root = current;
+    assert!(
+        !against_current.verify(),
+        "a historical proof must not verify against the current root"
+    );
+
+    // The resource no longer exists at the head generation.
+    let err = store.prove_resource(current, "notes.txt").unwrap_err();
+    assert!(matches!(
+        err,
+        digstore_store::StoreError::ResourceNotFound { .. }
+    ));
+}
+
+#[test]
+fn every_version_of_a_file_can_be_read_back() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("notes.txt", b"v1").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("notes.txt", b"v1").unwrap();
+    store.stage_file("other.txt", b"sibling").unwrap();
+    let unchanged = store.commit().unwrap();
+    store.stage_file("notes.txt", b"v2").unwrap();
+    let second = store.commit().unwrap();
+
+    assert_eq!(
+        store.generations_containing("notes.txt").unwrap(),
+        vec![first, unchanged, second]
+    );
+    assert_eq!(
+        store.generations_containing("other.txt").unwrap(),
+        vec![unchanged]
+    );
+
+    let versions = store.read_file_versions("notes.txt").unwrap();
+    let read: Vec<(Bytes32, &[u8])> = versions
+        .iter()
+        .map(|(v, content)| (v.root, content.as_slice()))
+        .collect();
+    assert_eq!(read, vec![(first, &b"v1"[..]), (second, &b"v2"[..])]);
+    assert!(store.read_file_versions("never.txt").unwrap().is_empty());
+}
+
+#[test]
+fn blame_attributes_each_line_to_the_version_that_added_it() {
+    use digstore_store::StoreError;
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store
+        .stage_file("app.rs", b"fn main() {\n    one();\n}\n")
+        .unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("other.txt", b"unrelated").unwrap();
+    store
+        .stage_file("app.rs", b"fn main() {\n    one();\n}\n")
+        .unwrap();
+    store.commit().unwrap();
+    store
+        .stage_file("app.rs", b"// entry\nfn main() {\n    two();\n}\n")
+        .unwrap();
+    let third = store.commit().unwrap();
+
+    let blame = store.blame("app.rs").unwrap();
+    let got: Vec<(usize, &str, Bytes32)> = blame
+        .iter()
+        .map(|l| (l.line, l.text.as_str(), l.root))
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            (1, "// entry", third),
+            (2, "fn main() {", first),
+            (3, "    two();", third),
+            (4, "}", first),
+        ]
+    );
+    assert!(store.blame("missing.txt").unwrap().is_empty());
+
+    store
+        .stage_file("logo.png", b"\x89PNG\r\n\x1a\n\0\0binary")
+        .unwrap();
+    store.commit().unwrap();
+    assert!(matches!(
+        store.blame("logo.png"),
+        Err(StoreError::BinaryFile(_))
+    ));
+}
+
+#[test]
+fn resource_not_found_names_a_generation_that_has_it() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("notes.txt", b"v1").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("notes.txt", b"v2").unwrap();
+    let second = store.commit().unwrap();
+    store.stage_file("other.txt", b"sibling").unwrap();
+    let third = store.commit().unwrap();
+
+    // Missing at the head: the newest generation that holds it is named.
+    match store.read_resource(third, "notes.txt") {
+        Err(digstore_store::StoreError::ResourceNotFound { found_in, .. }) => {
+            assert_eq!(found_in, Some(second.to_hex()));
+        }
+        other => panic!("expected ResourceNotFound, got {other:?}"),
+    }
+    let err = store.stat_file(first, "other.txt").unwrap_err();
+    assert!(
+        err.to_string()
+            .ends_with(&format!("(found at root {})", third.to_hex())),
+        "{err}"
+    );
+
+    // Held by no generation: nothing to suggest.
+    assert!(matches!(
+        store.stat_file(third, "never.txt"),
+        Err(digstore_store::StoreError::ResourceNotFound { found_in: None, .. })
+    ));
+}
+
+#[test]
+fn find_files_by_hash_labels_chunk_file_and_retrieval_key_matches() {
+    use digstore_store::{HashKind, HashMatch};
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    // Several chunks, so no chunk hash doubles as the file's leaf.
+    let shared = noise(600_000, 7);
+    store.stage_file("a.txt", &shared).unwrap();
+    store.stage_file("b.txt", b"other body").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("a.txt", &shared).unwrap();
+    let second = store.commit().unwrap();
+
+    let manifest = store.generation_manifest(first).unwrap();
+    let rec = manifest
+        .key_table
+        .iter()
+        .find(|r| r.resource_key == "a.txt")
+        .unwrap();
+    let chunk = manifest
+        .chunks
+        .iter()
+        .find(|c| c.index == rec.chunk_indices[0])
+        .unwrap()
+        .hash;
+    let at = |root, kind| HashMatch {
+        root,
+        resource_key: "a.txt".to_string(),
+        kind,
+    };
+    assert_eq!(
+        store.find_files_by_hash(chunk).unwrap(),
+        [at(first, HashKind::Chunk), at(second, HashKind::Chunk)]
+    );
+    assert_eq!(
+        store.find_files_by_hash(rec.static_key).unwrap(),
+        [
+            at(first, HashKind::RetrievalKey),
+            at(second, HashKind::RetrievalKey)
+        ]
+    );
+    let leaf = store.prove_resource(first, "a.txt").unwrap().leaf;
+    assert_eq!(
+        store.find_files_by_hash(leaf).unwrap(),
+        [at(first, HashKind::File), at(second, HashKind::File)]
+    );
+    assert!(store
+        .find_files_by_hash(Bytes32([0xab; 32]))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn get_file_versions_lists_only_generations_where_the_file_changed() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut commit = |files: &[(&str, &[u8])]| {
+        for (key, content) in files {
+            store.stage_file(key, content).unwrap();
+        }
+        store.commit().unwrap()
+    };
+    let first = commit(&[("a.txt", b"one"), ("b.txt", b"x")]);
+    commit(&[("a.txt", b"one"), ("b.txt", b"y")]);
+    let changed = commit(&[("a.txt", b"two"), ("b.txt", b"y")]);
+    commit(&[("b.txt", b"y")]);
+    let back = commit(&[("a.txt", b"two")]);
+
+    let versions = store.get_file_versions("a.txt").unwrap();
+    let roots: Vec<_> = versions.iter().map(|v| v.root).collect();
+    assert_eq!(roots, [first, changed, back]);
+    assert_eq!(
+        versions.iter().map(|v| v.generation_id).collect::<Vec<_>>(),
+        [0, 2, 4]
+    );
+    assert_ne!(versions[0].hash, versions[1].hash);
+    assert_eq!(versions[1].hash, versions[2].hash);
+    assert_eq!(
+        versions[0].hash,
+        store.prove_resource(first, "a.txt").unwrap().leaf
+    );
+    assert!(store.get_file_versions("missing.txt").unwrap().is_empty());
+}
+
+#[test]
+fn export_since_carries_only_new_generations_and_chunks_onto_a_copy_at_base() {
+    use digstore_store::StoreError;
+
+    let src_dir = tempdir().unwrap();
+    let mut src = Store::init(config(src_dir.path()), FixedClock::new(1)).unwrap();
+    let shared = vec![0x5Au8; 300_000];
+    src.stage_file("shared.bin", &shared).unwrap();
+    let base = src.commit().unwrap();
+    src.stage_file("shared.bin", &shared).unwrap();
+    src.stage_file("new.txt", b"after the backup").unwrap();
+    let next = src.commit().unwrap();
+    src.stage_file("new.txt", b"changed again").unwrap();
+    let last = src.commit_incremental().unwrap();
+
+    let bundle = src.export_since(base).unwrap();
+    assert_eq!(bundle.base, base);
+    assert_eq!(
+        bundle
+            .generations
+            .iter()
+            .map(|m| m.root)
+            .collect::<Vec<_>>(),
+        [next, last]
+    );
+    let base_chunks = src.generation_manifest(base).unwrap().chunks;
+    assert!(bundle
+        .chunks
+        .iter()
+        .all(|(h, _)| !base_chunks.iter().any(|c| c.hash == *h)));
+    let mut bytes = Vec::new();
+    bundle.write_to(&mut bytes).unwrap();
+    let bundle = digstore_store::DeltaBundle::read_from(bytes.as_slice()).unwrap();
+
+    let dst_dir = tempdir().unwrap();
+    let mut dst = Store::init(config(dst_dir.path()), FixedClock::new(1)).unwrap();
+    // Not at base yet.
+    assert!(matches!(
+        dst.import_delta(&bundle),
+        Err(StoreError::GenerationNotFound(_))
+    ));
+    dst.stage_file("shared.bin", &shared).unwrap();
+    assert_eq!(dst.commit().unwrap(), base);
+
+    let mut tampered = bundle.clone();
+    tampered.chunks[0].1[0] ^= 1;
+    assert!(matches!(
+        dst.import_delta(&tampered),
+        Err(StoreError::InvalidDelta(_))
+    ));
+
+    assert_eq!(dst.import_delta(&bundle).unwrap(), [next, last]);
+    assert_eq!(dst.roothash_history().unwrap(), [base, next, last]);
+    assert_eq!(
+        dst.read_resource(last, "new.txt").unwrap(),
+        b"changed again"
+    );
+    assert_eq!(dst.read_resource(next, "shared.bin").unwrap(), shared);
+    // Now past the base.
+    assert!(matches!(
+        dst.import_delta(&bundle),
+        Err(StoreError::InvalidDelta(_))
+    ));
+}
+
+#[test]
+fn from_urn_opens_the_named_store_and_reads_in_one_step() {
+    use digstore_core::Urn;
+    use digstore_store::StoreError;
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"first").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("a.txt", b"second").unwrap();
+    store.commit().unwrap();
+
+    let sid = config(dir.path()).store_id.to_hex();
+    let pinned = Urn::parse(&format!("urn:dig:chia:{sid}:{}/a.txt", first.to_hex())).unwrap();
+    let latest = Urn::parse(&format!("urn:dig:chia:{sid}/a.txt")).unwrap();
+    let reader = Store::from_urn(dir.path(), &pinned, FixedClock::new(1)).unwrap();
+    assert!(reader.is_read_only());
+    assert_eq!(reader.read_urn(&pinned).unwrap(), b"first");
+    assert_eq!(reader.read_urn(&latest).unwrap(), b"second");
+
+    let other = Urn::parse(&format!("urn:dig:chia:{}/a.txt", "55".repeat(32))).unwrap();
+    assert!(matches!(
+        Store::from_urn(dir.path(), &other, FixedClock::new(1)),
+        Err(StoreError::InvalidUrn(_))
+    ));
+    let unknown = Urn::parse(&format!("urn:dig:chia:{sid}:{}", "66".repeat(32))).unwrap();
+    assert!(matches!(
+        Store::from_urn(dir.path(), &unknown, FixedClock::new(1)),
+        Err(StoreError::GenerationNotFound(_))
+    ));
+    let bare = Urn::parse(&format!("urn:dig:chia:{sid}")).unwrap();
+    assert!(matches!(
+        reader.read_urn(&bare),
+        Err(StoreError::InvalidUrn(_))
+    ));
+}
+
+#[test]
+fn content_type_sniffs_the_first_chunk_of_a_committed_resource() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend((0..200_000u32).map(|i| (i * 7919 % 251) as u8));
+    store.stage_file("logo.png", &png).unwrap();
+    store
+        .stage_file("index.html", b"<p>caf\xc3\xa9</p>\n")
+        .unwrap();
+    store.stage_file("empty.txt", b"").unwrap();
+    let root = store.commit().unwrap();
+
+    let logo = store.content_type(root, "logo.png").unwrap();
+    assert_eq!((logo.mime_type, logo.is_binary), ("image/png", true));
+    let html = store.content_type(root, "index.html").unwrap();
+    assert_eq!(html.mime_type, "text/html");
+    assert_eq!(html.encoding_guess, Some("utf-8"));
+    assert!(!store.content_type(root, "empty.txt").unwrap().is_binary);
+    assert!(store.content_type(root, "missing").is_err());
+}
+
+#[test]
+fn access_policy_rejects_writers_it_does_not_list() {
+    let dir = tempdir().unwrap();
+    Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let (owner, owner_pk) = digstore_crypto::bls_keygen(&[3u8; 32]);
+    let (intruder, intruder_pk) = digstore_crypto::bls_keygen(&[4u8; 32]);
+
+    let mut store = Store::open_as(dir.path(), FixedClock::new(1), &owner).unwrap();
+    let other_only = digstore_store::AccessPolicy::writers([intruder_pk]);
+    assert!(matches!(
+        store.set_access_policy(Some(&other_only)),
+        Err(digstore_store::StoreError::Unauthorized(_))
+    ));
+    let policy = digstore_store::AccessPolicy::writers([owner_pk]);
+    store.set_access_policy(Some(&policy)).unwrap();
+    assert_eq!(store.access_policy().unwrap(), Some(policy));
+    store.stage_file("a.txt", b"owned").unwrap();
+    let root = store.commit().unwrap();
+
+    assert!(matches!(
+        Store::open_as(dir.path(), FixedClock::new(1), &intruder),
+        Err(digstore_store::StoreError::Unauthorized(_))
+    ));
+    // Opened without a wallet: still readable, but not writable.
+    let mut anonymous = Store::open(dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(anonymous.read_resource(root, "a.txt").unwrap(), b"owned");
+    assert!(matches!(
+        anonymous.stage_file("b.txt", b"x"),
+        Err(digstore_store::StoreError::Unauthorized(_))
+    ));
+    assert!(anonymous.set_access_policy(None).is_err());
+
+    store.set_access_policy(None).unwrap();
+    anonymous.stage_file("b.txt", b"x").unwrap();
+}
+
+#[test]
+fn ephemeral_store_serves_a_generation_from_a_memory_backend() {
+    let mut store = Store::init_ephemeral(
+        Bytes32([0x44u8; 32]),
+        Visibility::Public,
+        FixedClock::new(1),
+    )
+    .unwrap();
+    let dir = store.paths().root();
+    assert!(dir.exists());
+    let big: Vec<u8> = (0..300_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    store.stage_file("big.bin", &big).unwrap();
+    store.stage_file("a.txt", b"hello").unwrap();
+    let root = store.commit().unwrap();
+    assert!(store.prove_resource(root, "a.txt").unwrap().verify());
+
+    let memory = digstore_store::MemoryBackend::new();
+    let written = store.copy_chunks_to(root, &memory).unwrap();
+    assert_eq!(written, memory.chunk_count());
+    assert!(written > 1);
+    assert_eq!(store.copy_chunks_to(root, &memory).unwrap(), 0);
+    assert_eq!(
+        store.read_resource_from(&memory, root, "big.bin").unwrap(),
+        big
+    );
+    assert_eq!(
+        store.read_resource_from(&memory, root, "a.txt").unwrap(),
+        store.read_resource(root, "a.txt").unwrap()
+    );
+
+    drop(store);
+    assert!(!dir.exists(), "an ephemeral store removes its data dir");
+}
+
+#[test]
+fn allow_empty_commit_carries_the_head_forward() {
+    use digstore_store::{CommitOptions, StoreError};
+
+    let allow = CommitOptions {
+        allow_empty: true,
+        ..Default::default()
+    };
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    // Nothing to carry before the first generation.
+    assert!(matches!(
+        store.commit_with_options(allow),
+        Err(StoreError::EmptyStaging)
+    ));
+    store.stage_file("a.txt", b"checkpoint me").unwrap();
+    let first = store.commit_with_options(CommitOptions::default()).unwrap();
+    assert!(matches!(
+        store.commit_with_options(CommitOptions::default()),
+        Err(StoreError::EmptyStaging)
+    ));
+    drop(store);
+
+    let mut store = Store::open(dir.path(), FixedClock::new(50)).unwrap();
+    let second = store.commit_with_options(allow).unwrap();
+    assert_eq!(second, first, "same files, same root");
+    assert_eq!(store.commit_with_options(allow).unwrap(), first);
+    let log = store.log().unwrap();
+    assert_eq!(log.len(), 3);
+    assert_eq!((log[1].id, log[1].root, log[1].timestamp), (1, first, 50));
+    assert_eq!((log[2].id, log[2].root, log[2].timestamp), (2, first, 50));
+    // The checkpoints are history entries only: the generation keeps the
+    // id and time of the commit that wrote it.
+    let manifest = store.generation_manifest(first).unwrap();
+    assert_eq!((manifest.generation_id, manifest.timestamp), (0, 1));
+    assert!(!store.paths().pending_commit_file().exists());
+    assert_eq!(
+        store.read_resource(second, "a.txt").unwrap(),
+        b"checkpoint me"
+    );
+}
+
+#[test]
+fn changes_only_commit_reads_unstaged_files_through_its_parent() {
+    use digstore_store::{CommitOptions, StoreError};
+
+    let changes_only = CommitOptions {
+        changes_only: true,
+        ..Default::default()
+    };
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    store.stage_file("b.txt", b"bravo").unwrap();
+    let base = store.commit().unwrap();
+    store.stage_file("a.txt", b"alpha v2").unwrap();
+    let layer = store.commit_with_options(changes_only).unwrap();
+    store.stage_file("c.txt", b"charlie").unwrap();
+    let top = store.commit_with_options(changes_only).unwrap();
+
+    // Each layer's key table holds only what was staged for it.
+    let manifest = store.generation_manifest(top).unwrap();
+    assert_eq!(manifest.parent, Some(layer));
+    assert_eq!(
+        manifest.resource_keys().into_iter().collect::<Vec<_>>(),
+        ["c.txt"]
+    );
+
+    // Reads walk the parent chain to the nearest layer holding the file.
+    assert_eq!(store.generation_holding(top, "b.txt").unwrap(), base);
+    assert_eq!(store.read_resource(top, "b.txt").unwrap(), b"bravo");
+    assert_eq!(store.read_resource(top, "a.txt").unwrap(), b"alpha v2");
+    assert_eq!(store.read_resource(top, "c.txt").unwrap(), b"charlie");
+    assert_eq!(store.stat_file(top, "b.txt").unwrap().root, base);
+    assert!(matches!(
+        store.read_resource(top, "z.txt"),
+        Err(StoreError::ResourceNotFound { .. })
+    ));
+
+    // A plain commit is self-contained: nothing falls through.
+    store.stage_file("d.txt", b"delta").unwrap();
+    let plain = store.commit().unwrap();
+    assert_eq!(store.generation_manifest(plain).unwrap().parent, None);
+    assert!(store.read_resource(plain, "b.txt").is_err());
+}
+
+/// A store whose head is a changes-only layer on `base`: `base` holds a.txt
+/// and b.txt, the head restages only a.txt and reads b.txt through `base`.
+fn layered_store(dir: &std::path::Path) -> (Store<FixedClock>, Bytes32, Bytes32) {
+    let mut store = Store::init(config(dir), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    store.stage_file("b.txt", b"bravo needle").unwrap();
+    let base = store.commit().unwrap();
+    store.stage_file("a.txt", b"alpha v2 needle").unwrap();
+    let head = store
+        .commit_with_options(digstore_store::CommitOptions {
+            changes_only: true,
+            ..Default::default()
+        })
+        .unwrap();
+    (store, base, head)
+}
+
+#[test]
+fn list_files_of_a_changes_only_generation_includes_its_parent_chain() {
+    let dir = tempdir().unwrap();
+    let (store, base, head) = layered_store(dir.path());
+    let listed: Vec<(String, Bytes32)> = store
+        .list_files(Some(head))
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.resource_key, r.generation))
+        .collect();
+    assert_eq!(
+        listed,
+        [("a.txt".to_string(), head), ("b.txt".to_string(), base)]
+    );
+}
+
+#[test]
+fn get_files_of_a_changes_only_generation_reads_through_its_parent() {
+    let dir = tempdir().unwrap();
+    let (store, _, head) = layered_store(dir.path());
+    let dest = tempdir().unwrap();
+    let keys = ["a.txt".to_string(), "b.txt".to_string()];
+    let report = store.get_files(&keys, head, dest.path()).unwrap();
+    assert_eq!(report.files, 2);
+    assert_eq!(
+        std::fs::read(dest.path().join("a.txt")).unwrap(),
+        b"alpha v2 needle"
+    );
+    assert_eq!(
+        std::fs::read(dest.path().join("b.txt")).unwrap(),
+        b"bravo needle"
+    );
+    assert!(matches!(
+        store.get_files(&["z.txt".to_string()], head, dest.path()),
+        Err(digstore_store::StoreError::ResourceNotFound { .. })
+    ));
+}
+
+#[test]
+fn grep_of_a_changes_only_generation_searches_its_parent_chain() {
+    let dir = tempdir().unwrap();
+    let (store, _, head) = layered_store(dir.path());
+    let mut found = Vec::new();
+    store
+        .grep(&regex::Regex::new("needle").unwrap(), head, false, |m| {
+            found.push((m.resource_key, m.line));
+            true
+        })
+        .unwrap();
+    assert_eq!(
+        found,
+        [
+            ("a.txt".to_string(), "alpha v2 needle".to_string()),
+            ("b.txt".to_string(), "bravo needle".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn export_tar_of_a_changes_only_generation_writes_its_parent_chain() {
+    use std::io::Read;
+
+    let dir = tempdir().unwrap();
+    let (store, _, head) = layered_store(dir.path());
+    let mut tar = Vec::new();
+    assert_eq!(store.export_tar(head, &mut tar).unwrap(), 2);
+    let mut archive = tar::Archive::new(tar.as_slice());
+    let entries: Vec<(String, Vec<u8>)> = archive
+        .entries()
+        .unwrap()
+        .map(|e| {
+            let mut e = e.unwrap();
+            let path = e.path().unwrap().to_string_lossy().into_owned();
+            let mut body = Vec::new();
+            e.read_to_end(&mut body).unwrap();
+            (path, body)
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("a.txt".to_string(), b"alpha v2 needle".to_vec()),
+            ("b.txt".to_string(), b"bravo needle".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn export_manifest_of_a_changes_only_generation_lists_its_parent_chain() {
+    let dir = tempdir().unwrap();
+    let (store, base, head) = layered_store(dir.path());
+    let export = store.export_manifest(head).unwrap();
+    assert_eq!(export.root, head.to_hex());
+    let paths: Vec<&str> = export.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["a.txt", "b.txt"]);
+    // An inherited file is exported as its parent committed it.
+    let parent = store.export_manifest(base).unwrap();
+    assert_eq!(export.files[1], parent.files[1]);
+}
+
+#[test]
+fn verify_all_proofs_of_a_changes_only_generation_covers_its_parent_chain() {
+    let dir = tempdir().unwrap();
+    let (store, _, head) = layered_store(dir.path());
+    let results = store.verify_all_proofs(head).unwrap();
+    let keys: Vec<(&str, bool)> = results
+        .iter()
+        .map(|r| (r.resource_key.as_str(), r.verified))
+        .collect();
+    assert_eq!(keys, [("a.txt", true), ("b.txt", true)]);
+}
+
+#[test]
+fn file_versions_do_not_restart_at_a_changes_only_generation() {
+    let dir = tempdir().unwrap();
+    let (store, base, head) = layered_store(dir.path());
+    // b.txt is read through unchanged: still its one version.
+    let versions = store.get_file_versions("b.txt").unwrap();
+    assert_eq!(versions.iter().map(|v| v.root).collect::<Vec<_>>(), [base]);
+    let versions = store.get_file_versions("a.txt").unwrap();
+    assert_eq!(
+        versions.iter().map(|v| v.root).collect::<Vec<_>>(),
+        [base, head]
+    );
+}
+
+#[test]
+fn generations_containing_counts_files_read_through_a_parent() {
+    let dir = tempdir().unwrap();
+    let (store, base, head) = layered_store(dir.path());
+    assert_eq!(store.generations_containing("b.txt").unwrap(), [base, head]);
+    assert!(store.generations_containing("z.txt").unwrap().is_empty());
+}
+
+#[test]
+fn find_files_by_hash_matches_files_read_through_a_parent() {
+    use digstore_store::HashKind;
+
+    let dir = tempdir().unwrap();
+    let (store, base, head) = layered_store(dir.path());
+    let b = store
+        .generation_manifest(base)
+        .unwrap()
+        .key_table
+        .into_iter()
+        .find(|r| r.resource_key == "b.txt")
+        .unwrap();
+    let matches: Vec<(Bytes32, String, HashKind)> = store
+        .find_files_by_hash(b.static_key)
+        .unwrap()
+        .into_iter()
+        .map(|m| (m.root, m.resource_key, m.kind))
+        .collect();
+    assert_eq!(
+        matches,
+        [
+            (base, "b.txt".to_string(), HashKind::RetrievalKey),
+            (head, "b.txt".to_string(), HashKind::RetrievalKey),
+        ]
+    );
+}
+
+#[test]
+fn staged_diff_summary_compares_against_a_changes_only_head_as_it_reads() {
+    use digstore_store::FileChange;
+
+    let dir = tempdir().unwrap();
+    let (mut store, _, _) = layered_store(dir.path());
+    // b.txt is inherited: restaging it unchanged is no change, not an add.
+    store.stage_file("a.txt", b"alpha v3").unwrap();
+    store.stage_file("b.txt", b"bravo needle").unwrap();
+    let summary = store.staged_diff_summary().unwrap();
+    let files: Vec<(&str, FileChange)> = summary
+        .files
+        .iter()
+        .map(|f| (f.resource_key.as_str(), f.change))
+        .collect();
+    assert_eq!(files, [("a.txt", FileChange::Modified)]);
+}
+
+#[test]
+fn commits_advance_only_the_current_branch() {
+    use digstore_store::{StoreError, DEFAULT_BRANCH};
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"base").unwrap();
+    let base = store.commit().unwrap();
+    assert_eq!(store.current_branch().unwrap(), DEFAULT_BRANCH);
+    assert_eq!(
+        store.branches().unwrap().into_iter().collect::<Vec<_>>(),
+        [(DEFAULT_BRANCH.to_string(), base)]
+    );
+
+    assert_eq!(store.create_branch("exp", None).unwrap(), base);
+    assert!(store.create_branch("exp", None).is_err());
+    store.switch_branch("exp").unwrap();
+    store.stage_file("b.txt", b"experiment").unwrap();
+    let exp = store.commit_incremental().unwrap();
+    assert_eq!(store.current_root().unwrap(), Some(exp));
+    assert_eq!(store.branches().unwrap()[DEFAULT_BRANCH], base);
+
+    store.switch_branch(DEFAULT_BRANCH).unwrap();
+    assert_eq!(store.current_root().unwrap(), Some(base));
+    store.stage_file("c.txt", b"main").unwrap();
+    let main = store.commit_incremental().unwrap();
+    let keys = |root| -> Vec<String> {
+        store
+            .list_files(Some(root))
+            .unwrap()
+            .into_iter()
+            .map(|r| r.resource_key)
+            .collect()
+    };
+    assert_eq!(keys(main), ["a.txt", "c.txt"]);
+    assert_eq!(keys(exp), ["a.txt", "b.txt"]);
+    assert_eq!(store.branches().unwrap()["exp"], exp);
+    assert_eq!(store.root_history().unwrap().len(), 3);
+
+    // A branch tip is not removed, and only the latest version is amended.
+    assert!(matches!(
+        store.remove_root(exp),
+        Err(StoreError::InvalidBranch(_))
+    ));
+    store.switch_branch("exp").unwrap();
+    store.stage_file("b.txt", b"amended").unwrap();
+    assert!(matches!(
+        store.amend_commit(),
+        Err(StoreError::InvalidBranch(_))
+    ));
+}
+
+#[test]
+fn size_report_splits_dedup_within_and_across_generations() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    // Uniform content seals into repeats of one chunk under the file's key.
+    let zeros = vec![0u8; 1 << 20];
+    store.stage_file("zeros.bin", &zeros).unwrap();
+    store.commit().unwrap();
+    store.stage_file("zeros.bin", &zeros).unwrap();
+    store.stage_file("small.txt", b"small").unwrap();
+    store.commit().unwrap();
+
+    let report = store.size_report().unwrap();
+    let [first, second] = report.generations.as_slice() else {
+        panic!("expected two generations");
+    };
+    assert_eq!(first.logical_bytes, 1 << 20);
+    assert_eq!(second.logical_bytes, (1 << 20) + 5);
+    assert!(first.unique_chunks < first.chunks);
+    assert!(first.intra_dedup_bytes > 0);
+    assert_eq!(first.inter_dedup_bytes, 0);
+    assert_eq!(second.intra_dedup_bytes, first.intra_dedup_bytes);
+    assert_eq!(
+        second.inter_dedup_bytes,
+        first.stored_bytes - first.intra_dedup_bytes,
+        "every distinct chunk of zeros.bin was introduced by the first generation"
+    );
+    assert_eq!(report.unique_chunks, first.unique_chunks + 1);
+    assert_eq!(
+        report.stored_bytes(),
+        report.physical_bytes + report.intra_dedup_bytes() + report.inter_dedup_bytes()
+    );
+    assert_eq!(
+        report.physical_bytes,
+        count_all_chunk_bytes(&dir.path().join("generations"))
+    );
+}
+
+fn count_all_chunk_bytes(generations_dir: &std::path::Path) -> u64 {
+    let mut n = 0;
+    for gen in std::fs::read_dir(generations_dir).unwrap() {
+        let chunks = gen.unwrap().path().join("chunks");
+        if chunks.is_dir() {
+            for e in std::fs::read_dir(&chunks).unwrap() {
+                n += e.unwrap().metadata().unwrap().len();
+            }
+        }
+    }
+    n
+}
+
+#[test]
+fn chunk_size_distribution_counts_each_distinct_chunk_once() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    assert_eq!(
+        store.chunk_size_distribution(None).unwrap(),
+        Default::default()
+    );
+    store.stage_file("zeros.bin", &vec![0u8; 1 << 20]).unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("small.txt", b"small").unwrap();
+    store.commit().unwrap();
+
+    let report = store.size_report().unwrap();
+    let all = store.chunk_size_distribution(None).unwrap();
+    assert_eq!(all.count, report.unique_chunks);
+    assert_eq!(all.buckets.iter().map(|(_, n)| n).sum::<usize>(), all.count);
+    assert!(all.min <= all.median && all.median <= all.max);
+    assert!((all.mean * all.count as f64 - report.physical_bytes as f64).abs() < 1.0);
+    assert!(all.buckets.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(all
+        .buckets
+        .iter()
+        .all(|&(bound, _)| bound.is_power_of_two()));
+
+    let layer = store.chunk_size_distribution(Some(first)).unwrap();
+    assert_eq!(layer.count, report.generations[0].unique_chunks);
+    assert_eq!(layer.count + 1, all.count, "small.txt adds one chunk");
+    assert!(matches!(
+        store.chunk_size_distribution(Some(Bytes32([9; 32]))),
+        Err(digstore_store::StoreError::GenerationNotFound(_))
+    ));
+}
+
+#[test]
+fn estimate_commit_size_matches_what_the_commit_writes() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("zeros.bin", &vec![0u8; 1 << 20]).unwrap();
+    store.stage_file("noise.bin", &noise(100_000, 3)).unwrap();
+
+    let estimate = store.estimate_commit_size().unwrap();
+    assert_eq!(estimate.files, 2);
+    assert_eq!(estimate.logical_bytes, (1 << 20) + 100_000);
+    assert!(
+        estimate.new_chunks < estimate.chunks,
+        "zeros repeat one chunk"
+    );
+    assert!(estimate.reused_bytes > 0);
+    let generations = dir.path().join("generations");
+    assert_eq!(count_all_chunk_bytes(&generations), 0, "nothing is written");
+
+    store.commit().unwrap();
+    assert_eq!(count_all_chunk_bytes(&generations), estimate.new_bytes);
+    assert_eq!(
+        store.size_report().unwrap().unique_chunks,
+        estimate.new_chunks
+    );
+
+    // The same content again is all dedup; one new file adds only its chunk.
+    store.stage_file("zeros.bin", &vec![0u8; 1 << 20]).unwrap();
+    store.stage_file("noise.bin", &noise(100_000, 3)).unwrap();
+    store.stage_file("new.txt", b"new").unwrap();
+    let again = store.estimate_commit_size().unwrap();
+    assert_eq!(again.new_chunks, 1);
+    store.commit().unwrap();
+    assert_eq!(
+        count_all_chunk_bytes(&generations),
+        estimate.new_bytes + again.new_bytes
+    );
+}
+
+#[derive(Clone, Default)]
+struct Recorded(std::sync::Arc<std::sync::Mutex<Vec<(bool, u64, u64)>>>);
+
+impl digstore_store::ProgressSink for Recorded {
+    fn on_file(&self, done: u64, total: u64) {
+        self.0.lock().unwrap().push((true, done, total));
+    }
+    fn on_bytes(&self, done: u64, total: u64) {
+        self.0.lock().unwrap().push((false, done, total));
+    }
+}
+
+impl Recorded {
+    /// The last `(files, bytes)` reports, then forget everything seen.
+    fn take_last(&self) -> ((u64, u64), (u64, u64)) {
+        let seen = std::mem::take(&mut *self.0.lock().unwrap());
+        let last = |file| {
+            seen.iter()
+                .rev()
+                .find(|r| r.0 == file)
+                .map(|&(_, done, total)| (done, total))
+                .unwrap()
+        };
+        (last(true), last(false))
+    }
+}
+
+#[test]
+fn progress_sink_sees_batch_add_commit_and_restore() {
+    let dir = tempdir().unwrap();
+    let src = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let recorded = Recorded::default();
+    store.set_progress_sink(Some(Box::new(recorded.clone())));
+
+    let files: Vec<_> = [("a.txt", 3usize), ("sub/b.bin", 5000)]
+        .iter()
+        .map(|&(name, len)| {
+            let path = src.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, noise(len, 7)).unwrap();
+            path
+        })
+        .collect();
+    store.add_files(&files, src.path()).unwrap();
+    assert_eq!(recorded.take_last(), ((2, 2), (5003, 5003)));
+
+    let root = store.commit().unwrap();
+    assert_eq!(recorded.take_last(), ((2, 2), (5003, 5003)));
+
+    let out = tempdir().unwrap();
+    store
+        .get_files(&["sub/b.bin".to_string()], root, out.path())
+        .unwrap();
+    assert_eq!(recorded.take_last(), ((1, 1), (5000, 5000)));
+
+    store.set_progress_sink(None);
+    store.export_tar(root, std::io::sink()).unwrap();
+    assert!(recorded.0.lock().unwrap().is_empty());
+
+    let outside = dir.path().join("config.toml");
+    assert!(matches!(
+        store.add_files(&[outside], src.path()),
+        Err(digstore_store::StoreError::PathEscape(_))
+    ));
+}
+
+#[test]
+fn staged_diff_summary_counts_changes_against_the_head() {
+    use digstore_store::FileChange;
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("keep.txt", b"same").unwrap();
+    store.stage_file("edit.txt", b"old body").unwrap();
+    store.stage_file("drop.txt", b"going away").unwrap();
+    let summary = store.staged_diff_summary().unwrap();
+    assert_eq!(
+        (summary.added, summary.modified, summary.deleted),
+        (3, 0, 0)
+    );
+    store.commit().unwrap();
+    assert!(store.staged_diff_summary().unwrap().is_empty());
+
+    // A commit is a snapshot of staging: drop.txt is not restaged.
+    store.stage_file("keep.txt", b"same").unwrap();
+    store.stage_file("edit.txt", b"new body!").unwrap();
+    store.stage_file("new.txt", b"fresh").unwrap();
+    let summary = store.staged_diff_summary().unwrap();
+    assert_eq!(
+        (summary.added, summary.modified, summary.deleted),
+        (1, 1, 1)
+    );
+    assert_eq!(summary.bytes_added, 9 + 5);
+    assert_eq!(summary.bytes_removed, 8 + 10);
+    let files: Vec<(&str, FileChange, Option<u64>, Option<u64>)> = summary
+        .files
+        .iter()
+        .map(|f| (f.resource_key.as_str(), f.change, f.old_size, f.new_size))
+        .collect();
+    assert_eq!(
+        files,
+        [
+            ("drop.txt", FileChange::Deleted, Some(10), None),
+            ("edit.txt", FileChange::Modified, Some(8), Some(9)),
+            ("new.txt", FileChange::Added, None, Some(5)),
+        ]
+    );
+}
+
+#[test]
+fn verify_all_proofs_flags_only_the_tampered_resource() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    store.stage_file("b.txt", b"bravo").unwrap();
+    store.stage_file("c.txt", b"charlie").unwrap();
+    let root = store.commit().unwrap();
+
+    let results = store.verify_all_proofs(root).unwrap();
+    let keys: Vec<&str> = results.iter().map(|r| r.resource_key.as_str()).collect();
+    assert_eq!(keys, ["a.txt", "b.txt", "c.txt"]);
+    assert!(results.iter().all(|r| r.verified && r.error.is_none()));
+
+    // Flip one byte of b.txt's sealed chunk on disk.
+    let manifest = store.generation_manifest(root).unwrap();
+    let rec = manifest
+        .key_table
+        .iter()
+        .find(|r| r.resource_key == "b.txt")
+        .unwrap();
+    let hash = manifest
+        .chunks
+        .iter()
+        .find(|c| c.index == rec.chunk_indices[0])
+        .unwrap()
+        .hash;
+    let chunk = dir
+        .path()
+        .join("generations")
+        .join(root.to_hex())
+        .join("chunks")
+        .join(hash.to_hex());
+    let mut bytes = std::fs::read(&chunk).unwrap();
+    bytes[0] ^= 0xff;
+    std::fs::write(&chunk, bytes).unwrap();
+
+    let store = Store::open(dir.path(), FixedClock::new(1)).unwrap();
+    let results = store.verify_all_proofs(root).unwrap();
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.verified)
+        .map(|r| r.resource_key.as_str())
+        .collect();
+    assert_eq!(failed, ["b.txt"]);
+    assert!(results.iter().all(|r| r.verified == r.error.is_none()));
+}
+
+#[test]
+fn byte_range_proof_opens_only_the_covering_chunks() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let content = noise(600_000, 9);
+    store.stage_file("big.bin", &content).unwrap();
+    store.stage_file("other.txt", b"sibling").unwrap();
+    let root = store.commit().unwrap();
+
+    let urn = digstore_core::Urn {
+        chain: "chia".to_string(),
+        store_id: Bytes32([0x44u8; 32]),
+        root_hash: None,
+        resource_key: Some("big.bin".to_string()),
+    };
+    let key = digstore_crypto::derive_decryption_key(&urn.canonical(), None);
+
+    let proof = store
+        .prove_byte_range(root, "big.bin", 200_000..200_100)
+        .unwrap();
+    assert!(proof.chunks.len() > 2, "content spans several chunks");
+    assert!(proof.first_chunk > 0);
+    assert_eq!(proof.proof.root, root);
+    assert_eq!(
+        proof.verify_with(HashAlgorithm::Sha256, &key).unwrap(),
+        &content[200_000..200_100]
+    );
+
+    // A chunk before the range is never opened, but it still feeds the leaf.
+    let mut tampered = proof.clone();
+    tampered.chunks[0][0] ^= 0xff;
+    assert!(tampered.verify_with(HashAlgorithm::Sha256, &key).is_none());
+
+    let mut misplaced = proof.clone();
+    misplaced.first_chunk -= 1;
+    assert!(misplaced.verify_with(HashAlgorithm::Sha256, &key).is_none());
+
+    // A range past the end is clamped; one selecting nothing is refused.
+    let tail = store
+        .prove_byte_range(root, "big.bin", 599_990..u64::MAX)
+        .unwrap();
+    assert_eq!(tail.range, 599_990..600_000);
+    assert_eq!(
+        tail.verify_with(HashAlgorithm::Sha256, &key).unwrap(),
+        &content[599_990..]
+    );
+    assert!(matches!(
+        store.prove_byte_range(root, "big.bin", 600_000..600_010),
+        Err(digstore_store::StoreError::InvalidRange(_))
+    ));
+}
+
+#[test]
+fn refresh_picks_up_a_commit_and_salt_rotation_made_elsewhere() {
+    use digstore_core::SecretSalt;
+
+    let dir = tempdir().unwrap();
+    let mut cfg = config(dir.path());
+    cfg.visibility = Visibility::Private(SecretSalt([0x11; 32]));
+    let mut writer = Store::init(cfg, FixedClock::new(1)).unwrap();
+    writer.stage_file("note.txt", b"first").unwrap();
+    let first = writer.commit().unwrap();
+
+    let mut reader = Store::open_read_only(dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(reader.read_resource(first, "note.txt").unwrap(), b"first");
+
+    // Another instance rotates the salt, re-sealing the head, then commits.
+    let new_salt = SecretSalt([0x22; 32]);
+    writer.rotate_salt(new_salt, false).unwrap();
+    writer.stage_file("note.txt", b"second").unwrap();
+    let second = writer.commit().unwrap();
+
+    assert_eq!(reader.refresh().unwrap(), Some(second));
+    assert_eq!(reader.config().visibility, Visibility::Private(new_salt));
+    assert_eq!(reader.read_resource(second, "note.txt").unwrap(), b"second");
+}
+
+#[test]
+fn rotate_salt_reseals_head_under_new_key_and_bumps_epoch() {
+    use digstore_core::{SecretSalt, Urn};
+    use digstore_store::GenerationManifest;
+
+    let dir = tempdir().unwrap();
+    let mut cfg = config(dir.path());
+    cfg.visibility = Visibility::Private(SecretSalt([0x11; 32]));
+    let mut store = Store::init(cfg, FixedClock::new(1)).unwrap();
+    let big = vec![0x5Au8; 300_000];
+    store.stage_file("data.bin", &big).unwrap();
+    store.stage_file("note.txt", b"secret note").unwrap();
+    let old_root = store.commit().unwrap();
+    let old_manifest = store.generation_manifest(old_root).unwrap();
+
+    // Dry run: reports the work, changes nothing.
+    let new_salt = SecretSalt([0x22; 32]);
+    let plan = store.rotate_salt(new_salt, true).unwrap();
+    assert_eq!(plan.resources, 2);
+    assert_eq!(plan.chunks, old_manifest.chunks.len());
+    assert_eq!(plan.key_epoch, 1);
+    assert_eq!(plan.root, None);
+    assert_eq!(store.current_root().unwrap(), Some(old_root));
+
+    let done = store.rotate_salt(new_salt, false).unwrap();
+    let new_root = done.root.unwrap();
+    assert_ne!(new_root, old_root);
+    assert_eq!(store.current_root().unwrap(), Some(new_root));
+    assert_eq!(
+        store.config().visibility,
+        Visibility::Private(new_salt),
+        "config switched to the new salt"
+    );
+    let reopened = Store::open(dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(reopened.config().visibility, Visibility::Private(new_salt));
+    assert_eq!(
+        reopened.config().salt_keyring,
+        [(0, Some(SecretSalt([0x11; 32]))), (1, Some(new_salt))].into(),
+        "the keyring keeps the salt of every epoch"
+    );
+
+    // The old generation still opens, under its own epoch's salt.
+    assert_eq!(
+        reopened.read_resource(old_root, "note.txt").unwrap(),
+        b"secret note"
+    );
+    assert_eq!(reopened.read_resource(old_root, "data.bin").unwrap(), big);
+
+    // The new generation decrypts under the new salt and records epoch 1; the
+    // old generation keeps epoch 0.
+    let m = GenerationManifest::read_from(store.paths().generation_manifest(&new_root.to_hex()))
+        .unwrap();
+    assert_eq!(m.key_epoch, 1);
+    assert_eq!(store.generation_manifest(old_root).unwrap().key_epoch, 0);
+    let rec = m
+        .key_table
+        .iter()
+        .find(|r| r.resource_key == "note.txt")
+        .unwrap();
+    let urn = Urn {
+        chain: "chia".to_string(),
+        store_id: Bytes32([0x44u8; 32]),
+        root_hash: None,
+        resource_key: Some("note.txt".to_string()),
+    };
+    let ct = store
+        .resolve_chunk(m.chunks[rec.chunk_indices[0] as usize].hash)
+        .unwrap();
+    let new_key = digstore_crypto::derive_decryption_key(&urn.canonical(), Some(&new_salt));
+    assert_eq!(
+        digstore_crypto::decrypt_chunk(&new_key, &ct).unwrap(),
+        b"secret note"
+    );
+    let old_key =
+        digstore_crypto::derive_decryption_key(&urn.canonical(), Some(&SecretSalt([0x11; 32])));
+    assert!(digstore_crypto::decrypt_chunk(&old_key, &ct).is_err());
+
+    // A later ordinary commit inherits the rotated epoch.
+    store.stage_file("more.txt", b"more").unwrap();
+    let next = store.commit().unwrap();
+    assert_eq!(store.generation_manifest(next).unwrap().key_epoch, 1);
+}
+
+#[test]
+fn rotate_salt_reseals_what_a_changes_only_head_reads_through() {
+    use digstore_core::SecretSalt;
+
+    let dir = tempdir().unwrap();
+    let (mut store, base, head) = layered_store(dir.path());
+    let plan = store.rotate_salt(SecretSalt([0x33; 32]), true).unwrap();
+    assert_eq!(
+        plan.resources, 2,
+        "a.txt from the head, b.txt from its parent"
+    );
+
+    let rotated = store
+        .rotate_salt(SecretSalt([0x33; 32]), false)
+        .unwrap()
+        .root
+        .unwrap();
+    let manifest = store.generation_manifest(rotated).unwrap();
+    assert_eq!(manifest.parent, None, "the rotated head is self-contained");
+    assert_eq!(manifest.key_table.len(), 2);
+    assert_eq!(
+        store.read_resource(rotated, "a.txt").unwrap(),
+        b"alpha v2 needle"
+    );
+    assert_eq!(
+        store.read_resource(rotated, "b.txt").unwrap(),
+        b"bravo needle"
+    );
+
+    // The public layers it came from still read under epoch 0.
+    let reopened = Store::open(dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(reopened.config().epoch_visibility(0), Visibility::Public);
+    assert_eq!(
+        reopened.read_resource(head, "b.txt").unwrap(),
+        b"bravo needle"
+    );
+    assert_eq!(reopened.read_resource(base, "a.txt").unwrap(), b"alpha");
+}
+
+#[test]
+fn rotate_salt_refuses_dirty_staging() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"pending").unwrap();
+    let err = store
+        .rotate_salt(digstore_core::SecretSalt([1; 32]), false)
+        .unwrap_err();
+    assert!(matches!(err, digstore_store::StoreError::DirtyStaging));
+}
+
+/// Deterministic pseudo-random bytes, so content-defined chunking yields
+/// several DISTINCT chunks (a constant fill would dedup to identical ones).
+fn noise(len: usize, seed: u64) -> Vec<u8> {
+    let mut x = seed;
+    (0..len)
+        .map(|_| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (x >> 33) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn read_resource_returns_plaintext_and_rejects_reordered_chunks() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let content = noise(400_000, 7);
+    store.stage_file("data.bin", &content).unwrap();
+    let root = store.commit().unwrap();
+    assert_eq!(store.read_resource(root, "data.bin").unwrap(), content);
+
+    // Swap the first two chunks of the resource in the persisted manifest. Each
+    // chunk still decrypts on its own, but the sequence no longer folds to root.
+    let path = store.paths().generation_manifest(&root.to_hex());
+    let mut manifest = digstore_store::GenerationManifest::read_from(&path).unwrap();
+    assert!(manifest.key_table[0].chunk_indices.len() > 1);
+    manifest.key_table[0].chunk_indices.swap(0, 1);
+    manifest.write_to(&path).unwrap();
+
+    let err = store.read_resource(root, "data.bin").unwrap_err();
+    assert!(matches!(
+        err,
+        digstore_store::StoreError::RootMismatch { .. }
+    ));
+}
+
+#[test]
+fn read_resource_checks_only_its_own_chunks_against_the_leaf_cache() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let a = noise(400_000, 3);
+    let b = noise(400_000, 5);
+    store.stage_file("a.bin", &a).unwrap();
+    store.stage_file("b.bin", &b).unwrap();
+    let root = store.commit().unwrap();
+
+    // Reorder b.bin's chunks only. a.bin's leaf still folds to root along its
+    // own path, so it reads without b.bin's chunks being touched.
+    let path = store.paths().generation_manifest(&root.to_hex());
+    let mut manifest = digstore_store::GenerationManifest::read_from(&path).unwrap();
+    let rec = manifest
+        .key_table
+        .iter_mut()
+        .find(|r| r.resource_key == "b.bin")
+        .unwrap();
+    assert!(rec.chunk_indices.len() > 1);
+    rec.chunk_indices.swap(0, 1);
+    manifest.write_to(&path).unwrap();
+
+    assert_eq!(store.read_resource(root, "a.bin").unwrap(), a);
+    assert!(matches!(
+        store.read_resource(root, "b.bin").unwrap_err(),
+        digstore_store::StoreError::RootMismatch { .. }
+    ));
+
+    // Without the leaf cache the whole tree is rebuilt, which catches b.bin.
+    std::fs::remove_file(
+        store
+            .paths()
+            .generation_dir(&root.to_hex())
+            .join("leaves.bin"),
+    )
+    .unwrap();
+    assert!(matches!(
+        store.read_resource(root, "a.bin").unwrap_err(),
+        digstore_store::StoreError::RootMismatch { .. }
+    ));
+}
+
+#[test]
+fn read_resource_is_identical_at_any_read_parallelism() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let content = noise(1_500_000, 11);
+    store.stage_file("big.bin", &content).unwrap();
+    let root = store.commit().unwrap();
+    let manifest = store.generation_manifest(root).unwrap();
+    assert!(manifest.key_table[0].chunk_indices.len() > 4);
+
+    for threads in [0, 1, 3, 64] {
+        store.set_read_parallelism(threads);
+        store.set_chunk_cache_capacity(0);
+        assert_eq!(store.read_resource(root, "big.bin").unwrap(), content);
+    }
+}
+
+#[test]
+fn mapped_chunk_reads_match_owned_reads() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let content = noise(600_000, 5);
+    store.stage_file("m.bin", &content).unwrap();
+    let root = store.commit().unwrap();
+    let manifest = store.generation_manifest(root).unwrap();
+    let first = manifest.chunks[0].hash;
+
+    let mapped = store.map_chunk(first).unwrap();
+    assert!(mapped.is_mapped());
+    assert_eq!(&*mapped, store.resolve_chunk(first).unwrap().as_slice());
+    drop(mapped);
+
+    store.set_chunk_cache_capacity(0);
+    assert_eq!(store.read_resource(root, "m.bin").unwrap(), content);
+    store.set_mmap_reads(false);
+    assert!(!store.map_chunk(first).unwrap().is_mapped());
+    assert_eq!(store.read_resource(root, "m.bin").unwrap(), content);
+}
+
+#[test]
+fn list_files_reports_resources_at_head_and_historical_roots() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    assert!(store.list_files(None).unwrap().is_empty());
+
+    store.stage_file("z.txt", b"zz").unwrap();
+    store.stage_file("a/b.txt", b"abc").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("only.txt", b"x").unwrap();
+    store.commit().unwrap();
+
+    let head: Vec<String> = store
+        .list_files(None)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.resource_key)
+        .collect();
+    assert_eq!(head, vec!["only.txt"]);
+
+    let old = store.list_files(Some(first)).unwrap();
+    let keys: Vec<&str> = old.iter().map(|r| r.resource_key.as_str()).collect();
+    assert_eq!(keys, vec!["a/b.txt", "z.txt"]);
+    assert_eq!(old[0].total_size, 3);
+
+    let err = store.list_files(Some(Bytes32([9; 32]))).unwrap_err();
+    assert!(matches!(
+        err,
+        digstore_store::StoreError::GenerationNotFound(_)
+    ));
+}
+
+#[test]
+fn resolve_chunk_serves_repeat_reads_from_the_cache() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("note.txt", b"cached note").unwrap();
+    let root = store.commit().unwrap();
+    let hash = store.generation_manifest(root).unwrap().chunks[0].hash;
+
+    let first = store.resolve_chunk(hash).unwrap();
+    // Remove the file: the second read must come from the cache.
+    std::fs::remove_file(store.paths().chunk_file(&root.to_hex(), &hash.to_hex())).unwrap();
+    assert_eq!(store.resolve_chunk(hash).unwrap(), first);
+    let stats = store.cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+    // With caching disabled the missing file surfaces again.
+    store.set_chunk_cache_capacity(0);
+    assert!(matches!(
+        store.resolve_chunk(hash).unwrap_err(),
+        digstore_store::StoreError::ChunkNotFound(_)
+    ));
+}
+
+#[test]
+fn chunk_index_picks_up_generations_committed_by_another_handle() {
+    let dir = tempdir().unwrap();
+    let mut a = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    a.stage_file("one.txt", b"first").unwrap();
+    let r1 = a.commit().unwrap();
+    // Prime `a`'s chunk index.
+    let h1 = a.generation_manifest(r1).unwrap().chunks[0].hash;
+    a.resolve_chunk(h1).unwrap();
+
+    // A second handle commits a new chunk the first handle has never indexed.
+    let mut b = Store::open(dir.path(), FixedClock::new(2)).unwrap();
+    b.stage_file("two.txt", b"second").unwrap();
+    let r2 = b.commit().unwrap();
+    let h2 = b.generation_manifest(r2).unwrap().chunks[0].hash;
+
+    let body = a.resolve_chunk(h2).unwrap();
+    assert_eq!(digstore_crypto::sha256(&body), h2);
+}
+
+#[test]
+fn get_files_restores_a_batch_of_resources() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("docs/a.md", b"alpha").unwrap();
+    store.stage_file("docs/deep/b.md", b"beta").unwrap();
+    store.stage_file("c.txt", b"gamma").unwrap();
+    let root = store.commit().unwrap();
+
+    let dest = dir.path().join("restore");
+    let keys = vec!["docs/a.md".to_string(), "docs/deep/b.md".to_string()];
+    let report = store.get_files(&keys, root, &dest).unwrap();
+    assert_eq!((report.files, report.bytes), (2, 9));
+    assert_eq!(std::fs::read(dest.join("docs/a.md")).unwrap(), b"alpha");
+    assert_eq!(std::fs::read(dest.join("docs/deep/b.md")).unwrap(), b"beta");
+    assert!(!dest.join("c.txt").exists());
+
+    let missing = store.get_files(&["nope.txt".to_string()], root, &dest);
+    assert!(matches!(
+        missing,
+        Err(digstore_store::StoreError::ResourceNotFound { .. })
+    ));
+}
+
+#[test]
+fn read_only_open_reads_but_never_writes() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    let root = store.commit().unwrap();
+    let data_dir = store.paths().root().to_path_buf();
+    let staging = store.paths().staging_file();
+    drop(store);
+    std::fs::remove_file(&staging).unwrap();
+
+    let mut ro = Store::open_read_only(&data_dir, FixedClock::new(2)).unwrap();
+    assert!(ro.is_read_only());
+    assert_eq!(ro.current_root().unwrap(), Some(root));
+    assert_eq!(ro.read_resource(root, "a.txt").unwrap(), b"alpha");
+    assert!(matches!(
+        ro.stage_file("b.txt", b"beta"),
+        Err(digstore_store::StoreError::ReadOnly(_))
+    ));
+    assert!(matches!(
+        ro.commit(),
+        Err(digstore_store::StoreError::ReadOnly(_))
+    ));
+    assert!(
+        !staging.exists(),
+        "a read-only open must not create staging"
+    );
+
+    // A store with no history file reads as empty instead of creating one.
+    let history = ro.paths().history_file();
+    std::fs::remove_file(&history).unwrap();
+    assert_eq!(ro.current_root().unwrap(), None);
+    assert!(ro.root_history().unwrap().is_empty());
+    assert!(!history.exists());
+}
+
+#[test]
+fn amend_commit_folds_staging_into_the_head_and_keeps_history_length() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    assert!(matches!(
+        store.amend_commit(),
+        Err(digstore_store::StoreError::NothingToAmend)
+    ));
+
+    store.stage_file("first.txt", b"1").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    store.stage_file("b.txt", b"beta").unwrap();
+    let head = store.commit().unwrap();
+
+    // Nothing staged: re-sealing the head reproduces its root.
+    assert_eq!(store.amend_commit().unwrap(), head);
+    assert_eq!(store.roothash_history().unwrap(), vec![first, head]);
+
+    store.stage_file("b.txt", b"BETA").unwrap();
+    store.stage_file("c.txt", b"gamma").unwrap();
+    let amended = store.amend_commit().unwrap();
+    assert_ne!(amended, head);
+    assert_eq!(store.roothash_history().unwrap(), vec![first, amended]);
+    let history = store.root_history().unwrap();
+    assert_eq!(history[1].id, 1);
+    assert_eq!(store.generation_manifest(amended).unwrap().generation_id, 1);
+
+    assert_eq!(store.read_resource(amended, "a.txt").unwrap(), b"alpha");
+    assert_eq!(store.read_resource(amended, "b.txt").unwrap(), b"BETA");
+    assert_eq!(store.read_resource(amended, "c.txt").unwrap(), b"gamma");
+    assert!(store.read_resource(amended, "first.txt").is_err());
+    assert_eq!(store.list_files(None).unwrap().len(), 3);
+    // Staging is consumed like a normal commit.
+    assert!(matches!(
+        store.commit(),
+        Err(digstore_store::StoreError::EmptyStaging)
+    ));
+}
+
+#[test]
+fn squash_collapses_a_range_into_its_last_generation() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut roots = Vec::new();
+    for seed in 1..=4 {
+        store.stage_file("data.bin", &noise(5000, seed)).unwrap();
+        if seed == 3 {
+            // Carried into the squash target so its chunks must survive the move.
+            store.stage_file("keep.bin", &noise(3000, 9)).unwrap();
+        }
+        roots.push(store.commit().unwrap());
+    }
+    store.stage_file("keep.bin", &noise(3000, 9)).unwrap();
+    let after = store.commit().unwrap();
+    let before = store.read_resource(roots[3], "data.bin").unwrap();
+
+    let preview = store.squash(roots[1], roots[3], true).unwrap();
+    assert_eq!(preview.dropped, vec![roots[1], roots[2]]);
+    assert!(preview.chunks_freed > 0);
+    assert_eq!(store.roothash_history().unwrap().len(), 5);
+
+    let done = store.squash(roots[1], roots[3], false).unwrap();
+    assert_eq!(done, preview);
+    assert_eq!(
+        store.roothash_history().unwrap(),
+        vec![roots[0], roots[3], after]
+    );
+    assert_eq!(store.read_resource(roots[3], "data.bin").unwrap(), before);
+    assert_eq!(
+        store.read_resource(after, "keep.bin").unwrap(),
+        noise(3000, 9)
+    );
+    assert_eq!(store.generation_manifest(after).unwrap().generation_id, 2);
+    assert!(matches!(
+        store.read_resource(roots[2], "data.bin"),
+        Err(digstore_store::StoreError::GenerationNotFound(_))
+    ));
+    assert!(matches!(
+        store.squash(roots[3], roots[0], false),
+        Err(digstore_store::StoreError::InvalidSquash(_))
+    ));
+}
+
+#[test]
+fn stat_file_reports_layout_without_reading_chunks() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let body = noise(1_500_000, 5);
+    store.stage_file("big.bin", &body).unwrap();
+    let root = store.commit().unwrap();
+
+    // Remove every chunk file: stat only needs the manifest.
+    let chunks_dir = dir
+        .path()
+        .join("generations")
+        .join(root.to_hex())
+        .join("chunks");
+    std::fs::remove_dir_all(&chunks_dir).unwrap();
+
+    let stat = store.stat_file(root, "big.bin").unwrap();
+    assert_eq!(stat.resource_key, "big.bin");
+    assert_eq!(stat.size, body.len() as u64);
+    assert!(stat.chunks.len() > 1);
+    let listed = store.list_files(Some(root)).unwrap();
+    assert_eq!(stat.static_key, listed[0].static_key);
+    let histogram = stat.size_histogram();
+    assert_eq!(
+        histogram.iter().map(|(_, n)| n).sum::<usize>(),
+        stat.chunks.len()
+    );
+    assert!(histogram.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(stat
+        .chunks
+        .iter()
+        .all(|c| histogram.iter().any(|(bound, _)| c.size <= *bound)));
+    assert!(matches!(
+        store.stat_file(root, "nope"),
+        Err(digstore_store::StoreError::ResourceNotFound { .. })
+    ));
+}
+
+#[test]
+fn export_manifest_is_canonical_and_sorted() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("z/last.txt", b"zzz").unwrap();
+    store.stage_file("a/first.txt", b"aaa").unwrap();
+    store.stage_file("big.bin", &noise(1_500_000, 8)).unwrap();
+    let root = store.commit().unwrap();
+
+    let export = store.export_manifest(root).unwrap();
+    assert_eq!(export.root, root.to_hex());
+    let paths: Vec<&str> = export.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["a/first.txt", "big.bin", "z/last.txt"]);
+    let big = &export.files[1];
+    assert_eq!(big.size, 1_500_000);
+    assert_eq!(
+        big.chunks.len(),
+        store.stat_file(root, "big.bin").unwrap().chunks.len()
+    );
+
+    // A fresh handle exports byte-identical JSON.
+    let again = Store::open(dir.path(), FixedClock::new(99)).unwrap();
+    assert_eq!(
+        export.to_canonical_json().unwrap(),
+        again
+            .export_manifest(root)
+            .unwrap()
+            .to_canonical_json()
+            .unwrap()
+    );
+}
+
+#[test]
+fn sign_root_records_a_signature_that_verifies_only_for_its_key() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"signed").unwrap();
+    let root = store.commit().unwrap();
+    let (sk, pk) = digstore_crypto::bls_keygen(&[3u8; 32]);
+    let (_, other) = digstore_crypto::bls_keygen(&[4u8; 32]);
+
+    assert!(!store.verify_root_signature(root, &pk).unwrap());
+    let sig = store.sign_root(root, &sk).unwrap();
+    assert_eq!(sig.public_key, pk);
+    // Re-signing with the same key replaces rather than duplicates.
+    store.sign_root(root, &sk).unwrap();
+
+    let reopened = Store::open_read_only(dir.path(), FixedClock::new(2)).unwrap();
+    assert_eq!(reopened.root_signatures(root).unwrap(), vec![sig]);
+    assert!(reopened.verify_root_signature(root, &pk).unwrap());
+    assert!(!reopened.verify_root_signature(root, &other).unwrap());
+    assert!(reopened.sign_root(root, &sk).is_err());
+    assert!(store.sign_root(Bytes32([9u8; 32]), &sk).is_err());
+}
+
+#[test]
+fn commit_incremental_matches_a_full_commit_of_the_same_content() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let big = noise(1_500_000, 11);
+    store.stage_file("big.bin", &big).unwrap();
+    store.stage_file("a.txt", b"one").unwrap();
+    store.stage_file("b.txt", b"two").unwrap();
+    store.commit().unwrap();
+
+    // Same resource set: only a.txt's leaf path is rehashed.
+    store.stage_file("a.txt", b"one, edited").unwrap();
+    let edited = store.commit_incremental().unwrap();
+    // A new resource shifts leaf positions; the head's leaf cache is rebuilt
+    // from the stored chunks when it is missing.
+    std::fs::remove_file(
+        store
+            .paths()
+            .generation_dir(&edited.to_hex())
+            .join("leaves.bin"),
+    )
+    .unwrap();
+    store.stage_file("c.txt", b"three").unwrap();
+    let added = store.commit_incremental().unwrap();
+
+    let full_dir = tempdir().unwrap();
+    let mut full = Store::init(config(full_dir.path()), FixedClock::new(1)).unwrap();
+    full.stage_file("big.bin", &big).unwrap();
+    full.stage_file("a.txt", b"one, edited").unwrap();
+    full.stage_file("b.txt", b"two").unwrap();
+    assert_eq!(full.commit().unwrap(), edited);
+    full.stage_file("big.bin", &big).unwrap();
+    full.stage_file("a.txt", b"one, edited").unwrap();
+    full.stage_file("b.txt", b"two").unwrap();
+    full.stage_file("c.txt", b"three").unwrap();
+    assert_eq!(full.commit().unwrap(), added);
+
+    assert_eq!(store.log().unwrap().len(), 3);
+    assert_eq!(store.read_resource(added, "big.bin").unwrap(), big);
+    assert_eq!(store.read_resource(added, "c.txt").unwrap(), b"three");
+    // The export re-verifies every leaf against the root.
+    assert_eq!(store.export_manifest(added).unwrap().files.len(), 4);
+    assert!(matches!(
+        store.commit_incremental(),
+        Err(digstore_store::StoreError::EmptyStaging)
+    ));
+}
+
+#[test]
+#[ignore = "benchmark: 10k-file commit vs incremental commit of one change; run with --ignored --nocapture"]
+fn bench_incremental_commit_of_one_change_in_10k_files() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let files: Vec<(String, Vec<u8>)> = (0..10_000)
+        .map(|i| (format!("f/{i:05}.txt"), noise(256, i)))
+        .collect();
+    let stage_all = |store: &mut Store<FixedClock>| {
+        for (key, body) in &files {
+            store.stage_file(key, body).unwrap();
+        }
+    };
+
+    stage_all(&mut store);
+    let t = std::time::Instant::now();
+    store.commit().unwrap();
+    let first = t.elapsed();
+
+    // Full path: every resource is re-staged, re-chunked and re-encrypted.
+    stage_all(&mut store);
+    store.stage_file("f/05000.txt", b"changed").unwrap();
+    let t = std::time::Instant::now();
+    let full = store.commit().unwrap();
+    let full_time = t.elapsed();
+
+    store.stage_file("f/05000.txt", b"changed again").unwrap();
+    let t = std::time::Instant::now();
+    store.commit_incremental().unwrap();
+    let incremental = t.elapsed();
+
+    eprintln!("initial commit:      {first:?}");
+    eprintln!("full re-commit:      {full_time:?} ({})", full.to_hex());
+    eprintln!("incremental commit:  {incremental:?}");
+    assert!(incremental < full_time);
+}
+
+#[test]
+fn blake3_store_addresses_chunks_and_builds_its_tree_with_blake3() {
+    let sha_dir = tempdir().unwrap();
+    let b3_dir = tempdir().unwrap();
+    let mut sha = Store::init(config(sha_dir.path()), FixedClock::new(1)).unwrap();
+    let mut b3_config = config(b3_dir.path());
+    b3_config.hash_algorithm = HashAlgorithm::Blake3;
+    Store::init(b3_config, FixedClock::new(1)).unwrap();
+    // The choice is read back from config.toml on open.
+    let mut b3 = Store::open(b3_dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(b3.config().hash_algorithm, HashAlgorithm::Blake3);
+
+    let big = noise(1_500_000, 21);
+    for store in [&mut sha, &mut b3] {
+        store.stage_file("big.bin", &big).unwrap();
+        store.stage_file("a.txt", b"alpha").unwrap();
+    }
+    let sha_root = sha.commit().unwrap();
+    let b3_root = b3.commit().unwrap();
+    assert_ne!(sha_root, b3_root);
+
+    let stat = b3.stat_file(b3_root, "big.bin").unwrap();
+    assert!(stat.chunks.len() > 1);
+    for chunk in &stat.chunks {
+        let ct = b3.resolve_chunk(chunk.hash).unwrap();
+        assert_eq!(chunk.hash, HashAlgorithm::Blake3.digest(&ct));
+    }
+    assert_eq!(b3.read_resource(b3_root, "big.bin").unwrap(), big);
+
+    let proof = b3.prove_resource(b3_root, "a.txt").unwrap();
+    assert_eq!(proof.root, b3_root);
+    assert!(proof.verify_with(HashAlgorithm::Blake3));
+    assert!(!proof.verify());
+    assert_eq!(b3.export_manifest(b3_root).unwrap().files.len(), 2);
+
+    b3.stage_file("a.txt", b"alpha, edited").unwrap();
+    let next = b3.commit_incremental().unwrap();
+    assert!(b3
+        .prove_resource(next, "a.txt")
+        .unwrap()
+        .verify_with(HashAlgorithm::Blake3));
+}
+
+#[test]
+fn open_rolls_back_a_commit_cut_short_before_its_history_entry() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"first").unwrap();
+    let first = store.commit().unwrap();
+    store.stage_file("b.bin", &noise(1_500_000, 9)).unwrap();
+    let second = store.commit().unwrap();
+
+    // Simulate a crash partway through appending `second` to roots.log: its
+    // generation dir is fully written, the marker is still there, and the
+    // history line is torn.
+    let paths = store.paths().clone();
+    drop(store);
+    std::fs::write(paths.pending_commit_file(), second.to_hex()).unwrap();
+    let log = std::fs::read_to_string(paths.history_file()).unwrap();
+    let torn = log.len() - 20;
+    std::fs::write(paths.history_file(), &log[..torn]).unwrap();
+
+    let mut store = Store::open(dir.path(), FixedClock::new(2)).unwrap();
+    assert_eq!(store.current_root().unwrap(), Some(first));
+    assert_eq!(store.roothash_history().unwrap(), vec![first]);
+    assert!(!paths.generation_dir(&second.to_hex()).exists());
+    assert!(!paths.pending_commit_file().exists());
+    assert_eq!(store.read_resource(first, "a.txt").unwrap(), b"first");
+    assert_eq!(store.recover().unwrap(), None);
+
+    // The rolled-back content commits again cleanly.
+    store.stage_file("b.bin", &noise(1_500_000, 9)).unwrap();
+    assert_eq!(store.commit().unwrap(), second);
+    assert_eq!(store.roothash_history().unwrap(), vec![first, second]);
+    assert_eq!(
+        store.read_resource(second, "b.bin").unwrap(),
+        noise(1_500_000, 9)
+    );
+}
+
+#[test]
+fn a_read_only_open_leaves_a_commit_in_progress_alone() {
+    let dir = tempdir().unwrap();
+    let mut writer = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    writer.stage_file("a.txt", b"first").unwrap();
+    let root = writer.commit().unwrap();
+    // What a live writer's next commit looks like before its history line
+    // lands: a marker naming a generation dir roots.log does not list yet.
+    let pending = Bytes32([0x77; 32]);
+    let paths = writer.paths().clone();
+    std::fs::create_dir_all(paths.generation_dir(&pending.to_hex())).unwrap();
+    std::fs::write(paths.pending_commit_file(), pending.to_hex()).unwrap();
+
+    let mut reader = Store::open_read_only(dir.path(), FixedClock::new(1)).unwrap();
+    assert_eq!(reader.current_root().unwrap(), Some(root));
+    assert!(matches!(
+        reader.recover(),
+        Err(digstore_store::StoreError::ReadOnly(_))
+    ));
+    assert!(paths.pending_commit_file().exists());
+    assert!(paths.generation_dir(&pending.to_hex()).exists());
+
+    // The lock holder is the one that rolls it back.
+    assert_eq!(writer.recover().unwrap(), Some(pending));
+    assert!(!paths.generation_dir(&pending.to_hex()).exists());
+}
+
+#[test]
+fn recover_at_rolls_back_a_commit_laid_down_outside_the_store() {
+    let dir = tempdir().unwrap();
+    let paths = {
+        let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+        store.stage_file("a.txt", b"first").unwrap();
+        store.commit().unwrap();
+        store.paths().clone()
+    };
+    // Another writer's commit, cut short after it compiled the module.
+    let pending = Bytes32([0x77; 32]);
+    std::fs::create_dir_all(paths.generation_dir(&pending.to_hex())).unwrap();
+    std::fs::create_dir_all(paths.modules_dir()).unwrap();
+    std::fs::write(paths.module_file(&pending.to_hex()), b"module").unwrap();
+    std::fs::write(paths.pending_commit_file(), pending.to_hex()).unwrap();
+
+    assert_eq!(
+        Store::recover_at(dir.path(), FixedClock::new(1)).unwrap(),
+        Some(pending)
+    );
+    assert!(!paths.generation_dir(&pending.to_hex()).exists());
+    assert!(!paths.module_file(&pending.to_hex()).exists());
+    assert!(!paths.pending_commit_file().exists());
+    assert_eq!(
+        Store::recover_at(dir.path(), FixedClock::new(1)).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn recover_leaves_an_earlier_entry_of_the_pending_root_alone() {
+    use digstore_store::DEFAULT_BRANCH;
+
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"first").unwrap();
+    let first = store.commit().unwrap();
+    store.create_branch("side", None).unwrap();
+    store.stage_file("a.txt", b"second").unwrap();
+    let second = store.commit().unwrap();
+    // Re-committing `first` on top was cut short before its entry (id 2)
+    // landed.
+    let paths = store.paths().clone();
+    std::fs::write(paths.pending_commit_file(), format!("{} 2", first.to_hex())).unwrap();
+
+    assert_eq!(store.recover().unwrap(), Some(first));
+    assert_eq!(store.roothash_history().unwrap(), vec![first, second]);
+    assert!(paths.generation_dir(&first.to_hex()).exists());
+    assert_eq!(store.read_resource(first, "a.txt").unwrap(), b"first");
+    assert_eq!(store.branches().unwrap()[DEFAULT_BRANCH], second);
+}
+
+#[test]
+fn recover_keeps_a_commit_whose_history_entry_landed() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("a.txt", b"first").unwrap();
+    let root = store.commit().unwrap();
+    // Crash after the roots.log append, before the marker was removed.
+    std::fs::write(store.paths().pending_commit_file(), root.to_hex()).unwrap();
+
+    assert_eq!(store.recover().unwrap(), None);
+    assert!(!store.paths().pending_commit_file().exists());
+    assert_eq!(store.read_resource(root, "a.txt").unwrap(), b"first");
+}
+
+#[test]
+fn prune_keeps_the_newest_generations_and_frees_the_rest() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut roots = Vec::new();
+    for seed in 1..=5 {
+        store.stage_file("data.bin", &noise(5000, seed)).unwrap();
+        // Introduced by the first generation, used by every one after.
+        store.stage_file("shared.bin", &noise(3000, 99)).unwrap();
+        roots.push(store.commit().unwrap());
+    }
+
+    let preview = store.prune(None, Some(2), true).unwrap();
+    assert_eq!(preview.dropped, roots[..3].to_vec());
+    assert!(preview.chunks_freed > 0);
+    assert_eq!(store.roothash_history().unwrap().len(), 5);
+
+    let done = store.prune(None, Some(2), false).unwrap();
+    assert_eq!(done, preview);
+    assert_eq!(store.roothash_history().unwrap(), roots[3..].to_vec());
+    assert_eq!(
+        store.read_resource(roots[3], "shared.bin").unwrap(),
+        noise(3000, 99)
+    );
+    assert_eq!(
+        store.read_resource(roots[4], "data.bin").unwrap(),
+        noise(5000, 5)
+    );
+    assert_eq!(
+        store.generation_manifest(roots[4]).unwrap().generation_id,
+        1
+    );
+    assert!(!store.paths().generation_dir(&roots[0].to_hex()).exists());
+    assert!(matches!(
+        store.prune(None, None, false),
+        Err(digstore_store::StoreError::InvalidPrune(_))
+    ));
+}
+
+#[test]
+fn prune_before_a_generation_id_or_time_never_drops_the_head() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut roots = Vec::new();
+    for seed in 1..=3 {
+        store.stage_file("data.bin", &noise(2000, seed)).unwrap();
+        roots.push(store.commit().unwrap());
+    }
+    let by_id = store
+        .prune(Some(PruneBefore::Generation(1)), None, true)
+        .unwrap();
+    assert_eq!(by_id.dropped, vec![roots[0]]);
+    // Every generation has timestamp 1; the head survives a cutoff after it.
+    let by_time = store
+        .prune(Some(PruneBefore::Timestamp(10)), None, false)
+        .unwrap();
+    assert_eq!(by_time.dropped, roots[..2].to_vec());
+    assert_eq!(store.roothash_history().unwrap(), vec![roots[2]]);
+}
+
+#[test]
+fn remove_root_drops_one_generation_but_refuses_the_head() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut roots = Vec::new();
+    for seed in 1..=3 {
+        store.stage_file("data.bin", &noise(2000, seed)).unwrap();
+        store.stage_file("same.txt", b"unchanged").unwrap();
+        roots.push(store.commit().unwrap());
+    }
+    let removed = store.remove_root(roots[0]).unwrap();
+    assert_eq!(removed.dropped, vec![roots[0]]);
+    assert_eq!(store.roothash_history().unwrap(), roots[1..].to_vec());
+    assert_eq!(
+        store.read_resource(roots[2], "same.txt").unwrap(),
+        b"unchanged"
+    );
+    assert!(matches!(
+        store.remove_root(roots[2]),
+        Err(digstore_store::StoreError::InvalidPrune(_))
+    ));
+    assert!(matches!(
+        store.remove_root(roots[0]),
+        Err(digstore_store::StoreError::GenerationNotFound(_))
+    ));
+}
+
+#[test]
+fn generations_a_changes_only_layer_reads_through_are_never_dropped() {
+    use digstore_store::{CommitOptions, StoreError};
+
+    let changes_only = CommitOptions {
+        changes_only: true,
+        ..Default::default()
+    };
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("old.txt", b"unrelated").unwrap();
+    let old = store.commit().unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    let base = store.commit().unwrap();
+    store.stage_file("b.txt", b"bravo").unwrap();
+    let layer = store.commit_with_options(changes_only).unwrap();
+    store.stage_file("c.txt", b"charlie").unwrap();
+    let top = store.commit_with_options(changes_only).unwrap();
+
+    assert!(matches!(
+        store.remove_root(base),
+        Err(StoreError::InvalidPrune(_))
+    ));
+    assert!(matches!(
+        store.squash(base, top, false),
+        Err(StoreError::InvalidSquash(_))
+    ));
+    // Only the generation outside the chain goes; the chain still reads.
+    let pruned = store.prune(None, Some(1), false).unwrap();
+    assert_eq!(pruned.dropped, vec![old]);
+    assert_eq!(store.roothash_history().unwrap(), vec![base, layer, top]);
+    assert_eq!(store.read_resource(top, "a.txt").unwrap(), b"alpha");
+    assert_eq!(store.read_resource(top, "b.txt").unwrap(), b"bravo");
+}
+
+#[test]
+fn iter_generations_walks_history_in_order_reading_each_manifest() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let mut roots = Vec::new();
+    for n in 0..3u8 {
+        for i in 0..=n {
+            store.stage_file(&format!("f{i}.txt"), &[i; 10]).unwrap();
+        }
+        roots.push(store.commit().unwrap());
+    }
+    // A missing manifest fails only its own item.
+    std::fs::remove_file(store.paths().generation_manifest(&roots[1].to_hex())).unwrap();
+
+    let items: Vec<_> = store.iter_generations().unwrap().collect();
+    assert_eq!(items.len(), 3);
+    let (g0, m0) = items[0].as_ref().unwrap();
+    assert_eq!((g0.id, g0.root, m0.key_table.len()), (0, roots[0], 1));
+    assert!(matches!(
+        items[1],
+        Err(digstore_store::StoreError::GenerationNotFound(_))
+    ));
+    let (g2, m2) = items[2].as_ref().unwrap();
+    assert_eq!((g2.id, m2.root, m2.key_table.len()), (2, roots[2], 3));
+}
+
+#[test]
+fn grep_reports_matching_lines_and_skips_binaries() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store
+        .stage_file("notes.txt", b"alpha\r\nneedle one\nbeta\nneedle two")
+        .unwrap();
+    store.stage_file("bin.dat", b"needle\0\x01\x02").unwrap();
+    // Spans several chunks, so lines cross chunk boundaries.
+    let mut big = Vec::new();
+    for i in 0..60_000 {
+        big.extend_from_slice(format!("line {i} {}\n", i % 7).as_bytes());
+    }
+    store.stage_file("big.log", &big).unwrap();
+    let root = store.commit().unwrap();
+
+    let pattern = regex::Regex::new("needle|^line 59999 ").unwrap();
+    let mut found = Vec::new();
+    store
+        .grep(&pattern, root, false, |m| {
+            found.push((m.resource_key, m.line_number, m.line));
+            true
+        })
+        .unwrap();
+    assert_eq!(
+        found,
+        vec![
+            ("big.log".to_string(), 60_000, "line 59999 2".to_string()),
+            ("notes.txt".to_string(), 2, "needle one".to_string()),
+            ("notes.txt".to_string(), 4, "needle two".to_string()),
+        ]
+    );
+
+    // `text` searches binaries too; returning false stops at a file's first match.
+    let mut files = Vec::new();
+    store
+        .grep(&regex::Regex::new("needle").unwrap(), root, true, |m| {
+            files.push(m.resource_key);
+            false
+        })
+        .unwrap();
+    assert_eq!(files, vec!["bin.dat", "notes.txt"]);
+}
+
+#[test]
+fn add_bytes_stages_in_memory_content_like_a_file_on_disk() {
+    let disk = tempdir().unwrap();
+    let base = disk.path().join("site");
+    std::fs::create_dir_all(base.join("docs")).unwrap();
+    std::fs::write(base.join("docs/page.html"), b"<p>generated</p>").unwrap();
+    let a = tempdir().unwrap();
+    let mut from_disk = Store::init(config(a.path()), FixedClock::new(1)).unwrap();
+    from_disk.add(base.join("docs/page.html"), &base).unwrap();
+    let expected = from_disk.commit().unwrap();
+
+    let b = tempdir().unwrap();
+    let mut store = Store::init(config(b.path()), FixedClock::new(1)).unwrap();
+    store
+        .add_bytes(std::path::Path::new("docs/page.html"), b"<p>generated</p>")
+        .unwrap();
+    let root = store.commit().unwrap();
+    assert_eq!(root, expected);
+    assert_eq!(
+        store.read_resource(root, "docs/page.html").unwrap(),
+        b"<p>generated</p>"
+    );
+
+    for bad in ["../up.txt", "/abs.txt", ""] {
+        assert!(matches!(
+            store.add_bytes(bad, b"x"),
+            Err(digstore_store::StoreError::PathEscape(_))
+        ));
+    }
+}
+
+#[test]
+fn commit_seals_the_bytes_staged_by_add_even_if_the_file_changes_after() {
+    let disk = tempdir().unwrap();
+    let file = disk.path().join("report.csv");
+    let original = noise(400_000, 5);
+    std::fs::write(&file, &original).unwrap();
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.add(&file, disk.path()).unwrap();
+
+    // Rewritten (and truncated) between add and commit.
+    std::fs::write(&file, b"edited later").unwrap();
+    let root = store.commit().unwrap();
+    assert_eq!(store.read_resource(root, "report.csv").unwrap(), original);
+    let expected = expected_resource_leaves(
+        Bytes32([0x44u8; 32]),
+        None,
+        &[("report.csv", original.as_slice())],
+    );
+    assert_eq!(
+        root,
+        digstore_core::MerkleTree::from_leaves(expected).root()
+    );
+}
+
+#[test]
+fn add_reader_stages_piped_content() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let body = noise(700_000, 21);
+    let n = store
+        .add_reader("out/build.log", std::io::Cursor::new(body.clone()))
+        .unwrap();
+    assert_eq!(n, body.len() as u64);
+    let root = store.commit().unwrap();
+    assert_eq!(store.read_resource(root, "out/build.log").unwrap(), body);
+    assert!(matches!(
+        store.add_reader("../escape", std::io::empty()),
+        Err(digstore_store::StoreError::PathEscape(_))
+    ));
+}
+
+fn tar_of(files: &[(&str, &[u8])], symlink: Option<(&str, &str)>) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut dir = tar::Header::new_gnu();
+    dir.set_entry_type(tar::EntryType::Directory);
+    dir.set_mode(0o755);
+    dir.set_size(0);
+    builder
+        .append_data(&mut dir, "site/", std::io::empty())
+        .unwrap();
+    for (path, body) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(0o644);
+        header.set_size(body.len() as u64);
+        builder.append_data(&mut header, path, *body).unwrap();
+    }
+    if let Some((path, target)) = symlink {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, path, target).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn add_tar_stages_archive_files_by_path_and_reports_links() {
+    let big = noise(300_000, 8);
+    let archive = tar_of(
+        &[("site/index.html", b"<h1>hi</h1>"), ("site/blob.bin", &big)],
+        Some(("site/latest", "index.html")),
+    );
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let import = store.add_tar(archive.as_slice()).unwrap();
+    assert_eq!(
+        import.staged,
+        vec![
+            ("site/index.html".to_string(), 11),
+            ("site/blob.bin".to_string(), big.len() as u64)
+        ]
+    );
+    assert_eq!(import.skipped.len(), 1);
+    assert_eq!(
+        (import.skipped[0].path.as_str(), import.skipped[0].kind),
+        ("site/latest", "symlink")
+    );
+    let root = store.commit().unwrap();
+    assert_eq!(store.read_resource(root, "site/blob.bin").unwrap(), big);
+
+    // The same archive gzip-compressed stages the same content.
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    gz.write_all(&archive).unwrap();
+    let other = tempdir().unwrap();
+    let mut store = Store::init(config(other.path()), FixedClock::new(1)).unwrap();
+    store.add_tar(gz.finish().unwrap().as_slice()).unwrap();
+    assert_eq!(store.commit().unwrap(), root);
+}
+
+#[test]
+fn append_tar_over_the_limit_stages_nothing() {
+    let dir = tempdir().unwrap();
+    let mut staging = StagingArea::open(dir.path().join("staging.bin")).unwrap();
+    staging.append("keep.txt", b"already staged").unwrap();
+    let archive = tar_of(&[("a.txt", b"12345"), ("b.txt", b"67890")], None);
+    assert!(matches!(
+        staging.append_tar(archive.as_slice(), 8),
+        Err(digstore_store::StoreError::TarTooLarge { limit: 8 })
+    ));
+    let keys: Vec<String> = staging
+        .records()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.resource_key)
+        .collect();
+    assert_eq!(keys, ["keep.txt"]);
+    assert!(matches!(
+        staging.append_tar(&b"not a tar archive"[..], u64::MAX),
+        Err(digstore_store::StoreError::InvalidTar(_))
+    ));
+}
+
+#[test]
+fn export_tar_streams_a_generation_that_add_tar_reads_back() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1_700_000_000)).unwrap();
+    let big = noise(600_000, 12);
+    store.stage_file("b/big.bin", &big).unwrap();
+    store.stage_file("a.txt", b"alpha").unwrap();
+    let root = store.commit().unwrap();
+
+    let mut out = Vec::new();
+    assert_eq!(store.export_tar(root, &mut out).unwrap(), 2);
+    let mut archive = tar::Archive::new(out.as_slice());
+    let mut seen = Vec::new();
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let header = entry.header();
+        assert_eq!(header.mode().unwrap(), 0o644);
+        assert_eq!(header.mtime().unwrap(), 1_700_000_000);
+        let path = entry.path().unwrap().to_string_lossy().into_owned();
+        let mut body = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut body).unwrap();
+        seen.push((path, body));
+    }
+    assert_eq!(
+        seen,
+        vec![
+            ("a.txt".to_string(), b"alpha".to_vec()),
+            ("b/big.bin".to_string(), big)
+        ]
+    );
+
+    let other = tempdir().unwrap();
+    let mut copy = Store::init(config(other.path()), FixedClock::new(1)).unwrap();
+    copy.add_tar(out.as_slice()).unwrap();
+    assert_eq!(copy.commit().unwrap(), root);
+}
+
+#[test]
+fn open_names_a_truncated_leaf_cache_or_dangling_index_in_the_head() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    store.stage_file("data.bin", &noise(400_000, 3)).unwrap();
+    store.stage_file("note.txt", b"note").unwrap();
+    let root = store.commit().unwrap();
+    let manifest = store.generation_manifest(root).unwrap();
+    let leaves = store.paths().generation_leaves(&root.to_hex());
+    drop(store);
+
+    let body = std::fs::read(&leaves).unwrap();
+    std::fs::write(&leaves, &body[..100]).unwrap();
+    match Store::open(dir.path(), FixedClock::new(1)) {
+        Err(digstore_store::StoreError::Corrupted { entry, detail }) => {
+            assert!(entry.contains("leaves.bin"), "{entry}");
+            assert!(detail.contains("100 bytes"), "{detail}");
+        }
+        other => panic!("expected Corrupted, got {:?}", other.err()),
+    }
+    std::fs::write(&leaves, &body).unwrap();
+
+    let path = dir
+        .path()
+        .join("generations")
+        .join(root.to_hex())
+        .join("manifest.json");
+    let mut broken = manifest.clone();
+    broken.key_table[0].chunk_indices.push(999);
+    broken.write_to(&path).unwrap();
+    match Store::open(dir.path(), FixedClock::new(1)) {
+        Err(digstore_store::StoreError::Corrupted { entry, detail }) => {
+            assert!(entry.contains("data.bin"), "{entry}");
+            assert!(detail.contains("999"), "{detail}");
+        }
+        other => panic!("expected Corrupted, got {:?}", other.err()),
+    }
+    manifest.write_to(&path).unwrap();
+    assert!(Store::open(dir.path(), FixedClock::new(1)).is_ok());
+}
+
+#[test]
+fn incompressible_content_is_stored_as_raw_sealed_chunks() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let body = noise(500_000, 33);
+    store.stage_file("media.mp4", &body).unwrap();
+    let root = store.commit().unwrap();
+
+    // Each chunk file is exactly the sealed ciphertext the manifest records:
+    // no compression flag, no framing, addressed by the hash of its bytes.
+    let stat = store.stat_file(root, "media.mp4").unwrap();
+    assert!(stat.chunks.len() > 1);
+    let chunks_dir = store.paths().generation_chunks_dir(&root.to_hex());
+    let mut stored = 0u64;
+    for c in &stat.chunks {
+        let file = std::fs::read(chunks_dir.join(c.hash.to_hex())).unwrap();
+        assert_eq!(file.len() as u64, c.size);
+        assert_eq!(digstore_core::sha256(&file), c.hash);
+        stored += c.size;
+    }
+    assert!(stored >= body.len() as u64);
+    assert_eq!(store.read_resource(root, "media.mp4").unwrap(), body);
+}
+
+/// A backend that serves another store's chunk dirs and counts its reads.
+struct CountingBackend {
+    chunks: Vec<digstore_store::ChunkStore>,
+    reads: std::sync::atomic::AtomicUsize,
+    flip: bool,
+}
+
+impl digstore_store::ChunkBackend for CountingBackend {
+    fn len(&self, hash: Bytes32) -> digstore_store::Result<u64> {
+        let store = self.chunks.iter().find(|c| c.contains(hash).unwrap());
+        match store {
+            Some(c) => c.len(hash),
+            None => Err(digstore_store::StoreError::ChunkNotFound(hash.to_hex())),
+        }
+    }
+
+    fn read_range(
+        &self,
+        hash: Bytes32,
+        range: std::ops::Range<u64>,
+    ) -> digstore_store::Result<Vec<u8>> {
+        self.reads
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let store = self.chunks.iter().find(|c| c.contains(hash).unwrap());
+        let mut body = store.unwrap().read_range(hash, range)?;
+        if self.flip {
+            body[0] ^= 1;
+        }
+        Ok(body)
+    }
+
+    fn write(&self, _hash: Bytes32, _data: &[u8]) -> digstore_store::Result<bool> {
+        Err(digstore_store::StoreError::ReadOnly(
+            "counting backend".into(),
+        ))
+    }
+}
+
+#[test]
+fn read_resource_from_fetches_only_that_resources_chunks_from_the_backend() {
+    use digstore_store::ChunkBackend;
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let wanted = noise(300_000, 41);
+    store.stage_file("a.bin", &wanted).unwrap();
+    store.stage_file("b.bin", &noise(300_000, 42)).unwrap();
+    let root = store.commit().unwrap();
+    let stat = store.stat_file(root, "a.bin").unwrap();
+
+    // Move the chunk dir away: the local store keeps only its manifests.
+    let chunks_dir = store.paths().generation_chunks_dir(&root.to_hex());
+    let remote_dir = dir.path().join("remote-chunks");
+    std::fs::rename(&chunks_dir, &remote_dir).unwrap();
+    let backend = CountingBackend {
+        chunks: vec![digstore_store::ChunkStore::new(&remote_dir)],
+        reads: Default::default(),
+        flip: false,
+    };
+    assert!(backend.write(root, b"x").is_err());
+
+    let got = store.read_resource_from(&backend, root, "a.bin").unwrap();
+    assert_eq!(got, wanted);
+    assert_eq!(
+        backend.reads.load(std::sync::atomic::Ordering::Relaxed),
+        stat.chunks.len()
+    );
+
+    let tampered = CountingBackend {
+        flip: true,
+        ..backend
+    };
+    assert!(matches!(
+        store.read_resource_from(&tampered, root, "a.bin"),
+        Err(digstore_store::StoreError::Backend(_))
+    ));
+}
+
+/// Read `key` in sequential `window`-byte range reads; returns the bytes and
+/// how many chunk files were read (chunk cache misses; the cache is off).
+fn read_in_windows(
+    store: &Store<FixedClock>,
+    root: Bytes32,
+    key: &str,
+    len: u64,
+    window: u64,
+) -> (Vec<u8>, u64) {
+    let before = store.cache_stats().misses;
+    let mut out = Vec::new();
+    let mut at = 0;
+    while at < len {
+        out.extend(
+            store
+                .read_resource_range(root, key, at..at + window)
+                .unwrap(),
+        );
+        at += window;
+    }
+    (out, store.cache_stats().misses - before)
+}
+
+#[test]
+fn read_resource_range_reuses_decrypted_chunks_across_sequential_windows() {
+    let dir = tempdir().unwrap();
+    let mut store = Store::init(config(dir.path()), FixedClock::new(1)).unwrap();
+    let body = noise(2_000_000, 61);
+    store.stage_file("movie.mp4", &body).unwrap();
+    let root = store.commit().unwrap();
+    let chunks = store.stat_file(root, "movie.mp4").unwrap().chunks.len() as u64;
+    store.set_chunk_cache_capacity(0);
+
+    let len = body.len() as u64;
+    assert_eq!(
+        store
+            .read_resource_range(root, "movie.mp4", len - 10..len + 100)
+            .unwrap(),
+        body[body.len() - 10..]
+    );
+    assert!(store
+        .read_resource_range(root, "movie.mp4", len + 5..len + 9)
+        .unwrap()
+        .is_empty());
+
+    // Windows smaller than a chunk: each chunk file is still read only once.
+    // (Dropping the capacity to 0 and back empties the window first.)
+    store.set_read_ahead_capacity(0);
+    store.set_read_ahead_capacity(digstore_store::DEFAULT_READ_AHEAD_BYTES);
+    let (got, reads) = read_in_windows(&store, root, "movie.mp4", len, 10_000);
+    assert_eq!(got, body);
+    assert_eq!(reads, chunks);
+    assert!(store.read_ahead_stats().hits > 0);
+
+    store.set_read_ahead_capacity(0);
+    let (got, reads_without) = read_in_windows(&store, root, "movie.mp4", len, 10_000);
+    assert_eq!(got, body);
+    assert!(reads_without > reads);
+}
+
+#[test]
+#[ignore = "benchmark: sequential 1 MiB range reads over a 500 MB resource; run with --ignored --nocapture"]
+fn bench_sequential_range_reads_with_and_without_read_ahead() {
+    let dir = tempdir().unwrap();
+    let mut cfg = config(dir.path());
+    cfg.max_size = 1 << 30;
+    let mut store = Store::init(cfg, FixedClock::new(1)).unwrap();
+    let len = 500_000_000u64;
+    store
+        .add_reader("video.bin", std::io::Cursor::new(noise(len as usize, 3)))
+        .unwrap();
+    let root = store.commit().unwrap();
+    store.set_chunk_cache_capacity(0);
+    // Check the generation once up front so neither run pays for it.
+    store.read_resource_range(root, "video.bin", 0..1).unwrap();
+
+    let t = std::time::Instant::now();
+    let (_, with) = read_in_windows(&store, root, "video.bin", len, 1 << 20);
+    let with_time = t.elapsed();
+    store.set_read_ahead_capacity(0);
+    let t = std::time::Instant::now();
+    let (_, without) = read_in_windows(&store, root, "video.bin", len, 1 << 20);
+    let without_time = t.elapsed();
+
+    eprintln!("read-ahead on:  {with} chunk reads in {with_time:?}");
+    eprintln!("read-ahead off: {without} chunk reads in {without_time:?}");
+    assert!(with < without);
+}
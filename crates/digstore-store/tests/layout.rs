@@ -1,4 +1,4 @@
-use digstore_core::{Bytes32, StoreConfig, Visibility};
+use digstore_core::{Bytes32, HashAlgorithm, StoreConfig, Visibility};
 use digstore_store::{FixedClock, Store};
 use tempfile::tempdir;
 
@@ -10,6 +10,8 @@ fn config(dir: &std::path::Path) -> StoreConfig {
         visibility: Visibility::Public,
         label: None,
         description: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        salt_keyring: Default::default(),
     }
 }
 